@@ -12,7 +12,16 @@ pub enum GridAction {
     // Add carbon offset with type and construction cost multiplier (as percentage: 100-500%)
     AddCarbonOffset(CarbonOffsetType, u16),
     CloseGenerator(String),  // Generator ID
+    // Convert a generator in place to another type, e.g. coal to biomass
+    RetrofitGenerator(String, GeneratorType),  // Generator ID, target type
+    // Co-located pair sharing one site, e.g. solar with battery storage. Storage output is
+    // limited to the host's hourly dips; charging it from the host isn't modelled.
+    AddHybridPlant(GeneratorType, GeneratorType),
+    // Bring a settlement's EV charging under smart control so it can move to surplus hours
+    AddFlexibleLoad,
     DoNothing, // New no-op action
+    // Hold off building because next year's technology should be cheaper
+    DeferDecision,
 }
 
 impl std::fmt::Display for GridAction {
@@ -33,9 +42,21 @@ impl std::fmt::Display for GridAction {
             GridAction::CloseGenerator(id) => {
                 write!(f, "CloseGenerator({})", id)
             },
+            GridAction::RetrofitGenerator(id, target) => {
+                write!(f, "RetrofitGenerator({} -> {})", id, target)
+            },
+            GridAction::AddHybridPlant(primary, secondary) => {
+                write!(f, "AddHybridPlant({} + {})", primary, secondary)
+            },
+            GridAction::AddFlexibleLoad => {
+                write!(f, "AddFlexibleLoad")
+            },
             GridAction::DoNothing => {
                 write!(f, "DoNothing")
             },
+            GridAction::DeferDecision => {
+                write!(f, "DeferDecision")
+            },
         }
     }
 }
@@ -0,0 +1,75 @@
+// Grid Action Kind module - the payload-free form of GridAction that keys learned weights
+use serde::{Serialize, Deserialize};
+use crate::models::generator::GeneratorType;
+use crate::models::carbon_offset::CarbonOffsetType;
+use crate::ai::learning::constants::OPERATION_PERCENTAGE_MIN;
+use super::grid_action::GridAction;
+
+// Generator ids and operation percentages are instance payloads and are left out, so every
+// AdjustOperation shares one weight. Cost multipliers stay in: build speed is part of the decision.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum GridActionKind {
+    AddGenerator(GeneratorType, u16),
+    UpgradeEfficiency,
+    AdjustOperation,
+    AddCarbonOffset(CarbonOffsetType, u16),
+    CloseGenerator,
+    RetrofitGenerator(GeneratorType),
+    AddHybridPlant(GeneratorType, GeneratorType),
+    AddFlexibleLoad,
+    DoNothing,
+    DeferDecision,
+}
+
+impl From<&GridAction> for GridActionKind {
+    fn from(action: &GridAction) -> Self {
+        match action {
+            GridAction::AddGenerator(gen_type, cost_multiplier) => GridActionKind::AddGenerator(gen_type.clone(), *cost_multiplier),
+            GridAction::UpgradeEfficiency(_) => GridActionKind::UpgradeEfficiency,
+            GridAction::AdjustOperation(_, _) => GridActionKind::AdjustOperation,
+            GridAction::AddCarbonOffset(offset_type, cost_multiplier) => GridActionKind::AddCarbonOffset(offset_type.clone(), *cost_multiplier),
+            GridAction::CloseGenerator(_) => GridActionKind::CloseGenerator,
+            GridAction::RetrofitGenerator(_, target) => GridActionKind::RetrofitGenerator(target.clone()),
+            GridAction::AddHybridPlant(primary, secondary) => GridActionKind::AddHybridPlant(primary.clone(), secondary.clone()),
+            GridAction::AddFlexibleLoad => GridActionKind::AddFlexibleLoad,
+            GridAction::DoNothing => GridActionKind::DoNothing,
+            GridAction::DeferDecision => GridActionKind::DeferDecision,
+        }
+    }
+}
+
+impl GridAction {
+    pub fn kind(&self) -> GridActionKind {
+        GridActionKind::from(self)
+    }
+}
+
+impl GridActionKind {
+    // Concrete action with an unbound target; core::actions::bind_action_target picks one from the map
+    pub fn to_action(&self) -> GridAction {
+        match self {
+            GridActionKind::AddGenerator(gen_type, cost_multiplier) => GridAction::AddGenerator(gen_type.clone(), *cost_multiplier),
+            GridActionKind::UpgradeEfficiency => GridAction::UpgradeEfficiency(String::new()),
+            GridActionKind::AdjustOperation => GridAction::AdjustOperation(String::new(), OPERATION_PERCENTAGE_MIN),
+            GridActionKind::AddCarbonOffset(offset_type, cost_multiplier) => GridAction::AddCarbonOffset(offset_type.clone(), *cost_multiplier),
+            GridActionKind::CloseGenerator => GridAction::CloseGenerator(String::new()),
+            GridActionKind::RetrofitGenerator(target) => GridAction::RetrofitGenerator(String::new(), target.clone()),
+            GridActionKind::AddHybridPlant(primary, secondary) => GridAction::AddHybridPlant(primary.clone(), secondary.clone()),
+            GridActionKind::AddFlexibleLoad => GridAction::AddFlexibleLoad,
+            GridActionKind::DoNothing => GridAction::DoNothing,
+            GridActionKind::DeferDecision => GridAction::DeferDecision,
+        }
+    }
+}
+
+impl std::fmt::Display for GridActionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridActionKind::UpgradeEfficiency => write!(f, "UpgradeEfficiency"),
+            GridActionKind::AdjustOperation => write!(f, "AdjustOperation"),
+            GridActionKind::CloseGenerator => write!(f, "CloseGenerator"),
+            GridActionKind::RetrofitGenerator(target) => write!(f, "RetrofitGenerator(-> {})", target),
+            _ => write!(f, "{}", self.to_action()),
+        }
+    }
+}
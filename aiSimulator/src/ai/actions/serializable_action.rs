@@ -1,6 +1,10 @@
 // Serializable Action module - contains the SerializableAction struct
+use std::str::FromStr;
 use serde::{Serialize, Deserialize};
 use super::grid_action::GridAction;
+use crate::models::generator::GeneratorType;
+use crate::models::carbon_offset::CarbonOffsetType;
+use crate::config::constants::DEFAULT_COST_MULTIPLIER;
 
 #[derive(Serialize, Deserialize)]
 pub struct SerializableAction {
@@ -10,6 +14,8 @@ pub struct SerializableAction {
     pub operation_percentage: Option<u8>,
     pub offset_type: Option<String>,
     pub cost_multiplier: Option<u16>,
+    #[serde(default)]
+    pub secondary_generator_type: Option<String>,
 }
 
 impl From<&GridAction> for SerializableAction {
@@ -22,6 +28,7 @@ impl From<&GridAction> for SerializableAction {
                 operation_percentage: None,
                 offset_type: None,
                 cost_multiplier: Some(*cost_multiplier),
+                secondary_generator_type: None,
             },
             GridAction::UpgradeEfficiency(id) => SerializableAction {
                 action_type: "UpgradeEfficiency".to_string(),
@@ -30,6 +37,7 @@ impl From<&GridAction> for SerializableAction {
                 operation_percentage: None,
                 offset_type: None,
                 cost_multiplier: None,
+                secondary_generator_type: None,
             },
             GridAction::AdjustOperation(id, percentage) => SerializableAction {
                 action_type: "AdjustOperation".to_string(),
@@ -38,6 +46,7 @@ impl From<&GridAction> for SerializableAction {
                 operation_percentage: Some(*percentage),
                 offset_type: None,
                 cost_multiplier: None,
+                secondary_generator_type: None,
             },
             GridAction::AddCarbonOffset(offset_type, cost_multiplier) => SerializableAction {
                 action_type: "AddCarbonOffset".to_string(),
@@ -46,6 +55,7 @@ impl From<&GridAction> for SerializableAction {
                 operation_percentage: None,
                 offset_type: Some(offset_type.to_string()),
                 cost_multiplier: Some(*cost_multiplier),
+                secondary_generator_type: None,
             },
             GridAction::CloseGenerator(id) => SerializableAction {
                 action_type: "CloseGenerator".to_string(),
@@ -54,6 +64,34 @@ impl From<&GridAction> for SerializableAction {
                 operation_percentage: None,
                 offset_type: None,
                 cost_multiplier: None,
+                secondary_generator_type: None,
+            },
+            GridAction::RetrofitGenerator(id, target) => SerializableAction {
+                action_type: "RetrofitGenerator".to_string(),
+                generator_type: Some(target.to_string()),
+                generator_id: Some(id.clone()),
+                operation_percentage: None,
+                offset_type: None,
+                cost_multiplier: None,
+                secondary_generator_type: None,
+            },
+            GridAction::AddHybridPlant(primary, secondary) => SerializableAction {
+                action_type: "AddHybridPlant".to_string(),
+                generator_type: Some(primary.to_string()),
+                generator_id: None,
+                operation_percentage: None,
+                offset_type: None,
+                cost_multiplier: None,
+                secondary_generator_type: Some(secondary.to_string()),
+            },
+            GridAction::AddFlexibleLoad => SerializableAction {
+                action_type: "AddFlexibleLoad".to_string(),
+                generator_type: None,
+                generator_id: None,
+                operation_percentage: None,
+                offset_type: None,
+                cost_multiplier: None,
+                secondary_generator_type: None,
             },
             GridAction::DoNothing => SerializableAction {
                 action_type: "DoNothing".to_string(),
@@ -62,7 +100,65 @@ impl From<&GridAction> for SerializableAction {
                 operation_percentage: None,
                 offset_type: None,
                 cost_multiplier: None,
+                secondary_generator_type: None,
+            },
+            GridAction::DeferDecision => SerializableAction {
+                action_type: "DeferDecision".to_string(),
+                generator_type: None,
+                generator_id: None,
+                operation_percentage: None,
+                offset_type: None,
+                cost_multiplier: None,
+                secondary_generator_type: None,
+            },
+        }
+    }
+}
+
+// Strict conversion for externally authored plans: unknown action, generator or offset types
+// are errors rather than defaults. A missing generator id is left empty so the simulation can
+// bind a target when the action is applied.
+impl TryFrom<&SerializableAction> for GridAction {
+    type Error = String;
+
+    fn try_from(action: &SerializableAction) -> Result<Self, Self::Error> {
+        let generator_type = |field: &Option<String>, name: &str| -> Result<GeneratorType, String> {
+            let value = field.as_deref().filter(|s| !s.is_empty())
+                .ok_or_else(|| format!("{} requires a {}", action.action_type, name))?;
+            GeneratorType::from_str(value)
+        };
+        let generator_id = action.generator_id.clone().unwrap_or_default();
+        let cost_multiplier = action.cost_multiplier.unwrap_or(DEFAULT_COST_MULTIPLIER);
+
+        match action.action_type.as_str() {
+            "AddGenerator" => Ok(GridAction::AddGenerator(
+                generator_type(&action.generator_type, "generator type")?,
+                cost_multiplier,
+            )),
+            "UpgradeEfficiency" => Ok(GridAction::UpgradeEfficiency(generator_id)),
+            "AdjustOperation" => {
+                let percentage = action.operation_percentage
+                    .ok_or_else(|| "AdjustOperation requires an operation percentage".to_string())?;
+                Ok(GridAction::AdjustOperation(generator_id, percentage))
+            },
+            "AddCarbonOffset" => {
+                let offset_type = action.offset_type.as_deref().filter(|s| !s.is_empty())
+                    .ok_or_else(|| "AddCarbonOffset requires an offset type".to_string())?;
+                Ok(GridAction::AddCarbonOffset(CarbonOffsetType::from_str(offset_type)?, cost_multiplier))
             },
+            "CloseGenerator" => Ok(GridAction::CloseGenerator(generator_id)),
+            "RetrofitGenerator" => Ok(GridAction::RetrofitGenerator(
+                generator_id,
+                generator_type(&action.generator_type, "target generator type")?,
+            )),
+            "AddHybridPlant" => Ok(GridAction::AddHybridPlant(
+                generator_type(&action.generator_type, "generator type")?,
+                generator_type(&action.secondary_generator_type, "secondary generator type")?,
+            )),
+            "AddFlexibleLoad" => Ok(GridAction::AddFlexibleLoad),
+            "DoNothing" => Ok(GridAction::DoNothing),
+            "DeferDecision" => Ok(GridAction::DeferDecision),
+            other => Err(format!("Unknown action type: {}", other)),
         }
     }
 }
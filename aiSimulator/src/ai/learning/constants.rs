@@ -45,6 +45,7 @@ pub const LATE_YEAR_THRESHOLD: u32 = 2045;
 pub const DIVERGENCE_FOR_NEGATIVE_WEIGHT: f64 = 0.0001; // The difference of improvement necessary for a negative weight
 pub const ONSHORE_WIND_WEIGHT: f64 = 0.08;
 pub const OFFSHORE_WIND_WEIGHT: f64 = 0.08;
+pub const FLOATING_OFFSHORE_WIND_WEIGHT: f64 = 0.04;
 pub const DOMESTIC_SOLAR_WEIGHT: f64 = 0.05;
 pub const COMMERCIAL_SOLAR_WEIGHT: f64 = 0.05;
 pub const UTILITY_SOLAR_WEIGHT: f64 = 0.08;
@@ -58,11 +59,17 @@ pub const PUMPED_STORAGE_WEIGHT: f64 = 0.06;
 pub const BATTERY_STORAGE_WEIGHT: f64 = 0.07;
 pub const TIDAL_GENERATOR_WEIGHT: f64 = 0.05;
 pub const WAVE_ENERGY_WEIGHT: f64 = 0.05;
+pub const HYDROGEN_ELECTROLYZER_WEIGHT: f64 = 0.03;
+pub const HYDROGEN_TURBINE_WEIGHT: f64 = 0.03;
 pub const UPGRADE_EFFICIENCY_WEIGHT: f64 = 0.04;
 pub const ADJUST_OPERATION_WEIGHT: f64 = 0.04;
 pub const CARBON_OFFSET_WEIGHT: f64 = 0.02;
+pub const HYBRID_PLANT_WEIGHT: f64 = 0.03;
+pub const FLEXIBLE_LOAD_WEIGHT: f64 = 0.03;
 pub const CLOSE_GENERATOR_WEIGHT: f64 = 0.02;
+pub const RETROFIT_GENERATOR_WEIGHT: f64 = 0.02;
 pub const DO_NOTHING_WEIGHT: f64 = 0.1;
+pub const DEFER_DECISION_WEIGHT: f64 = 0.05;
 pub const DEFICIT_GAS_PEAKER_WEIGHT: f64 = 0.15;
 pub const DEFICIT_GAS_COMBINED_WEIGHT: f64 = 0.15;
 pub const DEFICIT_BATTERY_WEIGHT: f64 = 0.15;
@@ -70,9 +77,11 @@ pub const DEFICIT_PUMPED_STORAGE_WEIGHT: f64 = 0.10;
 pub const DEFICIT_BIOMASS_WEIGHT: f64 = 0.10;
 pub const DEFICIT_ONSHORE_WIND_WEIGHT: f64 = 0.07;
 pub const DEFICIT_OFFSHORE_WIND_WEIGHT: f64 = 0.07;
+pub const DEFICIT_FLOATING_OFFSHORE_WIND_WEIGHT: f64 = 0.03;
 pub const DEFICIT_UTILITY_SOLAR_WEIGHT: f64 = 0.06;
 pub const DEFICIT_HYDRO_DAM_WEIGHT: f64 = 0.06;
 pub const DEFICIT_NUCLEAR_WEIGHT: f64 = 0.05;
+pub const DEFICIT_HYDROGEN_TURBINE_WEIGHT: f64 = 0.05;
 pub const DEFICIT_SMALL_GENERATOR_WEIGHT: f64 = 0.01;
 pub const DEFICIT_DO_NOTHING_WEIGHT: f64 = 0.001;
 pub const ONSHORE_WIND_FALLBACK_WEIGHT: u32 = 15;
@@ -95,6 +104,9 @@ pub const STAGNATION_SCALE_FACTOR: f64 = 2.0;
 pub const RANDOMIZATION_FACTOR: f64 = 0.25;
 pub const SMALL_BOOST_FACTOR: f64 = 0.1;
 pub const NOOP_BOOST_FACTOR: f64 = 0.2;
+pub const NOOP_MAX_WEIGHT_SHARE: f64 = 0.25; // Default ceiling on DoNothing's share of a year's action weight
+pub const DEFER_MIN_COST_DECLINE: f64 = 0.015; // Average next-year cost drop across key techs that makes waiting worthwhile
+pub const DEFER_REWARD_SCALE: f64 = 10.0; // Scales the cost drop beyond DEFER_MIN_COST_DECLINE into a DeferDecision reward
 pub const COST_MULTIPLICATION_FACTOR: f64 = 8.0;
 pub const RENEWABLE_FALLBACK_WEIGHT_FACTOR: f64 = 0.5;
 pub const ADAPTIVE_LEARNING_RATE_FACTOR: f64 = 0.1;
@@ -143,12 +155,19 @@ pub const STAGNATION_DIVISOR: f64 = 1000.0;
 pub const STAGNATION_ITERATIONS_DIVISOR: f64 = 10.0;
 pub const MAX_ACTIONS_DIVISOR: f64 = 12.0;
 pub const BILLION_DIVISOR: f64 = 1_000_000_000.0;
+pub const MILLION_DIVISOR: f64 = 1_000_000.0;
 pub const EXPLORATION_DIVISOR: f64 = 2.0;
 
 //---------------------------------------------------------------------
 // Rate Constants
 //---------------------------------------------------------------------
 pub const EXPLORATION_DECAY_RATE: f64 = 0.1;
+pub const ADAPTIVE_EXPLORATION_WINDOW: usize = 20; // Recent scores kept for the adaptive exploration schedule
+pub const ADAPTIVE_EXPLORATION_FLAT_SPREAD: f64 = 0.01; // Relative standard deviation at or above which a window is not flat
+pub const ADAPTIVE_EXPLORATION_BOOST: f64 = 2.0; // A flat, fully stalled window triples the decayed rate
+pub const ADAPTIVE_EXPLORATION_DAMPING: f64 = 0.5; // A window of nothing but new highs halves it
+pub const MIN_ADAPTIVE_EXPLORATION_RATE: f64 = 0.01;
+pub const MAX_ADAPTIVE_EXPLORATION_RATE: f64 = 0.6;
 pub const ACTION_COUNT_DECAY_RATE: f64 = 0.8;
 
 //---------------------------------------------------------------------
@@ -187,19 +206,54 @@ pub const GAS_WEIGHT_MID: u32 = 10;
 pub const GAS_WEIGHT_LATE: u32 = 5;
 pub const BASE_NET_ZERO_SCORE: f64 = 1.0;
 pub const MAX_SCORE_RANGE: f64 = 2.0;
+pub const BUDGET_OVERRUN_BASE_PENALTY: f64 = 1.0; // Over-cap plans score at or below -1.0, under any plan within the cap
+pub const TRAJECTORY_TARGET_START_EMISSIONS: f64 = MAX_ACCEPTABLE_EMISSIONS; // Target line starts here in START_YEAR and reaches zero in END_YEAR
+pub const CARBON_BUDGET_BASE_PENALTY: f64 = 2.0; // Drops any plan over the carbon budget below every plan within it
+pub const TRAJECTORY_PENALTY_WEIGHT: f64 = 1.0; // Score lost when every year overshoots the target by MAX_ACCEPTABLE_EMISSIONS
+pub const MAX_ACCEPTABLE_LAND_USE: f64 = 500.0; // Fleet footprint in km² at which the "min_footprint" penalty saturates
+pub const FOOTPRINT_PENALTY_WEIGHT: f64 = 0.5; // Score lost by a fleet at or above MAX_ACCEPTABLE_LAND_USE
+pub const RENEWABLE_MANDATE_PENALTY_WEIGHT: f64 = 2.0; // Score lost when every year has no renewables against a 100% mandate
 pub const OPERATION_PERCENTAGE_MIN: u8 = 0;
 pub const STAGNATION_DIVISOR_INT: u32 = 100;
 
-// Use a static AtomicBool for debug weights output that can be set at runtime
+use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, Ordering};
+use crate::utils::logging::{self, LogLevel};
 
-// Use existing lazy_static macro since it's already imported at the crate root
-static DEBUG_WEIGHTS: AtomicBool = AtomicBool::new(false);
+// Level in force before weights debugging raised it, put back when debugging is turned off
+static LEVEL_BEFORE_DEBUG_WEIGHTS: Mutex<Option<LogLevel>> = Mutex::new(None);
 
+// Weights debugging is the Debug log level; enabling it raises the level if needed, and
+// disabling it restores the level it raised from
 pub fn set_debug_weights(enabled: bool) {
-    DEBUG_WEIGHTS.store(enabled, Ordering::SeqCst);
+    let mut previous = LEVEL_BEFORE_DEBUG_WEIGHTS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if enabled {
+        if !logging::log_enabled(LogLevel::Debug) {
+            *previous = Some(logging::log_level());
+            logging::set_log_level(LogLevel::Debug);
+        }
+    } else if let Some(level) = previous.take() {
+        logging::set_log_level(level);
+    }
 }
 
 pub fn is_debug_weights_enabled() -> bool {
-    DEBUG_WEIGHTS.load(Ordering::SeqCst)
+    logging::log_enabled(LogLevel::Debug)
+}
+
+// Write interval checkpoints with bincode instead of pretty JSON
+static BINARY_CHECKPOINTS: AtomicBool = AtomicBool::new(false);
+
+pub fn set_binary_checkpoints(enabled: bool) {
+    BINARY_CHECKPOINTS.store(enabled, Ordering::SeqCst);
+}
+
+pub fn is_binary_checkpoints_enabled() -> bool {
+    BINARY_CHECKPOINTS.load(Ordering::SeqCst)
+}
+
+// File name for an interval checkpoint, e.g. "latest_weights.json" or "latest_weights.bin"
+pub fn checkpoint_file_name(stem: &str) -> String {
+    let extension = if is_binary_checkpoints_enabled() { "bin" } else { "json" };
+    format!("{}.{}", stem, extension)
 }
@@ -1,10 +1,16 @@
 //! Serialization module for AI learning components
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
 use crate::ai::metrics::simulation_metrics::SimulationMetrics;
 use crate::ai::actions::serializable_action::SerializableAction;
 use crate::utils::csv_export::ImprovementRecord;
+use crate::ai::learning::constants::{DEFAULT_EXPLORATION_RATE, DEFAULT_LEARNING_RATE};
+
+/// Schema version written into every checkpoint. Bump it when `SerializableWeights` gains a
+/// field or changes meaning, and add the step from the previous version to `migrate_checkpoint`.
+pub const CHECKPOINT_VERSION: u32 = 2;
 
 /// A serializable version of the ImprovementRecord
 #[derive(Serialize, Deserialize)]
@@ -33,19 +39,61 @@ impl From<&ImprovementRecord> for SerializableImprovementRecord {
 }
 
 /// A serializable version of the weights data structure
-/// Used for saving and loading weights to/from JSON files
+/// Used for saving and loading weights to/from JSON files. Years are kept in order so the
+/// same weights always serialize to the same bytes.
 #[derive(Serialize, Deserialize)]
 pub struct SerializableWeights {
-    pub weights: HashMap<u32, Vec<(SerializableAction, f64)>>,
+    pub weights: BTreeMap<u32, Vec<(SerializableAction, f64)>>,
     pub learning_rate: f64,
     pub best_metrics: Option<SimulationMetrics>,
-    pub best_weights: Option<HashMap<u32, Vec<(SerializableAction, f64)>>>,
-    pub best_actions: Option<HashMap<u32, Vec<SerializableAction>>>,
+    pub best_weights: Option<BTreeMap<u32, Vec<(SerializableAction, f64)>>>,
+    pub best_actions: Option<BTreeMap<u32, Vec<SerializableAction>>>,
     pub iteration_count: u32,
     pub iterations_without_improvement: u32,
     pub exploration_rate: f64,
-    pub deficit_weights: HashMap<u32, Vec<(SerializableAction, f64)>>,
-    pub best_deficit_actions: Option<HashMap<u32, Vec<SerializableAction>>>,
+    pub deficit_weights: BTreeMap<u32, Vec<(SerializableAction, f64)>>,
+    pub best_deficit_actions: Option<BTreeMap<u32, Vec<SerializableAction>>>,
     pub optimization_mode: Option<String>,
     pub improvement_history: Option<Vec<SerializableImprovementRecord>>,
+    #[serde(default = "unversioned_checkpoint")]
+    pub version: u32,
+}
+
+// Checkpoints written before the version field existed
+fn unversioned_checkpoint() -> u32 {
+    1
+}
+
+/// Upgrades checkpoint JSON written by an earlier schema to CHECKPOINT_VERSION, one version at a
+/// time, so it deserializes as `SerializableWeights`. Checkpoints from a newer build are refused
+/// rather than loaded with their unknown fields dropped.
+pub fn migrate_checkpoint(mut checkpoint: Value) -> Result<Value, String> {
+    let object = checkpoint.as_object_mut().ok_or("checkpoint is not a JSON object")?;
+    let mut version = object.get("version").and_then(Value::as_u64).unwrap_or(1) as u32;
+    if version > CHECKPOINT_VERSION {
+        return Err(format!("checkpoint version {} is newer than this build supports ({})", version, CHECKPOINT_VERSION));
+    }
+
+    while version < CHECKPOINT_VERSION {
+        match version {
+            // Unversioned files from older releases may lack the learning state added over time
+            1 => {
+                let defaults = [
+                    ("learning_rate", json!(DEFAULT_LEARNING_RATE)),
+                    ("iteration_count", json!(0)),
+                    ("iterations_without_improvement", json!(0)),
+                    ("exploration_rate", json!(DEFAULT_EXPLORATION_RATE)),
+                    ("deficit_weights", json!({})),
+                ];
+                for (field, default) in defaults {
+                    object.entry(field).or_insert(default);
+                }
+            }
+            _ => unreachable!("no migration from checkpoint version {}", version),
+        }
+        version += 1;
+    }
+
+    object.insert("version".to_string(), json!(CHECKPOINT_VERSION));
+    Ok(checkpoint)
 }
@@ -1,16 +1,18 @@
 // Core operations for ActionWeights
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use rand::rngs::StdRng;
 use rand::Rng;
 use crate::models::generator::GeneratorType;
 use crate::models::carbon_offset::CarbonOffsetType;
-use crate::ai::actions::grid_action::GridAction;
+use crate::ai::actions::grid_action_kind::GridActionKind;
 use crate::ai::metrics::simulation_metrics::SimulationMetrics;
 use crate::ai::learning::constants::*;
 use crate::ai::score_metrics;
+use crate::ai::metrics::scoring::ScoringContext;
 use crate::config::constants::{DEFAULT_COST_MULTIPLIER, FAST_COST_MULTIPLIER, VERY_FAST_COST_MULTIPLIER};
-use super::ActionWeights;
+use super::{ActionWeights, ContrastLearningPolicy, ExplorationSchedule, StagnationPolicy};
+use super::rng::RngSource;
 use crate::utils::csv_export::ImprovementRecord;
 
 // Add a dummy public item to ensure this file is recognized by rust-analyzer
@@ -22,6 +24,117 @@ impl ActionWeights {
 // This file contains extracted code from the original weights.rs file
 // Appropriate imports will need to be added based on the specific requirements
 
+    /// Starting weight for building a generator type at the default cost multiplier.
+    /// The match is exhaustive so a new variant must be given a weight here.
+    pub fn default_generator_weight(gen_type: &GeneratorType) -> f64 {
+        match gen_type {
+            GeneratorType::OnshoreWind => ONSHORE_WIND_WEIGHT,
+            GeneratorType::OffshoreWind => OFFSHORE_WIND_WEIGHT,
+            GeneratorType::FloatingOffshoreWind => FLOATING_OFFSHORE_WIND_WEIGHT,
+            GeneratorType::DomesticSolar => DOMESTIC_SOLAR_WEIGHT,
+            GeneratorType::CommercialSolar => COMMERCIAL_SOLAR_WEIGHT,
+            GeneratorType::UtilitySolar => UTILITY_SOLAR_WEIGHT,
+            GeneratorType::Nuclear => NUCLEAR_WEIGHT,
+            GeneratorType::CoalPlant => COAL_PLANT_WEIGHT,
+            GeneratorType::GasCombinedCycle => GAS_COMBINED_CYCLE_WEIGHT,
+            GeneratorType::GasPeaker => GAS_PEAKER_WEIGHT,
+            GeneratorType::Biomass => BIOMASS_WEIGHT,
+            GeneratorType::HydroDam => HYDRO_DAM_WEIGHT,
+            GeneratorType::PumpedStorage => PUMPED_STORAGE_WEIGHT,
+            GeneratorType::BatteryStorage => BATTERY_STORAGE_WEIGHT,
+            GeneratorType::TidalGenerator => TIDAL_GENERATOR_WEIGHT,
+            GeneratorType::WaveEnergy => WAVE_ENERGY_WEIGHT,
+            GeneratorType::HydrogenElectrolyzer => HYDROGEN_ELECTROLYZER_WEIGHT,
+            GeneratorType::HydrogenTurbine => HYDROGEN_TURBINE_WEIGHT,
+        }
+    }
+
+    /// Cost multipliers a type can be built at, slowest first. Floating wind and the hydrogen
+    /// pathway can't be rushed beyond the fast tier.
+    pub fn build_speed_multipliers(gen_type: &GeneratorType) -> &'static [u16] {
+        match gen_type {
+            GeneratorType::FloatingOffshoreWind |
+            GeneratorType::HydrogenElectrolyzer |
+            GeneratorType::HydrogenTurbine => &[DEFAULT_COST_MULTIPLIER, FAST_COST_MULTIPLIER],
+            _ => &[DEFAULT_COST_MULTIPLIER, FAST_COST_MULTIPLIER, VERY_FAST_COST_MULTIPLIER],
+        }
+    }
+
+    /// Starting deficit-handling weight for a type, or None if it can't help close a deficit
+    /// (coal is never built new and electrolyzers only consume power)
+    pub fn default_deficit_weight(gen_type: &GeneratorType) -> Option<f64> {
+        match gen_type {
+            GeneratorType::GasPeaker => Some(DEFICIT_GAS_PEAKER_WEIGHT),
+            GeneratorType::GasCombinedCycle => Some(DEFICIT_GAS_COMBINED_WEIGHT),
+            GeneratorType::BatteryStorage => Some(DEFICIT_BATTERY_WEIGHT),
+            GeneratorType::PumpedStorage => Some(DEFICIT_PUMPED_STORAGE_WEIGHT),
+            GeneratorType::HydrogenTurbine => Some(DEFICIT_HYDROGEN_TURBINE_WEIGHT),
+            GeneratorType::Biomass => Some(DEFICIT_BIOMASS_WEIGHT),
+            GeneratorType::OnshoreWind => Some(DEFICIT_ONSHORE_WIND_WEIGHT),
+            GeneratorType::OffshoreWind => Some(DEFICIT_OFFSHORE_WIND_WEIGHT),
+            GeneratorType::FloatingOffshoreWind => Some(DEFICIT_FLOATING_OFFSHORE_WIND_WEIGHT),
+            GeneratorType::UtilitySolar => Some(DEFICIT_UTILITY_SOLAR_WEIGHT),
+            GeneratorType::HydroDam => Some(DEFICIT_HYDRO_DAM_WEIGHT),
+            // Lower weight due to long build time
+            GeneratorType::Nuclear => Some(DEFICIT_NUCLEAR_WEIGHT),
+            GeneratorType::DomesticSolar |
+            GeneratorType::CommercialSolar |
+            GeneratorType::TidalGenerator |
+            GeneratorType::WaveEnergy => Some(DEFICIT_SMALL_GENERATOR_WEIGHT),
+            GeneratorType::CoalPlant |
+            GeneratorType::HydrogenElectrolyzer => None,
+        }
+    }
+
+    /// One year's deficit weights before any learning, biased toward fast-responding generators
+    pub fn default_deficit_year_weights() -> HashMap<GridActionKind, f64> {
+        let mut deficit_year_weights: HashMap<GridActionKind, f64> = GeneratorType::iter()
+            .filter_map(|gen_type| {
+                let weight = Self::default_deficit_weight(&gen_type)?;
+                Some((GridActionKind::AddGenerator(gen_type, DEFAULT_COST_MULTIPLIER), weight))
+            })
+            .collect();
+        
+        // DoNothing should have very low weight for deficit handling
+        deficit_year_weights.insert(GridActionKind::DoNothing, DEFICIT_DO_NOTHING_WEIGHT);
+        deficit_year_weights
+    }
+
+    /// Re-seeds the build weights so each generator type's share of the AddGenerator mass
+    /// follows a historical deployment prior (e.g. Ireland's existing wind-heavy mix).
+    /// Only the starting point changes: learning updates, exploration and deficit weights
+    /// behave exactly as they would from `new()`. Types missing from the prior drop to MIN_WEIGHT.
+    pub fn with_prior(mut self, prior: &HashMap<GeneratorType, f64>) -> Self {
+        let prior_total: f64 = prior.values().filter(|share| **share > ZERO_F64).sum();
+        if prior_total <= ZERO_F64 {
+            return self;
+        }
+
+        for year_weights in self.weights.values_mut() {
+            // Current weight mass per generator type, and across all build actions
+            let mut type_mass: HashMap<GeneratorType, f64> = HashMap::new();
+            for (action, weight) in year_weights.iter() {
+                if let GridActionKind::AddGenerator(gen_type, _) = action {
+                    *type_mass.entry(gen_type.clone()).or_insert(ZERO_F64) += *weight;
+                }
+            }
+            let build_mass: f64 = type_mass.values().sum();
+
+            // Rescale each type's entries together so cost-multiplier variants keep their ratios
+            for (action, weight) in year_weights.iter_mut() {
+                if let GridActionKind::AddGenerator(gen_type, _) = action {
+                    let share = prior.get(gen_type).copied().unwrap_or(ZERO_F64).max(ZERO_F64) / prior_total;
+                    let current_mass = type_mass[gen_type];
+                    if current_mass > ZERO_F64 {
+                        *weight = (*weight / current_mass * share * build_mass).clamp(MIN_WEIGHT, MAX_WEIGHT);
+                    }
+                }
+            }
+        }
+
+        self
+    }
+
     pub fn new() -> Self {
         let mut weights = HashMap::new();
         let mut deficit_weights = HashMap::new();
@@ -31,125 +144,52 @@ impl ActionWeights {
         for year in START_YEAR..=END_YEAR {
             let mut year_weights = HashMap::new();
             
-            // Initialize wind generator weights
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::OnshoreWind, DEFAULT_COST_MULTIPLIER), ONSHORE_WIND_WEIGHT);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::OnshoreWind, FAST_COST_MULTIPLIER), ONSHORE_WIND_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::OnshoreWind, VERY_FAST_COST_MULTIPLIER), ONSHORE_WIND_WEIGHT * 0.25);
-            
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::OffshoreWind, DEFAULT_COST_MULTIPLIER), OFFSHORE_WIND_WEIGHT);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::OffshoreWind, FAST_COST_MULTIPLIER), OFFSHORE_WIND_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::OffshoreWind, VERY_FAST_COST_MULTIPLIER), OFFSHORE_WIND_WEIGHT * 0.25);
-            
-            // Initialize solar generator weights
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::DomesticSolar, DEFAULT_COST_MULTIPLIER), DOMESTIC_SOLAR_WEIGHT);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::DomesticSolar, FAST_COST_MULTIPLIER), DOMESTIC_SOLAR_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::DomesticSolar, VERY_FAST_COST_MULTIPLIER), DOMESTIC_SOLAR_WEIGHT * 0.25);
-            
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::CommercialSolar, DEFAULT_COST_MULTIPLIER), COMMERCIAL_SOLAR_WEIGHT);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::CommercialSolar, FAST_COST_MULTIPLIER), COMMERCIAL_SOLAR_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::CommercialSolar, VERY_FAST_COST_MULTIPLIER), COMMERCIAL_SOLAR_WEIGHT * 0.25);
-            
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::UtilitySolar, DEFAULT_COST_MULTIPLIER), UTILITY_SOLAR_WEIGHT);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::UtilitySolar, FAST_COST_MULTIPLIER), UTILITY_SOLAR_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::UtilitySolar, VERY_FAST_COST_MULTIPLIER), UTILITY_SOLAR_WEIGHT * 0.25);
-            
-            // Initialize nuclear and fossil fuel generator weights
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::Nuclear, DEFAULT_COST_MULTIPLIER), NUCLEAR_WEIGHT);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::Nuclear, FAST_COST_MULTIPLIER), NUCLEAR_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::Nuclear, VERY_FAST_COST_MULTIPLIER), NUCLEAR_WEIGHT * 0.25);
-            
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::CoalPlant, DEFAULT_COST_MULTIPLIER), COAL_PLANT_WEIGHT);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::CoalPlant, FAST_COST_MULTIPLIER), COAL_PLANT_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::CoalPlant, VERY_FAST_COST_MULTIPLIER), COAL_PLANT_WEIGHT * 0.25);
-            
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::GasCombinedCycle, DEFAULT_COST_MULTIPLIER), GAS_COMBINED_CYCLE_WEIGHT);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::GasCombinedCycle, FAST_COST_MULTIPLIER), GAS_COMBINED_CYCLE_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::GasCombinedCycle, VERY_FAST_COST_MULTIPLIER), GAS_COMBINED_CYCLE_WEIGHT * 0.25);
-            
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::GasPeaker, DEFAULT_COST_MULTIPLIER), GAS_PEAKER_WEIGHT);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::GasPeaker, FAST_COST_MULTIPLIER), GAS_PEAKER_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::GasPeaker, VERY_FAST_COST_MULTIPLIER), GAS_PEAKER_WEIGHT * 0.25);
-            
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::Biomass, DEFAULT_COST_MULTIPLIER), BIOMASS_WEIGHT);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::Biomass, FAST_COST_MULTIPLIER), BIOMASS_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::Biomass, VERY_FAST_COST_MULTIPLIER), BIOMASS_WEIGHT * 0.25);
-            
-            // Initialize hydro and storage generator weights
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::HydroDam, DEFAULT_COST_MULTIPLIER), HYDRO_DAM_WEIGHT);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::HydroDam, FAST_COST_MULTIPLIER), HYDRO_DAM_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::HydroDam, VERY_FAST_COST_MULTIPLIER), HYDRO_DAM_WEIGHT * 0.25);
-            
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::PumpedStorage, DEFAULT_COST_MULTIPLIER), PUMPED_STORAGE_WEIGHT);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::PumpedStorage, FAST_COST_MULTIPLIER), PUMPED_STORAGE_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::PumpedStorage, VERY_FAST_COST_MULTIPLIER), PUMPED_STORAGE_WEIGHT * 0.25);
-            
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::BatteryStorage, DEFAULT_COST_MULTIPLIER), BATTERY_STORAGE_WEIGHT);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::BatteryStorage, FAST_COST_MULTIPLIER), BATTERY_STORAGE_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::BatteryStorage, VERY_FAST_COST_MULTIPLIER), BATTERY_STORAGE_WEIGHT * 0.25);
-            
-            // Initialize marine generator weights
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::TidalGenerator, DEFAULT_COST_MULTIPLIER), TIDAL_GENERATOR_WEIGHT);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::TidalGenerator, FAST_COST_MULTIPLIER), TIDAL_GENERATOR_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::TidalGenerator, VERY_FAST_COST_MULTIPLIER), TIDAL_GENERATOR_WEIGHT * 0.25);
+            // Initialize generator weights, with faster (costlier) builds weighted lower
+            for gen_type in GeneratorType::iter() {
+                let base_weight = Self::default_generator_weight(&gen_type);
+                let mut speed_weight = base_weight;
+                for &multiplier in Self::build_speed_multipliers(&gen_type) {
+                    year_weights.insert(GridActionKind::AddGenerator(gen_type.clone(), multiplier), speed_weight);
+                    speed_weight *= 0.5;
+                }
+            }
             
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::WaveEnergy, DEFAULT_COST_MULTIPLIER), WAVE_ENERGY_WEIGHT);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::WaveEnergy, FAST_COST_MULTIPLIER), WAVE_ENERGY_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddGenerator(GeneratorType::WaveEnergy, VERY_FAST_COST_MULTIPLIER), WAVE_ENERGY_WEIGHT * 0.25);
+            // Initialize co-located hybrid plant weights
+            year_weights.insert(GridActionKind::AddHybridPlant(GeneratorType::UtilitySolar, GeneratorType::BatteryStorage), HYBRID_PLANT_WEIGHT);
+            year_weights.insert(GridActionKind::AddHybridPlant(GeneratorType::OnshoreWind, GeneratorType::BatteryStorage), HYBRID_PLANT_WEIGHT);
+            year_weights.insert(GridActionKind::AddFlexibleLoad, FLEXIBLE_LOAD_WEIGHT);
             
             // Initialize carbon offset weights
-            year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::Forest, DEFAULT_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT);
-            year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::Forest, FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::Forest, VERY_FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.25);
+            year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::Forest, DEFAULT_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT);
+            year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::Forest, FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.5);
+            year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::Forest, VERY_FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.25);
             
-            year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::Wetland, DEFAULT_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT);
-            year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::Wetland, FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::Wetland, VERY_FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.25);
+            year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::Wetland, DEFAULT_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT);
+            year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::Wetland, FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.5);
+            year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::Wetland, VERY_FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.25);
             
-            year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::ActiveCapture, DEFAULT_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT);
-            year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::ActiveCapture, FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::ActiveCapture, VERY_FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.25);
+            year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::ActiveCapture, DEFAULT_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT);
+            year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::ActiveCapture, FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.5);
+            year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::ActiveCapture, VERY_FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.25);
             
-            year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::CarbonCredit, DEFAULT_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT);
-            year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::CarbonCredit, FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.5);
-            year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::CarbonCredit, VERY_FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.25);
+            year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::CarbonCredit, DEFAULT_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT);
+            year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::CarbonCredit, FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.5);
+            year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::CarbonCredit, VERY_FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.25);
             
             // Initialize other action weights
-            year_weights.insert(GridAction::UpgradeEfficiency(String::new()), UPGRADE_EFFICIENCY_WEIGHT);
-            year_weights.insert(GridAction::AdjustOperation(String::new(), OPERATION_PERCENTAGE_MIN), ADJUST_OPERATION_WEIGHT);
-            year_weights.insert(GridAction::CloseGenerator(String::new()), CLOSE_GENERATOR_WEIGHT);
-            year_weights.insert(GridAction::DoNothing, DO_NOTHING_WEIGHT);
+            year_weights.insert(GridActionKind::UpgradeEfficiency, UPGRADE_EFFICIENCY_WEIGHT);
+            year_weights.insert(GridActionKind::AdjustOperation, ADJUST_OPERATION_WEIGHT);
+            year_weights.insert(GridActionKind::CloseGenerator, CLOSE_GENERATOR_WEIGHT);
+            year_weights.insert(GridActionKind::RetrofitGenerator(GeneratorType::Biomass), RETROFIT_GENERATOR_WEIGHT);
+            year_weights.insert(GridActionKind::DoNothing, DO_NOTHING_WEIGHT);
+            year_weights.insert(GridActionKind::DeferDecision, DEFER_DECISION_WEIGHT);
             
             // Add year's weights to the map
             weights.insert(year, year_weights);
 
             // Initialize deficit handling weights with a separate set of weights
             // focused on reliable power generation options
-            let mut deficit_year_weights = HashMap::new();
-            
-            // For deficit handling, prioritize fast-responding and reliable generators
-            deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::GasPeaker, DEFAULT_COST_MULTIPLIER), DEFICIT_GAS_PEAKER_WEIGHT);
-            deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::GasCombinedCycle, DEFAULT_COST_MULTIPLIER), DEFICIT_GAS_COMBINED_WEIGHT);
-            deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::BatteryStorage, DEFAULT_COST_MULTIPLIER), DEFICIT_BATTERY_WEIGHT);
-            deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::PumpedStorage, DEFAULT_COST_MULTIPLIER), DEFICIT_PUMPED_STORAGE_WEIGHT);
-            deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::Biomass, DEFAULT_COST_MULTIPLIER), DEFICIT_BIOMASS_WEIGHT);
-            
-            // Include renewables with lower initial weights for deficit handling
-            deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::OnshoreWind, DEFAULT_COST_MULTIPLIER), DEFICIT_ONSHORE_WIND_WEIGHT);
-            deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::OffshoreWind, DEFAULT_COST_MULTIPLIER), DEFICIT_OFFSHORE_WIND_WEIGHT);
-            deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::UtilitySolar, DEFAULT_COST_MULTIPLIER), DEFICIT_UTILITY_SOLAR_WEIGHT);
-            deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::HydroDam, DEFAULT_COST_MULTIPLIER), DEFICIT_HYDRO_DAM_WEIGHT);
-            
-            // Include nuclear with a lower weight due to long build time
-            deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::Nuclear, DEFAULT_COST_MULTIPLIER), DEFICIT_NUCLEAR_WEIGHT);
-            
-            // Add other types with minimal weights
-            deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::DomesticSolar, DEFAULT_COST_MULTIPLIER), DEFICIT_SMALL_GENERATOR_WEIGHT);
-            deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::CommercialSolar, DEFAULT_COST_MULTIPLIER), DEFICIT_SMALL_GENERATOR_WEIGHT);
-            deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::TidalGenerator, DEFAULT_COST_MULTIPLIER), DEFICIT_SMALL_GENERATOR_WEIGHT);
-            deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::WaveEnergy, DEFAULT_COST_MULTIPLIER), DEFICIT_SMALL_GENERATOR_WEIGHT);
-            
-            // DoNothing should have very low weight for deficit handling
-            deficit_year_weights.insert(GridAction::DoNothing, DEFICIT_DO_NOTHING_WEIGHT);
+            let deficit_year_weights = Self::default_deficit_year_weights();
             
             // Add deficit weights for this year
             deficit_weights.insert(year, deficit_year_weights);
@@ -187,7 +227,7 @@ impl ActionWeights {
             
             // Print the initial weights for visibility only if debug weights is enabled
             if year == START_YEAR && crate::ai::learning::constants::is_debug_weights_enabled() {
-                println!("\nInitial action count weights:");
+                crate::log!(Debug, "\nInitial action count weights:");
                 let instance = ActionWeights {
                     weights: HashMap::new(),
                     action_count_weights: HashMap::from([(year, count_weights.clone())]),
@@ -206,8 +246,14 @@ impl ActionWeights {
                     deterministic_rng: None,
                     guaranteed_best_actions: false,
                     optimization_mode: None,
+                    scoring: ScoringContext::default(),
                     replay_index: HashMap::new(),
                     improvement_history: Vec::new(),
+                    stagnation_policy: StagnationPolicy::default(),
+                    contrast_policy: ContrastLearningPolicy::default(),
+                    exploration_schedule: ExplorationSchedule::default(),
+                    recent_scores: VecDeque::new(),
+                    noop_max_share: NOOP_MAX_WEIGHT_SHARE,
                 };
                 instance.print_action_count_weights(year);
             }
@@ -216,8 +262,8 @@ impl ActionWeights {
         }
         
         // DIAGNOSTIC: Add logging to check ActionWeights initialization
-        println!("DIAGNOSTIC: Initializing new ActionWeights");
-        println!("  - Starting with weights for {} years", weights.len());
+        crate::log!(Debug, "DIAGNOSTIC: Initializing new ActionWeights");
+        crate::log!(Debug, "  - Starting with weights for {} years", weights.len());
         
         let instance = Self {
             weights,
@@ -237,14 +283,20 @@ impl ActionWeights {
             deterministic_rng: None,
             guaranteed_best_actions: false,
             optimization_mode: None,
+            scoring: ScoringContext::default(),
             replay_index: HashMap::new(),
             improvement_history: Vec::new(),
+            stagnation_policy: StagnationPolicy::default(),
+            contrast_policy: ContrastLearningPolicy::default(),
+            exploration_schedule: ExplorationSchedule::default(),
+            recent_scores: VecDeque::new(),
+            noop_max_share: NOOP_MAX_WEIGHT_SHARE,
         };
         
         // DIAGNOSTIC: Log the created instance details
-        println!("  - Instance created with iteration_count: {}", instance.iteration_count);
-        println!("  - Instance has best_metrics: {}", instance.best_metrics.is_some());
-        println!("  - Instance has best_actions: {}", instance.best_actions.is_some());
+        crate::log!(Debug, "  - Instance created with iteration_count: {}", instance.iteration_count);
+        crate::log!(Debug, "  - Instance has best_metrics: {}", instance.best_metrics.is_some());
+        crate::log!(Debug, "  - Instance has best_actions: {}", instance.best_actions.is_some());
         
         instance
     }
@@ -253,9 +305,111 @@ impl ActionWeights {
         self.deterministic_rng = Some(rng);
     }
 
+    pub fn set_stagnation_policy(&mut self, policy: StagnationPolicy) {
+        self.stagnation_policy = policy;
+    }
+
+    pub fn set_contrast_policy(&mut self, policy: ContrastLearningPolicy) {
+        self.contrast_policy = policy;
+    }
+
+    pub fn set_contrast_learning_enabled(&mut self, enabled: bool) {
+        self.contrast_policy.enabled = enabled;
+    }
+
+    pub fn set_exploration_schedule(&mut self, schedule: ExplorationSchedule) {
+        self.exploration_schedule = schedule;
+    }
+
+    pub fn set_noop_max_share(&mut self, share: f64) {
+        self.noop_max_share = share.clamp(ZERO_F64, ONE_F64);
+    }
+
+    // Keeps the last ADAPTIVE_EXPLORATION_WINDOW scores for the adaptive exploration schedule
+    pub fn record_recent_score(&mut self, score: f64) {
+        self.recent_scores.push_back(score);
+        while self.recent_scores.len() > ADAPTIVE_EXPLORATION_WINDOW {
+            self.recent_scores.pop_front();
+        }
+    }
+
+    // Scales `base_rate` up when the recent scores barely move and the search has stalled for
+    // most of a window, and down in proportion to how often the window set a new high
+    pub fn adaptive_exploration_rate(&self, base_rate: f64) -> f64 {
+        if self.recent_scores.len() < 2 {
+            return base_rate;
+        }
+
+        let count = self.recent_scores.len() as f64;
+        let mean = self.recent_scores.iter().sum::<f64>() / count;
+        let variance = self.recent_scores.iter().map(|score| (score - mean).powi(2)).sum::<f64>() / count;
+        let relative_spread = variance.sqrt() / mean.abs().max(ONE_F64);
+        let flatness = ONE_F64 - (relative_spread / ADAPTIVE_EXPLORATION_FLAT_SPREAD).min(ONE_F64);
+        let stagnation = (self.iterations_without_improvement as f64 / ADAPTIVE_EXPLORATION_WINDOW as f64).min(ONE_F64);
+
+        let mut running_best = self.recent_scores[0];
+        let mut new_highs = 0;
+        for &score in self.recent_scores.iter().skip(1) {
+            if score > running_best {
+                running_best = score;
+                new_highs += 1;
+            }
+        }
+        let improvement_rate = new_highs as f64 / (count - ONE_F64);
+
+        let factor = ONE_F64 + ADAPTIVE_EXPLORATION_BOOST * flatness * stagnation
+            - ADAPTIVE_EXPLORATION_DAMPING * improvement_rate;
+        (base_rate * factor).clamp(MIN_ADAPTIVE_EXPLORATION_RATE, MAX_ADAPTIVE_EXPLORATION_RATE)
+    }
+
+    /// Zeroes every build action for the given generator types in `year`, including hybrid
+    /// plants that use one of them, in both the regular and deficit weights.
+    pub fn zero_generator_type_weights(&mut self, year: u32, banned_types: &[GeneratorType]) {
+        if banned_types.is_empty() {
+            return;
+        }
+        let is_banned = |kind: &GridActionKind| match kind {
+            GridActionKind::AddGenerator(gen_type, _) => banned_types.contains(gen_type),
+            GridActionKind::AddHybridPlant(primary, secondary) => {
+                banned_types.contains(primary) || banned_types.contains(secondary)
+            }
+            _ => false,
+        };
+        for year_weights in [self.weights.get_mut(&year), self.deficit_weights.get_mut(&year)].into_iter().flatten() {
+            for (kind, weight) in year_weights.iter_mut() {
+                if is_banned(kind) {
+                    *weight = ZERO_F64;
+                }
+            }
+        }
+    }
+
+    /// Drops per-year actions weighted below `threshold` from the regular and deficit weights,
+    /// keeping `DoNothing` and at least one action per year. Pruned actions come back at
+    /// DEFAULT_WEIGHT if `update_weights` touches them again. Returns the number removed.
+    pub fn prune_weights(&mut self, threshold: f64) -> usize {
+        let mut pruned = 0;
+        for year_weights in self.weights.values_mut().chain(self.deficit_weights.values_mut()) {
+            let mut prunable: Vec<(GridActionKind, f64)> = year_weights.iter()
+                .filter(|(kind, weight)| **weight < threshold && !matches!(kind, GridActionKind::DoNothing))
+                .map(|(kind, weight)| (kind.clone(), *weight))
+                .collect();
+            if prunable.len() == year_weights.len() {
+                // Everything is below the threshold: keep the strongest action so the year stays sampleable
+                prunable.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+                prunable.pop();
+            }
+            for (kind, _) in prunable {
+                year_weights.remove(&kind);
+                pruned += 1;
+            }
+        }
+        pruned
+    }
+
     pub fn start_new_iteration(&mut self) {
         // DIAGNOSTIC: Log the beginning of a new iteration
-        println!("DIAGNOSTIC: Starting iteration {}", self.iteration_count + 1);
+        crate::log!(Debug, "DIAGNOSTIC: Starting iteration {}", self.iteration_count + 1);
         
         // Clear current actions
         self.current_run_actions.clear();
@@ -264,46 +418,49 @@ impl ActionWeights {
         
         // Adjust exploration rate based on iteration count and stagnation
         self.exploration_rate = DEFAULT_EXPLORATION_RATE * (ONE_F64 / (ONE_F64 + EXPLORATION_DECAY_RATE * self.iteration_count as f64));
+        if self.exploration_schedule == ExplorationSchedule::Adaptive {
+            self.exploration_rate = self.adaptive_exploration_rate(self.exploration_rate);
+        }
         
         // Increase exploration if we have stagnated
         if self.iterations_without_improvement > ZERO_U32 {
             if self.iterations_without_improvement % SMALL_LOG_INTERVAL == ZERO_U32 {
-                println!("⚠️ Currently at {} iterations without improvement", self.iterations_without_improvement);
+                crate::log!(Info, "⚠️ Currently at {} iterations without improvement", self.iterations_without_improvement);
             }
             
-            // Removed weight restoration to allow contrast learning to have more effect
-            // Previously restored best weights here when iterations_without_improvement > HIGH_ITERATION_THRESHOLD
+            let policy = self.stagnation_policy;
             
-            if self.iterations_without_improvement > FORCE_REPLAY_THRESHOLD {
+            if self.iterations_without_improvement > policy.replay_threshold {
+                // Weight restoration is off by default so contrast learning has more effect
+                if policy.restore_fraction > ZERO_F64 {
+                    self.restore_best_weights(policy.restore_fraction);
+                }
+                
                 // Based on how long we've been stagnant, consider forcing replay of best actions
-                let force_replay_probability = ((self.iterations_without_improvement - FORCE_REPLAY_THRESHOLD) as f64 / FORCE_REPLAY_DIVISOR).min(PERCENTAGE_THRESHOLD);
+                let force_replay_probability = ((self.iterations_without_improvement - policy.replay_threshold) as f64 / FORCE_REPLAY_DIVISOR).min(PERCENTAGE_THRESHOLD);
                 
                 // Get a random number for decision
-                let random_value = if let Some(ref mut rng) = self.deterministic_rng {
-                    rng.gen::<f64>()
-                } else {
-                    rand::thread_rng().gen::<f64>()
-                };
+                let random_value = self.rng().gen::<f64>();
                 
                 // Decide whether to force replay based on probability
                 self.force_best_actions = random_value < force_replay_probability;
                 
                 if self.force_best_actions {
-                    println!("🔁 After {} iterations without improvement, forcing replay of best actions (p={:.1}%)", 
+                    crate::log!(Info, "🔁 After {} iterations without improvement, forcing replay of best actions (p={:.1}%)", 
                             self.iterations_without_improvement, force_replay_probability * PERCENT_CONVERSION);
                 }
             }
             
             // If we've been stagnant for a very long time, try more aggressive randomization
-            if self.iterations_without_improvement > ITERATIONS_FOR_RANDOMIZATION {
-                println!("⚠️ EXTREME stagnation: Random reset after {} iterations without improvement", 
+            if self.iterations_without_improvement > policy.randomization_threshold {
+                crate::log!(Info, "⚠️ EXTREME stagnation: Random reset after {} iterations without improvement", 
                         self.iterations_without_improvement);
                 
                 // Apply randomization to weights
-                let mut rng = rand::thread_rng();
+                let mut rng = RngSource::new(self.deterministic_rng.as_mut());
                 for year_weights in self.weights.values_mut() {
                     for weight in year_weights.values_mut() {
-                        let random_factor = ONE_F64 + RANDOMIZATION_FACTOR * (rng.gen::<f64>() * RANDOM_RANGE_MULTIPLIER - ONE_F64);
+                        let random_factor = ONE_F64 + policy.randomization_magnitude * (rng.gen::<f64>() * RANDOM_RANGE_MULTIPLIER - ONE_F64);
                         *weight = (*weight * random_factor).clamp(MIN_WEIGHT, MAX_WEIGHT);
                     }
                 }
@@ -311,19 +468,19 @@ impl ActionWeights {
         }
         
         // DIAGNOSTIC: Log the iteration preparation details
-        println!("  - Cleared action records for current run");
-        println!("  - Set exploration_rate to {:.6}", self.exploration_rate);
-        println!("  - force_best_actions: {}", self.force_best_actions);
-        println!("  - iterations_without_improvement: {}", self.iterations_without_improvement);
+        crate::log!(Debug, "  - Cleared action records for current run");
+        crate::log!(Debug, "  - Set exploration_rate to {:.6}", self.exploration_rate);
+        crate::log!(Debug, "  - force_best_actions: {}", self.force_best_actions);
+        crate::log!(Debug, "  - iterations_without_improvement: {}", self.iterations_without_improvement);
     }
 
-    pub fn get_year_weights(&self, year: u32) -> Option<&HashMap<GridAction, f64>> {
+    pub fn get_year_weights(&self, year: u32) -> Option<&HashMap<GridActionKind, f64>> {
         self.weights.get(&year)
     }
 
     pub fn get_best_metrics(&self) -> Option<(f64, bool)> {
         self.best_metrics.as_ref().map(|metrics| {
-            (score_metrics(metrics, self.optimization_mode.as_deref()), metrics.final_net_emissions <= ZERO_F64)
+            (score_metrics(metrics, self.optimization_mode.as_deref(), &self.scoring), metrics.is_net_zero_under(self.scoring.net_zero_tolerance, self.scoring.net_zero_real_reductions_only))
         })
     }
 
@@ -350,6 +507,10 @@ impl ActionWeights {
         self.optimization_mode = mode;
     }
 
+    pub fn set_scoring_context(&mut self, scoring: ScoringContext) {
+        self.scoring = scoring;
+    }
+
     pub fn get_optimization_mode(&self) -> Option<&str> {
         self.optimization_mode.as_deref()
     }
@@ -375,3 +536,136 @@ impl ActionWeights {
         self.improvement_history.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::ai::actions::grid_action::GridAction;
+
+    fn first_iteration_wind_picks(mut weights: ActionWeights) -> usize {
+        weights.set_rng(StdRng::seed_from_u64(42));
+        (0..500)
+            .filter(|_| matches!(weights.sample_action(2025), GridAction::AddGenerator(GeneratorType::OnshoreWind, _)))
+            .count()
+    }
+
+    #[test]
+    fn heavy_wind_prior_biases_first_iteration_sampling() {
+        let prior = HashMap::from([(GeneratorType::OnshoreWind, 0.9), (GeneratorType::GasCombinedCycle, 0.1)]);
+
+        let uniform = first_iteration_wind_picks(ActionWeights::new());
+        let wind_heavy = first_iteration_wind_picks(ActionWeights::new().with_prior(&prior));
+
+        assert!(wind_heavy > 2 * uniform, "wind picked {} times with the prior, {} without", wind_heavy, uniform);
+    }
+
+    fn weights_after_extreme_stagnation(randomization_magnitude: f64) -> (ActionWeights, ActionWeights) {
+        let mut weights = ActionWeights::new();
+        let policy = StagnationPolicy { randomization_magnitude, ..StagnationPolicy::default() };
+        weights.set_stagnation_policy(policy);
+        weights.set_rng(StdRng::seed_from_u64(7));
+        weights.iterations_without_improvement = policy.randomization_threshold + 1;
+        let before = weights.clone();
+        weights.start_new_iteration();
+        (before, weights)
+    }
+
+    #[test]
+    fn zero_magnitude_randomization_leaves_weights_unchanged() {
+        let (before, after) = weights_after_extreme_stagnation(0.0);
+        assert_eq!(after.weights, before.weights);
+
+        // The default magnitude does move them, so the threshold really was crossed
+        let (before, after) = weights_after_extreme_stagnation(StagnationPolicy::default().randomization_magnitude);
+        assert_ne!(after.weights, before.weights);
+    }
+
+    #[test]
+    fn pruning_removes_a_sub_threshold_action_but_keeps_do_nothing() {
+        let year = 2030;
+        let threshold = 0.01;
+        let faint = GridAction::AddGenerator(GeneratorType::GasPeaker, 100);
+        let mut weights = ActionWeights::new();
+        let year_weights = weights.weights.get_mut(&year).unwrap();
+        year_weights.insert(faint.kind(), threshold / 10.0);
+        year_weights.insert(GridActionKind::DoNothing, threshold / 10.0);
+
+        assert!(weights.prune_weights(threshold) >= 1);
+        let year_weights = weights.get_year_weights(year).unwrap();
+        assert!(!year_weights.contains_key(&faint.kind()));
+        assert!(year_weights.contains_key(&GridActionKind::DoNothing));
+
+        // Touching the pruned action again brings it back
+        weights.update_weights(&faint, year, 0.5);
+        assert!(weights.get_year_weights(year).unwrap().contains_key(&faint.kind()));
+    }
+
+    #[test]
+    fn flat_reward_window_raises_the_adaptive_exploration_rate() {
+        let rate_after = |scores: &[f64], schedule: ExplorationSchedule| {
+            let mut weights = ActionWeights::new();
+            weights.set_exploration_schedule(schedule);
+            weights.iteration_count = 50;
+            weights.iterations_without_improvement = ADAPTIVE_EXPLORATION_WINDOW as u32;
+            for &score in scores {
+                weights.record_recent_score(score);
+            }
+            weights.start_new_iteration();
+            weights.exploration_rate
+        };
+
+        let flat = [0.5; ADAPTIVE_EXPLORATION_WINDOW];
+        let improving: Vec<f64> = (0..ADAPTIVE_EXPLORATION_WINDOW).map(|i| 0.1 + 0.04 * i as f64).collect();
+
+        let fixed_decay = rate_after(&flat, ExplorationSchedule::Decay);
+        let adaptive_flat = rate_after(&flat, ExplorationSchedule::Adaptive);
+        let adaptive_improving = rate_after(&improving, ExplorationSchedule::Adaptive);
+
+        assert!(adaptive_flat > fixed_decay, "adaptive {} vs decay {}", adaptive_flat, fixed_decay);
+        assert!(adaptive_flat > adaptive_improving);
+    }
+
+    // No wildcard arm, so adding a variant without listing it here fails to compile
+    fn variant_index(generator_type: &GeneratorType) -> usize {
+        match generator_type {
+            GeneratorType::OnshoreWind => 0,
+            GeneratorType::OffshoreWind => 1,
+            GeneratorType::FloatingOffshoreWind => 2,
+            GeneratorType::DomesticSolar => 3,
+            GeneratorType::CommercialSolar => 4,
+            GeneratorType::UtilitySolar => 5,
+            GeneratorType::Nuclear => 6,
+            GeneratorType::CoalPlant => 7,
+            GeneratorType::GasCombinedCycle => 8,
+            GeneratorType::GasPeaker => 9,
+            GeneratorType::Biomass => 10,
+            GeneratorType::HydroDam => 11,
+            GeneratorType::PumpedStorage => 12,
+            GeneratorType::BatteryStorage => 13,
+            GeneratorType::TidalGenerator => 14,
+            GeneratorType::WaveEnergy => 15,
+            GeneratorType::HydrogenElectrolyzer => 16,
+            GeneratorType::HydrogenTurbine => 17,
+        }
+    }
+
+    #[test]
+    fn every_generator_type_is_listed_once_and_has_a_default_weight() {
+        let mut indices: Vec<usize> = GeneratorType::all().iter().map(variant_index).collect();
+        indices.sort_unstable();
+        assert_eq!(indices, (0..18).collect::<Vec<_>>());
+        assert_eq!(GeneratorType::iter().count(), GeneratorType::all().len());
+
+        let weights = ActionWeights::new();
+        for (year, year_weights) in &weights.weights {
+            for generator_type in GeneratorType::iter() {
+                assert!(
+                    year_weights.keys().any(|kind| matches!(kind, GridActionKind::AddGenerator(t, _) if *t == generator_type)),
+                    "no {} weight in {}", generator_type, year
+                );
+            }
+        }
+        assert!(!weights.weights.is_empty());
+    }
+}
@@ -1,8 +1,40 @@
 // Diagnostic and debugging functions for ActionWeights
 
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use serde::{Deserialize, Serialize};
+use crate::ai::actions::grid_action::GridAction;
+use crate::ai::actions::grid_action_kind::GridActionKind;
 use crate::ai::learning::constants::*;
+use crate::analysis::metrics_calculation::calculate_yearly_metrics;
+use crate::config::constants::BASE_YEAR;
+use crate::core::actions::apply_action;
+use crate::core::simulation::update_population;
+use crate::utils::map_handler::Map;
 use super::ActionWeights;
 
+/// Marginal effect of a single action from the best plan, measured in the year it was applied
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionImpact {
+    pub year: u32,
+    pub action: GridAction,
+    pub emissions_change: f64,  // Change in net emissions that year (negative is a reduction)
+    pub cost_change: f64,       // Change in that year's total cost
+}
+
+/// Shape of the best plan: how many years took each number of actions, and the overall action mix
+#[derive(Debug, Clone, Default)]
+pub struct ActionCountHistogram {
+    pub years_by_action_count: BTreeMap<usize, usize>,  // Actions taken in a year -> years with that many
+    pub kind_counts: Vec<(GridActionKind, usize)>,      // Most frequent kinds first
+}
+
+impl ActionCountHistogram {
+    pub fn total_actions(&self) -> usize {
+        self.kind_counts.iter().map(|(_, count)| count).sum()
+    }
+}
+
 // Add a dummy public item to ensure this file is recognized by rust-analyzer
 #[allow(dead_code)]
 pub const MODULE_MARKER: &str = "diagnostics_module";
@@ -37,13 +69,13 @@ impl ActionWeights {
         // Only print detailed metrics if we're at a 100-iteration mark
         if self.iteration_count % 100 == 0 {
             // Print the distribution of actions per year
-            println!("Action distribution per year:");
+            self.print_action_count_histogram();
             
             // If we have best metrics, also show those
             if let Some(ref metrics) = self.best_metrics {
                 println!("✅ Best metrics recorded:");
                 println!("  Net emissions: {:.2} tonnes", metrics.final_net_emissions);
-                println!("  Is net zero: {}", if metrics.final_net_emissions <= ZERO_F64 { "true" } else { "false" });
+                println!("  Is net zero: {}", if metrics.is_net_zero_under(self.scoring.net_zero_tolerance, self.scoring.net_zero_real_reductions_only) { "true" } else { "false" });
                 println!("  Total cost: €{:.2}B", metrics.total_cost / BILLION_DIVISOR);
                 println!("  Public opinion: {:.1}%", metrics.average_public_opinion * PERCENT_CONVERSION);
                 println!("  Power reliability: {:.1}%", metrics.power_reliability * PERCENT_CONVERSION);
@@ -103,16 +135,77 @@ impl ActionWeights {
         let min_year = START_YEAR;
         let max_year = END_YEAR;
         
-        println!("  Per-year deficit action counts:");
+        crate::log!(Debug, "  Per-year deficit action counts:");
         for year in min_year..=max_year {
             if let Some(actions) = self.current_deficit_actions.get(&year) {
                 if !actions.is_empty() {
-                    println!("    Year {}: {} deficit actions", year, actions.len());
+                    crate::log!(Debug, "    Year {}: {} deficit actions", year, actions.len());
                 }
             }
         }
     }
 
+    /// Replays the best plan year by year and measures how much each action moved that
+    /// year's net emissions and cost, ranked by largest emissions reduction first.
+    /// Actions held up by construction delays only show their capital cost in the year applied.
+    pub fn attribute_impact(&self, base_map: &Map, enable_energy_sales: bool) -> Vec<ActionImpact> {
+        let plan = self.export_plan();
+        let mut map = base_map.clone();
+        let mut previous = None;
+        let mut impacts = Vec::with_capacity(plan.len());
+
+        for year in BASE_YEAR..=END_YEAR {
+            map.current_year = year;
+            map.update_construction_status();
+            map.add_scheduled_builds(year);
+            update_population(&mut map, year);
+
+            for (_, action) in plan.iter().filter(|(action_year, _)| *action_year == year) {
+                let before = calculate_yearly_metrics(&map, year, 0.0, 0.0, enable_energy_sales, previous.as_ref());
+                if let Err(e) = apply_action(&mut map, action, year, None) {
+                    eprintln!("Warning: failed to replay action {:?} in {}: {}", action, year, e);
+                    continue;
+                }
+                let after = calculate_yearly_metrics(&map, year, 0.0, 0.0, enable_energy_sales, previous.as_ref());
+                impacts.push(ActionImpact {
+                    year,
+                    action: action.clone(),
+                    emissions_change: after.net_co2_emissions - before.net_co2_emissions,
+                    cost_change: after.yearly_total_cost - before.yearly_total_cost,
+                });
+            }
+
+            let metrics = calculate_yearly_metrics(&map, year, 0.0, 0.0, enable_energy_sales, previous.as_ref());
+            map.record_opinion_trend(&metrics);
+            previous = Some(metrics);
+        }
+
+        // Biggest emissions drop first, cheaper action wins a tie, then plan order
+        impacts.sort_by(|a, b| {
+            a.emissions_change.partial_cmp(&b.emissions_change).unwrap_or(Ordering::Equal)
+                .then_with(|| a.cost_change.partial_cmp(&b.cost_change).unwrap_or(Ordering::Equal))
+                .then_with(|| a.year.cmp(&b.year))
+        });
+        impacts
+    }
+
+    pub fn print_impact_attribution(impacts: &[ActionImpact], n: usize) {
+        if impacts.is_empty() {
+            println!("\n❌ No best plan recorded, nothing to attribute");
+            return;
+        }
+
+        println!("\n📊 Top {} actions by emissions impact:", n.min(impacts.len()));
+        for (i, impact) in impacts.iter().take(n).enumerate() {
+            println!("{:2}. [{}] {:?}: {:+.2} tonnes CO2, {:+.2}M €",
+                i + 1,
+                impact.year,
+                impact.action,
+                impact.emissions_change,
+                impact.cost_change / MILLION_DIVISOR);
+        }
+    }
+
     pub fn print_action_count_weights(&self, year: u32) {
         if let Some(year_counts) = self.action_count_weights.get(&year) {
             println!("\n📊 Action Count Weights for Year {}:", year);
@@ -138,4 +231,90 @@ impl ActionWeights {
         }
     }
 
+    pub fn action_count_histogram(&self) -> ActionCountHistogram {
+        let mut histogram = ActionCountHistogram::default();
+        let Some(best_actions) = &self.best_actions else {
+            return histogram;
+        };
+
+        let mut kind_counts: HashMap<GridActionKind, usize> = HashMap::new();
+        for actions in best_actions.values() {
+            *histogram.years_by_action_count.entry(actions.len()).or_insert(0) += 1;
+            for action in actions {
+                *kind_counts.entry(action.kind()).or_insert(0) += 1;
+            }
+        }
+        histogram.kind_counts = kind_counts.into_iter().collect();
+        histogram.kind_counts.sort_by(|(kind_a, a), (kind_b, b)| {
+            b.cmp(a).then_with(|| kind_a.to_string().cmp(&kind_b.to_string()))
+        });
+        histogram
+    }
+
+    pub fn print_action_count_histogram(&self) {
+        let histogram = self.action_count_histogram();
+        if histogram.years_by_action_count.is_empty() {
+            println!("\n❌ No best actions recorded, no action distribution to show");
+            return;
+        }
+
+        println!("\n📊 Actions per year in the best plan ({} total):", histogram.total_actions());
+        let max_years = histogram.years_by_action_count.values().copied().max().unwrap_or(1);
+        for (count, years) in &histogram.years_by_action_count {
+            let bar = "#".repeat((*years as f64 / max_years as f64 * 50.0).round() as usize);
+            println!("{:3} actions: {:3} years | {}", count, years, bar);
+        }
+
+        println!("Action mix:");
+        for (kind, count) in &histogram.kind_counts {
+            println!("  {:40} {:4}", kind.to_string(), count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::poi::Coordinate;
+    use crate::models::generator::GeneratorType;
+    use crate::test_support::{small_map, test_generator};
+
+    #[test]
+    fn largest_emission_drop_ranks_first() {
+        let mut map = small_map();
+        map.add_generator(test_generator("Old_Coal", GeneratorType::CoalPlant, Coordinate::new(30_000.0, 20_000.0), 300.0, 2.0e6));
+
+        let mut weights = ActionWeights::new();
+        weights.best_actions = Some(HashMap::from([
+            (2026, vec![GridAction::AdjustOperation("Existing_Gas".to_string(), 50)]),
+            (2027, vec![GridAction::CloseGenerator("Old_Coal".to_string())]),
+            (2028, vec![GridAction::AddGenerator(GeneratorType::OnshoreWind, 100)]),
+        ]));
+
+        let impacts = weights.attribute_impact(&map, true);
+
+        assert_eq!(impacts.len(), 3);
+        assert_eq!(impacts[0].action, GridAction::CloseGenerator("Old_Coal".to_string()));
+        assert!(impacts[0].emissions_change < impacts[1].emissions_change);
+    }
+
+    #[test]
+    fn histogram_totals_match_the_stored_best_actions() {
+        let mut weights = ActionWeights::new();
+        weights.best_actions = Some(HashMap::from([
+            (2026, vec![GridAction::AddGenerator(GeneratorType::GasPeaker, 100), GridAction::AddGenerator(GeneratorType::GasPeaker, 100)]),
+            (2027, vec![GridAction::DoNothing]),
+            (2028, vec![GridAction::AddGenerator(GeneratorType::OnshoreWind, 100), GridAction::DoNothing]),
+            (2029, vec![]),
+        ]));
+        let stored: usize = weights.best_actions.as_ref().unwrap().values().map(Vec::len).sum();
+
+        let histogram = weights.action_count_histogram();
+
+        assert_eq!(histogram.total_actions(), stored);
+        let from_years: usize = histogram.years_by_action_count.iter().map(|(count, years)| count * years).sum();
+        assert_eq!(from_years, stored);
+        assert_eq!(histogram.years_by_action_count.values().sum::<usize>(), 4);
+        assert_eq!(histogram.years_by_action_count[&2], 2);
+    }
 }
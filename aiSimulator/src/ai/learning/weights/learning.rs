@@ -2,12 +2,15 @@
 //!
 //! This module contains learning-related functionality for the ActionWeights struct.
 
+use std::collections::HashMap;
 use rand::Rng;
 use crate::ai::actions::grid_action::GridAction;
+use crate::ai::actions::grid_action_kind::GridActionKind;
 use crate::ai::metrics::simulation_metrics::SimulationMetrics;
 use crate::ai::learning::constants::*;
 use crate::ai::score_metrics;
 use super::ActionWeights;
+use super::rng::RngSource;
 
 // Add a dummy public item to ensure this file is recognized by rust-analyzer
 #[allow(dead_code)]
@@ -19,6 +22,9 @@ impl ActionWeights {
 // Appropriate imports will need to be added based on the specific requirements
 
     pub fn update_weights(&mut self, action: &GridAction, year: u32, improvement: f64) {
+        // Every instance of the same kind of action shares one weight
+        let action = &action.kind();
+
         // Ensure we have weights for this year
         if !self.weights.contains_key(&year) {
             self.weights.insert(year, self.initialize_weights());
@@ -34,11 +40,11 @@ impl ActionWeights {
         let current_weight = year_weights.get(action).expect("Weight should exist");
         
         // Get the final 2050 impact score from best metrics if available
-        let final_impact_score = self.best_metrics.as_ref().map_or(ZERO_F64, |metrics| score_metrics(metrics, self.optimization_mode.as_deref()));
+        let final_impact_score = self.best_metrics.as_ref().map_or(ZERO_F64, |metrics| score_metrics(metrics, self.optimization_mode.as_deref(), &self.scoring));
         
         // Calculate the relative improvement compared to the best score
         let relative_improvement = if let Some(best) = &self.best_metrics {
-            let best_score = score_metrics(best, self.optimization_mode.as_deref());
+            let best_score = score_metrics(best, self.optimization_mode.as_deref(), &self.scoring);
             if best_score > ZERO_F64 {
                 (final_impact_score - best_score) / best_score
             } else {
@@ -74,17 +80,41 @@ impl ActionWeights {
         if combined_improvement < ZERO_F64 {
             let boost_factor = ONE_F64 + (self.learning_rate * SMALL_BOOST_FACTOR); // Small boost to alternatives
             for (other_action, weight) in year_weights.iter_mut() {
-                if other_action != action && matches!(other_action, GridAction::AddGenerator(_, _)) {
+                if other_action != action && matches!(other_action, GridActionKind::AddGenerator(_, _)) {
                     *weight = (*weight * boost_factor).min(MAX_WEIGHT);
                 }
             }
             // If we've achieved net zero but are suffering from high costs, further boost DoNothing.
-            if self.best_metrics.as_ref().map(|m| m.final_net_emissions <= ZERO_F64 && m.total_cost > MAX_ACCEPTABLE_COST * HIGH_COST_THRESHOLD_MULTIPLIER).unwrap_or(false) {
-                if let Some(noop_weight) = year_weights.get_mut(&GridAction::DoNothing) {
+            if self.best_metrics.as_ref().map(|m| m.is_net_zero_under(self.scoring.net_zero_tolerance, self.scoring.net_zero_real_reductions_only) && m.total_cost > MAX_ACCEPTABLE_COST * HIGH_COST_THRESHOLD_MULTIPLIER).unwrap_or(false) {
+                if let Some(noop_weight) = year_weights.get_mut(&GridActionKind::DoNothing) {
                     *noop_weight = (*noop_weight * (ONE_F64 + self.learning_rate * NOOP_BOOST_FACTOR)).min(MAX_WEIGHT);
                 }
             }
         }
+        
+        Self::cap_noop_share(year_weights, self.noop_max_share);
+    }
+
+    // Decays DoNothing and DeferDecision back to `max_share` of the year's total weight whenever
+    // boosts push them past, so a plan that is already "good enough" cannot stall the late-game search
+    pub fn cap_noop_share(year_weights: &mut HashMap<GridActionKind, f64>, max_share: f64) {
+        for noop in [GridActionKind::DoNothing, GridActionKind::DeferDecision] {
+            let other_weight: f64 = year_weights.iter()
+                .filter(|(kind, _)| **kind != noop)
+                .map(|(_, weight)| weight)
+                .sum();
+            if let Some(noop_weight) = year_weights.get_mut(&noop) {
+                // noop / (noop + others) <= share  <=>  noop <= others * share / (1 - share)
+                let cap = if max_share >= ONE_F64 {
+                    MAX_WEIGHT
+                } else {
+                    (other_weight * max_share / (ONE_F64 - max_share)).clamp(MIN_WEIGHT, MAX_WEIGHT)
+                };
+                if *noop_weight > cap {
+                    *noop_weight = cap;
+                }
+            }
+        }
     }
 
     pub fn update_action_count_weights(&mut self, year: u32, action_count: u32, improvement: f64) {
@@ -114,8 +144,8 @@ impl ActionWeights {
                 *weight = (*weight * adjustment_factor).max(MIN_ACTION_WEIGHT).min(ONE_F64);
                 
                 // Print information about significant weight updates only if debug weights is enabled
-                if improvement.abs() > 0.05 && crate::ai::learning::constants::is_debug_weights_enabled() {
-                    println!("Updated action count weight for {} actions in year {}: {:.4} (improvement: {:.4}, adjusted: {:.4})",
+                if improvement.abs() > 0.05 {
+                    crate::log!(Debug, "Updated action count weight for {} actions in year {}: {:.4} (improvement: {:.4}, adjusted: {:.4})",
                              action_count, year, *weight, improvement, adjusted_improvement);
                 }
                 
@@ -129,10 +159,15 @@ impl ActionWeights {
     }
 
     pub fn apply_contrast_learning(&mut self, current_metrics: &SimulationMetrics) {
+        if !self.contrast_policy.enabled {
+            return;
+        }
+        let policy = self.contrast_policy;
+        
         // Only apply contrast learning if we have a best run to compare against
         if let (Some(best_metrics), Some(best_actions)) = (&self.best_metrics, &self.best_actions) {
-            let best_score = score_metrics(best_metrics, self.optimization_mode.as_deref());
-            let current_score = score_metrics(current_metrics, self.optimization_mode.as_deref());
+            let best_score = score_metrics(best_metrics, self.optimization_mode.as_deref(), &self.scoring);
+            let current_score = score_metrics(current_metrics, self.optimization_mode.as_deref(), &self.scoring);
             
             // Calculate how much worse the current run is compared to the best
             let deterioration = if best_score > ZERO_F64 {
@@ -143,8 +178,8 @@ impl ActionWeights {
             
             // Calculate dynamic threshold based on iterations without improvement
             // Start at initial threshold (e.g., 10%) and decrease to minimum threshold (e.g., 0.001%) as iterations increase
-            let initial_threshold = CONTRAST_INITIAL_THRESHOLD;
-            let minimum_threshold = CONTRAST_MINIMUM_THRESHOLD;
+            let initial_threshold = policy.divergence_threshold;
+            let minimum_threshold = policy.minimum_threshold;
             let iterations = self.iterations_without_improvement as f64;
             
             // Scale factor determines how quickly the threshold decreases
@@ -161,11 +196,11 @@ impl ActionWeights {
                 // Calculate stagnation penalty with exponential scaling
                 // For stagnation, we want more iterations to have a stronger effect, so we use a power > 1
                 let stagnation_iterations = self.iterations_without_improvement as f64 / STAGNATION_ITERATIONS_DIVISOR;
-                let stagnation_factor = ONE_F64 + (STAGNATION_PENALTY_FACTOR * stagnation_iterations.powf(STAGNATION_EXPONENT));
+                let stagnation_factor = ONE_F64 + (STAGNATION_PENALTY_FACTOR * stagnation_iterations.powf(policy.stagnation_exponent));
                 
                 // Fix the divergence scaling - for values between 0 and 1, using a power < 1 makes them larger
                 // This ensures that worse divergence (higher values) results in stronger penalties
-                let scaled_deterioration = deterioration.powf(DIVERGENCE_EXPONENT);
+                let scaled_deterioration = deterioration.powf(policy.divergence_exponent);
                 
                 // Calculate the combined penalty multiplier
                 let combined_penalty = scaled_deterioration * stagnation_factor;
@@ -206,9 +241,10 @@ impl ActionWeights {
                         .cloned()
                         .unwrap_or_default();
                     
-                    // Combine best regular and deficit actions
+                    // Combine best regular and deficit actions, compared by kind since that is what carries weight
                     let mut complete_best_actions = best_year_actions.clone();
                     complete_best_actions.extend(best_deficit_actions);
+                    let complete_best_kinds: Vec<GridActionKind> = complete_best_actions.iter().map(GridAction::kind).collect();
                     
                     // Identify actions in the current run that differ from the best run
                     if let Some(year_weights) = self.weights.get_mut(year) {
@@ -219,7 +255,7 @@ impl ActionWeights {
                         
                         // First, STRONGLY boost all best actions, regardless of whether they appeared in current run
                         for best_action in &complete_best_actions {
-                            if let Some(weight) = year_weights.get_mut(best_action) {
+                            if let Some(weight) = year_weights.get_mut(&best_action.kind()) {
                                 // Very strong boost to best actions, especially with high stagnation
                                 *weight = (*weight * best_boost_factor).min(MAX_WEIGHT);
                                 boosted_actions.push(best_action.clone());
@@ -229,8 +265,9 @@ impl ActionWeights {
                         // Now process all current actions to possibly penalize
                         for (i, current_action) in current_year_actions.iter().enumerate() {
                             // Only penalize if this action doesn't appear in the best strategy at all
-                            if !complete_best_actions.contains(current_action) {
-                                if let Some(weight) = year_weights.get_mut(current_action) {
+                            let current_kind = current_action.kind();
+                            if !complete_best_kinds.contains(&current_kind) {
+                                if let Some(weight) = year_weights.get_mut(&current_kind) {
                                     *weight = (*weight * penalty_factor).max(MIN_WEIGHT);
                                     if *weight <= MIN_WEIGHT + WEIGHT_PRECISION_THRESHOLD {
                                         min_weight_count += 1;
@@ -241,9 +278,9 @@ impl ActionWeights {
                             } else {
                                 // This action appears in best strategy but might be at wrong time
                                 // Check if it's at the same position
-                                if i < complete_best_actions.len() && current_action != &complete_best_actions[i] {
+                                if i < complete_best_kinds.len() && current_kind != complete_best_kinds[i] {
                                     // It's in the best strategy but in the wrong order - mild penalty
-                                    if let Some(weight) = year_weights.get_mut(current_action) {
+                                    if let Some(weight) = year_weights.get_mut(&current_kind) {
                                         let mild_penalty = ONE_F64 / (ONE_F64 + adaptive_learning_rate * combined_penalty * MILD_PENALTY_FACTOR);
                                         *weight = (*weight * mild_penalty).max(MIN_WEIGHT);
                                         total_weights += 1;
@@ -257,19 +294,19 @@ impl ActionWeights {
                 // Show stats on how many weights were affected
                 if total_weights > 0 && self.iteration_count % 100 == 0 {
                     let total_curr_actions = self.current_run_actions.values().map(|v| v.len()).sum::<usize>();
-                    println!("DEBUG: Current run has {} actions recorded", total_curr_actions);
+                    crate::log!(Debug, "DEBUG: Current run has {} actions recorded", total_curr_actions);
                     println!("   - {}/{} weights ({:.1}%) reduced to minimum value", 
                             min_weight_count, total_weights, (min_weight_count as f64 / total_weights as f64) * PERCENT_CONVERSION);
                 }
 
                 // If we've been stagnating for a very long time, also apply some randomization
                 // to break out of local optima
-                if self.iterations_without_improvement > ITERATIONS_FOR_RANDOMIZATION {
+                if self.iterations_without_improvement > self.stagnation_policy.randomization_threshold {
                     // println!("   - Applying weight randomization to break stagnation after {} iterations", 
                     //         self.iterations_without_improvement);
                     
-                    let randomization_factor = RANDOMIZATION_FACTOR; // 10% random variation
-                    let mut rng = rand::thread_rng();
+                    let randomization_factor = self.stagnation_policy.randomization_magnitude;
+                    let mut rng = RngSource::new(self.deterministic_rng.as_mut());
                     
                     for year_weights in self.weights.values_mut() {
                         for weight in year_weights.values_mut() {
@@ -283,17 +320,22 @@ impl ActionWeights {
     }
 
     pub fn apply_deficit_contrast_learning(&mut self) {
+        if !self.contrast_policy.enabled {
+            return;
+        }
+        let policy = self.contrast_policy;
+        
         // Only apply contrast learning if we have a best run to compare against
         if let (Some(best_metrics), Some(best_deficit_actions)) = (&self.best_metrics, &self.best_deficit_actions) {
-            let __best_score = score_metrics(best_metrics, self.optimization_mode.as_deref());
+            let __best_score = score_metrics(best_metrics, self.optimization_mode.as_deref(), &self.scoring);
             // We don't have a current metrics specific to deficit actions, but we can use the deterioration
             // from the regular contrast learning as an approximation
             let deterioration = self.iterations_without_improvement as f64 / STAGNATION_ITERATIONS_DIVISOR; // Use iterations as a proxy for deterioration
             
             // Calculate dynamic threshold similar to the main contrast learning function
             // Start at a higher threshold and decrease over time without improvements
-            let initial_threshold = DEFICIT_CONTRAST_INITIAL_THRESHOLD;
-            let minimum_threshold = DEFICIT_CONTRAST_MINIMUM_THRESHOLD;
+            let initial_threshold = policy.deficit_divergence_threshold;
+            let minimum_threshold = policy.minimum_threshold;
             let iterations = self.iterations_without_improvement as f64;
             let scale_factor = DEFICIT_CONTRAST_SCALE_FACTOR;
             
@@ -306,10 +348,10 @@ impl ActionWeights {
             if deterioration > dynamic_threshold || force_contrast {
                 // Calculate stagnation penalty with exponential scaling
                 let stagnation_iterations = self.iterations_without_improvement as f64 / STAGNATION_ITERATIONS_DIVISOR;
-                let stagnation_factor = ONE_F64 + (STAGNATION_PENALTY_FACTOR * stagnation_iterations.powf(STAGNATION_EXPONENT));
+                let stagnation_factor = ONE_F64 + (STAGNATION_PENALTY_FACTOR * stagnation_iterations.powf(policy.stagnation_exponent));
                 
                 // Scale the deterioration like in regular contrast learning
-                let scaled_deterioration = deterioration.powf(DIVERGENCE_EXPONENT);
+                let scaled_deterioration = deterioration.powf(policy.divergence_exponent);
                 
                 // Calculate the combined penalty multiplier
                 let combined_penalty = scaled_deterioration * stagnation_factor;
@@ -337,15 +379,17 @@ impl ActionWeights {
                     if let Some(year_weights) = self.deficit_weights.get_mut(year) {
                         // First, boost all best deficit actions
                         for best_action in best_year_actions {
-                            if let Some(weight) = year_weights.get_mut(best_action) {
+                            if let Some(weight) = year_weights.get_mut(&best_action.kind()) {
                                 *weight = (*weight * best_boost_factor).min(MAX_WEIGHT);
                             }
                         }
                         
-                        // Then penalize current actions that don't appear in best actions
+                        // Then penalize current actions whose kind doesn't appear in best actions
+                        let best_kinds: Vec<GridActionKind> = best_year_actions.iter().map(GridAction::kind).collect();
                         for current_action in &current_year_actions {
-                            if !best_year_actions.contains(current_action) {
-                                if let Some(weight) = year_weights.get_mut(current_action) {
+                            let current_kind = current_action.kind();
+                            if !best_kinds.contains(&current_kind) {
+                                if let Some(weight) = year_weights.get_mut(&current_kind) {
                                     *weight = (*weight * penalty_factor).max(MIN_WEIGHT);
                                 }
                             }
@@ -355,15 +399,16 @@ impl ActionWeights {
                 
                 // If we've been stagnating for a very long time, also apply some randomization
                 // to break out of local optima
-                if self.iterations_without_improvement > ITERATIONS_FOR_RANDOMIZATION {
+                if self.iterations_without_improvement > self.stagnation_policy.randomization_threshold {
                     // println!("   - Applying weight randomization to deficit weights after {} iterations", 
                     //         self.iterations_without_improvement);
                     
-                    let mut rng = rand::thread_rng();
+                    let randomization_factor = self.stagnation_policy.randomization_magnitude;
+                    let mut rng = RngSource::new(self.deterministic_rng.as_mut());
                     
                     for year_weights in self.deficit_weights.values_mut() {
                         for weight in year_weights.values_mut() {
-                            let random_factor = ONE_F64 + RANDOMIZATION_FACTOR * (rng.gen::<f64>() * 2.0 - 1.0);
+                            let random_factor = ONE_F64 + randomization_factor * (rng.gen::<f64>() * RANDOM_RANGE_MULTIPLIER - ONE_F64);
                             *weight = (*weight * random_factor).clamp(MIN_WEIGHT, MAX_WEIGHT);
                         }
                     }
@@ -396,3 +441,67 @@ impl ActionWeights {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::generator::GeneratorType;
+    use crate::test_support::test_metrics;
+
+    #[test]
+    fn adjust_operations_share_one_weight_bucket() {
+        let year = 2030;
+        let half_gas = GridAction::AdjustOperation("Gas_1".to_string(), 50);
+        let mostly_coal = GridAction::AdjustOperation("Coal_7".to_string(), 80);
+        assert_eq!(half_gas.kind(), mostly_coal.kind());
+
+        let mut weights = ActionWeights::new();
+        let before = weights.get_year_weights(year).unwrap()[&GridActionKind::AdjustOperation];
+        weights.update_weights(&half_gas, year, 0.5);
+        let after_first = weights.get_year_weights(year).unwrap()[&GridActionKind::AdjustOperation];
+        weights.update_weights(&mostly_coal, year, 0.5);
+        let after_second = weights.get_year_weights(year).unwrap()[&GridActionKind::AdjustOperation];
+
+        assert!(after_first > before);
+        // Both updates landed on the same weight rather than two separate keys
+        assert!(after_second > after_first);
+    }
+
+    fn weights_after_a_worse_run(contrast_learning_enabled: bool) -> (ActionWeights, ActionWeights) {
+        let year = 2030;
+        let mut weights = ActionWeights::new();
+        weights.set_contrast_learning_enabled(contrast_learning_enabled);
+        weights.best_metrics = Some(test_metrics(1.0e5, 1.0e9, 0.8));
+        weights.best_actions = Some(HashMap::from([(year, vec![GridAction::AddGenerator(GeneratorType::OnshoreWind, 100)])]));
+        weights.current_run_actions.insert(year, vec![GridAction::AddGenerator(GeneratorType::GasCombinedCycle, 100)]);
+        let before = weights.clone();
+        weights.apply_contrast_learning(&test_metrics(5.0e7, 1.0e9, 0.4));
+        (before, weights)
+    }
+
+    #[test]
+    fn disabled_contrast_learning_leaves_weights_unchanged_after_a_worse_run() {
+        let (before, after) = weights_after_a_worse_run(false);
+        assert_eq!(after.weights, before.weights);
+
+        // Enabled, the same run does move them, so the run really was worse than the best
+        let (before, after) = weights_after_a_worse_run(true);
+        assert_ne!(after.weights, before.weights);
+    }
+
+    #[test]
+    fn do_nothing_weight_stays_under_its_cap_after_repeated_boosts() {
+        let year = 2045;
+        let max_share = 0.2;
+        let mut weights = ActionWeights::new();
+        weights.set_noop_max_share(max_share);
+
+        for _ in 0..200 {
+            weights.update_weights(&GridAction::DoNothing, year, 1.0);
+            let year_weights = &weights.weights[&year];
+            let total: f64 = year_weights.values().sum();
+            let share = year_weights[&GridActionKind::DoNothing] / total;
+            assert!(share <= max_share + 1e-9, "DoNothing share {share} exceeds cap {max_share}");
+        }
+    }
+}
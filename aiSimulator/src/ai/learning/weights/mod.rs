@@ -12,6 +12,7 @@ pub mod strategy;
 pub mod deficit;
 pub mod serialization;
 pub mod diagnostics;
+pub mod rng;
 
 // Remove the re-exports that are causing issues
 // pub use self::core::*;
@@ -22,17 +23,25 @@ pub mod diagnostics;
 // pub use self::serialization::*;
 // pub use self::diagnostics::*;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Mutex;
 
 // External crate imports
 use rand::rngs::StdRng;
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 
 // Internal module imports
 use crate::ai::actions::grid_action::GridAction;
+use crate::ai::actions::grid_action_kind::GridActionKind;
 use crate::ai::metrics::simulation_metrics::SimulationMetrics;
+use crate::ai::metrics::scoring::ScoringContext;
 use crate::utils::csv_export::ImprovementRecord;
+use crate::ai::learning::constants::{
+    FORCE_REPLAY_THRESHOLD, ITERATIONS_FOR_RANDOMIZATION, RANDOMIZATION_FACTOR, ZERO_F64,
+    CONTRAST_INITIAL_THRESHOLD, CONTRAST_MINIMUM_THRESHOLD, DEFICIT_CONTRAST_INITIAL_THRESHOLD,
+    DIVERGENCE_EXPONENT, STAGNATION_EXPONENT,
+};
 
 /// Mutex for file operations to prevent race conditions when
 /// multiple threads try to read/write weight files
@@ -40,6 +49,82 @@ lazy_static! {
     static ref FILE_MUTEX: Mutex<()> = Mutex::new(());
 }
 
+/// How ActionWeights tries to break out of long runs without improvement.
+/// All thresholds count iterations since the best score last improved.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StagnationPolicy {
+    /// Start forcing replay of the best actions past this many iterations
+    pub replay_threshold: u32,
+    
+    /// Start randomizing weights past this many iterations
+    pub randomization_threshold: u32,
+    
+    /// Maximum relative change applied to each weight when randomizing (0.25 = ±25%)
+    pub randomization_magnitude: f64,
+    
+    /// Fraction of the best weights mixed back in once past the replay threshold (0 disables)
+    pub restore_fraction: f64,
+}
+
+impl Default for StagnationPolicy {
+    fn default() -> Self {
+        Self {
+            replay_threshold: FORCE_REPLAY_THRESHOLD,
+            randomization_threshold: ITERATIONS_FOR_RANDOMIZATION,
+            randomization_magnitude: RANDOMIZATION_FACTOR,
+            restore_fraction: ZERO_F64,
+        }
+    }
+}
+
+/// Tuning for contrast learning, which penalises actions a worse run took that the best run
+/// did not. Ablation studies can switch it off entirely.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ContrastLearningPolicy {
+    /// Whether contrast learning runs at all
+    pub enabled: bool,
+    
+    /// Relative deterioration from the best score needed before penalising (decays with stagnation)
+    pub divergence_threshold: f64,
+    
+    /// Same threshold for deficit-handling actions
+    pub deficit_divergence_threshold: f64,
+    
+    /// Floor the decaying thresholds never drop below
+    pub minimum_threshold: f64,
+    
+    /// Power applied to the deterioration (lower = harsher for small divergences)
+    pub divergence_exponent: f64,
+    
+    /// Power applied to stagnation length (higher = harsher after long stagnation)
+    pub stagnation_exponent: f64,
+}
+
+impl Default for ContrastLearningPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            divergence_threshold: CONTRAST_INITIAL_THRESHOLD,
+            deficit_divergence_threshold: DEFICIT_CONTRAST_INITIAL_THRESHOLD,
+            minimum_threshold: CONTRAST_MINIMUM_THRESHOLD,
+            divergence_exponent: DIVERGENCE_EXPONENT,
+            stagnation_exponent: STAGNATION_EXPONENT,
+        }
+    }
+}
+
+/// How the exploration rate is set at the start of each iteration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ExplorationSchedule {
+    /// Decays with iteration count alone
+    #[default]
+    Decay,
+    
+    /// Starts from the decayed rate, then widens it when recent scores are flat and the search
+    /// has stalled, and narrows it when recent iterations keep improving
+    Adaptive,
+}
+
 /// The ActionWeights struct is responsible for managing the weights used
 /// to determine which actions to take during grid simulation.
 ///
@@ -48,8 +133,8 @@ lazy_static! {
 /// public opinion, power reliability, and cost.
 #[derive(Debug, Clone)]
 pub struct ActionWeights {
-    /// Maps years to action weights (action -> weight)
-    pub weights: HashMap<u32, HashMap<GridAction, f64>>,
+    /// Maps years to action weights (action kind -> weight)
+    pub weights: HashMap<u32, HashMap<GridActionKind, f64>>,
     
     /// Maps years to weights for different action counts
     pub action_count_weights: HashMap<u32, HashMap<u32, f64>>, 
@@ -61,7 +146,7 @@ pub struct ActionWeights {
     pub best_metrics: Option<SimulationMetrics>,
     
     /// Weights that produced the best results
-    pub best_weights: Option<HashMap<u32, HashMap<GridAction, f64>>>,
+    pub best_weights: Option<HashMap<u32, HashMap<GridActionKind, f64>>>,
     
     /// Actions that produced the best results
     pub best_actions: Option<HashMap<u32, Vec<GridAction>>>, 
@@ -82,7 +167,7 @@ pub struct ActionWeights {
     pub force_best_actions: bool,
     
     /// Weights for handling power deficits
-    pub deficit_weights: HashMap<u32, HashMap<GridAction, f64>>,
+    pub deficit_weights: HashMap<u32, HashMap<GridActionKind, f64>>,
     
     /// Deficit actions taken in the current run
     pub current_deficit_actions: HashMap<u32, Vec<GridAction>>,
@@ -99,9 +184,27 @@ pub struct ActionWeights {
     /// Optimization mode (e.g., "emissions", "cost", "balanced")
     pub optimization_mode: Option<String>,
     
+    /// Config-derived settings used whenever these weights score a run
+    pub scoring: ScoringContext,
+    
     /// Tracks the current index when replaying best actions for each year
     pub replay_index: HashMap<u32, usize>,
     
     /// Improvement history tracking - records each time the best strategy is improved
     pub improvement_history: Vec<ImprovementRecord>,
+    
+    /// Thresholds and magnitudes for escaping stagnation
+    pub stagnation_policy: StagnationPolicy,
+    
+    /// Whether and how aggressively worse runs penalise their divergent actions
+    pub contrast_policy: ContrastLearningPolicy,
+    
+    /// How exploration_rate is recomputed each iteration
+    pub exploration_schedule: ExplorationSchedule,
+    
+    /// Scores of the most recent iterations, oldest first, for the adaptive schedule
+    pub recent_scores: VecDeque<f64>,
+    
+    /// Largest share of a year's total action weight DoNothing may hold
+    pub noop_max_share: f64,
 }
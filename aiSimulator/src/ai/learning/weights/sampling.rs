@@ -5,9 +5,11 @@ use rand::Rng;
 use crate::models::generator::GeneratorType;
 use crate::models::carbon_offset::CarbonOffsetType;
 use crate::ai::actions::grid_action::GridAction;
+use crate::ai::actions::grid_action_kind::GridActionKind;
 use crate::ai::learning::constants::*;
 use crate::config::constants::{DEFAULT_COST_MULTIPLIER, FAST_COST_MULTIPLIER, VERY_FAST_COST_MULTIPLIER, RUSH_COST_MULTIPLIER};
-use super::ActionWeights;
+use super::{ActionWeights, ExplorationSchedule};
+use super::rng::RngSource;
 
 // Add a dummy public item to ensure this file is recognized by rust-analyzer
 #[allow(dead_code)]
@@ -19,56 +21,49 @@ impl ActionWeights {
 // Appropriate imports will need to be added based on the specific requirements
 
     // Initialize weights for a single year
-    pub fn initialize_weights(&self) -> HashMap<GridAction, f64> {
+    pub fn initialize_weights(&self) -> HashMap<GridActionKind, f64> {
         let mut year_weights = HashMap::new();
         
         // Add generators with default cost multiplier
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::OnshoreWind, DEFAULT_COST_MULTIPLIER), ONSHORE_WIND_WEIGHT);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::OffshoreWind, DEFAULT_COST_MULTIPLIER), OFFSHORE_WIND_WEIGHT);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::DomesticSolar, DEFAULT_COST_MULTIPLIER), DOMESTIC_SOLAR_WEIGHT);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::CommercialSolar, DEFAULT_COST_MULTIPLIER), COMMERCIAL_SOLAR_WEIGHT);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::UtilitySolar, DEFAULT_COST_MULTIPLIER), UTILITY_SOLAR_WEIGHT);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::Nuclear, DEFAULT_COST_MULTIPLIER), NUCLEAR_WEIGHT);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::CoalPlant, DEFAULT_COST_MULTIPLIER), COAL_PLANT_WEIGHT);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::GasCombinedCycle, DEFAULT_COST_MULTIPLIER), GAS_COMBINED_CYCLE_WEIGHT);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::GasPeaker, DEFAULT_COST_MULTIPLIER), GAS_PEAKER_WEIGHT);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::Biomass, DEFAULT_COST_MULTIPLIER), BIOMASS_WEIGHT);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::HydroDam, DEFAULT_COST_MULTIPLIER), HYDRO_DAM_WEIGHT);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::PumpedStorage, DEFAULT_COST_MULTIPLIER), PUMPED_STORAGE_WEIGHT);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::BatteryStorage, DEFAULT_COST_MULTIPLIER), BATTERY_STORAGE_WEIGHT);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::TidalGenerator, DEFAULT_COST_MULTIPLIER), TIDAL_GENERATOR_WEIGHT);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::WaveEnergy, DEFAULT_COST_MULTIPLIER), WAVE_ENERGY_WEIGHT);
+        for gen_type in GeneratorType::iter() {
+            let weight = Self::default_generator_weight(&gen_type);
+            year_weights.insert(GridActionKind::AddGenerator(gen_type, DEFAULT_COST_MULTIPLIER), weight);
+        }
         
         // Add generators with higher cost multipliers (faster construction)
         // Fast cost multiplier (150%)
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::OnshoreWind, FAST_COST_MULTIPLIER), ONSHORE_WIND_WEIGHT * 0.5);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::OffshoreWind, FAST_COST_MULTIPLIER), OFFSHORE_WIND_WEIGHT * 0.5);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::UtilitySolar, FAST_COST_MULTIPLIER), UTILITY_SOLAR_WEIGHT * 0.5);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::GasPeaker, FAST_COST_MULTIPLIER), GAS_PEAKER_WEIGHT * 0.5);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::BatteryStorage, FAST_COST_MULTIPLIER), BATTERY_STORAGE_WEIGHT * 0.5);
+        year_weights.insert(GridActionKind::AddGenerator(GeneratorType::OnshoreWind, FAST_COST_MULTIPLIER), ONSHORE_WIND_WEIGHT * 0.5);
+        year_weights.insert(GridActionKind::AddGenerator(GeneratorType::OffshoreWind, FAST_COST_MULTIPLIER), OFFSHORE_WIND_WEIGHT * 0.5);
+        year_weights.insert(GridActionKind::AddGenerator(GeneratorType::FloatingOffshoreWind, FAST_COST_MULTIPLIER), FLOATING_OFFSHORE_WIND_WEIGHT * 0.5);
+        year_weights.insert(GridActionKind::AddGenerator(GeneratorType::UtilitySolar, FAST_COST_MULTIPLIER), UTILITY_SOLAR_WEIGHT * 0.5);
+        year_weights.insert(GridActionKind::AddGenerator(GeneratorType::GasPeaker, FAST_COST_MULTIPLIER), GAS_PEAKER_WEIGHT * 0.5);
+        year_weights.insert(GridActionKind::AddGenerator(GeneratorType::BatteryStorage, FAST_COST_MULTIPLIER), BATTERY_STORAGE_WEIGHT * 0.5);
         
         // Very fast cost multiplier (200%)
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::OnshoreWind, VERY_FAST_COST_MULTIPLIER), ONSHORE_WIND_WEIGHT * 0.25);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::UtilitySolar, VERY_FAST_COST_MULTIPLIER), UTILITY_SOLAR_WEIGHT * 0.25);
-        year_weights.insert(GridAction::AddGenerator(GeneratorType::GasPeaker, VERY_FAST_COST_MULTIPLIER), GAS_PEAKER_WEIGHT * 0.25);
+        year_weights.insert(GridActionKind::AddGenerator(GeneratorType::OnshoreWind, VERY_FAST_COST_MULTIPLIER), ONSHORE_WIND_WEIGHT * 0.25);
+        year_weights.insert(GridActionKind::AddGenerator(GeneratorType::UtilitySolar, VERY_FAST_COST_MULTIPLIER), UTILITY_SOLAR_WEIGHT * 0.25);
+        year_weights.insert(GridActionKind::AddGenerator(GeneratorType::GasPeaker, VERY_FAST_COST_MULTIPLIER), GAS_PEAKER_WEIGHT * 0.25);
         
         // Add carbon offsets with default and higher cost multipliers
-        year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::Forest, DEFAULT_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT);
-        year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::Wetland, DEFAULT_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT);
-        year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::ActiveCapture, DEFAULT_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT);
-        year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::CarbonCredit, DEFAULT_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT);
+        year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::Forest, DEFAULT_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT);
+        year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::Wetland, DEFAULT_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT);
+        year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::ActiveCapture, DEFAULT_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT);
+        year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::CarbonCredit, DEFAULT_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT);
         
         // Add carbon offsets with higher cost multipliers
-        year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::Forest, FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.5);
-        year_weights.insert(GridAction::AddCarbonOffset(CarbonOffsetType::CarbonCredit, FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.5);
+        year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::Forest, FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.5);
+        year_weights.insert(GridActionKind::AddCarbonOffset(CarbonOffsetType::CarbonCredit, FAST_COST_MULTIPLIER), CARBON_OFFSET_WEIGHT * 0.5);
         
         // Other actions
-        year_weights.insert(GridAction::UpgradeEfficiency(String::new()), UPGRADE_EFFICIENCY_WEIGHT);
-        year_weights.insert(GridAction::AdjustOperation(String::new(), OPERATION_PERCENTAGE_MIN), ADJUST_OPERATION_WEIGHT);
-        year_weights.insert(GridAction::CloseGenerator(String::new()), CLOSE_GENERATOR_WEIGHT);
+        year_weights.insert(GridActionKind::UpgradeEfficiency, UPGRADE_EFFICIENCY_WEIGHT);
+        year_weights.insert(GridActionKind::AdjustOperation, ADJUST_OPERATION_WEIGHT);
+        year_weights.insert(GridActionKind::CloseGenerator, CLOSE_GENERATOR_WEIGHT);
+        year_weights.insert(GridActionKind::RetrofitGenerator(GeneratorType::Biomass), RETROFIT_GENERATOR_WEIGHT);
+        year_weights.insert(GridActionKind::AddFlexibleLoad, FLEXIBLE_LOAD_WEIGHT);
         
         // Initialize DoNothing with a base weight
-        year_weights.insert(GridAction::DoNothing, DO_NOTHING_WEIGHT);
+        year_weights.insert(GridActionKind::DoNothing, DO_NOTHING_WEIGHT);
+        year_weights.insert(GridActionKind::DeferDecision, DEFER_DECISION_WEIGHT);
         
         year_weights
     }
@@ -84,11 +79,8 @@ impl ActionWeights {
                     if current_index < year_actions.len() {
                         let action = year_actions[current_index].clone();
                         
-                        // Only print debug info if debug weights is enabled
-                        if crate::ai::learning::constants::is_debug_weights_enabled() {
-                            println!("🔄 REPLAY: Using best action #{} for year {}: {:?}", 
-                                    current_index + ONE_USIZE, year, action);
-                        }
+                        crate::log!(Debug, "🔄 REPLAY: Using best action #{} for year {}: {:?}", 
+                                current_index + ONE_USIZE, year, action);
                         
                         // Increment the replay index for this year
                         self.replay_index.insert(year, current_index + 1);
@@ -100,11 +92,8 @@ impl ActionWeights {
                         
                         return action;
                     } else {
-                        // Only print debug info if debug weights is enabled
-                        if crate::ai::learning::constants::is_debug_weights_enabled() {
-                            println!("⚠️ REPLAY FALLBACK: Ran out of best actions for year {} (needed action #{}, have {})", 
-                                    year, current_index + ONE_USIZE, year_actions.len());
-                        }
+                        crate::log!(Debug, "⚠️ REPLAY FALLBACK: Ran out of best actions for year {} (needed action #{}, have {})", 
+                                year, current_index + ONE_USIZE, year_actions.len());
                         
                         // Add smart fallback for when we run out of actions
                         let fallback_action = self.generate_smart_fallback_action(year, "ran out of best actions");
@@ -148,34 +137,33 @@ impl ActionWeights {
         let year_weights = self.weights.get(&year).expect("Year weights not found");
 
         // Calculate a dynamic exploration rate that decreases when we're stuck
-        let current_exploration = if self.iterations_without_improvement > LOW_ITERATION_THRESHOLD {
+        // The adaptive schedule has already accounted for stagnation in exploration_rate
+        let current_exploration = if self.iterations_without_improvement > LOW_ITERATION_THRESHOLD &&
+            self.exploration_schedule == ExplorationSchedule::Decay {
             // Reduce exploration drastically after being stuck for a while to focus on best known actions
             self.exploration_rate * (ONE_F64 / (ONE_F64 + EXPLORATION_DECAY_FACTOR * self.iterations_without_improvement as f64))
         } else {
             self.exploration_rate
         };
 
-        // Determine if we should explore based on the deterministic RNG or thread_rng
-        let should_explore = match &mut self.deterministic_rng {
-            Some(rng) => rng.gen::<f64>() < current_exploration,
-            None => rand::thread_rng().gen::<f64>() < current_exploration,
-        };
+        let mut rng = RngSource::new(self.deterministic_rng.as_mut());
+        let should_explore = rng.gen::<f64>() < current_exploration;
 
         // Epsilon-greedy exploration with dynamic rate
         if should_explore {
-            // Random exploration
-            let actions: Vec<_> = year_weights.keys().collect();
+            // Random exploration, skipping actions zeroed out (e.g. builds banned by a phase-out)
+            let actions: Vec<_> = year_weights.iter()
+                .filter(|(_, weight)| **weight > ZERO_F64)
+                .map(|(action, _)| action)
+                .collect();
             if actions.is_empty() {
                 // Fallback to a safe default action if no actions are available
                 return GridAction::AddGenerator(GeneratorType::GasPeaker, DEFAULT_COST_MULTIPLIER);
             }
             
-            let random_idx = match &mut self.deterministic_rng {
-                Some(rng) => rng.gen_range(ZERO_USIZE..actions.len()),
-                None => rand::thread_rng().gen_range(ZERO_USIZE..actions.len()),
-            };
+            let random_idx = rng.gen_range(ZERO_USIZE..actions.len());
             
-            return actions[random_idx].clone();
+            return actions[random_idx].to_action();
         }
 
         // Exploitation - weighted selection
@@ -202,33 +190,27 @@ impl ActionWeights {
                 .map(|(_, &w)| w.powf(power_scaling))
                 .sum();
             
-            let mut random_val = match &mut self.deterministic_rng {
-                Some(rng) => rng.gen::<f64>() * total_scaled_weight,
-                None => rand::thread_rng().gen::<f64>() * total_scaled_weight,
-            };
+            let mut random_val = rng.gen::<f64>() * total_scaled_weight;
             
             for (action, &weight) in &actions_with_weights {
                 let scaled_weight = weight.powf(power_scaling);
                 random_val -= scaled_weight;
                 if random_val <= ZERO_F64 {
-                    return (*action).clone();
+                    return action.to_action();
                 }
             }
             
             // Fallback to the highest weight action
-            return actions_with_weights.first().map(|(a, _)| (*a).clone())
+            return actions_with_weights.first().map(|(a, _)| a.to_action())
                 .unwrap_or(GridAction::AddGenerator(GeneratorType::GasPeaker, DEFAULT_COST_MULTIPLIER));
         } else {
             // Standard weighted selection for normal operation
-            let mut random_val = match &mut self.deterministic_rng {
-                Some(rng) => rng.gen::<f64>() * total_weight,
-                None => rand::thread_rng().gen::<f64>() * total_weight,
-            };
+            let mut random_val = rng.gen::<f64>() * total_weight;
             
             for (action, weight) in year_weights {
                 random_val -= weight;
                 if random_val <= ZERO_F64 {
-                    return action.clone();
+                    return action.to_action();
                 }
             }
         }
@@ -249,11 +231,8 @@ impl ActionWeights {
                     if current_index < year_deficit_actions.len() {
                         let action = year_deficit_actions[current_index].clone();
                         
-                        // Only print debug info if debug weights is enabled
-                        if crate::ai::learning::constants::is_debug_weights_enabled() {
-                            println!("🔄 DEFICIT REPLAY: Using best deficit action #{} for year {}: {:?}", 
-                                    current_index + ONE_USIZE, year, action);
-                        }
+                        crate::log!(Debug, "🔄 DEFICIT REPLAY: Using best deficit action #{} for year {}: {:?}", 
+                                current_index + ONE_USIZE, year, action);
                         
                         // Increment the deficit replay index for this year
                         self.replay_index.insert(deficit_year_key, current_index + 1);
@@ -265,11 +244,8 @@ impl ActionWeights {
                         
                         return action;
                     } else {
-                        // Only print debug info if debug weights is enabled
-                        if crate::ai::learning::constants::is_debug_weights_enabled() {
-                            println!("⚠️ DEFICIT REPLAY FALLBACK: Ran out of best deficit actions for year {} (needed action #{}, have {})",
-                                    year, current_index + ONE_USIZE, year_deficit_actions.len());
-                        }
+                        crate::log!(Debug, "⚠️ DEFICIT REPLAY FALLBACK: Ran out of best deficit actions for year {} (needed action #{}, have {})",
+                                year, current_index + ONE_USIZE, year_deficit_actions.len());
                         
                         // Smart fallback for deficit
                         let fallback_action = self.generate_smart_deficit_fallback_action(year);
@@ -282,10 +258,7 @@ impl ActionWeights {
                         return fallback_action;
                     }
                 } else {
-                    // Only print debug info if debug weights is enabled
-                    if crate::ai::learning::constants::is_debug_weights_enabled() {
-                        println!("⚠️ DEFICIT REPLAY FALLBACK: No best deficit actions recorded for year {}", year);
-                    }
+                    crate::log!(Debug, "⚠️ DEFICIT REPLAY FALLBACK: No best deficit actions recorded for year {}", year);
                     
                     // Smart fallback for deficit
                     let fallback_action = self.generate_smart_deficit_fallback_action(year);
@@ -322,17 +295,15 @@ impl ActionWeights {
             }
         };
         
-        // Determine if we should explore based on the deterministic RNG or thread_rng
-        let should_explore = match &mut self.deterministic_rng {
-            Some(rng) => rng.gen::<f64>() < self.exploration_rate,
-            None => rand::thread_rng().gen::<f64>() < self.exploration_rate,
-        };
+        let mut rng = RngSource::new(self.deterministic_rng.as_mut());
+        let should_explore = rng.gen::<f64>() < self.exploration_rate;
         
         // Apply epsilon-greedy strategy similar to main action sampling
         if should_explore {
-            // Random exploration
-            let actions: Vec<_> = year_weights.keys()
-                .filter(|action| matches!(action, GridAction::AddGenerator(_, _)))
+            // Random exploration among builds that haven't been zeroed out
+            let actions: Vec<_> = year_weights.iter()
+                .filter(|(action, weight)| matches!(action, GridActionKind::AddGenerator(_, _)) && **weight > ZERO_F64)
+                .map(|(action, _)| action)
                 .collect();
             
             if actions.is_empty() {
@@ -340,17 +311,14 @@ impl ActionWeights {
                 return GridAction::AddGenerator(GeneratorType::GasPeaker, DEFAULT_COST_MULTIPLIER);
             }
             
-            let random_idx = match &mut self.deterministic_rng {
-                Some(rng) => rng.gen_range(ZERO_USIZE..actions.len()),
-                None => rand::thread_rng().gen_range(ZERO_USIZE..actions.len()),
-            };
+            let random_idx = rng.gen_range(ZERO_USIZE..actions.len());
             
-            return actions[random_idx].clone();
+            return actions[random_idx].to_action();
         }
         
         // Exploitation - weighted selection of generator actions
         let total_weight: f64 = year_weights.iter()
-            .filter(|(action, _)| matches!(action, GridAction::AddGenerator(_, _)))
+            .filter(|(action, _)| matches!(action, GridActionKind::AddGenerator(_, _)))
             .map(|(_, &weight)| weight)
             .sum();
         
@@ -359,16 +327,13 @@ impl ActionWeights {
             return GridAction::AddGenerator(GeneratorType::GasPeaker, DEFAULT_COST_MULTIPLIER);
         }
         
-        let mut random_val = match &mut self.deterministic_rng {
-            Some(rng) => rng.gen::<f64>() * total_weight,
-            None => rand::thread_rng().gen::<f64>() * total_weight,
-        };
+        let mut random_val = rng.gen::<f64>() * total_weight;
         
         for (action, weight) in year_weights {
-            if matches!(action, GridAction::AddGenerator(_, _)) {
+            if matches!(action, GridActionKind::AddGenerator(_, _)) {
                 random_val -= weight;
                 if random_val <= ZERO_F64 {
-                    return action.clone();
+                    return action.to_action();
                 }
             }
         }
@@ -396,10 +361,7 @@ impl ActionWeights {
             return 0;
         }
         
-        let random_val = match &mut self.deterministic_rng {
-            Some(rng) => rng.gen::<f64>(),
-            None => rand::thread_rng().gen::<f64>(),
-        };
+        let random_val = self.rng().gen::<f64>();
         
         if let Some(year_counts) = self.action_count_weights.get(&year) {
             // Use weighted sampling based on historical data
@@ -435,14 +397,11 @@ impl ActionWeights {
                 return capped_min_actions;
             }
             
-            match &mut self.deterministic_rng {
-                Some(rng) => rng.gen_range(capped_min_actions..=capped_max_actions),
-                None => rand::thread_rng().gen_range(capped_min_actions..=capped_max_actions),
-            }
+            self.rng().gen_range(capped_min_actions..=capped_max_actions)
         }
     }
 
-    pub fn generate_smart_fallback_action(&self, year: u32, fallback_reason: &str) -> GridAction {
+    pub fn generate_smart_fallback_action(&mut self, year: u32, fallback_reason: &str) -> GridAction {
         println!("🔧 SMART FALLBACK: Generating strategic action for year {} (reason: {})", year, fallback_reason);
         
         // The year will influence what kind of actions are taken
@@ -475,8 +434,7 @@ impl ActionWeights {
         let total_weight: u32 = action_pool.iter().map(|(_, w)| w).sum();
         
         // Select an action based on weighted random choice
-        let mut rng = rand::thread_rng();
-        let mut choice = rng.gen_range(0..total_weight);
+        let mut choice = self.rng().gen_range(0..total_weight);
         
         for (action, weight) in action_pool {
             if choice < weight {
@@ -489,7 +447,7 @@ impl ActionWeights {
         GridAction::AddGenerator(GeneratorType::BatteryStorage, DEFAULT_COST_MULTIPLIER)
     }
 
-    pub fn generate_smart_deficit_fallback_action(&self, year: u32) -> GridAction {
+    pub fn generate_smart_deficit_fallback_action(&mut self, year: u32) -> GridAction {
         println!("🔧 SMART DEFICIT FALLBACK: Generating strategic deficit action for year {}", year);
         
         // For deficit handling, we need to prioritize reliable power generation
@@ -513,8 +471,7 @@ impl ActionWeights {
         let total_weight: u32 = action_pool.iter().map(|(_, w)| w).sum();
         
         // Select an action based on weighted random choice
-        let mut rng = rand::thread_rng();
-        let mut choice = rng.gen_range(0..total_weight);
+        let mut choice = self.rng().gen_range(0..total_weight);
         
         for (action, weight) in action_pool {
             if choice < weight {
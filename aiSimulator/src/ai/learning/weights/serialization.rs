@@ -2,90 +2,219 @@
 //!
 //! This module contains serialization-related functionality for the ActionWeights struct.
 
+use crate::ai::metrics::scoring::ScoringContext;
 use std::path::Path;
 use std::str::FromStr;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use crate::models::generator::GeneratorType;
 use crate::ai::actions::grid_action::GridAction;
+use crate::ai::actions::grid_action_kind::GridActionKind;
 use crate::ai::actions::serializable_action::SerializableAction;
 use crate::ai::learning::constants::*;
-use crate::ai::learning::serialization::SerializableWeights;
-use super::{ActionWeights, FILE_MUTEX};
+use crate::ai::learning::serialization::{migrate_checkpoint, SerializableWeights, CHECKPOINT_VERSION};
+use super::{ActionWeights, ContrastLearningPolicy, ExplorationSchedule, StagnationPolicy, FILE_MUTEX};
 use std::fs::File;
 use std::io::{BufReader, BufWriter};
 use serde::{Serialize, Deserialize};
 use crate::models::carbon_offset::CarbonOffsetType;
 use crate::config::constants::DEFAULT_COST_MULTIPLIER;
+use crate::error::SimulationError;
 
 // Add a dummy public item to ensure this file is recognized by rust-analyzer
 #[allow(dead_code)]
 pub const MODULE_MARKER: &str = "serialization_module";
 
+// Weights files with this extension are written and read with bincode instead of JSON
+pub const BINARY_WEIGHTS_EXTENSION: &str = "bin";
+
+pub fn is_binary_weights_path(path: &str) -> bool {
+    Path::new(path).extension().and_then(|ext| ext.to_str()) == Some(BINARY_WEIGHTS_EXTENSION)
+}
+
+// A year's weights sorted by action name, so saving the same weights twice gives identical files
+fn serializable_year_weights(year_weights: &HashMap<GridActionKind, f64>) -> Vec<(SerializableAction, f64)> {
+    let mut entries: Vec<_> = year_weights.iter().collect();
+    entries.sort_by_cached_key(|(kind, _)| kind.to_string());
+    entries.into_iter()
+        .map(|(kind, &weight)| (SerializableAction::from(&kind.to_action()), weight))
+        .collect()
+}
+
+// Files written before weights were keyed by kind can hold several entries per kind
+// (e.g. AdjustOperation at different percentages); keep the strongest of them.
+fn insert_kind_weight(year_weights: &mut HashMap<GridActionKind, f64>, action: &GridAction, weight: f64) {
+    year_weights.entry(action.kind())
+        .and_modify(|existing| *existing = existing.max(weight))
+        .or_insert(weight);
+}
+
+// The last good checkpoint is kept beside the current one so a damaged file can be recovered
+fn previous_checkpoint_path(path: &str) -> String {
+    format!("{}.prev", path)
+}
+
+// Writes go to a temporary file that is renamed over the target, so a crash mid-write
+// leaves the old checkpoint untouched; the file being replaced is kept as `.prev`.
+fn write_atomically(path: &str, write: impl FnOnce(&Path) -> std::io::Result<()>) -> std::io::Result<()> {
+    if let Some(parent) = Path::new(path).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let temp_path = format!("{}.tmp", path);
+    write(Path::new(&temp_path))?;
+    if Path::new(path).exists() {
+        std::fs::rename(path, previous_checkpoint_path(path))?;
+    }
+    std::fs::rename(&temp_path, path)
+}
+
+fn hybrid_plant_action(serializable_action: &SerializableAction) -> Option<GridAction> {
+    let primary = GeneratorType::from_str(serializable_action.generator_type.as_deref()?).ok()?;
+    let secondary = GeneratorType::from_str(serializable_action.secondary_generator_type.as_deref()?).ok()?;
+    Some(GridAction::AddHybridPlant(primary, secondary))
+}
+
+fn retrofit_action(serializable_action: &SerializableAction) -> Option<GridAction> {
+    let target = GeneratorType::from_str(serializable_action.generator_type.as_deref()?).ok()?;
+    Some(GridAction::RetrofitGenerator(serializable_action.generator_id.clone().unwrap_or_default(), target))
+}
+
 impl ActionWeights {
 
 // This file contains extracted code from the original weights.rs file
 // Appropriate imports will need to be added based on the specific requirements
 
     pub fn save_to_file(&self, path: &str) -> std::io::Result<()> {
+        if is_binary_weights_path(path) {
+            return self.save_to_file_binary(path);
+        }
+
         // Acquire lock for file operations
         let _lock = FILE_MUTEX.lock().map_err(|e| {
             println!("Error acquiring file lock for saving: {}", e);
             std::io::Error::new(std::io::ErrorKind::Other, "Failed to acquire file lock for saving")
         })?;
+
+        let serializable = self.to_serializable();
+
+        let json = serde_json::to_string_pretty(&serializable)
+            .map_err(std::io::Error::other)?;
         
-        // Create parent directory if it doesn't exist
-        if let Some(parent) = Path::new(path).parent() {
-            if let Err(e) = std::fs::create_dir_all(parent) {
-                println!("Error creating directory {}: {}", parent.display(), e);
-                return Err(e);
+        write_atomically(path, |temp_path| std::fs::write(temp_path, json))
+    }
+
+    /// Loads and validates a checkpoint. If it is truncated or otherwise invalid, the
+    /// previous checkpoint saved at the same path is used instead when there is one.
+    pub fn load_from_file(path: &str) -> Result<Self, SimulationError> {
+        match Self::load_checkpoint(path) {
+            Ok(weights) => Ok(weights),
+            Err(e) => {
+                let previous_path = previous_checkpoint_path(path);
+                if !Path::new(&previous_path).exists() {
+                    return Err(e);
+                }
+                println!("Warning: {}; recovering from previous checkpoint {}", e, previous_path);
+                Self::load_checkpoint(&previous_path)
             }
         }
+    }
+
+    fn load_checkpoint(path: &str) -> Result<Self, SimulationError> {
+        let weights = if is_binary_weights_path(path.trim_end_matches(".prev")) {
+            Self::load_from_file_binary(path)?
+        } else {
+            let _lock = FILE_MUTEX.lock().map_err(|e| {
+                SimulationError::Checkpoint(format!("Failed to acquire file lock for loading: {}", e))
+            })?;
+            
+            let json = std::fs::read_to_string(path)?;
+            let checkpoint: serde_json::Value = serde_json::from_str(&json)
+                .map_err(|e| SimulationError::Serialization(format!("Corrupt weights file {}: {}", path, e)))?;
+            let checkpoint = migrate_checkpoint(checkpoint)
+                .map_err(|reason| SimulationError::Checkpoint(format!("Cannot load weights file {}: {}", path, reason)))?;
+            let serializable: SerializableWeights = serde_json::from_value(checkpoint)
+                .map_err(|e| SimulationError::Serialization(format!("Corrupt weights file {}: {}", path, e)))?;
+
+            Self::from_serializable(serializable)?
+        };
+
+        weights.validate()
+            .map_err(|reason| SimulationError::Checkpoint(format!("Invalid weights file {}: {}", path, reason)))?;
+        Ok(weights)
+    }
+
+    // Every simulated year must be present with finite weights in the unit range
+    // (learning keeps them within MIN_WEIGHT..=MAX_WEIGHT)
+    fn validate(&self) -> Result<(), String> {
+        if let Some(year) = (START_YEAR..=END_YEAR).find(|year| !self.weights.contains_key(year)) {
+            return Err(format!("missing weights for {}", year));
+        }
+
+        let all_weights = self.weights.iter().chain(self.deficit_weights.iter());
+        for (year, year_weights) in all_weights {
+            if let Some((action, weight)) = year_weights.iter().find(|(_, w)| !w.is_finite() || **w < 0.0 || **w > 1.0) {
+                return Err(format!("weight {} for {:?} in {} is out of range", weight, action, year));
+            }
+        }
+        Ok(())
+    }
+
+    /// Same content as the JSON checkpoint, encoded with bincode for smaller files and faster interval saves
+    pub fn save_to_file_binary(&self, path: &str) -> std::io::Result<()> {
+        let _lock = FILE_MUTEX.lock().map_err(|e| {
+            println!("Error acquiring file lock for saving: {}", e);
+            std::io::Error::other("Failed to acquire file lock for saving")
+        })?;
+
+        write_atomically(path, |temp_path| {
+            let writer = BufWriter::new(File::create(temp_path)?);
+            bincode::serialize_into(writer, &self.to_serializable())
+                .map_err(std::io::Error::other)
+        })
+    }
+
+    pub fn load_from_file_binary(path: &str) -> Result<Self, SimulationError> {
+        let _lock = FILE_MUTEX.lock().map_err(|e| {
+            SimulationError::Checkpoint(format!("Failed to acquire file lock for loading: {}", e))
+        })?;
+
+        let reader = BufReader::new(File::open(path)?);
+        let serializable: SerializableWeights = bincode::deserialize_from(reader)
+            .map_err(|e| SimulationError::Serialization(format!("Corrupt binary weights file {}: {}", path, e)))?;
+        // Bincode has no field names to migrate by, so only the current schema is accepted
+        if serializable.version != CHECKPOINT_VERSION {
+            return Err(SimulationError::Checkpoint(format!(
+                "Binary weights file {} has schema version {}, expected {}", path, serializable.version, CHECKPOINT_VERSION)));
+        }
 
+        Self::from_serializable(serializable)
+    }
+
+    fn to_serializable(&self) -> SerializableWeights {
         // Convert to serializable format
-        let mut serializable_weights = HashMap::new();
+        let mut serializable_weights = BTreeMap::new();
         for (year, year_weights) in &self.weights {
-            let mut serializable_year_weights = Vec::new();
-            for (action, &weight) in year_weights {
-                serializable_year_weights.push((
-                    SerializableAction::from(action),
-                    weight,
-                ));
-            }
-            serializable_weights.insert(*year, serializable_year_weights);
+            serializable_weights.insert(*year, serializable_year_weights(year_weights));
         }
         
         // Convert deficit weights to serializable format
-        let mut serializable_deficit_weights = HashMap::new();
+        let mut serializable_deficit_weights = BTreeMap::new();
         for (year, year_weights) in &self.deficit_weights {
-            let mut serializable_year_weights = Vec::new();
-            for (action, &weight) in year_weights {
-                serializable_year_weights.push((
-                    SerializableAction::from(action),
-                    weight,
-                ));
-            }
-            serializable_deficit_weights.insert(*year, serializable_year_weights);
+            serializable_deficit_weights.insert(*year, serializable_year_weights(year_weights));
         }
         
         // Convert best weights to serializable format
         let serializable_best_weights = self.best_weights.as_ref().map(|best_weights| {
-            let mut serializable = HashMap::new();
+            let mut serializable = BTreeMap::new();
             for (year, year_weights) in best_weights {
-                let mut serializable_year_weights = Vec::new();
-                for (action, &weight) in year_weights {
-                    serializable_year_weights.push((
-                        SerializableAction::from(action),
-                        weight,
-                    ));
-                }
-                serializable.insert(*year, serializable_year_weights);
+                serializable.insert(*year, serializable_year_weights(year_weights));
             }
             serializable
         });
         
         // Convert best actions to serializable format
         let serializable_best_actions = self.best_actions.as_ref().map(|best_actions| {
-            let mut serializable = HashMap::new();
+            let mut serializable = BTreeMap::new();
             for (year, actions) in best_actions {
                 let mut serializable_actions = Vec::new();
                 for action in actions {
@@ -98,7 +227,7 @@ impl ActionWeights {
 
         // Convert best deficit actions to serializable format
         let serializable_best_deficit_actions = self.best_deficit_actions.as_ref().map(|best_actions| {
-            let mut serializable = HashMap::new();
+            let mut serializable = BTreeMap::new();
             for (year, actions) in best_actions {
                 let mut serializable_actions = Vec::new();
                 for action in actions {
@@ -109,7 +238,7 @@ impl ActionWeights {
             serializable
         });
 
-        let serializable = SerializableWeights {
+        SerializableWeights {
             weights: serializable_weights,
             learning_rate: self.learning_rate,
             best_metrics: self.best_metrics.clone(),
@@ -128,25 +257,11 @@ impl ActionWeights {
             } else {
                 None
             },
-        };
-        
-        let json = serde_json::to_string_pretty(&serializable)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-        
-        std::fs::write(path, json)?;
-        
-        Ok(())
+            version: CHECKPOINT_VERSION,
+        }
     }
 
-    pub fn load_from_file(path: &str) -> std::io::Result<Self> {
-        let _lock = FILE_MUTEX.lock().map_err(|e| {
-            std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to acquire file lock for loading: {}", e))
-        })?;
-        
-        let json = std::fs::read_to_string(path)?;
-        let serializable: SerializableWeights = serde_json::from_str(&json)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-        
+    fn from_serializable(serializable: SerializableWeights) -> Result<Self, SimulationError> {
         // Convert serializable weights to actual weights
         let mut weights = HashMap::new();
         for (year, serializable_year_weights) in &serializable.weights {
@@ -156,7 +271,7 @@ impl ActionWeights {
                     "AddGenerator" => {
                         if let Some(gen_type_str) = &serializable_action.generator_type {
                             let gen_type = GeneratorType::from_str(gen_type_str)
-                                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+                                .map_err(SimulationError::Checkpoint)?;
                             let cost_multiplier = serializable_action.cost_multiplier.unwrap_or(DEFAULT_COST_MULTIPLIER);
                             GridAction::AddGenerator(gen_type, cost_multiplier)
                         } else {
@@ -197,15 +312,22 @@ impl ActionWeights {
                             GridAction::CloseGenerator(String::new())
                         }
                     },
+                    "AddHybridPlant" => hybrid_plant_action(serializable_action).ok_or_else(|| {
+                        SimulationError::Checkpoint("Invalid hybrid plant action in weights file".to_string())
+                    })?,
+                    "RetrofitGenerator" => retrofit_action(serializable_action).ok_or_else(|| {
+                        SimulationError::Checkpoint("Invalid retrofit action in weights file".to_string())
+                    })?,
+                    "AddFlexibleLoad" => GridAction::AddFlexibleLoad,
                     "DoNothing" => GridAction::DoNothing,
+                    "DeferDecision" => GridAction::DeferDecision,
                     _ => {
-                        return Err(std::io::Error::new(
-                            std::io::ErrorKind::InvalidData,
+                        return Err(SimulationError::Checkpoint(
                             format!("Unknown action type: {}", serializable_action.action_type),
                         ));
                     }
                 };
-                year_weights.insert(action, *weight);
+                insert_kind_weight(&mut year_weights, &action, *weight);
             }
             weights.insert(*year, year_weights);
         }
@@ -255,10 +377,20 @@ impl ActionWeights {
                     "CloseGenerator" => {
                         GridAction::CloseGenerator(serializable_action.generator_id.clone().unwrap_or_default())
                     },
+                    "AddHybridPlant" => match hybrid_plant_action(serializable_action) {
+                        Some(action) => action,
+                        None => continue,
+                    },
+                    "RetrofitGenerator" => match retrofit_action(serializable_action) {
+                        Some(action) => action,
+                        None => continue,
+                    },
+                    "AddFlexibleLoad" => GridAction::AddFlexibleLoad,
                     "DoNothing" => GridAction::DoNothing,
+                    "DeferDecision" => GridAction::DeferDecision,
                     _ => continue,
                 };
-                year_weights.insert(action, *weight);
+                insert_kind_weight(&mut year_weights, &action, *weight);
             }
             deficit_weights.insert(*year, year_weights);
         }
@@ -266,19 +398,7 @@ impl ActionWeights {
         // If no deficit weights were found in the file, initialize them with defaults
         if deficit_weights.is_empty() {
             for year in START_YEAR..=END_YEAR {
-                let mut deficit_year_weights = HashMap::new();
-                deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::GasPeaker, DEFAULT_COST_MULTIPLIER), DEFICIT_GAS_PEAKER_WEIGHT);
-                deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::GasCombinedCycle, DEFAULT_COST_MULTIPLIER), DEFICIT_GAS_COMBINED_WEIGHT);
-                deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::BatteryStorage, DEFAULT_COST_MULTIPLIER), DEFICIT_BATTERY_WEIGHT);
-                deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::PumpedStorage, DEFAULT_COST_MULTIPLIER), DEFICIT_PUMPED_STORAGE_WEIGHT);
-                deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::Biomass, DEFAULT_COST_MULTIPLIER), DEFICIT_BIOMASS_WEIGHT);
-                deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::OnshoreWind, DEFAULT_COST_MULTIPLIER), DEFICIT_ONSHORE_WIND_WEIGHT);
-                deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::OffshoreWind, DEFAULT_COST_MULTIPLIER), DEFICIT_OFFSHORE_WIND_WEIGHT);
-                deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::UtilitySolar, DEFAULT_COST_MULTIPLIER), DEFICIT_UTILITY_SOLAR_WEIGHT);
-                deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::HydroDam, DEFAULT_COST_MULTIPLIER), DEFICIT_HYDRO_DAM_WEIGHT);
-                deficit_year_weights.insert(GridAction::AddGenerator(GeneratorType::Nuclear, DEFAULT_COST_MULTIPLIER), DEFICIT_NUCLEAR_WEIGHT);
-                deficit_year_weights.insert(GridAction::DoNothing, DEFICIT_DO_NOTHING_WEIGHT);
-                deficit_weights.insert(year, deficit_year_weights);
+                deficit_weights.insert(year, Self::default_deficit_year_weights());
             }
         }
         
@@ -328,10 +448,20 @@ impl ActionWeights {
                             "CloseGenerator" => {
                                 GridAction::CloseGenerator(serializable_action.generator_id.clone().unwrap_or_default())
                             },
+                            "AddHybridPlant" => match hybrid_plant_action(serializable_action) {
+                                Some(action) => action,
+                                None => continue,
+                            },
+                            "RetrofitGenerator" => match retrofit_action(serializable_action) {
+                                Some(action) => action,
+                                None => continue,
+                            },
+                            "AddFlexibleLoad" => GridAction::AddFlexibleLoad,
                             "DoNothing" => GridAction::DoNothing,
+                            "DeferDecision" => GridAction::DeferDecision,
                             _ => continue,
                         };
-                        year_weights.insert(action, *weight);
+                        insert_kind_weight(&mut year_weights, &action, *weight);
                     }
                     (*year, year_weights)
                 })
@@ -384,7 +514,17 @@ impl ActionWeights {
                         "CloseGenerator" => {
                             GridAction::CloseGenerator(serializable_action.generator_id.clone().unwrap_or_default())
                         },
+                        "AddHybridPlant" => match hybrid_plant_action(serializable_action) {
+                            Some(action) => action,
+                            None => continue,
+                        },
+                        "RetrofitGenerator" => match retrofit_action(serializable_action) {
+                            Some(action) => action,
+                            None => continue,
+                        },
+                        "AddFlexibleLoad" => GridAction::AddFlexibleLoad,
                         "DoNothing" => GridAction::DoNothing,
+                        "DeferDecision" => GridAction::DeferDecision,
                         _ => continue,
                     };
                     actions.push(action);
@@ -440,7 +580,17 @@ impl ActionWeights {
                         "CloseGenerator" => {
                             GridAction::CloseGenerator(serializable_action.generator_id.clone().unwrap_or_default())
                         },
+                        "AddHybridPlant" => match hybrid_plant_action(serializable_action) {
+                            Some(action) => action,
+                            None => continue,
+                        },
+                        "RetrofitGenerator" => match retrofit_action(serializable_action) {
+                            Some(action) => action,
+                            None => continue,
+                        },
+                        "AddFlexibleLoad" => GridAction::AddFlexibleLoad,
                         "DoNothing" => GridAction::DoNothing,
+                        "DeferDecision" => GridAction::DeferDecision,
                         _ => continue,
                     };
                     actions.push(action);
@@ -487,8 +637,14 @@ impl ActionWeights {
             deterministic_rng: None,
             guaranteed_best_actions: false,
             optimization_mode: serializable.optimization_mode,
+            scoring: ScoringContext::default(),
             replay_index: HashMap::new(),
             improvement_history,
+            stagnation_policy: StagnationPolicy::default(),
+            contrast_policy: ContrastLearningPolicy::default(),
+            exploration_schedule: ExplorationSchedule::default(),
+            recent_scores: VecDeque::new(),
+            noop_max_share: NOOP_MAX_WEIGHT_SHARE,
         })
     }
 
@@ -505,7 +661,7 @@ impl ActionWeights {
             "guaranteed_best_actions": self.guaranteed_best_actions,
             "optimization_mode": self.optimization_mode,
             "best_score": self.best_metrics.as_ref().map(|metrics| {
-                crate::ai::score_metrics(metrics, self.optimization_mode.as_deref())
+                crate::ai::score_metrics(metrics, self.optimization_mode.as_deref(), &self.scoring)
             }).unwrap_or(0.0)
         });
 
@@ -540,3 +696,112 @@ impl ActionWeights {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{scratch_path, test_metrics};
+
+    #[test]
+    fn corrupt_weights_file_is_a_serialization_error() {
+        let path = scratch_path("corrupt_weights.json");
+        std::fs::write(&path, "{\"weights\": [1, 2,").unwrap();
+
+        let result = ActionWeights::load_from_file(path.to_str().unwrap());
+
+        assert!(matches!(result, Err(SimulationError::Serialization(_))));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn truncated_checkpoint_errors_and_a_half_written_save_never_replaces_a_good_one() {
+        let path = scratch_path("checkpoint_weights.json");
+        let path_str = path.to_str().unwrap();
+        let mut good = ActionWeights::new();
+        good.update_best_strategy(test_metrics(1.0e6, 2.0e10, 0.6));
+        good.save_to_file(path_str).unwrap();
+
+        // A save that dies part way through its write leaves the good checkpoint in place
+        let failed = write_atomically(path_str, |temp_path| {
+            std::fs::write(temp_path, "{\"weights\": {\"2025\"")?;
+            Err(std::io::Error::other("interrupted"))
+        });
+        assert!(failed.is_err());
+        let loaded = ActionWeights::load_from_file(path_str).unwrap();
+        assert_eq!(loaded.get_best_metrics(), good.get_best_metrics());
+
+        // A truncated file with no earlier checkpoint to fall back on is an error
+        let lone = scratch_path("truncated_weights.json");
+        let json = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&lone, &json[..json.len() / 2]).unwrap();
+        assert!(ActionWeights::load_from_file(lone.to_str().unwrap()).is_err());
+
+        let _ = std::fs::remove_file(&lone);
+        for file in [path_str.to_string(), format!("{}.tmp", path_str), previous_checkpoint_path(path_str)] {
+            let _ = std::fs::remove_file(file);
+        }
+    }
+
+    #[test]
+    fn saving_the_same_weights_twice_gives_identical_bytes() {
+        let mut weights = ActionWeights::new();
+        for year in [2025, 2030, 2040] {
+            weights.update_weights(&GridAction::AddGenerator(GeneratorType::OnshoreWind, DEFAULT_COST_MULTIPLIER), year, 0.2);
+            weights.update_weights(&GridAction::CloseGenerator("Existing_Gas".to_string()), year, -0.1);
+            weights.update_weights(&GridAction::AddCarbonOffset(CarbonOffsetType::Forest, DEFAULT_COST_MULTIPLIER), year, 0.05);
+        }
+        weights.update_best_strategy(test_metrics(1.0e6, 2.0e10, 0.6));
+        let first = scratch_path("stable_weights_a.json");
+        let second = scratch_path("stable_weights_b.json");
+        weights.save_to_file(first.to_str().unwrap()).unwrap();
+        weights.save_to_file(second.to_str().unwrap()).unwrap();
+        assert_eq!(std::fs::read(&first).unwrap(), std::fs::read(&second).unwrap());
+
+        // Two loads build their maps separately, so any iteration order leaking into the file shows up
+        let reloaded_a = scratch_path("stable_weights_c.json");
+        let reloaded_b = scratch_path("stable_weights_d.json");
+        for path in [&reloaded_a, &reloaded_b] {
+            let reloaded = ActionWeights::load_from_file(first.to_str().unwrap()).unwrap();
+            reloaded.save_to_file(path.to_str().unwrap()).unwrap();
+        }
+        assert_eq!(std::fs::read(&reloaded_a).unwrap(), std::fs::read(&reloaded_b).unwrap());
+        for path in [first, second, reloaded_a, reloaded_b] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn v1_checkpoint_without_newer_fields_loads_with_defaults() {
+        let path = scratch_path("v1_weights.json");
+        let path_str = path.to_str().unwrap();
+        let mut trained = ActionWeights::new();
+        trained.learning_rate = 0.5;
+        trained.exploration_rate = 0.05;
+        trained.iteration_count = 42;
+        trained.iterations_without_improvement = 7;
+        trained.save_to_file(path_str).unwrap();
+
+        // Strip it back to the unversioned v1 shape, before the learning state was saved
+        let mut checkpoint: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let object = checkpoint.as_object_mut().unwrap();
+        for field in ["version", "learning_rate", "iteration_count", "iterations_without_improvement", "exploration_rate", "deficit_weights"] {
+            assert!(object.remove(field).is_some(), "{} not saved", field);
+        }
+        std::fs::write(&path, serde_json::to_string(&checkpoint).unwrap()).unwrap();
+
+        let loaded = ActionWeights::load_from_file(path_str).unwrap();
+
+        assert_eq!(loaded.learning_rate, DEFAULT_LEARNING_RATE);
+        assert_eq!(loaded.exploration_rate, DEFAULT_EXPLORATION_RATE);
+        assert_eq!(loaded.iteration_count, 0);
+        assert_eq!(loaded.iterations_without_improvement, 0);
+        assert_eq!(loaded.weights.len(), trained.weights.len());
+
+        // Saving again writes the current schema version
+        loaded.save_to_file(path_str).unwrap();
+        let saved: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["version"], CHECKPOINT_VERSION);
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(previous_checkpoint_path(path_str));
+    }
+}
@@ -17,50 +17,47 @@ impl ActionWeights {
 // Appropriate imports will need to be added based on the specific requirements
 
     pub fn update_best_strategy(&mut self, metrics: SimulationMetrics) {
-        let current_score = score_metrics(&metrics, self.optimization_mode.as_deref());
+        let current_score = score_metrics(&metrics, self.optimization_mode.as_deref(), &self.scoring);
+        self.record_recent_score(current_score);
         
         // Debug: Print current_run_actions info with more detailed breakdown - only if debug weights is enabled
         if crate::ai::learning::constants::is_debug_weights_enabled() {
             let total_curr_actions = self.current_run_actions.values().map(|v| v.len()).sum::<usize>();
             let years_with_curr_actions = self.current_run_actions.values().filter(|v| !v.is_empty()).count();
-            println!("DEBUG: Before update - Current run has {} actions across {} years", 
+            crate::log!(Debug, "DEBUG: Before update - Current run has {} actions across {} years", 
                     total_curr_actions, years_with_curr_actions);
             
             // More detailed per-year breakdown for the current run
-            println!("Current run actions per year:");
+            crate::log!(Debug, "Current run actions per year:");
             
             // If we have empty current_run_actions but non-empty best actions, something's wrong
             if total_curr_actions == ZERO_USIZE && self.best_actions.is_some() {
-                println!("⚠️ WARNING: Attempting to update best strategy with 0 actions in current run!");
-                println!("This suggests actions aren't being recorded properly during simulation");
+                crate::log!(Warn, "⚠️ WARNING: Attempting to update best strategy with 0 actions in current run!");
+                crate::log!(Warn, "This suggests actions aren't being recorded properly during simulation");
             }
             
             // DIAGNOSTIC: Add logging to check metrics values
-            println!("DIAGNOSTIC: Metrics values before processing:");
-            println!("  - final_net_emissions: {}", metrics.final_net_emissions);
-            println!("  - total_cost: {}", metrics.total_cost);
-            println!("  - average_public_opinion: {}", metrics.average_public_opinion);
-            println!("  - power_reliability: {}", metrics.power_reliability);
-            println!("  - iteration_count: {}", self.iteration_count);
+            crate::log!(Debug, "DIAGNOSTIC: Metrics values before processing:");
+            crate::log!(Debug, "  - final_net_emissions: {}", metrics.final_net_emissions);
+            crate::log!(Debug, "  - total_cost: {}", metrics.total_cost);
+            crate::log!(Debug, "  - average_public_opinion: {}", metrics.average_public_opinion);
+            crate::log!(Debug, "  - power_reliability: {}", metrics.power_reliability);
+            crate::log!(Debug, "  - iteration_count: {}", self.iteration_count);
         }
         
         // Increment iteration count
         self.iteration_count += 1;
         
         // Print iteration count update only if debug weights is enabled
-        if crate::ai::learning::constants::is_debug_weights_enabled() {
-            println!("DIAGNOSTIC: Incremented iteration_count to {}", self.iteration_count);
-        }
+        crate::log!(Debug, "DIAGNOSTIC: Incremented iteration_count to {}", self.iteration_count);
         
         let should_update = match &self.best_metrics {
             None => true,
             Some(best) => {
-                let best_score = score_metrics(best, self.optimization_mode.as_deref());
+                let best_score = score_metrics(best, self.optimization_mode.as_deref(), &self.scoring);
                 
                 // DIAGNOSTIC: Add score comparison logging - only if debug weights is enabled
-                if crate::ai::learning::constants::is_debug_weights_enabled() {
-                    println!("DIAGNOSTIC: Score comparison - current: {}, best: {}", current_score, best_score);
-                }
+                crate::log!(Debug, "DIAGNOSTIC: Score comparison - current: {}, best: {}", current_score, best_score);
                 
                 // If new score is better than best score, update
                 current_score > best_score
@@ -85,7 +82,7 @@ impl ActionWeights {
         
             // Only print improvement message if we actually had a previous best
             if let Some(best) = &self.best_metrics {
-                let best_score = score_metrics(best, self.optimization_mode.as_deref());
+                let best_score = score_metrics(best, self.optimization_mode.as_deref(), &self.scoring);
                 let improvement = ((current_score - best_score) / best_score * PERCENT_CONVERSION).abs();
                 
                 // Create a VERY visible message with details about the improvement
@@ -109,8 +106,8 @@ impl ActionWeights {
                         emissions_emoji, best.final_net_emissions, metrics.final_net_emissions, emissions_change);
                 
                 // Net zero status comparison
-                let old_net_zero = best.final_net_emissions <= ZERO_F64;
-                let new_net_zero = metrics.final_net_emissions <= ZERO_F64;
+                let old_net_zero = best.is_net_zero_under(self.scoring.net_zero_tolerance, self.scoring.net_zero_real_reductions_only);
+                let new_net_zero = metrics.is_net_zero_under(self.scoring.net_zero_tolerance, self.scoring.net_zero_real_reductions_only);
                 let net_zero_emoji = if new_net_zero { "✅" } else { "⚠️" };
                 println!("  {} Net zero: {} → {}", 
                         net_zero_emoji, 
@@ -169,7 +166,7 @@ impl ActionWeights {
             }
             
             // DIAGNOSTIC: Log before updating best metrics
-            println!("DIAGNOSTIC: Updating best_metrics from current metrics");
+            crate::log!(Debug, "DIAGNOSTIC: Updating best_metrics from current metrics");
             
             self.best_metrics = Some(metrics);
             self.best_weights = Some(self.weights.clone());
@@ -189,7 +186,7 @@ impl ActionWeights {
                 if !actions.is_empty() {
                     // Only print debug messages every 100 iterations
                     if self.iteration_count % 100 == 0 {
-                        println!("DEBUG: Copying {} actions for year {} to best_actions", actions.len(), year);
+                        crate::log!(Debug, "DEBUG: Copying {} actions for year {} to best_actions", actions.len(), year);
                     }
                     complete_actions.insert(*year, actions.clone());
                 }
@@ -199,7 +196,7 @@ impl ActionWeights {
                 if !actions.is_empty() {
                     // Only print debug messages every 100 iterations
                     if self.iteration_count % 100 == 0 {
-                        println!("DEBUG: Copying {} deficit actions for year {} to best_deficit_actions", actions.len(), year);
+                        crate::log!(Debug, "DEBUG: Copying {} deficit actions for year {} to best_deficit_actions", actions.len(), year);
                     }
                     complete_deficit_actions.insert(*year, actions.clone());
                 }
@@ -208,7 +205,7 @@ impl ActionWeights {
             // Debug: Check if we're actually capturing any actions
             let total_complete_actions = complete_actions.values().map(|v| v.len()).sum::<usize>();
             let years_with_complete_actions = complete_actions.values().filter(|v| !v.is_empty()).count();
-            println!("DEBUG: Created best_actions map with {} actions across {} years", 
+            crate::log!(Debug, "DEBUG: Created best_actions map with {} actions across {} years", 
                     total_complete_actions, years_with_complete_actions);
             
             // More detailed per-year breakdown for complete_actions
@@ -229,7 +226,7 @@ impl ActionWeights {
             if let Some(ref best_actions) = self.best_actions {
                 let total_best_actions = best_actions.values().map(|v| v.len()).sum::<usize>();
                 let years_with_best_actions = best_actions.values().filter(|v| !v.is_empty()).count();
-                println!("DEBUG: After update - best_actions now has {} actions across {} years", 
+                crate::log!(Debug, "DEBUG: After update - best_actions now has {} actions across {} years", 
                         total_best_actions, years_with_best_actions);
                 
                 // Detailed per-year breakdown of best actions
@@ -278,6 +275,28 @@ impl ActionWeights {
         self.current_run_actions.get(&year)
     }
 
+    // Flatten the stored best strategy into a year-ordered build plan.
+    // Deficit actions come first within a year, matching the order they're applied in the simulation.
+    pub fn export_plan(&self) -> Vec<(u32, GridAction)> {
+        let mut years: Vec<u32> = self.best_actions.iter()
+            .chain(self.best_deficit_actions.iter())
+            .flat_map(|actions| actions.keys().copied())
+            .collect();
+        years.sort_unstable();
+        years.dedup();
+
+        let mut plan = Vec::new();
+        for year in years {
+            if let Some(deficit_actions) = self.best_deficit_actions.as_ref().and_then(|a| a.get(&year)) {
+                plan.extend(deficit_actions.iter().map(|action| (year, action.clone())));
+            }
+            if let Some(actions) = self.best_actions.as_ref().and_then(|a| a.get(&year)) {
+                plan.extend(actions.iter().map(|action| (year, action.clone())));
+            }
+        }
+        plan
+    }
+
     pub fn update_weights_from(&mut self, other: &ActionWeights) {
         // Copy weights
         for (year, other_year_weights) in &other.weights {
@@ -310,6 +329,45 @@ impl ActionWeights {
         self.transfer_recorded_actions_from(other);
     }
 
+    /// Combines another instance's learned weights with these, averaging each shared entry by the
+    /// two instances' iteration counts. Entries only one side has learned are kept as they are.
+    pub fn merge(&mut self, other: &ActionWeights) {
+        // Instances that haven't iterated yet still count, so fresh weights average evenly
+        let self_share = self.iteration_count.max(1) as f64;
+        let other_share = other.iteration_count.max(1) as f64;
+        let average = |ours: f64, theirs: f64| (ours * self_share + theirs * other_share) / (self_share + other_share);
+
+        for (year, other_year_weights) in &other.weights {
+            let year_weights = self.weights.entry(*year).or_default();
+            for (action, weight) in other_year_weights {
+                year_weights.entry(action.clone())
+                    .and_modify(|ours| *ours = average(*ours, *weight))
+                    .or_insert(*weight);
+            }
+        }
+
+        for (year, other_deficit_weights) in &other.deficit_weights {
+            let deficit_weights = self.deficit_weights.entry(*year).or_default();
+            for (action, weight) in other_deficit_weights {
+                deficit_weights.entry(action.clone())
+                    .and_modify(|ours| *ours = average(*ours, *weight))
+                    .or_insert(*weight);
+            }
+        }
+
+        for (year, other_count_weights) in &other.action_count_weights {
+            let count_weights = self.action_count_weights.entry(*year).or_default();
+            for (count, weight) in other_count_weights {
+                count_weights.entry(*count)
+                    .and_modify(|ours| *ours = average(*ours, *weight))
+                    .or_insert(*weight);
+            }
+        }
+
+        // Parallel instances share their history up to the fork, so counts aren't summed
+        self.iteration_count = std::cmp::max(self.iteration_count, other.iteration_count);
+    }
+
     pub fn transfer_recorded_actions_from(&mut self, other: &ActionWeights) {
         // Clear our current run actions first
         self.current_run_actions.clear();
@@ -336,9 +394,59 @@ impl ActionWeights {
             let total_actions = self.current_run_actions.values().map(|v| v.len()).sum::<usize>();
             let years_with_actions = self.current_run_actions.values().filter(|v| !v.is_empty()).count();
             
-            println!("DEBUG: Transferred {} actions across {} years from local weights", 
+            crate::log!(Debug, "DEBUG: Transferred {} actions across {} years from local weights", 
                     total_actions, years_with_actions);
         }
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::actions::grid_action_kind::GridActionKind;
+    use crate::models::generator::GeneratorType;
+
+    #[test]
+    fn known_best_actions_export_in_year_order() {
+        let mut weights = ActionWeights::new();
+        weights.best_actions = Some(HashMap::from([
+            (2030, vec![GridAction::AddGenerator(GeneratorType::OnshoreWind, 100)]),
+            (2026, vec![GridAction::CloseGenerator("Coal_1".to_string()), GridAction::DoNothing]),
+        ]));
+        weights.best_deficit_actions = Some(HashMap::from([
+            (2028, vec![GridAction::AddGenerator(GeneratorType::BatteryStorage, 100)]),
+        ]));
+
+        let plan = weights.export_plan();
+
+        assert_eq!(plan, vec![
+            (2026, GridAction::CloseGenerator("Coal_1".to_string())),
+            (2026, GridAction::DoNothing),
+            (2028, GridAction::AddGenerator(GeneratorType::BatteryStorage, 100)),
+            (2030, GridAction::AddGenerator(GeneratorType::OnshoreWind, 100)),
+        ]);
+    }
+
+    #[test]
+    fn merge_averages_weights_for_the_same_action() {
+        let wind = GridActionKind::AddGenerator(GeneratorType::OnshoreWind, 100);
+        let with_wind_weight = |weight: f64, iteration_count: u32| {
+            let mut weights = ActionWeights::new();
+            weights.weights.entry(2030).or_default().insert(wind.clone(), weight);
+            weights.iteration_count = iteration_count;
+            weights
+        };
+        let merged_weight = |weights: &ActionWeights| weights.weights[&2030][&wind];
+
+        let mut merged = with_wind_weight(0.2, 0);
+        merged.merge(&with_wind_weight(0.6, 0));
+        assert!((merged_weight(&merged) - 0.4).abs() < 1e-12);
+
+        // Weighted by iteration count, so the longer-trained instance counts for more
+        let mut merged = with_wind_weight(0.2, 30);
+        merged.merge(&with_wind_weight(0.6, 10));
+        assert!((merged_weight(&merged) - 0.3).abs() < 1e-12);
+        assert_eq!(merged.iteration_count, 30);
+    }
+}
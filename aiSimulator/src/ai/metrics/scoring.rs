@@ -1,24 +1,253 @@
 // Scoring module - contains functions for evaluating simulation metrics
 use super::simulation_metrics::{SimulationMetrics, ActionResult};
 use crate::ai::learning::constants::*;
+use crate::config::simulation_config::SimulationConfig;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
-pub fn score_metrics(metrics: &SimulationMetrics, optimization_mode: Option<&str>) -> f64 {
+// Settings from the run's SimulationConfig that change how metrics score. Built once per run
+// (or per scoring call site) and passed in, so every entry point scores against its own config.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoringContext {
+    pub cost_cap: f64, // Spend limit for the "budget_capped" mode, infinite when none is set
+    pub score_weights: ScoreWeights, // Objective weights for the "weighted" mode
+    pub carbon_budget: f64, // Cumulative net emissions budget in tonnes, infinite when none is set
+    pub renewable_mandate: Vec<(u32, f64)>, // Year -> minimum renewable share for the "renewable_mandate" mode
+    pub net_zero_tolerance: f64,            // Final-year net emissions in tonnes still counted as net zero
+    pub net_zero_real_reductions_only: bool, // Leave carbon credits out when checking net zero
+}
+
+impl ScoringContext {
+    pub fn from_config(config: &SimulationConfig) -> Self {
+        Self {
+            cost_cap: config.cost_cap.unwrap_or(f64::INFINITY),
+            score_weights: config.score_weights.unwrap_or_default(),
+            carbon_budget: config.carbon_budget.unwrap_or(f64::INFINITY),
+            renewable_mandate: config.renewable_mandate.clone(),
+            net_zero_tolerance: config.net_zero_tolerance,
+            net_zero_real_reductions_only: config.net_zero_real_reductions_only,
+        }
+    }
+}
+
+impl Default for ScoringContext {
+    fn default() -> Self {
+        Self {
+            cost_cap: f64::INFINITY,
+            score_weights: ScoreWeights::DEFAULT,
+            carbon_budget: f64::INFINITY,
+            renewable_mandate: Vec::new(),
+            net_zero_tolerance: 0.0,
+            net_zero_real_reductions_only: false,
+        }
+    }
+}
+
+// Relative importance of each objective for the "weighted" optimization mode.
+// Every metric is normalized to [0,1] before the weighted sum, so only the ratios matter.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScoreWeights {
+    pub emissions: f64,
+    pub cost: f64,
+    pub opinion: f64,
+    pub reliability: f64,
+}
+
+impl ScoreWeights {
+    pub const DEFAULT: ScoreWeights = ScoreWeights { emissions: 0.6, cost: 0.25, opinion: 0.15, reliability: 0.0 };
+    pub const COST_ONLY: ScoreWeights = ScoreWeights { emissions: 0.0, cost: 1.0, opinion: 0.0, reliability: 0.0 };
+
+    // Preset weight vector for a named mode: "cost_only" is scored through COST_ONLY, and
+    // DEFAULT is what the "weighted" mode uses when no weights are configured
+    pub fn for_mode(optimization_mode: Option<&str>) -> Option<ScoreWeights> {
+        match optimization_mode {
+            None | Some("default") => Some(Self::DEFAULT),
+            Some("cost_only") => Some(Self::COST_ONLY),
+            Some(_) => None,
+        }
+    }
+
+    fn total(&self) -> f64 {
+        self.emissions + self.cost + self.opinion + self.reliability
+    }
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+// Accepts a preset name ("default", "cost_only") or four comma-separated
+// weights in the order emissions,cost,opinion,reliability
+impl FromStr for ScoreWeights {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(preset) = Self::for_mode(Some(s.trim())) {
+            return Ok(preset);
+        }
+
+        let values = s.split(',')
+            .map(|part| part.trim().parse::<f64>()
+                .map_err(|e| format!("Invalid score weight '{}': {}", part.trim(), e)))
+            .collect::<Result<Vec<f64>, String>>()?;
+        if values.len() != 4 {
+            return Err(format!("Expected 4 score weights (emissions,cost,opinion,reliability), got {}", values.len()));
+        }
+        if values.iter().any(|w| *w < ZERO_F64 || !w.is_finite()) {
+            return Err("Score weights must be finite and non-negative".to_string());
+        }
+
+        let weights = ScoreWeights { emissions: values[0], cost: values[1], opinion: values[2], reliability: values[3] };
+        if weights.total() <= ZERO_F64 {
+            return Err("At least one score weight must be positive".to_string());
+        }
+        Ok(weights)
+    }
+}
+
+// Emissions mapped to [0,1], where 1.0 is net zero or better
+fn normalized_emissions(net_emissions: f64) -> f64 {
+    ONE_F64 - (net_emissions / MAX_ACCEPTABLE_EMISSIONS).clamp(ZERO_F64, ONE_F64)
+}
+
+// Cost mapped to [0,1] on a log scale, where 1.0 is at or under the acceptable cost
+fn normalized_cost(total_cost: f64) -> f64 {
+    let log_cost = (total_cost / MAX_ACCEPTABLE_COST).max(ONE_F64).ln();
+    let max_expected_log_cost = MAX_BUDGET_MULTIPLIER.ln();
+    ONE_F64 - (log_cost / max_expected_log_cost).min(ONE_F64)
+}
+
+fn weighted_sum(weights: &ScoreWeights, emissions: f64, cost: f64, opinion: f64, reliability: f64) -> f64 {
+    let total = weights.total();
+    if total <= ZERO_F64 {
+        return ZERO_F64;
+    }
+    (weights.emissions * emissions +
+        weights.cost * cost +
+        weights.opinion * opinion.clamp(ZERO_F64, ONE_F64) +
+        weights.reliability * reliability.clamp(ZERO_F64, ONE_F64)) / total
+}
+
+// Weighted scalarization of the normalized objectives, in [0,1]
+pub fn score_metrics_weighted(metrics: &SimulationMetrics, weights: &ScoreWeights) -> f64 {
+    weighted_sum(
+        weights,
+        normalized_emissions(metrics.final_net_emissions),
+        normalized_cost(metrics.total_cost),
+        metrics.average_public_opinion,
+        metrics.power_reliability,
+    )
+}
+
+fn score_action_result_weighted(state: &ActionResult, weights: &ScoreWeights) -> f64 {
+    let reliability = if state.power_balance >= ZERO_F64 { ONE_F64 } else { ZERO_F64 };
+    weighted_sum(
+        weights,
+        normalized_emissions(state.net_emissions),
+        normalized_cost(state.total_cost),
+        state.public_opinion,
+        reliability,
+    )
+}
+
+// Mean yearly overshoot above a target line falling linearly to zero by END_YEAR, in [0,1]
+fn emissions_trajectory_overshoot(yearly_net_emissions: &[f64]) -> f64 {
+    if yearly_net_emissions.is_empty() {
+        return ZERO_F64;
+    }
+    let span = (END_YEAR - START_YEAR) as f64;
+    let overshoot: f64 = yearly_net_emissions.iter()
+        .enumerate()
+        .map(|(offset, emissions)| {
+            let remaining = (ONE_F64 - offset as f64 / span).max(ZERO_F64);
+            (emissions - TRAJECTORY_TARGET_START_EMISSIONS * remaining).max(ZERO_F64)
+        })
+        .sum();
+    (overshoot / (MAX_ACCEPTABLE_EMISSIONS * yearly_net_emissions.len() as f64)).min(ONE_F64)
+}
+
+// Mean yearly shortfall of the renewable share below the mandated trajectory, in [0,1]
+fn renewable_mandate_shortfall(yearly_renewable_share: &[f64], mandate: &[(u32, f64)]) -> f64 {
+    if mandate.is_empty() || yearly_renewable_share.is_empty() {
+        return ZERO_F64;
+    }
+    let shortfall: f64 = yearly_renewable_share.iter()
+        .enumerate()
+        .map(|(offset, share)| {
+            let required = crate::config::const_funcs::interpolate_trajectory(mandate, START_YEAR + offset as u32);
+            (required - share).max(ZERO_F64)
+        })
+        .sum();
+    (shortfall / yearly_renewable_share.len() as f64).min(ONE_F64)
+}
+
+// Score lost by a plan whose cumulative net emissions exceed the carbon budget, zero within it
+fn carbon_budget_penalty(cumulative_net_emissions: f64, budget: f64) -> f64 {
+    if cumulative_net_emissions <= budget {
+        return ZERO_F64;
+    }
+    let overrun = (cumulative_net_emissions - budget) / budget.abs().max(ONE_F64);
+    CARBON_BUDGET_BASE_PENALTY + overrun.min(ONE_F64)
+}
+
+pub fn score_metrics(metrics: &SimulationMetrics, optimization_mode: Option<&str>, scoring: &ScoringContext) -> f64 {
+    // The carbon budget is a hard constraint on top of whichever objective is in use
+    score_objective(metrics, optimization_mode, scoring) - carbon_budget_penalty(metrics.cumulative_net_emissions, scoring.carbon_budget)
+}
+
+fn score_objective(metrics: &SimulationMetrics, optimization_mode: Option<&str>, scoring: &ScoringContext) -> f64 {
     // Check for cost-only optimization mode
     if let Some(mode) = optimization_mode {
         if mode == "cost_only" {
-            // In cost-only mode, only consider cost improvements regardless of emissions state
-            // Normalize and invert cost so lower costs give higher scores
-            let normalized_cost = (metrics.total_cost / MAX_ACCEPTABLE_COST).max(ONE_F64);
-            let log_cost = normalized_cost.ln();
-            let max_expected_log_cost = (MAX_ACCEPTABLE_COST * MAX_BUDGET_MULTIPLIER / MAX_ACCEPTABLE_COST).ln(); // Assume 100x budget is max
-            return MAX_SCORE_RANGE - (log_cost / max_expected_log_cost).min(ONE_F64); // Return value between 1.0 and 2.0
+            // In cost-only mode, only consider cost improvements regardless of emissions state.
+            // Offset by one so the score stays between 1.0 and 2.0 as before the preset existed.
+            return ONE_F64 + score_metrics_weighted(metrics, &ScoreWeights::COST_ONLY);
+        }
+        
+        if mode == "weighted" {
+            return score_metrics_weighted(metrics, &scoring.score_weights);
+        }
+        
+        if mode == "trajectory" {
+            // Default priorities, less the emissions carried above the decarbonization path on the way to 2050
+            return score_objective(metrics, None, scoring) -
+                TRAJECTORY_PENALTY_WEIGHT * emissions_trajectory_overshoot(&metrics.yearly_net_emissions);
+        }
+        
+        if mode == "renewable_mandate" {
+            // Default priorities, less the renewable share missing against the mandated trajectory
+            return score_objective(metrics, None, scoring) -
+                RENEWABLE_MANDATE_PENALTY_WEIGHT * renewable_mandate_shortfall(&metrics.yearly_renewable_share, &scoring.renewable_mandate);
+        }
+        
+        if mode == "min_footprint" {
+            // Default priorities, less a penalty growing with the land the final fleet occupies
+            return score_objective(metrics, None, scoring) -
+                FOOTPRINT_PENALTY_WEIGHT * (metrics.total_land_use / MAX_ACCEPTABLE_LAND_USE).min(ONE_F64);
+        }
+        
+        if mode == "budget_capped" {
+            // Any plan over the cap scores below every plan within it
+            let cost_cap = scoring.cost_cap;
+            if metrics.total_cost > cost_cap {
+                let overrun = (metrics.total_cost - cost_cap) / cost_cap.max(ONE_F64);
+                return -BUDGET_OVERRUN_BASE_PENALTY - overrun.min(ONE_F64);
+            }
+            
+            // Within the cap, spend is free: reduce emissions first, then maximise opinion
+            if !metrics.is_net_zero_under(scoring.net_zero_tolerance, scoring.net_zero_real_reductions_only) {
+                return ONE_F64 - (metrics.counted_net_emissions(scoring.net_zero_real_reductions_only) / MAX_ACCEPTABLE_EMISSIONS).min(ONE_F64);
+            }
+            return BASE_NET_ZERO_SCORE + metrics.average_public_opinion;
         }
     }
 
     // Default scoring logic - First priority: Reach net zero emissions
-    if metrics.final_net_emissions > ZERO_F64 {
+    if !metrics.is_net_zero_under(scoring.net_zero_tolerance, scoring.net_zero_real_reductions_only) {
         // If we haven't achieved net zero, only focus on reducing emissions
-        ONE_F64 - (metrics.final_net_emissions / MAX_ACCEPTABLE_EMISSIONS).min(ONE_F64)
+        ONE_F64 - (metrics.counted_net_emissions(scoring.net_zero_real_reductions_only) / MAX_ACCEPTABLE_EMISSIONS).min(ONE_F64)
     }
     // Second priority: Optimize costs after achieving net zero
     else {
@@ -47,6 +276,7 @@ pub fn evaluate_action_impact(
     current_state: &ActionResult,
     new_state: &ActionResult,
     optimization_mode: Option<&str>,
+    scoring: &ScoringContext,
 ) -> f64 {
     // Check for cost-only optimization mode
     if let Some(mode) = optimization_mode {
@@ -55,6 +285,31 @@ pub fn evaluate_action_impact(
             let cost_change = new_state.total_cost - current_state.total_cost;
             return -cost_change / current_state.total_cost.abs().max(ONE_F64);
         }
+        
+        if mode == "weighted" {
+            return score_action_result_weighted(new_state, &scoring.score_weights) -
+                score_action_result_weighted(current_state, &scoring.score_weights);
+        }
+        
+        if mode == "budget_capped" {
+            let cost_cap = scoring.cost_cap;
+            if new_state.total_cost > cost_cap {
+                let cost_change = new_state.total_cost - current_state.total_cost;
+                if current_state.total_cost <= cost_cap {
+                    // Pushing spend over the cap is always a regression
+                    return -BUDGET_OVERRUN_BASE_PENALTY - (cost_change / cost_cap.max(ONE_F64)).min(ONE_F64);
+                }
+                // Already over the cap: only cost reductions count
+                return -cost_change / current_state.total_cost.abs().max(ONE_F64);
+            }
+            
+            if current_state.net_emissions > ZERO_F64 {
+                return (current_state.net_emissions - new_state.net_emissions) /
+                    current_state.net_emissions.abs().max(ONE_F64);
+            }
+            return (new_state.public_opinion - current_state.public_opinion) /
+                current_state.public_opinion.abs().max(ONE_F64);
+        }
     }
 
     // Default evaluation logic
@@ -82,4 +337,136 @@ pub fn evaluate_action_impact(
         // Combined improvement score
         cost_improvement * cost_weight + opinion_improvement * opinion_weight
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::metrics_calculation::{annual_energy_mwh, calculate_yearly_metrics};
+    use crate::analysis::uncertainty::AvailabilityFactors;
+    use crate::config::constants::{BASE_YEAR, END_YEAR};
+    use crate::config::simulation_config::SimulationConfig;
+    use crate::models::generator::GeneratorType;
+    use crate::test_support::{empty_map, test_generator, test_metrics, TEST_SITE};
+
+    #[test]
+    fn any_over_cap_plan_scores_below_any_under_cap_plan() {
+        let scoring = ScoringContext { cost_cap: 5.0e9, ..ScoringContext::default() };
+        let score = |emissions: f64, cost: f64, opinion: f64| {
+            score_metrics(&test_metrics(emissions, cost, opinion), Some("budget_capped"), &scoring)
+        };
+
+        // Worst plans within the cap: heavy emitters, unpopular, spending right up to it
+        let worst_under = [score(MAX_ACCEPTABLE_EMISSIONS * 10.0, 5.0e9, 0.0), score(1.0e6, 4.9e9, 0.0)];
+        // Best plans over it: net zero and popular, from a hair over to far over
+        let best_over = [score(-1.0e6, 5.0e9 + 1.0, 1.0), score(0.0, 5.1e9, 1.0), score(0.0, 1.0e12, 1.0)];
+
+        let lowest_under = worst_under.iter().copied().fold(f64::INFINITY, f64::min);
+        let highest_over = best_over.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        assert!(highest_over < lowest_under, "over cap {} vs under cap {}", highest_over, lowest_under);
+    }
+
+    #[test]
+    fn equal_2050_emissions_score_by_the_path_taken_under_trajectory_mode() {
+        let years = (END_YEAR - START_YEAR + 1) as usize;
+        let with_path = |yearly_net_emissions: Vec<f64>| SimulationMetrics {
+            yearly_net_emissions,
+            ..test_metrics(0.0, 1.0e9, 0.7)
+        };
+        // Both reach net zero in 2050; one gets there straight away, the other only at the end
+        let early = with_path(vec![0.0; years]);
+        let mut late_path = vec![MAX_ACCEPTABLE_EMISSIONS; years];
+        late_path[years - 1] = 0.0;
+        let late = with_path(late_path);
+
+        let scoring = ScoringContext::default();
+        let early_score = score_metrics(&early, Some("trajectory"), &scoring);
+        let late_score = score_metrics(&late, Some("trajectory"), &scoring);
+
+        assert!(early_score > late_score, "early {} vs late {}", early_score, late_score);
+        assert_eq!(score_metrics(&early, None, &scoring), score_metrics(&late, None, &scoring));
+    }
+
+    #[test]
+    fn net_zero_plans_score_by_cumulative_emissions_against_the_budget() {
+        let budget = 2.0e7;
+        let scoring = ScoringContext::from_config(&SimulationConfig::builder().carbon_budget(Some(budget)).build());
+        let net_zero_with = |cumulative_net_emissions: f64| SimulationMetrics {
+            cumulative_net_emissions,
+            ..test_metrics(0.0, 1.0e9, 0.7)
+        };
+
+        let early = score_metrics(&net_zero_with(0.5 * budget), None, &scoring);
+        let late = score_metrics(&net_zero_with(3.0 * budget), None, &scoring);
+
+        assert!(early > late, "within budget {} vs over it {}", early, late);
+    }
+
+    #[test]
+    fn nuclear_beats_equal_energy_solar_under_min_footprint() {
+        let year = 2030;
+        let land_for_energy = |generator_type: GeneratorType, energy_mwh: f64| {
+            let built = |id: &str, size: f64| {
+                let mut generator = test_generator(id, generator_type.clone(), TEST_SITE, size, 0.0);
+                generator.initialize_construction(year, 0.5, false);
+                generator
+            };
+            let energy_per_mw = annual_energy_mwh(&built("Probe", 1.0), year, &AvailabilityFactors::default());
+            let mut map = empty_map(SimulationConfig::default());
+            map.add_generator(built("Plant", energy_mwh / energy_per_mw));
+            map.calc_total_land_use()
+        };
+        let energy_mwh = 1.0e6;
+        let score = |land_use: f64| {
+            let metrics = SimulationMetrics { total_land_use: land_use, ..test_metrics(0.0, 2.0e10, 0.7) };
+            score_metrics(&metrics, Some("min_footprint"), &ScoringContext::default())
+        };
+
+        let nuclear_land = land_for_energy(GeneratorType::Nuclear, energy_mwh);
+        let solar_land = land_for_energy(GeneratorType::UtilitySolar, energy_mwh);
+
+        assert!(nuclear_land < solar_land);
+        assert!(score(nuclear_land) > score(solar_land), "nuclear {} km² vs solar {} km²", nuclear_land, solar_land);
+    }
+
+    #[test]
+    fn mostly_gas_scores_below_mostly_wind_under_an_80_percent_mandate() {
+        let renewable_shares = |gas_mw: f64, wind_mw: f64| {
+            let mut map = empty_map(SimulationConfig::default());
+            for (id, generator_type, size) in [("Gas", GeneratorType::GasCombinedCycle, gas_mw), ("Wind", GeneratorType::OnshoreWind, wind_mw)] {
+                let mut generator = test_generator(id, generator_type, TEST_SITE, size, 0.0);
+                generator.initialize_construction(BASE_YEAR, 0.5, false);
+                map.add_generator(generator);
+            }
+            (BASE_YEAR..=END_YEAR)
+                .map(|year| calculate_yearly_metrics(&map, year, 0.0, 0.0, false, None).renewable_share)
+                .collect::<Vec<_>>()
+        };
+        let scoring = ScoringContext { renewable_mandate: vec![(BASE_YEAR, 0.8)], ..ScoringContext::default() };
+        // Identical on every other metric, so only the renewable share separates them
+        let score = |yearly_renewable_share: Vec<f64>| {
+            let metrics = SimulationMetrics { yearly_renewable_share, ..test_metrics(1.0e6, 2.0e10, 0.7) };
+            score_metrics(&metrics, Some("renewable_mandate"), &scoring)
+        };
+
+        let mostly_gas = renewable_shares(400.0, 50.0);
+        let mostly_wind = renewable_shares(50.0, 1200.0);
+        assert!(mostly_gas.iter().all(|share| *share < 0.8));
+        assert!(mostly_wind.iter().all(|share| *share >= 0.8), "{:?}", mostly_wind);
+
+        assert!(score(mostly_gas) < score(mostly_wind));
+    }
+
+    #[test]
+    fn cost_only_mode_scores_through_the_cost_only_preset() {
+        let scoring = ScoringContext::default();
+        let cheap_dirty = test_metrics(5.0e6, 2.0e10, 0.3);
+        let pricey_clean = test_metrics(0.0, 4.0e11, 0.9);
+
+        for metrics in [&cheap_dirty, &pricey_clean] {
+            let expected = 1.0 + score_metrics_weighted(metrics, &ScoreWeights::COST_ONLY);
+            assert!((score_metrics(metrics, Some("cost_only"), &scoring) - expected).abs() < 1e-12);
+        }
+        assert!(score_metrics(&cheap_dirty, Some("cost_only"), &scoring) > score_metrics(&pricey_clean, Some("cost_only"), &scoring));
+        assert_eq!("cost_only".parse::<ScoreWeights>().unwrap(), ScoreWeights::COST_ONLY);
+    }
+}
@@ -1,5 +1,7 @@
 // Simulation Metrics module - contains the SimulationMetrics and ActionResult structs
 use serde::{Serialize, Deserialize};
+use crate::config::constants::{MAX_ACCEPTABLE_COST, MAX_ACCEPTABLE_EMISSIONS, MIN_FEASIBLE_RELIABILITY};
+use crate::config::simulation_config::SimulationConfig;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationMetrics {
@@ -7,6 +9,78 @@ pub struct SimulationMetrics {
     pub average_public_opinion: f64,
     pub total_cost: f64,
     pub power_reliability: f64,
+    #[serde(default)]
+    pub yearly_net_emissions: Vec<f64>,  // Net emissions for each simulated year from BASE_YEAR, for trajectory scoring
+    #[serde(default)]
+    pub cumulative_net_emissions: f64,   // Net emissions summed over every simulated year, checked against the carbon budget
+    #[serde(default)]
+    pub total_land_use: f64,             // Land occupied by the final-year fleet in km², for footprint scoring
+    #[serde(default)]
+    pub yearly_renewable_share: Vec<f64>, // Renewable energy fraction for each simulated year from BASE_YEAR, checked against the mandate
+    #[serde(default)]
+    pub final_carbon_credit_offset: f64, // Final-year emissions covered by purchased carbon credits in tonnes
+}
+
+impl SimulationMetrics {
+    /// Final-year net emissions within `tolerance` tonnes of zero. With `real_reductions_only`,
+    /// carbon credits bought that year are added back first, so credits alone can't reach it.
+    pub fn is_net_zero_under(&self, tolerance: f64, real_reductions_only: bool) -> bool {
+        self.counted_net_emissions(real_reductions_only) <= tolerance
+    }
+
+    pub fn counted_net_emissions(&self, real_reductions_only: bool) -> f64 {
+        if real_reductions_only {
+            self.final_net_emissions + self.final_carbon_credit_offset
+        } else {
+            self.final_net_emissions
+        }
+    }
+
+    /// Checks the run against the plan targets: net zero by the final year, total cost within
+    /// the configured cost cap (MAX_ACCEPTABLE_COST when none is set), and demand met
+    pub fn is_feasible(&self, config: &SimulationConfig) -> FeasibilityReport {
+        let budget = config.cost_cap.unwrap_or(MAX_ACCEPTABLE_COST);
+        let counted_emissions = self.counted_net_emissions(config.net_zero_real_reductions_only);
+        FeasibilityReport {
+            net_zero: self.is_net_zero_under(config.net_zero_tolerance, config.net_zero_real_reductions_only),
+            within_budget: self.total_cost <= budget,
+            reliable: self.power_reliability >= MIN_FEASIBLE_RELIABILITY,
+            emissions_excess: (counted_emissions - config.net_zero_tolerance).max(0.0) / MAX_ACCEPTABLE_EMISSIONS,
+            budget_overrun: (self.total_cost - budget).max(0.0) / budget,
+        }
+    }
+}
+
+/// Which feasibility constraints a run passes, from `SimulationMetrics::is_feasible`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FeasibilityReport {
+    pub net_zero: bool,
+    pub within_budget: bool,
+    pub reliable: bool,
+    pub emissions_excess: f64,  // Final net emissions above the tolerance as a fraction of MAX_ACCEPTABLE_EMISSIONS, 0 at net zero
+    pub budget_overrun: f64,    // Cost over budget as a fraction of the budget, 0 within it
+}
+
+impl FeasibilityReport {
+    pub fn is_feasible(&self) -> bool {
+        self.net_zero && self.within_budget && self.reliable
+    }
+
+    pub fn emissions_status(&self) -> String {
+        if self.net_zero {
+            "✅ NET ZERO ACHIEVED".to_string()
+        } else {
+            format!("⚠ {:.1}% above target", self.emissions_excess * 100.0)
+        }
+    }
+
+    pub fn cost_status(&self) -> String {
+        if self.within_budget {
+            "✅ WITHIN BUDGET".to_string()
+        } else {
+            format!("❌ {:.1}% OVER BUDGET", self.budget_overrun * 100.0)
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,3 +90,49 @@ pub struct ActionResult {
     pub power_balance: f64,
     pub total_cost: f64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::GridAction;
+    use crate::config::constants::{DEFAULT_COST_MULTIPLIER, END_YEAR};
+    use crate::core::multi_simulation::score_plans;
+    use crate::models::carbon_offset::CarbonOffsetType;
+    use crate::test_support::{small_map, test_metrics};
+
+    #[test]
+    fn just_over_budget_fails_on_cost_but_passes_on_emissions() {
+        let default_config = SimulationConfig::default();
+        let capped_config = SimulationConfig { cost_cap: Some(2.0e10), ..SimulationConfig::default() };
+
+        for (config, budget) in [(default_config, MAX_ACCEPTABLE_COST), (capped_config, 2.0e10)] {
+            let report = test_metrics(0.0, budget * 1.01, 0.6).is_feasible(&config);
+
+            assert!(!report.within_budget);
+            assert!((report.budget_overrun - 0.01).abs() < 1e-9);
+            assert!(report.net_zero && report.reliable);
+            assert_eq!(report.emissions_excess, 0.0);
+            assert!(!report.is_feasible());
+            assert!(test_metrics(0.0, budget, 0.6).is_feasible(&config).is_feasible());
+        }
+    }
+
+    #[test]
+    fn credits_only_plan_fails_the_real_reductions_net_zero_check() {
+        let map = small_map();
+        // Enough credits bought in the final year to cover the gas plant's emissions
+        let credits: Vec<(u32, GridAction)> = (0..20)
+            .map(|_| (END_YEAR, GridAction::AddCarbonOffset(CarbonOffsetType::CarbonCredit, DEFAULT_COST_MULTIPLIER)))
+            .collect();
+        let scored = score_plans(&map, &[Vec::new(), credits]).unwrap();
+        let (no_plan, credits_only) = (&scored[0], &scored[1]);
+        assert!(credits_only.final_net_emissions <= 0.0);
+        assert!((credits_only.counted_net_emissions(true) - no_plan.final_net_emissions).abs() < 1e-6 * no_plan.final_net_emissions);
+
+        let credits_allowed = SimulationConfig::default();
+        let real_reductions_only = SimulationConfig { net_zero_real_reductions_only: true, ..SimulationConfig::default() };
+        assert!(credits_only.is_feasible(&credits_allowed).net_zero);
+        assert!(!credits_only.is_feasible(&real_reductions_only).net_zero);
+        assert!(!credits_only.is_net_zero_under(real_reductions_only.net_zero_tolerance, true));
+    }
+}
@@ -4,6 +4,7 @@
 // Actions module - contains grid action definitions and serialization
 pub mod actions {
     pub mod grid_action;
+    pub mod grid_action_kind;
     pub mod serializable_action;
 }
 
@@ -22,6 +23,7 @@ pub mod learning {
 
 // Re-export common types for convenience
 pub use actions::grid_action::GridAction;
-pub use metrics::simulation_metrics::{SimulationMetrics, ActionResult};
-pub use metrics::scoring::{score_metrics, evaluate_action_impact};
-pub use learning::weights::ActionWeights;
+pub use actions::grid_action_kind::GridActionKind;
+pub use metrics::simulation_metrics::{SimulationMetrics, ActionResult, FeasibilityReport};
+pub use metrics::scoring::{score_metrics, score_metrics_weighted, evaluate_action_impact, ScoreWeights, ScoringContext};
+pub use learning::weights::{ActionWeights, ContrastLearningPolicy, StagnationPolicy};
@@ -1,8 +1,15 @@
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
 use serde::{Serialize, Deserialize};
 use std::path::Path;
+use crate::ai::{score_metrics, ActionWeights, GridAction, GridActionKind, ScoringContext, SimulationMetrics};
+use crate::config::simulation_config::SimulationConfig;
+use crate::core::iteration::run_iteration;
+use crate::core::multi_simulation::score_plans;
+use crate::error::SimulationError;
 use crate::models::generator::GeneratorType;
 use crate::data::poi::Coordinate;
+use crate::utils::map_handler::Map;
 
 pub trait LocationAnalysisSource {
     fn calculate_generator_suitability(&self, coordinate: &Coordinate, generator_type: &GeneratorType) -> f64;
@@ -140,4 +147,387 @@ impl LocationAnalysis {
             Ok(None)
         }
     }
-} 
\ No newline at end of file
+} 
+// Runtime knobs a sensitivity sweep can vary; compile-time constants stay fixed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SensitivityParameter {
+    CostCap,            // Cumulative spend cap in euros
+    LearningRate,       // Weight update step size
+    CarbonPriceScale,   // Multiplier on every point of the carbon price trajectory
+    OpinionMacroWeight, // Blend of the trend-driven opinion term
+}
+
+impl std::fmt::Display for SensitivityParameter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SensitivityParameter::CostCap => write!(f, "CostCap"),
+            SensitivityParameter::LearningRate => write!(f, "LearningRate"),
+            SensitivityParameter::CarbonPriceScale => write!(f, "CarbonPriceScale"),
+            SensitivityParameter::OpinionMacroWeight => write!(f, "OpinionMacroWeight"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParamSweep {
+    pub parameter: SensitivityParameter,
+    pub values: Vec<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SensitivityOptions {
+    pub iterations: usize,  // Short training run per cell
+    pub seed: u64,          // Every cell replays the same seed sequence
+    pub optimization_mode: Option<String>,
+    pub enable_energy_sales: bool,
+    pub enable_construction_delays: bool,
+}
+
+impl Default for SensitivityOptions {
+    fn default() -> Self {
+        Self {
+            iterations: 10,
+            seed: 42,
+            optimization_mode: None,
+            enable_energy_sales: false,
+            enable_construction_delays: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensitivityResult {
+    pub parameter: SensitivityParameter,
+    pub value: f64,
+    pub metrics: SimulationMetrics,
+    pub baseline: SimulationMetrics,
+}
+
+impl SensitivityResult {
+    pub fn emissions_change(&self) -> f64 {
+        self.metrics.final_net_emissions - self.baseline.final_net_emissions
+    }
+
+    pub fn cost_change(&self) -> f64 {
+        self.metrics.total_cost - self.baseline.total_cost
+    }
+
+    pub fn opinion_change(&self) -> f64 {
+        self.metrics.average_public_opinion - self.baseline.average_public_opinion
+    }
+}
+
+/// Runs a short simulation for the base config and for each swept value, varying one
+/// parameter at a time, and reports how the best final metrics move against the baseline.
+pub fn run_sensitivity(
+    base_map: &Map,
+    base_config: &SimulationConfig,
+    param_sweeps: &[ParamSweep],
+    options: &SensitivityOptions,
+) -> Result<Vec<SensitivityResult>, SimulationError> {
+    let baseline = run_sensitivity_cell(base_map, base_config.clone(), None, options)?;
+
+    let mut results = Vec::new();
+    for sweep in param_sweeps {
+        for &value in &sweep.values {
+            let mut config = base_config.clone();
+            let mut learning_rate = None;
+            match sweep.parameter {
+                SensitivityParameter::CostCap => config.cost_cap = Some(value),
+                SensitivityParameter::LearningRate => learning_rate = Some(value),
+                SensitivityParameter::CarbonPriceScale => {
+                    for (_, price) in config.carbon_price_trajectory.iter_mut() {
+                        *price *= value;
+                    }
+                }
+                SensitivityParameter::OpinionMacroWeight => config.opinion_dynamics.macro_weight = value,
+            }
+
+            println!("Sensitivity: {} = {}", sweep.parameter, value);
+            let metrics = run_sensitivity_cell(base_map, config, learning_rate, options)?;
+            results.push(SensitivityResult {
+                parameter: sweep.parameter,
+                value,
+                metrics,
+                baseline: baseline.clone(),
+            });
+        }
+    }
+    Ok(results)
+}
+
+fn run_sensitivity_cell(
+    base_map: &Map,
+    config: SimulationConfig,
+    learning_rate: Option<f64>,
+    options: &SensitivityOptions,
+) -> Result<SimulationMetrics, SimulationError> {
+    train_short(base_map, config, learning_rate, options).map(|(metrics, _)| metrics)
+}
+
+// Short training run from fresh weights, returning the best metrics seen and the trained weights
+fn train_short(
+    base_map: &Map,
+    config: SimulationConfig,
+    learning_rate: Option<f64>,
+    options: &SensitivityOptions,
+) -> Result<(SimulationMetrics, ActionWeights), SimulationError> {
+    let optimization_mode = options.optimization_mode.as_deref();
+
+    let mut weights = match &config.deployment_prior {
+        Some(prior) => ActionWeights::new().with_prior(prior),
+        None => ActionWeights::new(),
+    };
+    if let Some(rate) = learning_rate {
+        weights.learning_rate = rate;
+    }
+
+    let scoring = ScoringContext::from_config(&config);
+    let mut map = base_map.clone();
+    map.set_config(config);
+
+    let mut best: Option<(f64, SimulationMetrics)> = None;
+    for i in 0..options.iterations.max(1) {
+        let result = run_iteration(
+            i,
+            &mut map,
+            &mut weights,
+            false,
+            Some(options.seed.wrapping_add(i as u64)),
+            false,
+            optimization_mode,
+            options.enable_energy_sales,
+            options.enable_construction_delays,
+        )?;
+        weights.apply_contrast_learning(&result.metrics);
+        weights.update_best_strategy(result.metrics.clone());
+
+        let score = score_metrics(&result.metrics, optimization_mode, &scoring);
+        if best.as_ref().is_none_or(|(best_score, _)| score > *best_score) {
+            best = Some((score, result.metrics));
+        }
+    }
+
+    let metrics = best.map(|(_, metrics)| metrics).expect("at least one sensitivity iteration");
+    Ok((metrics, weights))
+}
+
+/// Overlap of two plans in [0,1], averaged over every year either plan acts in. Each year is
+/// compared as a multiset of action kinds (Jaccard: shared count over combined count), so
+/// generator ids picked at random for upgrades and closures don't count as differences.
+/// Two empty plans are identical.
+pub fn strategy_similarity(a: &[(u32, GridAction)], b: &[(u32, GridAction)]) -> f64 {
+    let count_by_year = |plan: &[(u32, GridAction)]| {
+        let mut counts: HashMap<(u32, GridActionKind), usize> = HashMap::new();
+        for (year, action) in plan {
+            *counts.entry((*year, action.kind())).or_insert(0) += 1;
+        }
+        counts
+    };
+    let a_counts = count_by_year(a);
+    let b_counts = count_by_year(b);
+
+    let years: BTreeSet<u32> = a.iter().chain(b.iter()).map(|(year, _)| *year).collect();
+    if years.is_empty() {
+        return 1.0;
+    }
+
+    let mut shared = HashMap::new();
+    let mut combined = HashMap::new();
+    for key in a_counts.keys().chain(b_counts.keys()) {
+        let in_a = a_counts.get(key).copied().unwrap_or(0);
+        let in_b = b_counts.get(key).copied().unwrap_or(0);
+        shared.insert(key, in_a.min(in_b));
+        combined.insert(key, in_a.max(in_b));
+    }
+
+    let total: f64 = years.iter()
+        .map(|year| {
+            let year_shared: usize = shared.iter().filter(|((y, _), _)| y == year).map(|(_, n)| n).sum();
+            let year_combined: usize = combined.iter().filter(|((y, _), _)| y == year).map(|(_, n)| n).sum();
+            year_shared as f64 / year_combined as f64
+        })
+        .sum();
+    total / years.len() as f64
+}
+
+/// One point on a marginal abatement cost curve: what a single plan action costs per tonne
+/// of CO2 it keeps out of the atmosphere over the whole run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbatementCost {
+    pub year: u32,
+    pub action: GridAction,
+    pub incremental_cost: f64,        // Plan total cost minus the total cost without this action
+    pub emissions_avoided: f64,       // Cumulative tonnes CO2 saved by the action (negative when it adds emissions)
+    pub cost_per_tonne: Option<f64>,  // Incremental cost per tonne avoided, None unless the action reduces emissions
+}
+
+impl AbatementCost {
+    pub fn increases_emissions(&self) -> bool {
+        self.emissions_avoided < 0.0
+    }
+}
+
+/// Builds a MAC curve for `plan` by replaying it once in full and once without each action,
+/// so every action's cost and abatement include its knock-on effects for the rest of the run.
+/// Emission-reducing actions come first, cheapest abatement per tonne first; the rest follow
+/// in plan order with no cost per tonne, those that add emissions flagged by `increases_emissions`.
+pub fn marginal_abatement_costs(
+    base_map: &Map,
+    plan: &[(u32, GridAction)],
+) -> Result<Vec<AbatementCost>, SimulationError> {
+    let mut plans = Vec::with_capacity(plan.len() + 1);
+    plans.push(plan.to_vec());
+    for skip in 0..plan.len() {
+        plans.push(plan.iter().enumerate()
+            .filter(|(i, _)| *i != skip)
+            .map(|(_, entry)| entry.clone())
+            .collect());
+    }
+
+    let results = score_plans(base_map, &plans)?;
+    let full = &results[0];
+
+    let mut costs: Vec<AbatementCost> = plan.iter().zip(&results[1..])
+        .map(|((year, action), without)| {
+            let incremental_cost = full.total_cost - without.total_cost;
+            let emissions_avoided = without.cumulative_net_emissions - full.cumulative_net_emissions;
+            AbatementCost {
+                year: *year,
+                action: action.clone(),
+                incremental_cost,
+                emissions_avoided,
+                cost_per_tonne: (emissions_avoided > 0.0).then(|| incremental_cost / emissions_avoided),
+            }
+        })
+        .collect();
+
+    // Stable, so actions without a cost per tonne keep plan order at the back
+    costs.sort_by(|a, b| match (a.cost_per_tonne, b.cost_per_tonne) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+    Ok(costs)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StabilityReport {
+    pub seeds: Vec<u64>,
+    pub best_plans: Vec<Vec<(u32, GridAction)>>,  // Best plan found by each training, in seed order
+    pub best_scores: Vec<f64>,
+    pub mean_pairwise_similarity: f64,
+}
+
+/// Trains `runs` times from fresh weights, each with its own seed derived from `options.seed`,
+/// and measures how alike the best plans are. A mean pairwise similarity near 1.0 means the
+/// search converges on the same plan regardless of its random draws.
+pub fn run_stability_check(
+    base_map: &Map,
+    config: &SimulationConfig,
+    runs: usize,
+    options: &SensitivityOptions,
+) -> Result<StabilityReport, SimulationError> {
+    let scoring = ScoringContext::from_config(config);
+    let mut seeds = Vec::new();
+    let mut best_plans = Vec::new();
+    let mut best_scores = Vec::new();
+
+    for run in 0..runs {
+        // Iteration seeds count up from the run seed, so keep runs far apart
+        let mut run_options = options.clone();
+        run_options.seed = options.seed.wrapping_add((run as u64) << 32);
+
+        println!("Stability: training {} of {} (seed {})", run + 1, runs, run_options.seed);
+        let (metrics, weights) = train_short(base_map, config.clone(), None, &run_options)?;
+
+        let mut plan: Vec<(u32, GridAction)> = weights.best_actions.as_ref()
+            .map(|actions| actions.iter()
+                .flat_map(|(year, year_actions)| year_actions.iter().map(move |action| (*year, action.clone())))
+                .collect())
+            .unwrap_or_default();
+        plan.sort_by_key(|(year, _)| *year);
+
+        seeds.push(run_options.seed);
+        best_scores.push(score_metrics(&metrics, options.optimization_mode.as_deref(), &scoring));
+        best_plans.push(plan);
+    }
+
+    let mut similarities = Vec::new();
+    for i in 0..best_plans.len() {
+        for j in (i + 1)..best_plans.len() {
+            similarities.push(strategy_similarity(&best_plans[i], &best_plans[j]));
+        }
+    }
+    let mean_pairwise_similarity = if similarities.is_empty() {
+        1.0
+    } else {
+        similarities.iter().sum::<f64>() / similarities.len() as f64
+    };
+    println!("Stability: mean pairwise plan similarity {:.3} over {} trainings", mean_pairwise_similarity, runs);
+
+    Ok(StabilityReport { seeds, best_plans, best_scores, mean_pairwise_similarity })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::constants::DEFAULT_COST_MULTIPLIER;
+    use crate::models::carbon_offset::CarbonOffsetType;
+    use crate::test_support::{scratch_path, small_map};
+    use crate::utils::csv_export::write_sensitivity_results;
+
+    #[test]
+    fn two_point_sweep_produces_two_rows() {
+        let map = small_map();
+        let sweeps = [ParamSweep { parameter: SensitivityParameter::CostCap, values: vec![1.0e10, 5.0e10] }];
+        let options = SensitivityOptions { iterations: 1, ..SensitivityOptions::default() };
+
+        let results = run_sensitivity(&map, map.get_config(), &sweeps, &options).unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results.iter().map(|r| r.value).collect::<Vec<_>>(), vec![1.0e10, 5.0e10]);
+        let path = scratch_path("sensitivity.csv");
+        write_sensitivity_results(&path, &results).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1 + 2);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn identical_plans_score_one_and_disjoint_plans_score_zero() {
+        let plan = vec![
+            (2025, GridAction::AddGenerator(GeneratorType::OnshoreWind, 100)),
+            (2025, GridAction::DoNothing),
+            (2030, GridAction::AddGenerator(GeneratorType::UtilitySolar, 50)),
+        ];
+        let disjoint = vec![
+            (2025, GridAction::AddGenerator(GeneratorType::Nuclear, 100)),
+            (2030, GridAction::AddGenerator(GeneratorType::GasPeaker, 50)),
+        ];
+
+        assert_eq!(strategy_similarity(&plan, &plan), 1.0);
+        assert_eq!(strategy_similarity(&plan, &disjoint), 0.0);
+        assert_eq!(strategy_similarity(&disjoint, &plan), 0.0);
+    }
+
+    #[test]
+    fn cheap_high_abatement_ranks_before_expensive_low_abatement() {
+        let map = small_map();
+        let expensive = GridAction::AddCarbonOffset(CarbonOffsetType::Wetland, 3 * DEFAULT_COST_MULTIPLIER);
+        let cheap = GridAction::AddCarbonOffset(CarbonOffsetType::Forest, DEFAULT_COST_MULTIPLIER);
+        // Embodied construction emissions make the wind farm a net emitter over the run
+        let emitter = GridAction::AddGenerator(GeneratorType::OnshoreWind, DEFAULT_COST_MULTIPLIER);
+        let plan = vec![(2026, emitter.clone()), (2026, expensive.clone()), (2026, cheap.clone())];
+
+        let costs = marginal_abatement_costs(&map, &plan).unwrap();
+
+        let ranked: Vec<&GridAction> = costs.iter().map(|cost| &cost.action).collect();
+        assert_eq!(ranked, vec![&cheap, &expensive, &emitter]);
+        let (cheap_cost, expensive_cost) = (&costs[0], &costs[1]);
+        assert!(cheap_cost.incremental_cost < expensive_cost.incremental_cost);
+        assert!(cheap_cost.emissions_avoided > expensive_cost.emissions_avoided);
+        assert!(cheap_cost.cost_per_tonne.unwrap() < expensive_cost.cost_per_tonne.unwrap());
+        assert!(costs[2].emissions_avoided < 0.0 && costs[2].cost_per_tonne.is_none());
+    }
+}
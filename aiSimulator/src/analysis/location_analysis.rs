@@ -3,10 +3,56 @@ use serde::{Serialize, Deserialize};
 use std::path::Path;
 use crate::models::generator::GeneratorType;
 use crate::utils::map_handler::Map;
-use crate::data::poi::Coordinate;
-use crate::config::constants::{MAP_MAX_X, MAP_MAX_Y, GRID_CELL_SIZE};
+use crate::data::poi::{Coordinate, MapExtent};
+use crate::config::constants::{
+    GRID_CELL_SIZE,
+    ONSHORE_WIND_QUALITY_RANGE, OFFSHORE_WIND_QUALITY_RANGE, SOLAR_QUALITY_RANGE, DEFAULT_RESOURCE_QUALITY,
+};
 use std::collections::HashSet;
 
+// Output multiplier for the wind or solar resource at a site; other types are unaffected.
+// Wind improves towards the west edge of `extent` and solar towards the south edge.
+pub fn resource_quality(generator_type: &GeneratorType, coordinate: &Coordinate, extent: &MapExtent) -> f64 {
+    let west = 1.0 - (coordinate.x / extent.max_x).clamp(0.0, 1.0);
+    let south = 1.0 - (coordinate.y / extent.max_y).clamp(0.0, 1.0);
+    let lerp = |(low, high): (f64, f64), t: f64| low + (high - low) * t;
+
+    match generator_type {
+        GeneratorType::OnshoreWind => lerp(ONSHORE_WIND_QUALITY_RANGE, west),
+        GeneratorType::OffshoreWind | GeneratorType::FloatingOffshoreWind => lerp(OFFSHORE_WIND_QUALITY_RANGE, west),
+        GeneratorType::DomesticSolar | GeneratorType::CommercialSolar | GeneratorType::UtilitySolar => lerp(SOLAR_QUALITY_RANGE, south),
+        _ => DEFAULT_RESOURCE_QUALITY,
+    }
+}
+
+/// Samples the CPU location search's siting score (`Map::siting_score`, no size penalty) at the
+/// centre of each cell of a `resolution` x `resolution` grid over the map and writes `x,y,score`
+/// rows for heatmap rendering. Excluded locations score 0, the same as unsuitable ones.
+pub fn export_suitability_grid(
+    map: &Map,
+    generator_type: &GeneratorType,
+    resolution: usize,
+    path: impl AsRef<Path>,
+) -> std::io::Result<()> {
+    use std::fs::File;
+    use std::io::{BufWriter, Write};
+
+    let extent = map.get_config().map_extent;
+    let mut file = BufWriter::new(File::create(path)?);
+    writeln!(file, "x,y,score")?;
+
+    for j in 0..resolution {
+        let y = extent.max_y * (j as f64 + 0.5) / resolution as f64;
+        for i in 0..resolution {
+            let x = extent.max_x * (i as f64 + 0.5) / resolution as f64;
+            let score = map.siting_score(&Coordinate::new(x, y), generator_type, 0.0).unwrap_or(0.0);
+            writeln!(file, "{:.1},{:.1},{:.4}", x, y, score)?;
+        }
+    }
+
+    file.flush()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LocationSuitability {
     pub coordinate: Coordinate,
@@ -32,15 +78,16 @@ impl LocationAnalysis {
         let step_size = GRID_CELL_SIZE * 2.0;
         
         // Calculate number of steps in each direction
-        let x_steps = (MAP_MAX_X / step_size).ceil() as i32;
-        let y_steps = (MAP_MAX_Y / step_size).ceil() as i32;
+        let extent = map.get_config().map_extent;
+        let x_steps = (extent.max_x / step_size).ceil() as i32;
+        let y_steps = (extent.max_y / step_size).ceil() as i32;
 
         // Analyze grid points
         for i in -x_steps..=x_steps {
             for j in -y_steps..=y_steps {
                 let x = i as f64 * step_size;
                 let y = j as f64 * step_size;
-                let coordinate = Coordinate::new(x, y);
+                let coordinate = extent.clamp(x, y);
 
                 let mut suitable_types = Vec::new();
                 let mut suitability_scores = HashMap::new();
@@ -49,6 +96,7 @@ impl LocationAnalysis {
                 for generator_type in [
                     GeneratorType::OnshoreWind,
                     GeneratorType::OffshoreWind,
+                    GeneratorType::FloatingOffshoreWind,
                     GeneratorType::DomesticSolar,
                     GeneratorType::CommercialSolar,
                     GeneratorType::UtilitySolar,
@@ -62,6 +110,8 @@ impl LocationAnalysis {
                     GeneratorType::BatteryStorage,
                     GeneratorType::TidalGenerator,
                     GeneratorType::WaveEnergy,
+                    GeneratorType::HydrogenElectrolyzer,
+                    GeneratorType::HydrogenTurbine,
                 ].iter() {
                     let suitability = map.calculate_generator_suitability(&coordinate, generator_type);
                     
@@ -215,4 +265,48 @@ impl LocationAnalysis {
             Ok(None)
         }
     }
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{scratch_path, small_map};
+
+    #[test]
+    fn suitability_grid_has_a_row_per_cell_with_bounded_scores() {
+        let map = small_map();
+        let extent = map.get_config().map_extent;
+        let resolution = 8;
+        let path = scratch_path("suitability_grid.csv");
+
+        export_suitability_grid(&map, &GeneratorType::OnshoreWind, resolution, &path).unwrap();
+
+        let csv = std::fs::read_to_string(&path).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("x,y,score"));
+        let rows: Vec<Vec<f64>> = lines
+            .map(|line| line.split(',').map(|field| field.parse().unwrap()).collect())
+            .collect();
+        assert_eq!(rows.len(), resolution * resolution);
+        for row in &rows {
+            let (x, y, score) = (row[0], row[1], row[2]);
+            assert!(x > 0.0 && x < extent.max_x && y > 0.0 && y < extent.max_y);
+            // Onshore wind tops out at a coastal, uncrowded site
+            assert!((0.0..=0.7).contains(&score), "score {} at ({}, {})", score, x, y);
+        }
+        assert!(rows.iter().any(|row| row[2] > 0.0));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn wider_extent_keeps_grading_wind_past_the_default_bounds() {
+        let wide = MapExtent { max_x: 4.0 * MapExtent::default().max_x, max_y: MapExtent::default().max_y };
+        let quality_at = |x: f64| resource_quality(&GeneratorType::OnshoreWind, &wide.clamp(x, 10_000.0), &wide);
+        let default_edge = MapExtent::default().max_x;
+
+        // Both sites are east of the default map, so only the configured extent tells them apart
+        assert!(quality_at(1.5 * default_edge) > quality_at(3.0 * default_edge));
+        assert_eq!(quality_at(0.0), ONSHORE_WIND_QUALITY_RANGE.1);
+        assert_eq!(quality_at(wide.max_x), ONSHORE_WIND_QUALITY_RANGE.0);
+    }
+}
@@ -2,6 +2,23 @@ use serde::Serialize;
 use crate::core::action_weights::GridAction;
 use crate::core::action_weights::SimulationMetrics;
 use crate::utils::csv_export;
+use crate::utils::map_handler::Retirement;
+
+// Last year's grid trajectory, used to drive macro opinion dynamics
+#[derive(Debug, Clone, Copy)]
+pub struct OpinionTrend {
+    pub net_emissions: f64,
+    pub yearly_cost: f64,
+}
+
+impl From<&YearlyMetrics> for OpinionTrend {
+    fn from(metrics: &YearlyMetrics) -> Self {
+        Self {
+            net_emissions: metrics.net_co2_emissions,
+            yearly_cost: metrics.yearly_total_cost,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize)]
 pub struct YearlyMetrics {
@@ -10,23 +27,43 @@ pub struct YearlyMetrics {
     pub total_power_usage: f64,
     pub total_power_generation: f64,
     pub power_balance: f64,
+    pub peak_deficit: f64,                   // Worst hourly shortfall in MW after smart charging shifts flexible demand
+    pub firm_capacity_margin: f64,           // Capacity-credited MW above peak demand, negative when short
+    pub unserved_zone_demand: f64,           // Zonal demand the inter-zone links couldn't cover (MW)
     pub average_public_opinion: f64,
     pub yearly_capital_cost: f64,            // Capital cost for the current year only
     pub total_capital_cost: f64,             // Accumulated capital cost up to this year
     pub inflation_factor: f64,
     pub total_co2_emissions: f64,
     pub total_carbon_offset: f64,
+    pub carbon_credit_offset: f64,           // Part of total_carbon_offset bought as carbon credits
     pub net_co2_emissions: f64,
+    pub cumulative_net_emissions: f64,       // Net emissions accumulated from the first simulated year up to this one
+    pub emissions_intensity_g_per_kwh: f64, // Net emissions per unit of delivered energy
     pub yearly_carbon_credit_revenue: f64, // Revenue for the current year only
     pub total_carbon_credit_revenue: f64,  // Accumulated revenue up to this year
+    pub yearly_carbon_tax_cost: f64,       // Carbon tax on positive net emissions for the current year
+    pub total_carbon_tax_cost: f64,        // Accumulated carbon tax up to this year
     pub yearly_energy_sales_revenue: f64,  // Revenue from energy sales for current year
     pub total_energy_sales_revenue: f64,   // Accumulated energy sales revenue up to this year
+    pub yearly_capacity_payment_revenue: f64, // Capacity payments earned by firm capacity this year
+    pub storage_state_of_charge: f64,      // Stored energy at year end as a share of total storage capacity
+    pub storage_energy_charged: f64,       // Energy put into storage this year in MWh
+    pub storage_energy_discharged: f64,    // Energy delivered from storage this year in MWh
+    pub total_land_use: f64,               // Land occupied by the operating fleet in km²
+    pub renewable_share: f64,              // Fraction of this year's generated energy from renewables
     pub generator_efficiencies: Vec<(String, f64)>,
     pub generator_operations: Vec<(String, f64)>,
+    pub settlement_demand: Vec<(String, u32, f64)>, // Each settlement's name, population and power usage (MW)
     pub active_generators: usize,
     pub yearly_upgrade_costs: f64,            // Upgrade costs for the current year
     pub yearly_closure_costs: f64,            // Closure costs for the current year
-    pub yearly_total_cost: f64,               // Total cost for this year only
+    pub capex: f64,                           // New build, upgrade and closure spend for this year
+    pub opex: f64,                            // Operation and maintenance for this year, not in yearly_total_cost
+    pub fuel_cost: f64,                       // Fuel bought for this year, not in yearly_total_cost
+    pub carbon_cost: f64,                     // Carbon tax for this year
+    pub revenue: f64,                         // Carbon credit and energy sales income for this year
+    pub yearly_total_cost: f64,               // capex + carbon_cost - revenue
     pub total_cost: f64,                      // Accumulated total cost up to this year
 }
 
@@ -36,6 +73,7 @@ pub struct SimulationResult {
     pub output: String,
     pub actions: Vec<(u32, GridAction)>,
     pub yearly_metrics: Vec<YearlyMetrics>, // Add yearly metrics to the struct
+    pub retirements: Vec<Retirement>,       // Forced closures, which are not part of `actions`
 }
 
 // Implement YearlyMetricsLike trait from csv_export for our YearlyMetrics
@@ -52,15 +90,27 @@ impl csv_export::YearlyMetricsLike for YearlyMetrics {
     fn get_total_co2_emissions(&self) -> f64 { self.total_co2_emissions }
     fn get_total_carbon_offset(&self) -> f64 { self.total_carbon_offset }
     fn get_net_co2_emissions(&self) -> f64 { self.net_co2_emissions }
+    fn get_cumulative_net_emissions(&self) -> f64 { self.cumulative_net_emissions }
+    fn get_emissions_intensity_g_per_kwh(&self) -> f64 { self.emissions_intensity_g_per_kwh }
     fn get_yearly_carbon_credit_revenue(&self) -> f64 { self.yearly_carbon_credit_revenue }
     fn get_total_carbon_credit_revenue(&self) -> f64 { self.total_carbon_credit_revenue }
     fn get_yearly_energy_sales_revenue(&self) -> f64 { self.yearly_energy_sales_revenue }
     fn get_total_energy_sales_revenue(&self) -> f64 { self.total_energy_sales_revenue }
+    fn get_storage_state_of_charge(&self) -> f64 { self.storage_state_of_charge }
+    fn get_storage_energy_charged(&self) -> f64 { self.storage_energy_charged }
+    fn get_storage_energy_discharged(&self) -> f64 { self.storage_energy_discharged }
+    fn get_unserved_zone_demand(&self) -> f64 { self.unserved_zone_demand }
     fn get_generator_efficiencies(&self) -> Vec<(String, f64)> { self.generator_efficiencies.clone() }
     fn get_generator_operations(&self) -> Vec<(String, f64)> { self.generator_operations.clone() }
+    fn get_settlement_demand(&self) -> Vec<(String, u32, f64)> { self.settlement_demand.clone() }
     fn get_active_generators(&self) -> usize { self.active_generators }
     fn get_yearly_upgrade_costs(&self) -> f64 { self.yearly_upgrade_costs }
     fn get_yearly_closure_costs(&self) -> f64 { self.yearly_closure_costs }
+    fn get_capex(&self) -> f64 { self.capex }
+    fn get_opex(&self) -> f64 { self.opex }
+    fn get_fuel_cost(&self) -> f64 { self.fuel_cost }
+    fn get_carbon_cost(&self) -> f64 { self.carbon_cost }
+    fn get_revenue(&self) -> f64 { self.revenue }
     fn get_yearly_total_cost(&self) -> f64 { self.yearly_total_cost }
     fn get_total_cost(&self) -> f64 { self.total_cost }
 }
\ No newline at end of file
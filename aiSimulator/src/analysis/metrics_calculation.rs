@@ -1,32 +1,98 @@
 use crate::utils::map_handler::Map;
-use super::metrics::YearlyMetrics;
+use super::metrics::{YearlyMetrics, OpinionTrend};
+use crate::config::constants::{REFERENCE_ANNUAL_EXPENDITURE, HOURS_PER_YEAR};
+use crate::models::generator::Generator;
+use super::uncertainty::AvailabilityFactors;
 use crate::utils::logging::{self, OperationCategory, PowerCalcType};
 use crate::config::const_funcs;
+use crate::config::simulation_config::OpinionWeighting;
 use crate::data::poi::POI;
 
+// Energy a generator delivers over the year (MWh): its effective average output across 8760 hours
+pub fn annual_energy_mwh(generator: &Generator, year: u32, availability: &AvailabilityFactors) -> f64 {
+    generator.effective_power(year, availability) * HOURS_PER_YEAR
+}
+
+// Fleet energy over the year (MWh), including the current renewable availability draw
+pub fn fleet_annual_energy_mwh(map: &Map, year: u32) -> f64 {
+    map.get_generators().iter()
+        .map(|generator| annual_energy_mwh(generator, year, &map.availability_factors))
+        .sum()
+}
+
+// Share of the year's generated energy from renewable sources, in [0,1]. Storage and
+// hydrogen units are left out of both sides since they only shift energy in time.
+pub fn renewable_energy_share(map: &Map, year: u32) -> f64 {
+    let (renewable, total) = map.get_generators().iter()
+        .filter(|g| !g.get_generator_type().is_storage() && !g.get_generator_type().is_hydrogen())
+        .fold((0.0, 0.0), |(renewable, total), generator| {
+            let energy = annual_energy_mwh(generator, year, &map.availability_factors);
+            if generator.get_generator_type().is_renewable() {
+                (renewable + energy, total + energy)
+            } else {
+                (renewable, total + energy)
+            }
+        });
+    if total > 0.0 { renewable / total } else { 0.0 }
+}
+
+// Energy left over after meeting demand for the year (MWh). Curtailed intermittent output
+// can't be sold, so the fleet surplus is also bounded by the dispatched power balance.
+pub fn annual_surplus_energy_mwh(map: &Map, year: u32, power_balance: f64) -> f64 {
+    if power_balance <= 0.0 {
+        return 0.0;
+    }
+    let demand_mwh = map.calc_total_power_usage(year) * HOURS_PER_YEAR;
+    (fleet_annual_energy_mwh(map, year) - demand_mwh)
+        .min(power_balance * HOURS_PER_YEAR)
+        .max(0.0)
+}
+
+// Opinion of the active sites, averaged per site or by rated output as configured.
+// Hybrid sub-units share their host's site, so the site is only counted once.
+fn siting_opinion(map: &Map, year: u32) -> f64 {
+    let capacity_weighted = map.get_config().opinion_weighting == OpinionWeighting::Capacity;
+    let (total_opinion, total_weight) = map.get_generators().iter()
+        .filter(|generator| generator.is_active() && !generator.is_hybrid_sub_unit())
+        .fold((0.0, 0.0), |(total_opinion, total_weight), generator| {
+            let weight = if capacity_weighted { generator.power_out } else { 1.0 };
+            let opinion = map.calc_new_generator_opinion(generator.get_coordinate(), generator, year);
+            (total_opinion + weight * opinion, total_weight + weight)
+        });
+    if total_weight > 0.0 { total_opinion / total_weight } else { 1.0 }
+}
+
 pub fn calculate_average_opinion(map: &Map, year: u32) -> f64 {
     let _timing = logging::start_timing("calculate_average_opinion",
         OperationCategory::PowerCalculation { subcategory: PowerCalcType::Other });
      
-    let mut total_opinion = 0.0;
-    let mut count = 0;
-     
-    for generator in map.get_generators() {
-        if generator.is_active() {
-            total_opinion += map.calc_new_generator_opinion(
-                generator.get_coordinate(),
-                generator,
-                year
-            );
-            count += 1;
-        }
+    let siting_opinion = siting_opinion(map, year);
+
+    match map.get_opinion_trend() {
+        Some(trend) if map.get_config().opinion_dynamics.macro_weight > 0.0 => {
+            let net_emissions = map.calc_net_co2_emissions(year);
+            blend_macro_opinion(map, siting_opinion, net_emissions, &trend)
+        },
+        _ => siting_opinion,
     }
-     
-    if count > 0 {
-        total_opinion / count as f64
-    } else {
-        1.0
+}
+
+// Blend siting-based opinion with a macro term: the public warms to falling
+// emissions and sours on yearly spend above the reference budget
+fn blend_macro_opinion(map: &Map, siting_opinion: f64, net_emissions: f64, trend: &OpinionTrend) -> f64 {
+    let dynamics = &map.get_config().opinion_dynamics;
+    if dynamics.macro_weight <= 0.0 {
+        return siting_opinion;
     }
+
+    let emissions_reduction = ((trend.net_emissions - net_emissions) / trend.net_emissions.abs().max(1.0)).clamp(-1.0, 1.0);
+    let cost_burden = (trend.yearly_cost / REFERENCE_ANNUAL_EXPENDITURE - 1.0).max(0.0);
+    let macro_opinion = (siting_opinion
+        + dynamics.emissions_sensitivity * emissions_reduction
+        - dynamics.cost_sensitivity * cost_burden).clamp(0.0, 1.0);
+
+    let weight = dynamics.macro_weight.min(1.0);
+    siting_opinion * (1.0 - weight) + macro_opinion * weight
 }
 
 pub fn calculate_yearly_metrics(
@@ -59,6 +125,7 @@ pub fn calculate_yearly_metrics(
     };
      
     let power_balance = total_power_gen - total_power_usage;
+    let peak_deficit = map.calc_peak_deficit(year);
      
     let (total_co2_emissions, total_carbon_offset, net_co2_emissions) = {
         let _timing = logging::start_timing("calc_emissions",
@@ -70,6 +137,12 @@ pub fn calculate_yearly_metrics(
         )
     };
      
+    // Delivered energy is the generated power held over the year
+    let emissions_intensity_g_per_kwh = const_funcs::calculate_emissions_intensity(
+        net_co2_emissions,
+        total_power_gen.max(0.0) * HOURS_PER_YEAR,
+    );
+     
     // Calculate revenue from carbon credits for negative emissions
     let carbon_credit_revenue = {
         let _timing = logging::start_timing("calc_carbon_credit_revenue",
@@ -77,8 +150,13 @@ pub fn calculate_yearly_metrics(
         const_funcs::calculate_carbon_credit_revenue(net_co2_emissions, year)
     };
 
-    let mut total_opinion = 0.0;
-    let mut opinion_count = 0;
+    // Carbon tax on positive net emissions, symmetric with the credit revenue above
+    let carbon_tax_cost = const_funcs::calculate_carbon_tax_cost(
+        net_co2_emissions,
+        year,
+        &map.get_config().carbon_price_trajectory,
+    );
+
     let mut generator_efficiencies = Vec::new();
     let mut generator_operations = Vec::new();
     let mut active_count = 0;
@@ -89,12 +167,6 @@ pub fn calculate_yearly_metrics(
          
         for generator in map.get_generators() {
             if generator.is_active() {
-                total_opinion += map.calc_new_generator_opinion(
-                    generator.get_coordinate(),
-                    generator,
-                    year
-                );
-                opinion_count += 1;
                 active_count += 1;
 
                 generator_efficiencies.push((generator.get_id().to_string(), generator.get_efficiency()));
@@ -117,14 +189,20 @@ pub fn calculate_yearly_metrics(
     };
      
     let total_capital_cost = map.calc_total_capital_cost(year);
-    let inflation_factor = const_funcs::calc_inflation_factor(year);
+    let inflation_factor = map.get_config().inflation.factor(year);
      
     // Calculate energy sales revenue based on power surplus
-    let yearly_energy_sales_revenue = calculate_energy_sales(power_balance, year, enable_energy_sales);
+    let yearly_energy_sales_revenue = calculate_energy_sales(map, power_balance, year, enable_energy_sales);
+    let yearly_capacity_payment_revenue = calculate_capacity_payments(map, year);
      
-    // Calculate yearly and accumulated costs, subtracting energy sales revenue if enabled
-    let yearly_total_cost = yearly_capital_cost + total_upgrade_costs + total_closure_costs - carbon_credit_revenue -
+    // Break the year's cost into its components, counting energy sales only if enabled.
+    // Running costs (opex and fuel) are reported alongside but stay out of the total.
+    let capex = yearly_capital_cost + total_upgrade_costs + total_closure_costs;
+    let (opex, fuel_cost) = map.calc_yearly_operating_costs(year);
+    let carbon_cost = carbon_tax_cost;
+    let revenue = carbon_credit_revenue + yearly_capacity_payment_revenue +
         (if enable_energy_sales { yearly_energy_sales_revenue } else { 0.0 });
+    let yearly_total_cost = capex + carbon_cost - revenue;
      
     // Properly accumulate total_cost across years by adding yearly costs to previous total
     let total_cost = match previous_metrics {
@@ -141,43 +219,272 @@ pub fn calculate_yearly_metrics(
         None => carbon_credit_revenue // First year, just use current year's revenue
     };
 
+    let total_carbon_tax_cost = match previous_metrics {
+        Some(prev) => prev.total_carbon_tax_cost + carbon_tax_cost,
+        None => carbon_tax_cost
+    };
+
     // Calculate total energy sales revenue with proper accumulation across years 
     let total_energy_sales_revenue = match previous_metrics {
         Some(prev) => prev.total_energy_sales_revenue + yearly_energy_sales_revenue,
         None => yearly_energy_sales_revenue // First year, just use current year's revenue
     };
 
+    let cumulative_net_emissions = match previous_metrics {
+        Some(prev) => prev.cumulative_net_emissions + net_co2_emissions,
+        None => net_co2_emissions
+    };
+
+    let (storage_state_of_charge, storage_energy_charged, storage_energy_discharged) =
+        map.storage_utilization(year);
+
+    let settlement_demand = map.get_settlements().iter()
+        .map(|settlement| (settlement.get_name().to_string(), settlement.get_population(), settlement.get_power_usage()))
+        .collect();
+
+    let siting_opinion = siting_opinion(map, year);
+    let average_public_opinion = match previous_metrics {
+        Some(prev) => blend_macro_opinion(map, siting_opinion, net_co2_emissions, &OpinionTrend::from(prev)),
+        None => siting_opinion,
+    };
+
     YearlyMetrics {
         year,
         total_population: total_pop,
         total_power_usage,
         total_power_generation: total_power_gen,
         power_balance,
-        average_public_opinion: if opinion_count > 0 { total_opinion / opinion_count as f64 } else { 1.0 },
+        peak_deficit,
+        firm_capacity_margin: map.calc_firm_capacity_margin(year),
+        unserved_zone_demand: map.calc_unserved_zone_demand(year, None),
+        average_public_opinion,
         yearly_capital_cost,
         total_capital_cost,
         inflation_factor,
         total_co2_emissions,
         total_carbon_offset,
+        carbon_credit_offset: map.calc_carbon_credit_offset(year),
         net_co2_emissions,
+        cumulative_net_emissions,
+        emissions_intensity_g_per_kwh,
         yearly_carbon_credit_revenue,
         total_carbon_credit_revenue,
+        yearly_carbon_tax_cost: carbon_tax_cost,
+        total_carbon_tax_cost,
         yearly_energy_sales_revenue,
         total_energy_sales_revenue,
+        yearly_capacity_payment_revenue,
+        storage_state_of_charge,
+        storage_energy_charged,
+        storage_energy_discharged,
+        total_land_use: map.calc_total_land_use(),
+        renewable_share: renewable_energy_share(map, year),
         generator_efficiencies,
         generator_operations,
+        settlement_demand,
         active_generators: active_count,
         yearly_upgrade_costs: total_upgrade_costs,
         yearly_closure_costs: total_closure_costs,
+        capex,
+        opex,
+        fuel_cost,
+        carbon_cost,
+        revenue,
         yearly_total_cost,
         total_cost,
     }
 }
 
-fn calculate_energy_sales(power_balance: f64, year: u32, enable_sales: bool) -> f64 {
+// Reliability payments for firm capacity, when the config sets a rate. Variable and storage
+// capacity is paid on its derated capacity credit rather than nameplate.
+fn calculate_capacity_payments(map: &Map, year: u32) -> f64 {
+    match map.get_config().capacity_payment_rate {
+        Some(rate) => {
+            let payments: f64 = map.get_generators().iter()
+                .map(|generator| generator.capacity_payment(rate))
+                .sum();
+            payments * map.get_config().inflation.factor(year)
+        }
+        None => 0.0,
+    }
+}
+
+fn calculate_energy_sales(map: &Map, power_balance: f64, year: u32, enable_sales: bool) -> f64 {
     if enable_sales && power_balance > 0.0 {
-        const_funcs::calculate_energy_sales_revenue(power_balance, year, crate::config::constants::DEFAULT_ENERGY_SALES_RATE)
+        let surplus_energy_mwh = annual_surplus_energy_mwh(map, year, power_balance);
+        let demand_energy_mwh = map.calc_total_power_usage(year) * HOURS_PER_YEAR;
+        const_funcs::calculate_energy_sales_revenue(
+            surplus_energy_mwh,
+            demand_energy_mwh,
+            year,
+            crate::config::constants::DEFAULT_ENERGY_SALES_RATE,
+            &map.get_config().energy_price_curve,
+        )
     } else {
         0.0
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::simulation_config::SimulationConfig;
+    use crate::data::poi::Coordinate;
+    use crate::models::generator::GeneratorType;
+    use crate::test_support::{empty_map, small_map, test_generator, TEST_SITE};
+
+    #[test]
+    fn cost_components_sum_to_reported_total() {
+        let mut map = small_map();
+        let mut config = map.get_config().clone();
+        config.carbon_price_trajectory = vec![(2025, 100.0)];
+        map.set_config(config);
+
+        let first = calculate_yearly_metrics(&map, 2025, 1.0e6, 5.0e5, true, None);
+        // A build in the second year, so every component is non-zero there
+        let mut wind = test_generator("New_Wind", GeneratorType::OnshoreWind, TEST_SITE, 50.0, 0.0);
+        wind.initialize_construction(2026, 0.5, false);
+        map.add_generator(wind);
+        let second = calculate_yearly_metrics(&map, 2026, 2.0e6, 0.0, true, Some(&first));
+        assert!(second.capex > 0.0 && second.carbon_cost > 0.0 && second.revenue > 0.0);
+
+        for metrics in [&first, &second] {
+            let total = metrics.capex + metrics.carbon_cost - metrics.revenue;
+            assert!((total - metrics.yearly_total_cost).abs() < 1e-6 * metrics.yearly_total_cost.abs().max(1.0));
+            // Running costs are reported alongside the total but not counted in it
+            assert!(metrics.opex > 0.0 && metrics.fuel_cost > 0.0);
+        }
+        assert!((second.total_cost - (first.yearly_total_cost + second.yearly_total_cost)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn falling_emissions_raise_opinion_under_a_positive_macro_weight() {
+        let opinion_after = |macro_weight: f64, previous_emissions_factor: f64| {
+            let mut map = small_map();
+            let mut config = map.get_config().clone();
+            config.opinion_dynamics.macro_weight = macro_weight;
+            map.set_config(config);
+
+            let mut previous = calculate_yearly_metrics(&map, 2025, 0.0, 0.0, true, None);
+            previous.net_co2_emissions *= previous_emissions_factor;
+            previous.yearly_total_cost = 0.0;
+            calculate_yearly_metrics(&map, 2026, 0.0, 0.0, true, Some(&previous)).average_public_opinion
+        };
+
+        // Last year emitted twice as much, so this year's emissions have halved
+        assert!(opinion_after(0.5, 2.0) > opinion_after(0.5, 1.0));
+        assert_eq!(opinion_after(0.0, 2.0), opinion_after(0.0, 1.0));
+    }
+
+    #[test]
+    fn carbon_price_adds_cost_proportional_to_positive_net_emissions() {
+        let carbon_cost = |price: f64, gas_co2: f64| {
+            let mut map = small_map();
+            let mut config = map.get_config().clone();
+            config.carbon_price_trajectory = vec![(2025, price)];
+            map.set_config(config);
+            map.get_generator_mut("Existing_Gas").unwrap().co2_out = gas_co2;
+
+            let metrics = calculate_yearly_metrics(&map, 2025, 0.0, 0.0, true, None);
+            assert!(metrics.net_co2_emissions > 0.0);
+            (metrics.yearly_carbon_tax_cost, metrics.net_co2_emissions)
+        };
+
+        let (cost, emissions) = carbon_cost(100.0, 1.2e6);
+        assert!((cost - 100.0 * emissions).abs() < 1e-6 * cost);
+        let (doubled_cost, doubled_emissions) = carbon_cost(100.0, 2.4e6);
+        assert!((doubled_cost / cost - doubled_emissions / emissions).abs() < 1e-9);
+        assert_eq!(carbon_cost(0.0, 1.2e6).0, 0.0);
+    }
+
+    #[test]
+    fn half_loaded_100_mw_unit_yields_the_expected_energy() {
+        let year = 2025;
+        let mut generator = test_generator("Baseload", GeneratorType::Nuclear, TEST_SITE, 100.0, 0.0);
+        generator.initialize_construction(year, 0.5, false);
+        generator.operation_percentage = 0.5;
+        generator.efficiency = 1.0;
+        generator.resource_quality = 1.0;
+        generator.forced_outage_rate = 0.0;
+        generator.planned_maintenance_weeks = 0.0;
+
+        let energy = annual_energy_mwh(&generator, year, &AvailabilityFactors::default());
+
+        assert!((energy - 100.0 * 0.5 * 8760.0).abs() < 1e-6, "{} MWh", energy);
+    }
+
+    #[test]
+    fn doubling_generation_at_constant_emissions_halves_intensity() {
+        // 2026, so the 2025 build's one-off embodied emissions don't count
+        let year = 2026;
+        let intensity = |gas_power: f64| {
+            let mut map = small_map();
+            map.get_generator_mut("Existing_Gas").unwrap().power_out = gas_power;
+            let metrics = calculate_yearly_metrics(&map, year, 0.0, 0.0, true, None);
+            (metrics.emissions_intensity_g_per_kwh, metrics.net_co2_emissions)
+        };
+
+        let (single, emissions) = intensity(400.0);
+        let (doubled, same_emissions) = intensity(800.0);
+
+        assert_eq!(emissions, same_emissions);
+        assert!(single > 0.0);
+        assert!((doubled - single / 2.0).abs() < 1e-9 * single);
+    }
+
+    #[test]
+    fn capacity_weighting_lets_a_large_unpopular_plant_dominate() {
+        let year = 2030;
+        let average_with = |opinion_weighting: OpinionWeighting| {
+            let mut map = empty_map(SimulationConfig { opinion_weighting, ..SimulationConfig::default() });
+            map.add_generator(test_generator("Big_Coal", GeneratorType::CoalPlant, TEST_SITE, 1_000.0, 5.0e6));
+            map.add_generator(test_generator("Rooftop_1", GeneratorType::DomesticSolar, Coordinate::new(40_000.0, 10_000.0), 1.0, 0.0));
+            map.add_generator(test_generator("Rooftop_2", GeneratorType::DomesticSolar, Coordinate::new(10_000.0, 40_000.0), 1.0, 0.0));
+            let coal_opinion = map.calc_new_generator_opinion(&TEST_SITE, &map.get_generators()[0], year);
+            (calculate_average_opinion(&map, year), coal_opinion)
+        };
+
+        let (by_count, coal_opinion) = average_with(OpinionWeighting::Count);
+        let (by_capacity, _) = average_with(OpinionWeighting::Capacity);
+
+        assert!(coal_opinion < by_count, "coal {} is not the unpopular one (average {})", coal_opinion, by_count);
+        assert!(by_capacity < by_count, "capacity-weighted {} vs count-weighted {}", by_capacity, by_count);
+        assert!((by_capacity - coal_opinion).abs() < (by_count - coal_opinion).abs());
+    }
+
+    #[test]
+    fn yearly_metrics_report_the_capacity_weighted_opinion() {
+        let year = 2030;
+        let mut map = empty_map(SimulationConfig { opinion_weighting: OpinionWeighting::Capacity, ..SimulationConfig::default() });
+        map.add_generator(test_generator("Big_Coal", GeneratorType::CoalPlant, TEST_SITE, 1_000.0, 5.0e6));
+        map.add_generator(test_generator("Rooftop_1", GeneratorType::DomesticSolar, Coordinate::new(40_000.0, 10_000.0), 1.0, 0.0));
+
+        let metrics = calculate_yearly_metrics(&map, year, 0.0, 0.0, false, None);
+
+        assert!((metrics.average_public_opinion - calculate_average_opinion(&map, year)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn capacity_payments_favour_firm_gas_over_equal_nameplate_solar() {
+        // Yearly cost cut by capacity payments for a map with just one 400 MW unit of `gen_type`
+        let payment_saving = |gen_type: GeneratorType| {
+            let mut map = empty_map(SimulationConfig::default());
+            let mut generator = test_generator("Plant", gen_type, TEST_SITE, 400.0, 0.0);
+            generator.initialize_construction(2025, 0.5, false);
+            map.add_generator(generator);
+            let without = calculate_yearly_metrics(&map, 2026, 0.0, 0.0, true, None);
+            let mut config = map.get_config().clone();
+            config.capacity_payment_rate = Some(50_000.0);
+            map.set_config(config);
+            let with = calculate_yearly_metrics(&map, 2026, 0.0, 0.0, true, None);
+            assert_eq!(without.yearly_capacity_payment_revenue, 0.0);
+            assert!((without.yearly_total_cost - with.yearly_total_cost - with.yearly_capacity_payment_revenue).abs() < 1e-3);
+            with.yearly_capacity_payment_revenue
+        };
+
+        let gas = payment_saving(GeneratorType::GasCombinedCycle);
+        let solar = payment_saving(GeneratorType::UtilitySolar);
+        // Gas is paid on nearly all of its nameplate, solar on only its small capacity credit
+        assert!(gas >= 400.0 * 0.8 * 50_000.0);
+        assert!(solar > 0.0 && solar < 0.2 * gas);
+    }
+}
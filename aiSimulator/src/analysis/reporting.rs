@@ -1,4 +1,11 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
 use super::metrics::YearlyMetrics;
+use crate::error::SimulationError;
+use crate::data::poi::POI;
+use crate::models::generator::{ConstructionStatus, GeneratorType};
+use crate::utils::map_handler::Map;
 
 pub fn print_yearly_summary(metrics: &YearlyMetrics) {
     println!("\nYear {} Summary", metrics.year);
@@ -17,16 +24,30 @@ pub fn print_yearly_summary(metrics: &YearlyMetrics) {
         println!("  Yearly Carbon Credit Revenue: €{:.2}", metrics.yearly_carbon_credit_revenue);
         println!("  Total Carbon Credit Revenue: €{:.2}", metrics.total_carbon_credit_revenue);
     }
+    if metrics.yearly_carbon_tax_cost > 0.0 {
+        println!("  Yearly Carbon Tax: €{:.2}", metrics.yearly_carbon_tax_cost);
+        println!("  Total Carbon Tax: €{:.2}", metrics.total_carbon_tax_cost);
+    }
     if metrics.yearly_energy_sales_revenue > 0.0 {
         println!("  Yearly Energy Sales Revenue: €{:.2}", metrics.yearly_energy_sales_revenue);
         println!("  Total Energy Sales Revenue: €{:.2}", metrics.total_energy_sales_revenue);
     }
+    if metrics.yearly_capacity_payment_revenue > 0.0 {
+        println!("  Yearly Capacity Payment Revenue: €{:.2}", metrics.yearly_capacity_payment_revenue);
+    }
+    println!("  Cost Breakdown: capex €{:.2}, carbon €{:.2}, revenue €{:.2}",
+        metrics.capex, metrics.carbon_cost, metrics.revenue);
+    println!("  Running Costs (not in total): opex €{:.2}, fuel €{:.2}", metrics.opex, metrics.fuel_cost);
     println!("  Yearly Total Cost: €{:.2}", metrics.yearly_total_cost);
     println!("  Accumulated Total Cost: €{:.2}", metrics.total_cost);
     println!("Environmental Metrics:");
     println!("  CO2 Emissions: {:.2} tonnes", metrics.total_co2_emissions);
     println!("  Carbon Offset: {:.2} tonnes", metrics.total_carbon_offset);
     println!("  Net Emissions: {:.2} tonnes", metrics.net_co2_emissions);
+    println!("  Cumulative Net Emissions: {:.2} tonnes", metrics.cumulative_net_emissions);
+    println!("  Emissions Intensity: {:.2} gCO2/kWh", metrics.emissions_intensity_g_per_kwh);
+    println!("  Land Use: {:.2} km²", metrics.total_land_use);
+    println!("  Renewable Share: {:.1}%", metrics.renewable_share * 100.0);
     println!("Public Opinion: {:.3}", metrics.average_public_opinion);
     println!("Active Generators: {}", metrics.active_generators);
     
@@ -54,4 +75,127 @@ pub fn print_generator_details(metrics: &YearlyMetrics) {
             id, efficiency, operation);
     }
     println!("----------------------------------------");
-}
\ No newline at end of file
+}
+
+/// Writes the dispatch stack for `year` to a CSV: active generators sorted by marginal cost with
+/// running cumulative capacity, flagging the unit that meets the demand level. Closed and
+/// not-yet-built plant is left out, as are electrolyzers, which draw power rather than supply it.
+pub fn export_dispatch_curve(map: &Map, year: u32, path: &Path) -> Result<(), SimulationError> {
+    let inflation = &map.get_config().inflation;
+    let mut stack: Vec<_> = map.get_generators().iter()
+        .filter(|g| g.is_active() && g.get_generator_type() != &GeneratorType::HydrogenElectrolyzer)
+        .map(|g| (g, g.marginal_cost(year, inflation)))
+        .collect();
+    stack.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    let demand = map.calc_total_power_usage(year);
+    let mut file = File::create(path)?;
+    writeln!(file, "Rank,GeneratorId,Type,CapacityMW,CumulativeCapacityMW,MarginalCostPerMWh,IsMarginal")?;
+
+    let mut cumulative = 0.0;
+    let mut marginal_found = false;
+    for (rank, (generator, marginal_cost)) in stack.iter().enumerate() {
+        cumulative += generator.power_out;
+        let is_marginal = !marginal_found && cumulative >= demand;
+        marginal_found |= is_marginal;
+        writeln!(
+            file,
+            "{},{},{},{:.2},{:.2},{:.4},{}",
+            rank + 1,
+            generator.get_id(),
+            generator.get_generator_type(),
+            generator.power_out,
+            cumulative,
+            marginal_cost,
+            is_marginal
+        )?;
+    }
+
+    println!("Dispatch curve for {}: {:.2} MW of capacity against {:.2} MW demand{}",
+        year, cumulative, demand, if marginal_found { "" } else { " (demand exceeds the stack)" });
+    Ok(())
+}
+
+/// Writes the age profile of the fleet as of `end_year`, soonest retirement first, so
+/// replacement capacity can be planned ahead of the closures. Closed and decommissioned units are
+/// left out; plant still being built counts from its commissioning year.
+pub fn export_fleet_age(map: &Map, end_year: u32, path: &Path) -> Result<(), SimulationError> {
+    let mut fleet: Vec<_> = map.get_generators().iter()
+        .filter(|g| g.is_active && g.construction_status != ConstructionStatus::Decommissioned)
+        .collect();
+    fleet.sort_by_key(|g| (g.get_end_of_life_year(), g.get_id().to_string()));
+
+    let mut file = File::create(path)?;
+    writeln!(file, "GeneratorId,Type,CapacityMW,CommissioningYear,AgeAtEnd,LifespanYears,RetirementYear")?;
+    for generator in &fleet {
+        let commissioning_year = generator.service_start_year();
+        writeln!(
+            file,
+            "{},{},{:.2},{},{},{},{}",
+            generator.get_id(),
+            generator.get_generator_type(),
+            generator.power_out,
+            commissioning_year,
+            end_year.saturating_sub(commissioning_year),
+            generator.eol,
+            generator.get_end_of_life_year()
+        )?;
+    }
+
+    let retiring = fleet.iter().filter(|g| g.get_end_of_life_year() <= end_year).count();
+    println!("Fleet age profile: {} generators, {} reach end of life by {}", fleet.len(), retiring, end_year);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::poi::Coordinate;
+    use crate::test_support::{scratch_path, small_map, test_generator, TEST_SITE};
+
+    #[test]
+    fn cumulative_capacity_rises_to_the_total_active_capacity() {
+        let mut map = small_map();
+        map.add_generator(test_generator("Wind", GeneratorType::OnshoreWind, TEST_SITE, 120.0, 0.0));
+        map.add_generator(test_generator("Peaker", GeneratorType::GasPeaker, Coordinate::new(26_000.0, 24_000.0), 60.0, 5.0e5));
+        let mut closed = test_generator("Closed_Coal", GeneratorType::CoalPlant, Coordinate::new(24_000.0, 24_000.0), 300.0, 2.0e6);
+        closed.is_active = false;
+        map.add_generator(closed);
+        let path = scratch_path("dispatch_curve.csv");
+
+        export_dispatch_curve(&map, 2030, &path).unwrap();
+
+        let mut reader = csv::Reader::from_path(&path).unwrap();
+        let cumulative: Vec<f64> = reader.records()
+            .map(|record| record.unwrap()[4].parse().unwrap())
+            .collect();
+        let total: f64 = map.get_generators().iter().filter(|g| g.is_active()).map(|g| g.power_out).sum();
+        assert_eq!(cumulative.len(), 3);
+        assert!(cumulative.windows(2).all(|pair| pair[1] > pair[0]), "{:?}", cumulative);
+        assert!((cumulative.last().unwrap() - total).abs() < 0.01);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn unit_commissioned_in_2025_with_a_25_year_life_retires_in_2050() {
+        let mut map = small_map();
+        let mut generator = test_generator("New_Wind", GeneratorType::OnshoreWind, TEST_SITE, 100.0, 0.0);
+        generator.eol = 25;
+        generator.initialize_construction(2025, 0.5, false);
+        map.add_generator(generator);
+        let path = scratch_path("fleet_age.csv");
+
+        export_fleet_age(&map, 2050, &path).unwrap();
+
+        let mut reader = csv::Reader::from_path(&path).unwrap();
+        let row = reader.records()
+            .map(|record| record.unwrap())
+            .find(|record| &record[0] == "New_Wind")
+            .expect("the new unit is listed");
+        assert_eq!(&row[3], "2025");
+        assert_eq!(&row[4], "25");
+        assert_eq!(&row[5], "25");
+        assert_eq!(&row[6], "2050");
+        let _ = std::fs::remove_file(path);
+    }
+}
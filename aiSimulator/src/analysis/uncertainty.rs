@@ -0,0 +1,285 @@
+// Monte Carlo over renewable availability and forced outages: replays a fixed plan under sampled
+// wind/solar/marine availability and generator outages to put uncertainty bands on the final metrics.
+// Plants aren't re-dispatched around a draw, so operating emissions stay put and the weather
+// shows up in emissions intensity per kWh delivered rather than in tonnes.
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use crate::ai::GridAction;
+use crate::analysis::metrics::YearlyMetrics;
+use crate::analysis::metrics_calculation::calculate_yearly_metrics;
+use crate::config::constants::{BASE_YEAR, END_YEAR};
+use crate::config::simulation_config::{AvailabilityDistribution, RenewableUncertainty};
+use crate::core::actions::apply_action;
+use crate::core::simulation::update_population;
+use crate::models::generator::GeneratorType;
+use crate::utils::map_handler::Map;
+
+// Factors are clamped so a single draw can't produce negative or absurd output
+const MIN_AVAILABILITY_FACTOR: f64 = 0.0;
+const MAX_AVAILABILITY_FACTOR: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AvailabilityFactors {
+    pub wind: f64,
+    pub solar: f64,
+    pub marine: f64,
+}
+
+impl Default for AvailabilityFactors {
+    fn default() -> Self {
+        Self {
+            wind: 1.0,
+            solar: 1.0,
+            marine: 1.0,
+        }
+    }
+}
+
+impl AvailabilityFactors {
+    // With a non-zero correlation, every source independently reuses one shared weather shock
+    // with probability sqrt(correlation) and otherwise draws its own. Each source keeps its
+    // marginal distribution, and any two sources end up correlated by exactly `correlation`.
+    pub fn sample(config: &RenewableUncertainty, rng: &mut StdRng) -> Self {
+        let correlation = config.correlation.clamp(0.0, 1.0);
+        let common = (correlation > 0.0).then(|| standard_shock(config.distribution, rng));
+        let share_probability = correlation.sqrt();
+
+        let mut factor = |std_dev: f64| {
+            if std_dev <= 0.0 {
+                return 1.0;
+            }
+            let shock = match common {
+                Some(common) if rng.gen::<f64>() < share_probability => common,
+                _ => standard_shock(config.distribution, rng),
+            };
+            (1.0 + shock * std_dev).clamp(MIN_AVAILABILITY_FACTOR, MAX_AVAILABILITY_FACTOR)
+        };
+
+        Self {
+            wind: factor(config.wind_std_dev),
+            solar: factor(config.solar_std_dev),
+            marine: factor(config.marine_std_dev),
+        }
+    }
+
+    pub fn factor_for(&self, generator_type: &GeneratorType) -> f64 {
+        match generator_type {
+            GeneratorType::OnshoreWind | GeneratorType::OffshoreWind | GeneratorType::FloatingOffshoreWind => self.wind,
+            GeneratorType::DomesticSolar | GeneratorType::CommercialSolar | GeneratorType::UtilitySolar => self.solar,
+            GeneratorType::TidalGenerator | GeneratorType::WaveEnergy => self.marine,
+            _ => 1.0,
+        }
+    }
+}
+
+// Zero-mean, unit-variance draw, scaled by each source's std dev around 1.0
+fn standard_shock(distribution: AvailabilityDistribution, rng: &mut StdRng) -> f64 {
+    match distribution {
+        AvailabilityDistribution::Normal => {
+            // Box-Muller transform
+            let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+            let u2: f64 = rng.gen::<f64>();
+            (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+        },
+        AvailabilityDistribution::Uniform => {
+            // A uniform band of half-width w has std dev w / sqrt(3)
+            let half_width = 3.0_f64.sqrt();
+            rng.gen_range(-half_width..=half_width)
+        },
+    }
+}
+
+// Draw each generator's forced outage for the year uniformly on [0, 2 * rate],
+// which keeps the expected outage equal to the deterministic rate
+fn sample_forced_outages(map: &mut Map, rng: &mut StdRng) {
+    for generator in map.get_generators_mut() {
+        let rate = generator.forced_outage_rate;
+        generator.sampled_forced_outage = if rate > 0.0 {
+            Some(rng.gen_range(0.0..=(2.0 * rate).min(1.0)))
+        } else {
+            None
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct UncertaintyBand {
+    pub mean: f64,
+    pub p5: f64,
+    pub p95: f64,
+}
+
+impl UncertaintyBand {
+    pub fn from_samples(samples: &[f64]) -> Self {
+        if samples.is_empty() {
+            return Self { mean: 0.0, p5: 0.0, p95: 0.0 };
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let mean = sorted.iter().sum::<f64>() / sorted.len() as f64;
+
+        Self {
+            mean,
+            p5: percentile(&sorted, 5.0),
+            p95: percentile(&sorted, 95.0),
+        }
+    }
+}
+
+// Nearest-rank percentile over already sorted samples
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UncertaintySummary {
+    pub draws: usize,
+    pub net_emissions: UncertaintyBand,
+    pub emissions_intensity: UncertaintyBand,
+    pub total_cost: UncertaintyBand,
+    pub power_balance: UncertaintyBand,
+    pub public_opinion: UncertaintyBand,
+}
+
+impl UncertaintySummary {
+    pub fn print(&self) {
+        println!("\nRenewable Availability Uncertainty ({} draws)", self.draws);
+        println!("----------------------------------------");
+        println!("                     {:>16} {:>16} {:>16}", "Mean", "P5", "P95");
+        println!("Net Emissions (t)    {:>16.2} {:>16.2} {:>16.2}",
+            self.net_emissions.mean, self.net_emissions.p5, self.net_emissions.p95);
+        println!("Intensity (g/kWh)    {:>16.2} {:>16.2} {:>16.2}",
+            self.emissions_intensity.mean, self.emissions_intensity.p5, self.emissions_intensity.p95);
+        println!("Total Cost (€)       {:>16.2} {:>16.2} {:>16.2}",
+            self.total_cost.mean, self.total_cost.p5, self.total_cost.p95);
+        println!("Power Balance (MW)   {:>16.2} {:>16.2} {:>16.2}",
+            self.power_balance.mean, self.power_balance.p5, self.power_balance.p95);
+        println!("Public Opinion       {:>16.3} {:>16.3} {:>16.3}",
+            self.public_opinion.mean, self.public_opinion.p5, self.public_opinion.p95);
+    }
+}
+
+/// Replays `plan` against fresh copies of `base_map`, drawing new availability
+/// factors every year, and summarises the final-year metrics across draws.
+pub fn run_availability_monte_carlo(
+    base_map: &Map,
+    plan: &[(u32, GridAction)],
+    seed: Option<u64>,
+    enable_energy_sales: bool,
+) -> UncertaintySummary {
+    let config = base_map.get_config().renewable_uncertainty.clone();
+    let base_seed = seed.unwrap_or(0);
+
+    let mut net_emissions = Vec::with_capacity(config.draws);
+    let mut emissions_intensity = Vec::with_capacity(config.draws);
+    let mut total_cost = Vec::with_capacity(config.draws);
+    let mut power_balance = Vec::with_capacity(config.draws);
+    let mut public_opinion = Vec::with_capacity(config.draws);
+
+    for draw in 0..config.draws {
+        let mut rng = StdRng::seed_from_u64(base_seed.wrapping_add(draw as u64));
+        let mut map = base_map.clone();
+        let mut previous: Option<YearlyMetrics> = None;
+
+        for year in BASE_YEAR..=END_YEAR {
+            map.current_year = year;
+            map.update_construction_status();
+            map.add_scheduled_builds(year);
+            update_population(&mut map, year);
+
+            for (_, action) in plan.iter().filter(|(action_year, _)| *action_year == year) {
+                if let Err(e) = apply_action(&mut map, action, year, None) {
+                    eprintln!("Warning: failed to replay action {:?} in {} (draw {}): {}", action, year, draw, e);
+                }
+            }
+
+            map.set_availability_factors(AvailabilityFactors::sample(&config, &mut rng));
+            sample_forced_outages(&mut map, &mut rng);
+            let metrics = calculate_yearly_metrics(&map, year, 0.0, 0.0, enable_energy_sales, previous.as_ref());
+            map.record_opinion_trend(&metrics);
+            previous = Some(metrics);
+        }
+
+        if let Some(final_metrics) = previous {
+            net_emissions.push(final_metrics.net_co2_emissions);
+            emissions_intensity.push(final_metrics.emissions_intensity_g_per_kwh);
+            total_cost.push(final_metrics.total_cost);
+            power_balance.push(final_metrics.power_balance);
+            public_opinion.push(final_metrics.average_public_opinion);
+        }
+    }
+
+    UncertaintySummary {
+        draws: config.draws,
+        net_emissions: UncertaintyBand::from_samples(&net_emissions),
+        emissions_intensity: UncertaintyBand::from_samples(&emissions_intensity),
+        total_cost: UncertaintyBand::from_samples(&total_cost),
+        power_balance: UncertaintyBand::from_samples(&power_balance),
+        public_opinion: UncertaintyBand::from_samples(&public_opinion),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::poi::Coordinate;
+    use crate::test_support::{small_map, test_generator};
+
+    fn intensity_band_width(wind_std_dev: f64) -> f64 {
+        let mut map = small_map();
+        map.add_generator(test_generator(
+            "Wind_Farm", GeneratorType::OnshoreWind, Coordinate::new(26_000.0, 24_000.0), 300.0, 0.0,
+        ));
+        let mut config = map.get_config().clone();
+        config.renewable_uncertainty.draws = 40;
+        config.renewable_uncertainty.wind_std_dev = wind_std_dev;
+        map.set_config(config);
+
+        let summary = run_availability_monte_carlo(&map, &[], Some(11), false);
+        summary.emissions_intensity.p95 - summary.emissions_intensity.p5
+    }
+
+    #[test]
+    fn higher_wind_variance_widens_the_emissions_band() {
+        let narrow = intensity_band_width(0.05);
+        let wide = intensity_band_width(0.4);
+
+        assert!(narrow > 0.0);
+        assert!(wide > 2.0 * narrow, "wide band {} vs narrow band {}", wide, narrow);
+    }
+
+    fn power_balance_p5(correlation: f64) -> f64 {
+        let mut map = small_map();
+        map.add_generator(test_generator(
+            "Wind_Farm", GeneratorType::OnshoreWind, Coordinate::new(26_000.0, 24_000.0), 300.0, 0.0,
+        ));
+        map.add_generator(test_generator(
+            "Solar_Farm", GeneratorType::UtilitySolar, Coordinate::new(24_000.0, 24_000.0), 300.0, 0.0,
+        ));
+        let mut config = map.get_config().clone();
+        config.renewable_uncertainty.draws = 100;
+        config.renewable_uncertainty.wind_std_dev = 0.3;
+        config.renewable_uncertainty.solar_std_dev = 0.3;
+        config.renewable_uncertainty.correlation = correlation;
+        map.set_config(config);
+        // No outages, so the spread comes from the weather alone
+        for generator in map.get_generators_mut() {
+            generator.forced_outage_rate = 0.0;
+        }
+
+        run_availability_monte_carlo(&map, &[], Some(7), false).power_balance.p5
+    }
+
+    #[test]
+    fn correlated_wind_and_solar_give_a_worse_combined_p5() {
+        let uncorrelated = power_balance_p5(0.0);
+        let correlated = power_balance_p5(0.9);
+
+        // Lulls line up when the sources move together, so the bad tail gets worse
+        assert!(correlated < uncorrelated, "correlated p5 {} vs uncorrelated p5 {}", correlated, uncorrelated);
+    }
+}
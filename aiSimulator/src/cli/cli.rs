@@ -1,8 +1,28 @@
-use clap::Parser;
-
+use clap::{ArgGroup, Parser, Subcommand};
+use crate::ai::metrics::scoring::ScoreWeights;
+use crate::models::generator::GeneratorType;
+use crate::config::simulation_config::{FullRunStrategy, OpinionWeighting, OutputFormat};
+use crate::ai::learning::weights::ExplorationSchedule;
+use crate::ai::learning::constants::NOOP_MAX_WEIGHT_SHARE;
+use crate::config::constants::{DEFAULT_EMBODIED_CARBON_FACTOR, DEFAULT_MAX_DEFICIT_ATTEMPTS, INFLATION_RATE};
+use crate::utils::logging::LogLevel;
+
+// Year -> value points; an alias so clap parses the whole list as a single argument
+type YearPoints = Vec<(u32, f64)>;
+
+// Generator type -> count pairs, aliased for the same reason
+type TypeCounts = Vec<(GeneratorType, usize)>;
+
+// Each of these picks the optimization mode, so at most one may be given
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
+#[command(group(ArgGroup::new("objective")
+    .args(["score_weights", "cost_only", "trajectory_scoring", "renewable_mandate", "min_footprint", "cost_cap"])
+    .multiple(false)))]
 pub struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(short = 'n', long, default_value_t = 1000)]
     iterations: usize,
 
@@ -30,6 +50,9 @@ pub struct Args {
     #[arg(long, default_value_t = false)]
     enable_timing: bool,
 
+    #[arg(long, help = "Record timings and write them as folded stacks to this file for flamegraph tools, with per-operation totals and call counts in a sibling .json")]
+    profile: Option<String>,
+
     #[arg(long, help = "Random seed for deterministic simulation")]
     seed: Option<u64>,
 
@@ -39,12 +62,93 @@ pub struct Args {
     #[arg(long, help = "Optimize for cost only, ignoring emissions and public opinion", default_value_t = false)]
     cost_only: bool,
     
+    #[arg(long, help = "Penalize emissions above a path declining linearly to zero by 2050 in every year, not just the final one", default_value_t = false)]
+    trajectory_scoring: bool,
+    
+    #[arg(long, help = "Penalize plans whose renewable energy share falls below this trajectory, as year:share points (e.g. 2030:0.5,2040:0.8)", value_parser = parse_year_points)]
+    renewable_mandate: Option<YearPoints>,
+    
+    #[arg(long, help = "Penalize plans by the land their final fleet occupies, favoring compact sources", default_value_t = false)]
+    min_footprint: bool,
+    
+    #[arg(long, help = "Maximise emissions reduction while keeping total cost under this cap (euros)")]
+    cost_cap: Option<f64>,
+    
+    #[arg(long, help = "Heavily penalize plans whose net emissions summed over 2025-2050 exceed this budget (tonnes)")]
+    carbon_budget: Option<f64>,
+    
+    #[arg(long, help = "Count final-year net emissions up to this many tonnes as net zero", default_value_t = 0.0)]
+    net_zero_tolerance: f64,
+    
+    #[arg(long, help = "Only count real reductions towards net zero, not purchased carbon credits")]
+    real_reductions_only: bool,
+    
+    #[arg(long, help = "Scale the construction emissions charged when generators and offsets are commissioned (0 ignores them)", default_value_t = DEFAULT_EMBODIED_CARBON_FACTOR)]
+    embodied_carbon_factor: f64,
+    
+    #[arg(long, help = "Pay generators this many euros per MW of firm capacity each year, derated by type (e.g. 50000)")]
+    capacity_payment: Option<f64>,
+    
+    #[arg(long, help = "Annual inflation rate applied to all cost projections (e.g. 0.05 for 5%)", default_value_t = INFLATION_RATE)]
+    inflation_rate: f64,
+    
+    #[arg(long, help = "Replace the inflation rate in individual years, as year:rate points (e.g. 2026:0.08,2027:0.05)", value_parser = parse_year_points)]
+    inflation_overrides: Option<YearPoints>,
+    
+    #[arg(long, help = "Stop taking new actions in a year once its capital spend reaches this limit (euros)")]
+    annual_capex_limit: Option<f64>,
+    
+    #[arg(long, help = "Cap new units of a generator type per year, as type:count pairs (e.g. OffshoreWind:2,Nuclear:1)", value_parser = parse_type_counts)]
+    max_builds_per_year: Option<TypeCounts>,
+    
+    #[arg(long, help = "Weight each generator's opinion by its rated output when averaging public opinion", default_value_t = false)]
+    capacity_weighted_opinion: bool,
+    
+    #[arg(long, help = "Raise exploration when recent scores plateau and lower it while they keep improving, instead of decaying it by iteration count alone", default_value_t = false)]
+    adaptive_exploration: bool,
+    
+    #[arg(long, help = "Largest share of a year's action weight DoNothing may reach (1.0 disables the cap)", default_value_t = NOOP_MAX_WEIGHT_SHARE)]
+    noop_max_share: f64,
+    
+    #[arg(long, help = "Stop starting new iterations after this many seconds, then checkpoint and export the best result as usual")]
+    max_runtime: Option<u64>,
+    
+    #[arg(long, help = "Keep this many of the best distinct plans and export each to top_results/rank_N (1 keeps only the best)", default_value_t = 1)]
+    top_k: usize,
+    
+    #[arg(long, help = "Record the iteration, thread and best score of every weight merge to merge_trace.csv", default_value_t = false)]
+    trace_merges: bool,
+    
+    #[arg(long, help = "At each checkpoint, drop learned actions weighted below this threshold (DoNothing is always kept)")]
+    prune_weights: Option<f64>,
+    
+    #[arg(long, help = "Score this many sampled generator candidates in parallel for each power deficit step (0 or 1 = sequential)", default_value_t = 0)]
+    deficit_candidates: usize,
+    
+    #[arg(long, help = "Sampled builds allowed per power deficit before an emergency gas peaker is added to close it", default_value_t = DEFAULT_MAX_DEFICIT_ATTEMPTS)]
+    max_deficit_attempts: usize,
+    
+    #[arg(long, help = "Score plans by a weighted sum of normalized objectives: a preset (default, cost_only, budget_capped) or emissions,cost,opinion,reliability")]
+    score_weights: Option<ScoreWeights>,
+    
     #[arg(long, help = "Enable revenue from energy sales to offset costs", default_value_t = true)]
     enable_energy_sales: bool,
 
     #[arg(long, help = "Enable CSV export of detailed simulation results", default_value_t = true)]
     enable_csv_export: bool,
 
+    #[arg(long, help = "Result files to write for the best run: csv, json, both or none (none also skips checkpoints)", default_value_t = OutputFormat::Both)]
+    output_format: OutputFormat,
+
+    #[arg(long, help = "Which iterations run the full simulation instead of cached locations: last:N (final N%), every:N, never or always", default_value_t = FullRunStrategy::default())]
+    full_run_strategy: FullRunStrategy,
+
+    #[arg(long, help = "Let full runs sample new plans instead of replaying the best plan found so far", default_value_t = false)]
+    no_full_run_replay: bool,
+
+    #[arg(long, help = "Diagnostic verbosity: error, warn, info, debug or trace (overrides the debug and verbose flags)")]
+    log_level: Option<LogLevel>,
+
     #[arg(long, help = "Enable debug logging (more detailed output)", default_value_t = false)]
     debug_logging: bool,
 
@@ -56,10 +160,71 @@ pub struct Args {
 
     #[arg(long, help = "Track and save weight history over time", default_value_t = false)]
     track_weight_history: bool,
+
+    #[arg(long, help = "Monte Carlo draws over renewable availability for the best plan (0 disables)", default_value_t = 0)]
+    uncertainty_draws: usize,
+
+    #[arg(long, help = "Correlation between wind, solar and marine availability in the Monte Carlo draws (0-1), so lulls coincide", default_value_t = 0.0)]
+    renewable_correlation: f64,
+
+    #[arg(long, help = "Start from this weights file instead of scanning the checkpoint directory")]
+    resume_weights: Option<String>,
+
+    #[arg(long, help = "Write interval checkpoints in compact binary (.bin) format instead of JSON", default_value_t = false)]
+    binary_checkpoints: bool,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Print the best action plan stored in a weights file without re-running the simulation
+    Planner {
+        #[arg(short, long, help = "Path to a saved weights file (e.g. best_weights.json)")]
+        weights: String,
+
+        #[arg(short, long, help = "Write the plan to this CSV file instead of stdout")]
+        output: Option<String>,
+    },
+    /// Re-simulate the best plan stored in a weights file and write the full enhanced CSV set
+    Replay {
+        #[arg(short, long, help = "Path to a saved weights file (e.g. best_weights.json)")]
+        weights: String,
+
+        #[arg(short, long, help = "Random seed used for map setup and replay")]
+        seed: Option<u64>,
+
+        #[arg(short, long, help = "Directory for the exported CSVs", default_value = "replay_output")]
+        output: String,
+    },
+    /// Simulate an externally authored action plan (CSV or JSON) and write the full enhanced CSV set
+    Evaluate {
+        #[arg(short, long, help = "Path to the plan (.csv in the planner layout, or .json)")]
+        plan: String,
+
+        #[arg(short, long, help = "Random seed used for map setup")]
+        seed: Option<u64>,
+
+        #[arg(short, long, help = "Directory for the exported CSVs", default_value = "plan_output")]
+        output: String,
+    },
+    /// Time a fixed training workload with output disabled and report iterations/sec, peak memory and per-category timings
+    Benchmark {
+        #[arg(short = 'n', long, help = "Number of iterations to run", default_value_t = 20)]
+        iterations: usize,
+
+        #[arg(short, long, help = "Worker threads (0 uses every core)", default_value_t = 0)]
+        threads: usize,
+
+        #[arg(short, long, help = "Base random seed for the workload", default_value_t = 42)]
+        seed: u64,
+    },
 }
 
 // Add getter methods for all fields
 impl Args {
+    pub fn command(&self) -> Option<&Command> {
+        self.command.as_ref()
+    }
+
     pub fn iterations(&self) -> usize {
         self.iterations
     }
@@ -93,7 +258,11 @@ impl Args {
     }
 
     pub fn enable_timing(&self) -> bool {
-        self.enable_timing
+        self.enable_timing || self.profile.is_some()
+    }
+
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
     }
 
     pub fn seed(&self) -> Option<u64> {
@@ -108,6 +277,118 @@ impl Args {
         self.cost_only
     }
 
+    pub fn trajectory_scoring(&self) -> bool {
+        self.trajectory_scoring
+    }
+
+    pub fn renewable_mandate(&self) -> Vec<(u32, f64)> {
+        self.renewable_mandate.clone().unwrap_or_default()
+    }
+
+    pub fn min_footprint(&self) -> bool {
+        self.min_footprint
+    }
+
+    pub fn cost_cap(&self) -> Option<f64> {
+        self.cost_cap
+    }
+
+    pub fn carbon_budget(&self) -> Option<f64> {
+        self.carbon_budget
+    }
+
+    pub fn net_zero_tolerance(&self) -> f64 {
+        self.net_zero_tolerance
+    }
+
+    pub fn real_reductions_only(&self) -> bool {
+        self.real_reductions_only
+    }
+
+    pub fn embodied_carbon_factor(&self) -> f64 {
+        self.embodied_carbon_factor
+    }
+
+    pub fn capacity_payment(&self) -> Option<f64> {
+        self.capacity_payment
+    }
+
+    pub fn inflation_rate(&self) -> f64 {
+        self.inflation_rate
+    }
+
+    pub fn inflation_overrides(&self) -> Vec<(u32, f64)> {
+        self.inflation_overrides.clone().unwrap_or_default()
+    }
+
+    pub fn max_builds_per_year(&self) -> Vec<(GeneratorType, usize)> {
+        self.max_builds_per_year.clone().unwrap_or_default()
+    }
+
+    pub fn opinion_weighting(&self) -> OpinionWeighting {
+        if self.capacity_weighted_opinion {
+            OpinionWeighting::Capacity
+        } else {
+            OpinionWeighting::Count
+        }
+    }
+
+    pub fn noop_max_share(&self) -> f64 {
+        self.noop_max_share
+    }
+
+    pub fn exploration_schedule(&self) -> ExplorationSchedule {
+        if self.adaptive_exploration {
+            ExplorationSchedule::Adaptive
+        } else {
+            ExplorationSchedule::Decay
+        }
+    }
+
+    pub fn output_format(&self) -> OutputFormat {
+        self.output_format
+    }
+
+    pub fn full_run_strategy(&self) -> FullRunStrategy {
+        self.full_run_strategy
+    }
+
+    pub fn no_full_run_replay(&self) -> bool {
+        self.no_full_run_replay
+    }
+
+    pub fn max_runtime(&self) -> Option<u64> {
+        self.max_runtime
+    }
+
+    pub fn top_k(&self) -> usize {
+        self.top_k
+    }
+
+    pub fn trace_merges(&self) -> bool {
+        self.trace_merges
+    }
+
+    pub fn prune_weights(&self) -> Option<f64> {
+        self.prune_weights
+    }
+
+    pub fn deficit_candidates(&self) -> usize {
+        self.deficit_candidates
+    }
+
+    pub fn max_deficit_attempts(&self) -> usize {
+        self.max_deficit_attempts
+    }
+
+    pub fn annual_capex_limit(&self) -> Option<f64> {
+        self.annual_capex_limit
+    }
+
+    pub fn score_weights(&self) -> Option<ScoreWeights> {
+        self.score_weights
+    }
+
     pub fn enable_energy_sales(&self) -> bool {
         self.enable_energy_sales
     }
@@ -116,6 +397,11 @@ impl Args {
         self.enable_csv_export
     }
     
+    /// Explicit `--log-level`, else the level the older debug and verbose flags imply
+    pub fn log_level(&self) -> LogLevel {
+        self.log_level.unwrap_or_else(|| LogLevel::from_flags(self.debug_logging, self.debug_weights, self.verbose_state_logging))
+    }
+
     pub fn debug_logging(&self) -> bool {
         self.debug_logging
     }
@@ -131,4 +417,49 @@ impl Args {
     pub fn track_weight_history(&self) -> bool {
         self.track_weight_history
     }
+
+    pub fn uncertainty_draws(&self) -> usize {
+        self.uncertainty_draws
+    }
+
+    pub fn renewable_correlation(&self) -> f64 {
+        self.renewable_correlation
+    }
+
+    pub fn resume_weights(&self) -> Option<&str> {
+        self.resume_weights.as_deref()
+    }
+
+    pub fn binary_checkpoints(&self) -> bool {
+        self.binary_checkpoints
+    }
+}
+
+// Parses comma-separated year:value points, e.g. "2030:0.5,2040:0.8"
+fn parse_year_points(s: &str) -> Result<YearPoints, String> {
+    s.split(',')
+        .map(|point| {
+            let (year, value) = point.trim().split_once(':')
+                .ok_or_else(|| format!("Expected year:value, got '{}'", point.trim()))?;
+            let year = year.trim().parse::<u32>()
+                .map_err(|e| format!("Invalid year '{}': {}", year.trim(), e))?;
+            let value = value.trim().parse::<f64>()
+                .map_err(|e| format!("Invalid value '{}': {}", value.trim(), e))?;
+            Ok((year, value))
+        })
+        .collect()
+}
+
+// Parses comma-separated type:count pairs, e.g. "OffshoreWind:2,Nuclear:1"
+fn parse_type_counts(s: &str) -> Result<TypeCounts, String> {
+    s.split(',')
+        .map(|pair| {
+            let (gen_type, count) = pair.trim().split_once(':')
+                .ok_or_else(|| format!("Expected type:count, got '{}'", pair.trim()))?;
+            let gen_type = gen_type.trim().parse::<GeneratorType>()?;
+            let count = count.trim().parse::<usize>()
+                .map_err(|e| format!("Invalid count '{}': {}", count.trim(), e))?;
+            Ok((gen_type, count))
+        })
+        .collect()
 }
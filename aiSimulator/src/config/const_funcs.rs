@@ -4,16 +4,13 @@ use crate::data::poi::Coordinate;
 use serde_json;
 use lazy_static::lazy_static;
 use crate::models::carbon_offset::CarbonOffsetType;
+use crate::config::simulation_config::{Electrification, InflationModel};
 use crate::config::tech_type::{TechType, BuildSpeed, map_to_tech_type, planning_duration, construction_duration, convert_cost_multiplier};
 
 
 
 
 
-pub fn calc_inflation_factor(year: u32) -> f64 {
-    (1.0 + INFLATION_RATE).powi((year - BASE_YEAR) as i32)
-}
-
 pub fn calc_power_usage_per_capita(year: u32) -> f64 {
     // Base power usage per capita in 2025 (in MW)
     const BASE_USAGE: f64 = 0.001;  // 1 kW per person
@@ -25,8 +22,16 @@ pub fn calc_power_usage_per_capita(year: u32) -> f64 {
     BASE_USAGE * (1.0 + ANNUAL_INCREASE).powf(years_from_base)
 }
 
-pub fn calc_generator_cost(gen_type: &GeneratorType, base_cost: f64, year: u32, is_urban: bool, is_coastal: bool, is_river: bool) -> f64 {
-    let inflation = calc_inflation_factor(year);
+/// Added per-capita load (MW) from EV and heat-pump adoption in the given year.
+pub fn calc_electrification_usage_per_capita(electrification: &Electrification, year: u32) -> f64 {
+    let ev_share = interpolate_trajectory(&electrification.ev_adoption, year).clamp(0.0, 1.0);
+    let heat_pump_share = interpolate_trajectory(&electrification.heat_pump_adoption, year).clamp(0.0, 1.0);
+    ev_share * electrification.ev_usage_per_adopter +
+        heat_pump_share * electrification.heat_pump_usage_per_adopter
+}
+
+pub fn calc_generator_cost(gen_type: &GeneratorType, base_cost: f64, year: u32, is_urban: bool, is_coastal: bool, is_river: bool, inflation: &InflationModel) -> f64 {
+    let inflation = inflation.factor(year);
     let years_from_base = (year - BASE_YEAR) as f64;
     
     // Get technology-specific cost evolution rate
@@ -56,19 +61,20 @@ pub fn calc_generator_cost(gen_type: &GeneratorType, base_cost: f64, year: u32,
     base_cost * inflation * technology_factor * location_modifier
 }
 
-pub fn calc_operating_cost(gen_type: &GeneratorType, base_operating_cost: f64, year: u32) -> f64 {
-    let inflation = calc_inflation_factor(year);
+pub fn calc_operating_cost(gen_type: &GeneratorType, base_operating_cost: f64, year: u32, inflation: &InflationModel) -> f64 {
+    let inflation = inflation.factor(year);
     let years_from_base = (year - BASE_YEAR) as f64;
     
     let efficiency_factor = match gen_type {
-        GeneratorType::OnshoreWind | GeneratorType::OffshoreWind => WIND_EFFICIENCY_GAIN.powf(years_from_base),
+        GeneratorType::OnshoreWind | GeneratorType::OffshoreWind | GeneratorType::FloatingOffshoreWind => WIND_EFFICIENCY_GAIN.powf(years_from_base),
         GeneratorType::DomesticSolar | GeneratorType::CommercialSolar | GeneratorType::UtilitySolar => SOLAR_EFFICIENCY_GAIN.powf(years_from_base),
         GeneratorType::Nuclear => NUCLEAR_EFFICIENCY_GAIN.powf(years_from_base),
         GeneratorType::CoalPlant => COAL_EFFICIENCY_LOSS.powf(years_from_base),
         GeneratorType::GasCombinedCycle | GeneratorType::GasPeaker => GAS_EFFICIENCY_LOSS.powf(years_from_base),
         GeneratorType::HydroDam | GeneratorType::PumpedStorage => HYDRO_EFFICIENCY_GAIN.powf(years_from_base),
         GeneratorType::TidalGenerator | GeneratorType::WaveEnergy => MARINE_EFFICIENCY_GAIN.powf(years_from_base),
-        GeneratorType::BatteryStorage => BATTERY_EFFICIENCY_GAIN.powf(years_from_base),
+        GeneratorType::BatteryStorage | GeneratorType::HydrogenElectrolyzer => BATTERY_EFFICIENCY_GAIN.powf(years_from_base),
+        GeneratorType::HydrogenTurbine => GAS_EFFICIENCY_LOSS.powf(years_from_base),
         GeneratorType::Biomass => BIOMASS_EFFICIENCY_GAIN.powf(years_from_base),
     };
     
@@ -78,7 +84,7 @@ pub fn calc_operating_cost(gen_type: &GeneratorType, base_operating_cost: f64, y
 pub fn calc_type_opinion(gen_type: &GeneratorType, year: u32) -> f64 {
     let _years_passed = (year - BASE_YEAR) as f64;
     let (base_opinion, annual_change) = match gen_type {
-        GeneratorType::OnshoreWind | GeneratorType::OffshoreWind => (WIND_BASE_OPINION, WIND_OPINION_CHANGE),
+        GeneratorType::OnshoreWind | GeneratorType::OffshoreWind | GeneratorType::FloatingOffshoreWind => (WIND_BASE_OPINION, WIND_OPINION_CHANGE),
         GeneratorType::DomesticSolar | GeneratorType::CommercialSolar | GeneratorType::UtilitySolar => (SOLAR_BASE_OPINION, SOLAR_OPINION_CHANGE),
         GeneratorType::Nuclear => (NUCLEAR_BASE_OPINION, NUCLEAR_OPINION_CHANGE),
         GeneratorType::CoalPlant => (COAL_BASE_OPINION, COAL_OPINION_CHANGE),
@@ -86,14 +92,15 @@ pub fn calc_type_opinion(gen_type: &GeneratorType, year: u32) -> f64 {
         GeneratorType::HydroDam | GeneratorType::PumpedStorage => (HYDRO_BASE_OPINION, HYDRO_OPINION_CHANGE),
         GeneratorType::TidalGenerator | GeneratorType::WaveEnergy => (MARINE_BASE_OPINION, MARINE_OPINION_CHANGE),
         GeneratorType::BatteryStorage => (BATTERY_BASE_OPINION, BATTERY_OPINION_CHANGE),
+        GeneratorType::HydrogenElectrolyzer | GeneratorType::HydrogenTurbine => (HYDROGEN_BASE_OPINION, HYDROGEN_OPINION_CHANGE),
         GeneratorType::Biomass => (0.60, 0.001),
     };
     
     (base_opinion + annual_change * _years_passed).clamp(0.0, 1.0)
 }
 
-pub fn calc_cost_opinion(cost: f64, year: u32) -> f64 {
-    let inflation_adjusted_max = REFERENCE_ANNUAL_EXPENDITURE * calc_inflation_factor(year);
+pub fn calc_cost_opinion(cost: f64, year: u32, inflation: &InflationModel) -> f64 {
+    let inflation_adjusted_max = REFERENCE_ANNUAL_EXPENDITURE * inflation.factor(year);
     let normalized_cost = cost / inflation_adjusted_max;
     
     if normalized_cost <= 1.0 {
@@ -217,23 +224,111 @@ pub fn calculate_carbon_credit_revenue(net_emissions: f64, year: u32) -> f64 {
     negative_emissions * price
 }
 
+/// Returns the carbon tax (€/tCO₂) from a configured (year, price) trajectory.
+/// Prices are interpolated linearly between points, zero before the first and held after the last.
+pub fn carbon_price_per_tonne(trajectory: &[(u32, f64)], year: u32) -> f64 {
+    interpolate_trajectory(trajectory, year)
+}
+
+/// Linear interpolation over Year -> value points sorted by year: 0 before the first
+/// point, held at the last value after the final point.
+pub fn interpolate_trajectory(trajectory: &[(u32, f64)], year: u32) -> f64 {
+    let (first_year, _) = match trajectory.first() {
+        Some(point) => *point,
+        None => return 0.0,
+    };
+    if year < first_year {
+        return 0.0;
+    }
+
+    for window in trajectory.windows(2) {
+        let (start_year, start_price) = window[0];
+        let (end_year, end_price) = window[1];
+        if year < end_year {
+            let t = (year - start_year) as f64 / (end_year - start_year) as f64;
+            return start_price + t * (end_price - start_price);
+        }
+    }
+
+    trajectory.last().map_or(0.0, |(_, price)| *price)
+}
+
+/// Calculates the carbon tax owed on positive net emissions, the mirror of the credit revenue.
+pub fn calculate_carbon_tax_cost(net_emissions: f64, year: u32, trajectory: &[(u32, f64)]) -> f64 {
+    if net_emissions <= 0.0 {
+        // Net negative emitters earn credits instead of paying tax
+        return 0.0;
+    }
+
+    net_emissions * carbon_price_per_tonne(trajectory, year)
+}
+
 /// Calculates the revenue from selling excess energy.
 /// 
-/// * `power_surplus` - The power surplus in MW
+/// * `surplus_energy_mwh` - The energy surplus over the year in MWh
+/// * `demand_energy_mwh` - Demand over the year in MWh, to size the surplus against
 /// * `year` - The simulation year
 /// * `sales_rate` - The sales rate in € per GWh
-pub fn calculate_energy_sales_revenue(power_surplus: f64, __year: u32, sales_rate: f64) -> f64 {
-    if power_surplus <= 0.0 {
+/// * `price_curve` - Surplus/demand ratio -> multiplier on `sales_rate`; empty keeps the flat rate
+pub fn calculate_energy_sales_revenue(
+    surplus_energy_mwh: f64,
+    demand_energy_mwh: f64,
+    __year: u32,
+    sales_rate: f64,
+    price_curve: &[(f64, f64)],
+) -> f64 {
+    if surplus_energy_mwh <= 0.0 {
         // No surplus, no energy sales revenue
         return 0.0;
     }
 
-    // Convert power surplus (MW) to yearly energy (GWh)
-    // A power of 1 MW for a full year is 8.76 GWh (8760 hours / 1000)
-    let yearly_energy_gwh = power_surplus * super::constants::MW_TO_GWH_CONVERSION;
+    let yearly_energy_gwh = surplus_energy_mwh / super::constants::MWH_PER_GWH;
+    
+    // Oversupplied markets pay less, or charge for dumping, the more the surplus outgrows demand
+    let price_multiplier = if price_curve.is_empty() {
+        1.0
+    } else {
+        let surplus_ratio = surplus_energy_mwh / demand_energy_mwh.max(1.0);
+        interpolate_curve(price_curve, surplus_ratio)
+    };
     
     // Calculate revenue
-    yearly_energy_gwh * sales_rate
+    yearly_energy_gwh * sales_rate * price_multiplier
+}
+
+/// Linear interpolation over (x, y) points sorted by x, holding the end values outside the range.
+pub fn interpolate_curve(points: &[(f64, f64)], x: f64) -> f64 {
+    let (first_x, first_y) = match points.first() {
+        Some(point) => *point,
+        None => return 0.0,
+    };
+    if x <= first_x {
+        return first_y;
+    }
+
+    for window in points.windows(2) {
+        let (start_x, start_y) = window[0];
+        let (end_x, end_y) = window[1];
+        if x < end_x {
+            let t = (x - start_x) / (end_x - start_x);
+            return start_y + t * (end_y - start_y);
+        }
+    }
+
+    points.last().map_or(0.0, |(_, y)| *y)
+}
+
+/// Grid carbon intensity in gCO2/kWh.
+/// 
+/// * `net_emissions` - Net emissions over the year in tonnes
+/// * `delivered_energy_mwh` - Energy delivered over the year in MWh
+/// 
+/// Returns 0 when nothing was delivered.
+pub fn calculate_emissions_intensity(net_emissions: f64, delivered_energy_mwh: f64) -> f64 {
+    if delivered_energy_mwh <= 0.0 {
+        return 0.0;
+    }
+    net_emissions / delivered_energy_mwh * super::constants::G_PER_KWH_PER_TONNE_PER_MWH
 }
 
 pub fn is_point_inside_ireland(coordinate: &Coordinate) -> bool {
@@ -348,4 +443,26 @@ pub fn calc_time_reduction_factor(cost_multiplier: f64, reduction_factor: f64) -
     // This creates a diminishing returns curve
     let log_reduction = (bounded_multiplier.ln() * reduction_factor).min(0.8);
     1.0 - log_reduction
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::constants::DEFAULT_ENERGY_SALES_RATE;
+
+    #[test]
+    fn large_surplus_earns_less_per_mwh_under_the_price_curve() {
+        let demand_mwh = 1.0e6;
+        let curve = [(0.0, 1.0), (0.1, 1.0), (1.0, -0.2)];
+        let per_mwh = |surplus_mwh: f64, curve: &[(f64, f64)]| {
+            calculate_energy_sales_revenue(surplus_mwh, demand_mwh, 2030, DEFAULT_ENERGY_SALES_RATE, curve) / surplus_mwh
+        };
+
+        let small = per_mwh(0.05 * demand_mwh, &curve);
+        let large = per_mwh(2.0 * demand_mwh, &curve);
+        assert!(small > 0.0);
+        assert!(large < small, "{} per MWh at a large surplus vs {} at a small one", large, small);
+
+        // Without a curve every MWh sells at the flat rate
+        assert!((per_mwh(2.0 * demand_mwh, &[]) - per_mwh(0.05 * demand_mwh, &[])).abs() < 1e-9);
+    }
+}
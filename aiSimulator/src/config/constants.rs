@@ -6,6 +6,13 @@ pub const END_YEAR: u32 = 2050;
 pub const MAP_MAX_X: f64 = 50_000.0;
 pub const MAP_MAX_Y: f64 = 50_000.0;
 pub const GRID_CELL_SIZE: f64 = 1000.0;              // 1km grid cells
+pub const LOCATION_SCORE_TIE_EPSILON: f64 = 1e-9;    // Location scores closer than this count as a tie
+
+// Resource Quality (output multiplier from site; west is windier, south is sunnier)
+pub const ONSHORE_WIND_QUALITY_RANGE: (f64, f64) = (0.8, 1.2);   // East coast to west coast
+pub const OFFSHORE_WIND_QUALITY_RANGE: (f64, f64) = (0.95, 1.15); // Irish Sea to Atlantic
+pub const SOLAR_QUALITY_RANGE: (f64, f64) = (0.9, 1.1);          // North to south
+pub const DEFAULT_RESOURCE_QUALITY: f64 = 1.0;
 
 // Generator Placement Weights
 pub const TRANSMISSION_LOSS_WEIGHT: f64 = 0.03;    // Weight for transmission losses in placement
@@ -43,6 +50,7 @@ pub const BIOMASS_BASE_OPINION: f64 = 0.55;
 pub const PUMPED_STORAGE_BASE_OPINION: f64 = 0.75;
 pub const TIDAL_BASE_OPINION: f64 = 0.80;
 pub const WAVE_BASE_OPINION: f64 = 0.85;
+pub const HYDROGEN_BASE_OPINION: f64 = 0.70;
 
 
 // Public Opinion Annual Changes
@@ -56,6 +64,7 @@ pub const BIOMASS_OPINION_CHANGE: f64 = 0.001;
 pub const PUMPED_STORAGE_OPINION_CHANGE: f64 = 0.002;
 pub const TIDAL_OPINION_CHANGE: f64 = 0.005;
 pub const WAVE_OPINION_CHANGE: f64 = 0.005;
+pub const HYDROGEN_OPINION_CHANGE: f64 = 0.004;
 
 
 
@@ -73,6 +82,7 @@ pub const REFERENCE_ANNUAL_EXPENDITURE: f64 = 1_384_000_000.0; // 10 billion eur
 // Operating Costs (per year)
 pub const ONSHORE_WIND_OPERATING_COST: f64 = 45_000.0;
 pub const OFFSHORE_WIND_OPERATING_COST: f64 = 65_000.0;
+pub const FLOATING_OFFSHORE_WIND_OPERATING_COST: f64 = 85_000.0;
 
 pub const DOMESTIC_SOLAR_OPERATING_COST: f64 = 200.0;
 pub const UTILITY_SOLAR_OPERATING_COST: f64 = 10_000.0;
@@ -87,6 +97,61 @@ pub const HYDRO_DAM_OPERATING_COST: f64 = 145_000.0;
 pub const PUMPED_STORAGE_OPERATING_COST: f64 = 93_000.0;
 pub const TIDAL_OPERATING_COST: f64 = 118_000.0;
 pub const WAVE_OPERATING_COST: f64 = 145_000.0;
+pub const HYDROGEN_ELECTROLYZER_OPERATING_COST: f64 = 80_000.0;
+pub const HYDROGEN_TURBINE_OPERATING_COST: f64 = 100_000.0;
+
+// Share of operating cost that is fuel purchase, the rest being O&M
+pub const COAL_FUEL_COST_SHARE: f64 = 0.6;
+pub const GAS_CC_FUEL_COST_SHARE: f64 = 0.7;
+pub const GAS_PEAKER_FUEL_COST_SHARE: f64 = 0.75;
+pub const BIOMASS_FUEL_COST_SHARE: f64 = 0.5;
+pub const NUCLEAR_FUEL_COST_SHARE: f64 = 0.2;
+
+// Land take per MW of capacity in km². Offshore and marine units use sea area and rooftop
+// solar uses existing buildings, so they count as zero; hydro includes its reservoir.
+pub const ONSHORE_WIND_LAND_INTENSITY: f64 = 0.005;
+pub const UTILITY_SOLAR_LAND_INTENSITY: f64 = 0.02;
+pub const NUCLEAR_LAND_INTENSITY: f64 = 0.0005;
+pub const COAL_LAND_INTENSITY: f64 = 0.001;
+pub const GAS_LAND_INTENSITY: f64 = 0.0005;
+pub const BIOMASS_LAND_INTENSITY: f64 = 0.002;
+pub const HYDRO_DAM_LAND_INTENSITY: f64 = 0.1;
+pub const PUMPED_STORAGE_LAND_INTENSITY: f64 = 0.005;
+pub const BATTERY_LAND_INTENSITY: f64 = 0.0002;
+pub const HYDROGEN_LAND_INTENSITY: f64 = 0.0005;
+
+// Embodied construction emissions in tonnes CO2 per MW of capacity, counted once in the year a
+// unit is commissioned. Deliberately modest lifecycle figures; scale with embodied_carbon_factor.
+pub const ONSHORE_WIND_EMBODIED_CO2: f64 = 400.0;
+pub const OFFSHORE_WIND_EMBODIED_CO2: f64 = 600.0;
+pub const FLOATING_OFFSHORE_WIND_EMBODIED_CO2: f64 = 800.0;
+pub const SOLAR_EMBODIED_CO2: f64 = 700.0;
+pub const NUCLEAR_EMBODIED_CO2: f64 = 1000.0;
+pub const COAL_EMBODIED_CO2: f64 = 800.0;
+pub const GAS_EMBODIED_CO2: f64 = 300.0;
+pub const BIOMASS_EMBODIED_CO2: f64 = 500.0;
+pub const HYDRO_DAM_EMBODIED_CO2: f64 = 1500.0;
+pub const PUMPED_STORAGE_EMBODIED_CO2: f64 = 1000.0;
+pub const BATTERY_EMBODIED_CO2: f64 = 250.0;
+pub const MARINE_EMBODIED_CO2: f64 = 800.0;
+pub const HYDROGEN_EMBODIED_CO2: f64 = 200.0;
+
+// Embodied emissions of carbon offsets in tonnes CO2 per tonne a year of removal capacity.
+// Credits are bought rather than built.
+pub const NATURAL_OFFSET_EMBODIED_CO2: f64 = 0.1;
+pub const ACTIVE_CAPTURE_EMBODIED_CO2: f64 = 1.0;
+pub const DEFAULT_EMBODIED_CARBON_FACTOR: f64 = 1.0;
+
+// Share of nameplate capacity a type can be counted on at peak demand. Weather-driven sources
+// are heavily derated and storage is limited by how long it can discharge.
+pub const NUCLEAR_CAPACITY_CREDIT: f64 = 0.9;
+pub const THERMAL_CAPACITY_CREDIT: f64 = 0.9;
+pub const HYDRO_DAM_CAPACITY_CREDIT: f64 = 0.8;
+pub const PUMPED_STORAGE_CAPACITY_CREDIT: f64 = 0.7;
+pub const BATTERY_CAPACITY_CREDIT: f64 = 0.5;
+pub const WIND_CAPACITY_CREDIT: f64 = 0.15;
+pub const SOLAR_CAPACITY_CREDIT: f64 = 0.1;
+pub const MARINE_CAPACITY_CREDIT: f64 = 0.2;
 
 // Urban Placement Factors
 pub const URBAN_SOLAR_BONUS: f64 = 1.1;        // 20% bonus for urban solar
@@ -100,6 +165,11 @@ pub const RIVER_BONUS: f64 = 1.10;             // 10% bonus for water-based gene
 pub const MAX_INTERMITTENT_PERCENTAGE: f64 = 0.40;  // Maximum 30% intermittent without storage
 pub const STORAGE_CAPACITY_FACTOR: f64 = 0.5;      // Each MW of storage allows 0.5 MW more intermittent
 
+// Hydrogen Storage Constants
+pub const HYDROGEN_ELECTROLYSIS_EFFICIENCY: f64 = 0.70;  // Share of surplus electricity kept as hydrogen
+pub const HYDROGEN_TURBINE_EFFICIENCY: f64 = 0.55;       // Share of stored hydrogen returned as electricity
+pub const HYDROGEN_STORAGE_HOURS: f64 = 4.0;             // Tank size as multiples of electrolyzer power
+
 // Marine and Battery Storage Power Outputs
 pub const MARINE_EFFICIENCY_GAIN: f64 = 0.93;      // 7% annual efficiency gain for marine tech
 pub const BATTERY_EFFICIENCY_GAIN: f64 = 0.95;     // 5% annual efficiency gain for batteries
@@ -113,6 +183,7 @@ pub const BATTERY_OPINION_CHANGE: f64 = 0.003;     // Annual change in battery o
 // Scoring constants
 pub const MAX_ACCEPTABLE_EMISSIONS: f64 = 1_000_000.0;  // 1 million tonnes CO2 (scaled to match new emission rates)
 pub const MAX_ACCEPTABLE_COST: f64 = 50_000_000_000.0;  // 50 billion euros
+pub const MIN_FEASIBLE_RELIABILITY: f64 = 1.0;         // Final-year demand must be fully met for a plan to count as feasible
 
 // Cost Opinion Constants
 pub const COST_OPINION_DECAY_FACTOR: f64 = 0.5; // Decay factor for high costs
@@ -120,6 +191,13 @@ pub const COST_OPINION_EXP_SCALE: f64 = -0.5;   // Exponential scale for cost op
 
 // Decommissioning Costs
 pub const DECOMMISSION_COST_RATIO: f64 = 0.12; // 25% of base cost for decommissioning
+pub const MIN_CLOSURE_AGE: u32 = 1; // Years in service before a generator can be closed early
+pub const NUCLEAR_DECOMMISSION_MULTIPLIER: f64 = 4.0; // Fuel removal and long-term waste storage
+pub const COAL_DECOMMISSION_MULTIPLIER: f64 = 1.5;    // Ash ponds and site remediation
+
+// Retrofit Costs (coal to biomass conversion keeps the site, turbine hall and grid connection)
+pub const COAL_TO_BIOMASS_RETROFIT_COST_RATIO: f64 = 0.3;  // Share of the plant's base cost spent on conversion
+pub const COAL_TO_BIOMASS_EFFICIENCY_FACTOR: f64 = 0.9;    // Biomass firing runs a little less efficiently than coal
 
 // CO2 Emission Rates (tonnes per MW per year)
 pub const COAL_CO2_RATE: f64 = 6_300.0;  // ~6,300 tonnes per MW per year (typical coal plant)
@@ -139,6 +217,7 @@ pub const COASTAL_THRESHOLD: f64 = 0.1; // Proportion of map width to consider c
 // Generator Size Constraints
 pub const ONSHORE_WIND_MIN_SIZE: f64 = 0.2;
 pub const OFFSHORE_WIND_MIN_SIZE: f64 = 0.5;
+pub const FLOATING_OFFSHORE_WIND_MIN_SIZE: f64 = 0.5;
 pub const DOMESTIC_SOLAR_MIN_SIZE: f64 = 0.001;
 pub const DOMESTIC_SOLAR_MAX_SIZE: f64 = 0.01;
 pub const COMMERCIAL_SOLAR_MIN_SIZE: f64 = 0.01;
@@ -159,10 +238,13 @@ pub const WAVE_MIN_SIZE: f64 = 0.05;
 pub const WAVE_MAX_SIZE: f64 = 0.3;
 pub const BATTERY_MIN_SIZE: f64 = 0.1;
 pub const BATTERY_MAX_SIZE: f64 = 0.5;
+pub const HYDROGEN_MIN_SIZE: f64 = 0.1;
+pub const HYDROGEN_MAX_SIZE: f64 = 0.6;
 
 // Power Output Reference Values (in MW)
 pub const MAX_ONSHORE_WIND_POWER: f64 = 500.0;
 pub const MAX_OFFSHORE_WIND_POWER: f64 = 800.0;
+pub const MAX_FLOATING_OFFSHORE_WIND_POWER: f64 = 800.0;
 
 pub const MAX_DOMESTIC_SOLAR_POWER: f64 = 10.0;
 pub const MAX_COMMERCIAL_SOLAR_POWER: f64 = 50.0;
@@ -177,7 +259,13 @@ pub const MAX_HYDRO_DAM_POWER: f64 = 1200.0;
 pub const MAX_PUMPED_STORAGE_POWER: f64 = 600.0;
 pub const MAX_TIDAL_POWER: f64 = 200.0;
 pub const MAX_WAVE_POWER: f64 = 100.0;
+
+// National resource caps on total installed capacity (MW); Ireland has little unexploited hydro
+pub const HYDRO_DAM_MAX_INSTALLED_CAPACITY: f64 = 2000.0;
+pub const TIDAL_MAX_INSTALLED_CAPACITY: f64 = 1000.0;
 pub const MAX_BATTERY_STORAGE_POWER: f64 = 500.0;
+pub const MAX_HYDROGEN_ELECTROLYZER_POWER: f64 = 300.0;
+pub const MAX_HYDROGEN_TURBINE_POWER: f64 = 400.0;
 
 pub const MAX_BIOMASS_POWER: f64 = 50.0;
 
@@ -187,7 +275,10 @@ pub const BIOMASS_OPERATING_COST: f64 = 120_000.0;
 pub const BATTERY_STORAGE_OPERATING_COST: f64 = 10_000_000.0; 
 
 pub const WIND_CAPACITY_FACTOR: f64 = 0.35;  // Average wind capacity factor
+pub const FLOATING_WIND_CAPACITY_FACTOR: f64 = 0.40;  // Floating turbines reach steadier deep-water wind
 pub const SOLAR_CAPACITY_FACTOR: f64 = 0.20;  // Average solar capacity factor
+pub const WEEKS_PER_YEAR: f64 = 52.0;
+pub const UPGRADE_DEGRADATION_RECOVERY: f64 = 0.5;  // Share of accumulated ageing an efficiency upgrade restores
 
 pub const NIGHT_START_HOUR: u8 = 6;        // Start of night period
 pub const DAY_END_HOUR: u8 = 18;           // End of day period
@@ -195,7 +286,6 @@ pub const DAY_END_HOUR: u8 = 18;           // End of day period
 pub const SOLAR_PEAK_HOUR: f64 = 12.0;     // Hour of peak solar output
 pub const SOLAR_WINDOW: f64 = 6.0;         // Hours from peak for solar operation
 
-pub const EFFICIENCY_UPGRADE_COST_FACTOR: f64 = 2.0;  // Multiplier for efficiency upgrade costs
 
 // Generator Operation Percentages
 pub const NUCLEAR_MIN_OPERATION: u8 = 60;  // Nuclear needs high base load
@@ -210,6 +300,7 @@ pub const DEFAULT_GENERATOR_SIZE: u32 = 100;
 
 // Generator Base Maximum Efficiencies
 pub const WIND_BASE_MAX_EFFICIENCY: f64 = 0.45;
+pub const FLOATING_WIND_BASE_MAX_EFFICIENCY: f64 = 0.48;
 pub const UTILITY_SOLAR_BASE_MAX_EFFICIENCY: f64 = 0.40;
 pub const NUCLEAR_BASE_MAX_EFFICIENCY: f64 = 0.50;
 pub const GAS_CC_BASE_MAX_EFFICIENCY: f64 = 0.60;
@@ -242,6 +333,12 @@ pub const ACTIVE_CAPTURE_POWER_PER_UNIT: f64 = 0.5; // 0.5 MW per unit of captur
 // Carbon Offset Maturity Curve
 pub const CARBON_OFFSET_MATURITY_FACTOR: f64 = -0.1; // Controls how quickly natural offsets mature
 
+// Carbon Offset Lifespans (years from commissioning)
+pub const FOREST_OFFSET_LIFESPAN: u32 = 30;        // Harvest/rotation cycle for managed forestry
+pub const WETLAND_OFFSET_LIFESPAN: u32 = 40;       // Restored wetlands need re-wetting works after this
+pub const ACTIVE_CAPTURE_OFFSET_LIFESPAN: u32 = 25; // Plant life before major rebuild
+pub const CARBON_CREDIT_OFFSET_LIFESPAN: u32 = 10; // Credit contracts are time-limited
+
 // Carbon Offset Base Costs
 pub const FOREST_BASE_COST: f64 = 1_000_000.0;
 pub const WETLAND_BASE_COST: f64 = 1_000_000.0;
@@ -254,6 +351,11 @@ pub const WETLAND_OPERATING_COST: f64 = 15_000.0;
 pub const ACTIVE_CAPTURE_OPERATING_COST: f64 = 100_000.0;
 pub const CARBON_CREDIT_OPERATING_COST: f64 = 5_000.0;
 
+// Managed EV charging (flexible load)
+pub const EV_CHARGING_DEMAND_SHARE: f64 = 0.1;             // Share of a settlement's demand that is EV charging
+pub const FLEXIBLE_LOAD_COST_PER_MW: f64 = 150_000.0;      // Smart chargers and aggregation per MW under control
+pub const DEFAULT_FLEXIBLE_LOAD_SHIFT_FRACTION: f64 = 0.5; // Share of managed charging that can move to another hour
+
 // Carbon Credit Price Constants
 pub const PRICE_BEFORE_PHASE1: f64 = 75.0;
 pub const PRICE_PHASE1_START: f64 = 75.0;
@@ -272,7 +374,9 @@ pub const GRID_SCALE_Y: f64 = 12500.0;
 
 // Energy Sales Constants
 pub const DEFAULT_ENERGY_SALES_RATE: f64 = 50_000.0;  // €50k per GWh
-pub const MW_TO_GWH_CONVERSION: f64 = 8.76;  // Convert MW (power) to GWh/year (energy), 8760 hours per year / 1000
+pub const HOURS_PER_YEAR: f64 = 8760.0;  // Converts a sustained MW figure into MWh/year
+pub const MWH_PER_GWH: f64 = 1000.0;
+pub const G_PER_KWH_PER_TONNE_PER_MWH: f64 = 1000.0;  // 1 tCO2/MWh is 1000 gCO2/kWh
 
 // Planning permission and construction time constants
 pub const ENABLE_CONSTRUCTION_DELAYS: bool = true;  // Default setting
@@ -334,3 +438,11 @@ pub const DEFAULT_COST_MULTIPLIER: u16 = 100;  // 100% = normal cost
 pub const FAST_COST_MULTIPLIER: u16 = 120;     // 120% = faster construction
 pub const VERY_FAST_COST_MULTIPLIER: u16 = 150; // 150% = very fast construction
 pub const RUSH_COST_MULTIPLIER: u16 = 200;     // 200% = rush construction
+
+// Deficit handling constants
+pub const DEFAULT_MAX_DEFICIT_ATTEMPTS: usize = 100;  // Sampled builds per deficit before emergency capacity is added
+pub const DEFAULT_FULL_RUN_PERCENTAGE: usize = 10;  // Final share of training iterations run as full simulations
+
+// Grid zone constants
+pub const DEFAULT_GRID_ZONE: &str = "national";   // Zone for anything outside every configured zone
+pub const ZONE_TRANSFER_EPSILON: f64 = 1e-9;      // Residual capacity treated as saturated (MW)
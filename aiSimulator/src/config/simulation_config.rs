@@ -1,6 +1,20 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 use serde::{Deserialize, Serialize};
-use crate::models::generator::GeneratorType;
+use crate::models::generator::{Generator, GeneratorType};
 use crate::models::carbon_offset::CarbonOffsetType;
+use crate::ai::metrics::scoring::ScoreWeights;
+use crate::ai::learning::weights::ExplorationSchedule;
+use crate::ai::learning::constants::NOOP_MAX_WEIGHT_SHARE;
+use crate::config::constants::{
+    BASE_YEAR, DEFAULT_EMBODIED_CARBON_FACTOR, DEFAULT_FULL_RUN_PERCENTAGE, DEFAULT_MAX_DEFICIT_ATTEMPTS, INFLATION_RATE,
+    HYDRO_DAM_MAX_INSTALLED_CAPACITY, TIDAL_MAX_INSTALLED_CAPACITY, DEFAULT_FLEXIBLE_LOAD_SHIFT_FRACTION,
+};
+use crate::error::SimulationError;
+use crate::data::generators_loader::GeneratorColumnMapping;
+use crate::data::poi::{Coordinate, MapExtent};
+use crate::config::const_funcs::is_point_inside_polygon;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeneratorConstraints {
@@ -9,6 +23,10 @@ pub struct GeneratorConstraints {
     pub upgrade_cost_multiplier: f64,            // Cost multiplier for efficiency upgrades
     pub min_operation_percentage: f64,           // Minimum operating capacity (0.0-1.0)
     pub closure_cost_multiplier: f64,            // Cost multiplier for early closure
+    #[serde(default)]
+    pub ramp_limits: HashMap<GeneratorType, u8>,          // Max operation change per year (percentage points)
+    #[serde(default)]
+    pub min_stable_operation: HashMap<GeneratorType, u8>, // Lowest operation percentage a type can run at
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,7 +38,287 @@ pub struct CarbonOffsetConstraints {
     pub max_carbon_credits: f64,   // Maximum carbon credits in tonnes
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AvailabilityDistribution {
+    Normal,   // Gaussian around 1.0 with the configured standard deviation
+    Uniform,  // Flat band around 1.0 with the same standard deviation
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenewableUncertainty {
+    pub draws: usize,                              // Monte Carlo draws over the best plan (0 disables)
+    pub distribution: AvailabilityDistribution,
+    pub wind_std_dev: f64,                         // Std dev of the yearly wind availability factor
+    pub solar_std_dev: f64,                        // Std dev of the yearly solar availability factor
+    pub marine_std_dev: f64,                       // Std dev of the yearly tidal/wave availability factor
+    #[serde(default)]
+    pub correlation: f64,                          // Pairwise correlation of wind, solar and marine draws (0-1)
+}
+
+impl Default for RenewableUncertainty {
+    fn default() -> Self {
+        Self {
+            draws: 0,
+            distribution: AvailabilityDistribution::Normal,
+            wind_std_dev: 0.15,
+            solar_std_dev: 0.10,
+            marine_std_dev: 0.10,
+            correlation: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridZone {
+    pub name: String,
+    pub min_x: f64,   // Bounding box claiming generators and settlements without an explicit zone
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneLink {
+    pub from: String,
+    pub to: String,
+    pub capacity: f64,  // Maximum transfer in either direction (MW)
+}
+
+// Committed project (announced plant, planned interconnector) added to the fleet at the start
+// of `year` in every simulation path, before any AI action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledBuild {
+    pub year: u32,
+    pub generator: Generator,
+}
+
+// Protected area no generator may be sited in (national parks, SACs), in grid metres
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "shape")]
+pub enum ExclusionZone {
+    Circle { name: String, center: Coordinate, radius: f64 },
+    Polygon { name: String, vertices: Vec<Coordinate> },
+}
+
+impl ExclusionZone {
+    pub fn name(&self) -> &str {
+        match self {
+            ExclusionZone::Circle { name, .. } | ExclusionZone::Polygon { name, .. } => name,
+        }
+    }
+
+    pub fn contains(&self, coordinate: &Coordinate) -> bool {
+        match self {
+            ExclusionZone::Circle { center, radius, .. } => center.distance_to(coordinate) <= *radius,
+            ExclusionZone::Polygon { vertices, .. } => {
+                !vertices.is_empty() && is_point_inside_polygon(coordinate, vertices)
+            }
+        }
+    }
+}
+
+// Empty by default, which keeps the whole island on a single copper plate
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GridZones {
+    pub zones: Vec<GridZone>,
+    pub links: Vec<ZoneLink>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpinionDynamics {
+    pub macro_weight: f64,           // Blend of the trend-driven term into average opinion (0 = siting only)
+    pub emissions_sensitivity: f64,  // Opinion gain per unit of fractional year-over-year emissions reduction
+    pub cost_sensitivity: f64,       // Opinion loss per unit of yearly spend above the reference budget
+}
+
+impl Default for OpinionDynamics {
+    fn default() -> Self {
+        Self {
+            macro_weight: 0.0,
+            emissions_sensitivity: 0.5,
+            cost_sensitivity: 0.2,
+        }
+    }
+}
+
+// How generators are weighted when averaging siting opinion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OpinionWeighting {
+    #[default]
+    Count,     // Every site counts equally
+    Capacity,  // Sites count in proportion to their rated output, so large plants dominate
+}
+
+// Extra demand from electrifying transport and heat, layered onto per-capita usage.
+// Adoption curves are Year -> share of the population (0-1), interpolated between points.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Electrification {
+    pub ev_adoption: Vec<(u32, f64)>,
+    pub heat_pump_adoption: Vec<(u32, f64)>,
+    pub ev_usage_per_adopter: f64,         // Average added load per person driving an EV (MW)
+    pub heat_pump_usage_per_adopter: f64,  // Average added load per person heated by a heat pump (MW)
+}
+
+impl Default for Electrification {
+    fn default() -> Self {
+        Self {
+            ev_adoption: Vec::new(),
+            heat_pump_adoption: Vec::new(),
+            ev_usage_per_adopter: 0.0003,        // ~2,600 kWh a year
+            heat_pump_usage_per_adopter: 0.0004, // ~3,500 kWh a year
+        }
+    }
+}
+
+// Annual price growth applied to every cost projection. Overrides replace the base rate for
+// single years (e.g. an energy price shock), each applying to the step from the previous year.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InflationModel {
+    pub base_rate: f64,
+    #[serde(default)]
+    pub rate_overrides: Vec<(u32, f64)>,  // Year -> rate used instead of base_rate for that year
+}
+
+impl InflationModel {
+    pub const fn new(base_rate: f64) -> Self {
+        Self { base_rate, rate_overrides: Vec::new() }
+    }
+
+    // Cumulative price level in `year` relative to BASE_YEAR
+    pub fn factor(&self, year: u32) -> f64 {
+        let years_from_base = year as i32 - BASE_YEAR as i32;
+        if self.rate_overrides.is_empty() || years_from_base <= 0 {
+            return (1.0 + self.base_rate).powi(years_from_base);
+        }
+
+        let mut overridden_years = 0;
+        let mut override_growth = 1.0;
+        for (override_year, rate) in &self.rate_overrides {
+            if *override_year > BASE_YEAR && *override_year <= year {
+                overridden_years += 1;
+                override_growth *= 1.0 + rate;
+            }
+        }
+        (1.0 + self.base_rate).powi(years_from_base - overridden_years) * override_growth
+    }
+}
+
+impl Default for InflationModel {
+    fn default() -> Self {
+        Self::new(INFLATION_RATE)
+    }
+}
+
+// Which result files a run writes; `None` writes nothing at all, checkpoints included
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OutputFormat {
+    Csv,
+    Json,
+    #[default]
+    Both,
+    None,
+}
+
+impl OutputFormat {
+    pub fn writes_csv(&self) -> bool {
+        matches!(self, OutputFormat::Csv | OutputFormat::Both)
+    }
+
+    pub fn writes_json(&self) -> bool {
+        matches!(self, OutputFormat::Json | OutputFormat::Both)
+    }
+
+    pub fn writes_files(&self) -> bool {
+        *self != OutputFormat::None
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "csv" => Ok(OutputFormat::Csv),
+            "json" => Ok(OutputFormat::Json),
+            "both" => Ok(OutputFormat::Both),
+            "none" => Ok(OutputFormat::None),
+            other => Err(format!("Unknown output format '{}' (expected csv, json, both or none)", other)),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OutputFormat::Csv => write!(f, "csv"),
+            OutputFormat::Json => write!(f, "json"),
+            OutputFormat::Both => write!(f, "both"),
+            OutputFormat::None => write!(f, "none"),
+        }
+    }
+}
+
+// Which training iterations give up cached-location fast runs for a full simulation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FullRunStrategy {
+    LastNPercent(usize), // The final N% of iterations
+    EveryNth(usize),     // Iterations N, 2N, 3N, ... counted from 1
+    Never,
+    Always,
+}
+
+impl Default for FullRunStrategy {
+    fn default() -> Self {
+        FullRunStrategy::LastNPercent(DEFAULT_FULL_RUN_PERCENTAGE)
+    }
+}
+
+impl FullRunStrategy {
+    // `iteration` is the zero-based iteration index and `completed` how many have finished so far
+    pub fn is_full_run(&self, iteration: usize, completed: usize, num_iterations: usize) -> bool {
+        match *self {
+            FullRunStrategy::LastNPercent(percent) => {
+                let full_run_count = (num_iterations * percent) / 100;
+                completed >= num_iterations.saturating_sub(full_run_count)
+            }
+            FullRunStrategy::EveryNth(n) => n > 0 && (iteration + 1).is_multiple_of(n),
+            FullRunStrategy::Never => false,
+            FullRunStrategy::Always => true,
+        }
+    }
+}
+
+impl FromStr for FullRunStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        let count = |value: &str| value.trim().parse::<usize>()
+            .map_err(|_| format!("Invalid full run count '{}'", value));
+        match s.split_once(':') {
+            Some(("last", percent)) => Ok(FullRunStrategy::LastNPercent(count(percent)?)),
+            Some(("every", n)) => Ok(FullRunStrategy::EveryNth(count(n)?)),
+            None if s == "never" => Ok(FullRunStrategy::Never),
+            None if s == "always" => Ok(FullRunStrategy::Always),
+            _ => Err(format!("Unknown full run strategy '{}' (expected last:N, every:N, never or always)", s)),
+        }
+    }
+}
+
+impl fmt::Display for FullRunStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FullRunStrategy::LastNPercent(percent) => write!(f, "last:{}", percent),
+            FullRunStrategy::EveryNth(n) => write!(f, "every:{}", n),
+            FullRunStrategy::Never => write!(f, "never"),
+            FullRunStrategy::Always => write!(f, "always"),
+        }
+    }
+}
+
+// Missing fields in a config file fall back to `SimulationConfig::default()`
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SimulationConfig {
     pub target_net_zero_2050: bool,
     pub allow_generator_upgrades: bool,
@@ -28,6 +326,118 @@ pub struct SimulationConfig {
     pub allow_operation_adjustment: bool,
     pub generator_constraints: GeneratorConstraints,
     pub offset_constraints: CarbonOffsetConstraints,
+    #[serde(default)]
+    pub renewable_uncertainty: RenewableUncertainty,
+    #[serde(default)]
+    pub cost_cap: Option<f64>,                     // Cumulative spend cap in euros for the "budget_capped" mode
+    #[serde(default)]
+    pub carbon_budget: Option<f64>,                // Cumulative net emissions allowed over the whole run in tonnes
+    #[serde(default)]
+    pub net_zero_tolerance: f64,                   // Final-year net emissions in tonnes still counted as net zero
+    #[serde(default)]
+    pub net_zero_real_reductions_only: bool,       // Don't count purchased carbon credits towards net zero
+    #[serde(default)]
+    pub annual_capex_limit: Option<f64>,           // Capital spend allowed per year in euros before sampling stops
+    #[serde(default)]
+    pub score_weights: Option<ScoreWeights>,       // Objective weights for the "weighted" mode, defaults to ScoreWeights::DEFAULT
+    #[serde(default)]
+    pub opinion_dynamics: OpinionDynamics,
+    #[serde(default)]
+    pub opinion_weighting: OpinionWeighting,
+    #[serde(default)]
+    pub exploration_schedule: ExplorationSchedule,
+    pub noop_max_share: f64,                       // Ceiling on DoNothing's share of a year's action weight, 1.0 disables
+    #[serde(default)]
+    pub generator_columns: GeneratorColumnMapping, // CSV headers read for each field of the existing-generator import
+    #[serde(default)]
+    pub grid_zones: GridZones,
+    #[serde(default)]
+    pub carbon_price_trajectory: Vec<(u32, f64)>,  // Year -> carbon tax (€/tCO₂) on positive net emissions, sorted by year
+    #[serde(default)]
+    pub renewable_mandate: Vec<(u32, f64)>,        // Year -> minimum renewable energy share for "renewable_mandate" scoring, sorted by year
+    pub embodied_carbon_factor: f64,               // Scales construction emissions charged at commissioning, 0 ignores them
+    #[serde(default)]
+    pub inflation: InflationModel,                 // Price growth behind every cost projection, 1.85% a year by default
+    #[serde(default)]
+    pub deployment_prior: Option<HashMap<GeneratorType, f64>>,  // Historical build share per type, seeds fresh weights only
+    #[serde(default)]
+    pub no_new_after_year: HashMap<GeneratorType, u32>,    // Last year each type may be built (e.g. no new coal after 2030)
+    #[serde(default)]
+    pub force_retire_by_year: HashMap<GeneratorType, u32>, // Year by which every unit of a type must be closed
+    #[serde(default)]
+    pub max_builds_per_year: HashMap<GeneratorType, usize>, // New units of a type allowed per year (supply chain, permitting)
+    #[serde(default)]
+    pub max_installed_capacity: HashMap<GeneratorType, f64>, // National resource cap on a type's total installed MW
+    pub flexible_load_shift_fraction: f64, // Share of managed EV charging that can move from deficit to surplus hours
+    #[serde(default)]
+    pub type_opinion_modifiers: HashMap<GeneratorType, f64>, // Added to a type's baseline opinion wherever it is sited
+    #[serde(default)]
+    pub electrification: Electrification,          // EV and heat-pump demand growth, none by default
+    #[serde(default)]
+    pub map_extent: MapExtent,                     // Size of the modelled region, Ireland's grid by default
+    #[serde(default)]
+    pub exclusion_zones: Vec<ExclusionZone>,       // Areas generators are never sited in
+    #[serde(default)]
+    pub scheduled_builds: Vec<ScheduledBuild>,     // Committed projects added in their year regardless of the AI
+    #[serde(default)]
+    pub energy_price_curve: Vec<(f64, f64)>,       // Surplus/demand ratio -> multiplier on the sales rate (may go negative), sorted by ratio
+    #[serde(default)]
+    pub capacity_payment_rate: Option<f64>,        // Yearly € per MW of firm capacity paid to generators, None disables
+    #[serde(default)]
+    pub max_runtime_secs: Option<u64>,             // Wall-clock budget after which no new training iterations start
+    pub keep_top_results: usize,                   // Best distinct plans exported to top_results/, 1 keeps only the best
+    #[serde(default)]
+    pub trace_merges: bool,                        // Write merge_trace.csv with the iteration/thread order of weight merges
+    #[serde(default)]
+    pub prune_weight_threshold: Option<f64>,       // Drop actions weighted below this at each checkpoint to keep them compact
+    #[serde(default)]
+    pub parallel_deficit_candidates: usize,        // AddGenerator candidates scored side by side per deficit step (0 or 1 = sequential)
+    pub max_deficit_attempts: usize,               // Sampled builds per deficit before falling back to emergency capacity
+    #[serde(default)]
+    pub output_format: OutputFormat,               // Result files written for the best run
+    #[serde(default)]
+    pub full_run_strategy: FullRunStrategy,        // Which iterations run the full simulation instead of cached locations
+    pub replay_best_in_full_runs: bool,            // Full runs replay the best plan found so far instead of sampling
+}
+
+impl SimulationConfig {
+    pub fn builder() -> SimulationConfigBuilder {
+        SimulationConfigBuilder::default()
+    }
+
+    pub fn load_from_file(path: &str) -> Result<Self, SimulationError> {
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| SimulationError::Config(format!("Invalid config file {}: {}", path, e)))
+    }
+
+    pub fn save_to_file(&self, path: &str) -> Result<(), SimulationError> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    // A type that must already be retired can't be built either, or it would run until next year's closures
+    pub fn is_build_banned(&self, generator_type: &GeneratorType, year: u32) -> bool {
+        self.no_new_after_year.get(generator_type).is_some_and(|last_year| year > *last_year)
+            || self.must_retire(generator_type, year)
+    }
+
+    pub fn banned_build_types(&self, year: u32) -> Vec<GeneratorType> {
+        self.no_new_after_year.keys()
+            .chain(self.force_retire_by_year.keys().filter(|generator_type| !self.no_new_after_year.contains_key(*generator_type)))
+            .filter(|generator_type| self.is_build_banned(generator_type, year))
+            .cloned()
+            .collect()
+    }
+
+    pub fn type_opinion_modifier(&self, generator_type: &GeneratorType) -> f64 {
+        self.type_opinion_modifiers.get(generator_type).copied().unwrap_or(0.0)
+    }
+
+    pub fn must_retire(&self, generator_type: &GeneratorType, year: u32) -> bool {
+        self.force_retire_by_year.get(generator_type).is_some_and(|retire_year| year >= *retire_year)
+    }
 }
 
 impl Default for SimulationConfig {
@@ -41,6 +451,7 @@ impl Default for SimulationConfig {
                 allowed_types: vec![
                     GeneratorType::OnshoreWind,
                     GeneratorType::OffshoreWind,
+                    GeneratorType::FloatingOffshoreWind,
                     GeneratorType::DomesticSolar,
                     GeneratorType::CommercialSolar,
                     GeneratorType::UtilitySolar,
@@ -62,6 +473,19 @@ impl Default for SimulationConfig {
                 upgrade_cost_multiplier: 2.0,
                 min_operation_percentage: 0.2,
                 closure_cost_multiplier: 0.5,
+                ramp_limits: HashMap::from([
+                    (GeneratorType::Nuclear, 10),
+                    (GeneratorType::CoalPlant, 30),
+                    (GeneratorType::Biomass, 30),
+                    (GeneratorType::GasCombinedCycle, 50),
+                ]),
+                min_stable_operation: HashMap::from([
+                    (GeneratorType::Nuclear, 60),
+                    (GeneratorType::CoalPlant, 40),
+                    (GeneratorType::Biomass, 40),
+                    (GeneratorType::GasCombinedCycle, 35),
+                    (GeneratorType::GasPeaker, 20),
+                ]),
             },
             offset_constraints: CarbonOffsetConstraints {
                 allowed_types: vec![
@@ -75,6 +499,365 @@ impl Default for SimulationConfig {
                 max_active_capture: 1000.0,    // 1,000 tonnes
                 max_carbon_credits: 5000.0,    // 5,000 tonnes
             },
+            renewable_uncertainty: RenewableUncertainty::default(),
+            cost_cap: None,
+            carbon_budget: None,
+            net_zero_tolerance: 0.0,
+            net_zero_real_reductions_only: false,
+            annual_capex_limit: None,
+            score_weights: None,
+            opinion_dynamics: OpinionDynamics::default(),
+            opinion_weighting: OpinionWeighting::default(),
+            exploration_schedule: ExplorationSchedule::default(),
+            noop_max_share: NOOP_MAX_WEIGHT_SHARE,
+            generator_columns: GeneratorColumnMapping::default(),
+            grid_zones: GridZones::default(),
+            carbon_price_trajectory: Vec::new(),  // No carbon tax unless configured
+            renewable_mandate: Vec::new(),
+            embodied_carbon_factor: DEFAULT_EMBODIED_CARBON_FACTOR,
+            inflation: InflationModel::default(),
+            deployment_prior: None,
+            no_new_after_year: HashMap::new(),
+            force_retire_by_year: HashMap::new(),
+            max_builds_per_year: HashMap::new(),
+            max_installed_capacity: HashMap::from([
+                (GeneratorType::HydroDam, HYDRO_DAM_MAX_INSTALLED_CAPACITY),
+                (GeneratorType::TidalGenerator, TIDAL_MAX_INSTALLED_CAPACITY),
+            ]),
+            flexible_load_shift_fraction: DEFAULT_FLEXIBLE_LOAD_SHIFT_FRACTION,
+            type_opinion_modifiers: HashMap::new(),
+            electrification: Electrification::default(),
+            map_extent: MapExtent::default(),
+            exclusion_zones: Vec::new(),
+            scheduled_builds: Vec::new(),
+            energy_price_curve: Vec::new(),  // Flat sales rate unless configured
+            capacity_payment_rate: None,
+            max_runtime_secs: None,
+            keep_top_results: 1,
+            trace_merges: false,
+            prune_weight_threshold: None,
+            parallel_deficit_candidates: 0,
+            max_deficit_attempts: DEFAULT_MAX_DEFICIT_ATTEMPTS,
+            output_format: OutputFormat::default(),
+            full_run_strategy: FullRunStrategy::default(),
+            replay_best_in_full_runs: true,
         }
     }
-} 
\ No newline at end of file
+} 
+
+// Fluent construction starting from the defaults, so unset fields match `SimulationConfig::default()`
+#[derive(Debug, Clone, Default)]
+pub struct SimulationConfigBuilder {
+    config: SimulationConfig,
+}
+
+impl SimulationConfigBuilder {
+    pub fn target_net_zero_2050(mut self, enabled: bool) -> Self {
+        self.config.target_net_zero_2050 = enabled;
+        self
+    }
+
+    pub fn allow_generator_upgrades(mut self, allowed: bool) -> Self {
+        self.config.allow_generator_upgrades = allowed;
+        self
+    }
+
+    pub fn allow_generator_closure(mut self, allowed: bool) -> Self {
+        self.config.allow_generator_closure = allowed;
+        self
+    }
+
+    pub fn allow_operation_adjustment(mut self, allowed: bool) -> Self {
+        self.config.allow_operation_adjustment = allowed;
+        self
+    }
+
+    pub fn generator_constraints(mut self, constraints: GeneratorConstraints) -> Self {
+        self.config.generator_constraints = constraints;
+        self
+    }
+
+    pub fn upgrade_cost_multiplier(mut self, multiplier: f64) -> Self {
+        self.config.generator_constraints.upgrade_cost_multiplier = multiplier;
+        self
+    }
+
+    pub fn offset_constraints(mut self, constraints: CarbonOffsetConstraints) -> Self {
+        self.config.offset_constraints = constraints;
+        self
+    }
+
+    pub fn renewable_uncertainty(mut self, uncertainty: RenewableUncertainty) -> Self {
+        self.config.renewable_uncertainty = uncertainty;
+        self
+    }
+
+    pub fn uncertainty_draws(mut self, draws: usize) -> Self {
+        self.config.renewable_uncertainty.draws = draws;
+        self
+    }
+
+    pub fn renewable_correlation(mut self, correlation: f64) -> Self {
+        self.config.renewable_uncertainty.correlation = correlation.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn cost_cap(mut self, cap: Option<f64>) -> Self {
+        self.config.cost_cap = cap;
+        self
+    }
+
+    pub fn carbon_budget(mut self, budget: Option<f64>) -> Self {
+        self.config.carbon_budget = budget;
+        self
+    }
+
+    pub fn net_zero_tolerance(mut self, tolerance: f64) -> Self {
+        self.config.net_zero_tolerance = tolerance;
+        self
+    }
+
+    pub fn net_zero_real_reductions_only(mut self, enabled: bool) -> Self {
+        self.config.net_zero_real_reductions_only = enabled;
+        self
+    }
+
+    pub fn annual_capex_limit(mut self, limit: Option<f64>) -> Self {
+        self.config.annual_capex_limit = limit;
+        self
+    }
+
+    pub fn score_weights(mut self, weights: Option<ScoreWeights>) -> Self {
+        self.config.score_weights = weights;
+        self
+    }
+
+    pub fn opinion_dynamics(mut self, dynamics: OpinionDynamics) -> Self {
+        self.config.opinion_dynamics = dynamics;
+        self
+    }
+
+    pub fn opinion_weighting(mut self, weighting: OpinionWeighting) -> Self {
+        self.config.opinion_weighting = weighting;
+        self
+    }
+
+    pub fn exploration_schedule(mut self, schedule: ExplorationSchedule) -> Self {
+        self.config.exploration_schedule = schedule;
+        self
+    }
+
+    pub fn noop_max_share(mut self, share: f64) -> Self {
+        self.config.noop_max_share = share;
+        self
+    }
+
+    pub fn generator_columns(mut self, columns: GeneratorColumnMapping) -> Self {
+        self.config.generator_columns = columns;
+        self
+    }
+
+    pub fn grid_zones(mut self, zones: GridZones) -> Self {
+        self.config.grid_zones = zones;
+        self
+    }
+
+    pub fn carbon_price_trajectory(mut self, trajectory: Vec<(u32, f64)>) -> Self {
+        self.config.carbon_price_trajectory = trajectory;
+        self.config.carbon_price_trajectory.sort_by_key(|(year, _)| *year);
+        self
+    }
+
+    pub fn renewable_mandate(mut self, trajectory: Vec<(u32, f64)>) -> Self {
+        self.config.renewable_mandate = trajectory;
+        self.config.renewable_mandate.sort_by_key(|(year, _)| *year);
+        self
+    }
+
+    pub fn embodied_carbon_factor(mut self, factor: f64) -> Self {
+        self.config.embodied_carbon_factor = factor;
+        self
+    }
+
+    pub fn inflation_rate(mut self, rate: f64) -> Self {
+        self.config.inflation.base_rate = rate;
+        self
+    }
+
+    pub fn inflation_overrides(mut self, overrides: Vec<(u32, f64)>) -> Self {
+        self.config.inflation.rate_overrides = overrides;
+        self.config.inflation.rate_overrides.sort_by_key(|(year, _)| *year);
+        self
+    }
+
+    pub fn deployment_prior(mut self, prior: Option<HashMap<GeneratorType, f64>>) -> Self {
+        self.config.deployment_prior = prior;
+        self
+    }
+
+    pub fn no_new_after_year(mut self, generator_type: GeneratorType, year: u32) -> Self {
+        self.config.no_new_after_year.insert(generator_type, year);
+        self
+    }
+
+    pub fn force_retire_by_year(mut self, generator_type: GeneratorType, year: u32) -> Self {
+        self.config.force_retire_by_year.insert(generator_type, year);
+        self
+    }
+
+    pub fn max_builds_per_year(mut self, generator_type: GeneratorType, cap: usize) -> Self {
+        self.config.max_builds_per_year.insert(generator_type, cap);
+        self
+    }
+
+    pub fn max_installed_capacity(mut self, generator_type: GeneratorType, capacity: f64) -> Self {
+        self.config.max_installed_capacity.insert(generator_type, capacity);
+        self
+    }
+
+    pub fn flexible_load_shift_fraction(mut self, fraction: f64) -> Self {
+        self.config.flexible_load_shift_fraction = fraction;
+        self
+    }
+
+    pub fn type_opinion_modifier(mut self, generator_type: GeneratorType, modifier: f64) -> Self {
+        self.config.type_opinion_modifiers.insert(generator_type, modifier);
+        self
+    }
+
+    pub fn electrification(mut self, electrification: Electrification) -> Self {
+        self.config.electrification = electrification;
+        self.config.electrification.ev_adoption.sort_by_key(|(year, _)| *year);
+        self.config.electrification.heat_pump_adoption.sort_by_key(|(year, _)| *year);
+        self
+    }
+
+    pub fn map_extent(mut self, max_x: f64, max_y: f64) -> Self {
+        self.config.map_extent = MapExtent { max_x, max_y };
+        self
+    }
+
+    pub fn exclusion_zone(mut self, zone: ExclusionZone) -> Self {
+        self.config.exclusion_zones.push(zone);
+        self
+    }
+
+    pub fn scheduled_build(mut self, year: u32, generator: Generator) -> Self {
+        self.config.scheduled_builds.push(ScheduledBuild { year, generator });
+        self
+    }
+
+    pub fn energy_price_curve(mut self, curve: Vec<(f64, f64)>) -> Self {
+        self.config.energy_price_curve = curve;
+        self.config.energy_price_curve.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        self
+    }
+
+    pub fn capacity_payment_rate(mut self, rate: Option<f64>) -> Self {
+        self.config.capacity_payment_rate = rate;
+        self
+    }
+
+    pub fn output_format(mut self, format: OutputFormat) -> Self {
+        self.config.output_format = format;
+        self
+    }
+
+    pub fn full_run_strategy(mut self, strategy: FullRunStrategy) -> Self {
+        self.config.full_run_strategy = strategy;
+        self
+    }
+
+    pub fn replay_best_in_full_runs(mut self, replay: bool) -> Self {
+        self.config.replay_best_in_full_runs = replay;
+        self
+    }
+
+    pub fn max_runtime_secs(mut self, secs: Option<u64>) -> Self {
+        self.config.max_runtime_secs = secs;
+        self
+    }
+
+    pub fn keep_top_results(mut self, count: usize) -> Self {
+        self.config.keep_top_results = count;
+        self
+    }
+
+    pub fn trace_merges(mut self, enabled: bool) -> Self {
+        self.config.trace_merges = enabled;
+        self
+    }
+
+    pub fn prune_weight_threshold(mut self, threshold: Option<f64>) -> Self {
+        self.config.prune_weight_threshold = threshold;
+        self
+    }
+
+    pub fn parallel_deficit_candidates(mut self, candidates: usize) -> Self {
+        self.config.parallel_deficit_candidates = candidates;
+        self
+    }
+
+    pub fn max_deficit_attempts(mut self, attempts: usize) -> Self {
+        self.config.max_deficit_attempts = attempts;
+        self
+    }
+
+    pub fn build(self) -> SimulationConfig {
+        self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::constants::END_YEAR;
+
+    // The config holds nested structs without PartialEq, so compare through their JSON form
+    fn as_json(config: &SimulationConfig) -> serde_json::Value {
+        serde_json::to_value(config).unwrap()
+    }
+
+    #[test]
+    fn config_survives_a_serde_round_trip_and_the_builder_matches_the_default() {
+        let config = SimulationConfig::builder()
+            .target_net_zero_2050(false)
+            .carbon_price_trajectory(vec![(2025, 50.0), (2050, 250.0)])
+            .no_new_after_year(GeneratorType::CoalPlant, 2030)
+            .build();
+        let round_tripped: SimulationConfig = serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!(as_json(&round_tripped), as_json(&config));
+
+        assert_eq!(as_json(&SimulationConfig::builder().build()), as_json(&SimulationConfig::default()));
+    }
+
+    #[test]
+    fn zero_inflation_keeps_the_price_level_flat() {
+        let flat = SimulationConfig::builder().inflation_rate(0.0).build().inflation;
+        for year in BASE_YEAR..=END_YEAR {
+            assert_eq!(flat.factor(year), 1.0, "price level in {}", year);
+        }
+
+        // The default still compounds, and a one-year shock only lifts the level from its year on
+        let default = SimulationConfig::default().inflation;
+        assert!((default.factor(BASE_YEAR + 10) - (1.0 + INFLATION_RATE).powi(10)).abs() < 1e-12);
+        let shocked = SimulationConfig::builder().inflation_rate(0.0).inflation_overrides(vec![(2030, 0.1)]).build().inflation;
+        assert_eq!(shocked.factor(2029), 1.0);
+        assert!((shocked.factor(2030) - 1.1).abs() < 1e-12);
+        assert!((shocked.factor(END_YEAR) - 1.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn every_fifth_strategy_marks_exactly_every_fifth_iteration() {
+        let strategy: FullRunStrategy = "every:5".parse().unwrap();
+        assert_eq!(strategy, FullRunStrategy::EveryNth(5));
+
+        let num_iterations = 23;
+        let full_runs: Vec<usize> = (0..num_iterations)
+            .filter(|&iteration| strategy.is_full_run(iteration, iteration, num_iterations))
+            .collect();
+        // Counted from 1, so the 5th, 10th, ... iterations
+        assert_eq!(full_runs, vec![4, 9, 14, 19]);
+        assert!(!FullRunStrategy::EveryNth(0).is_full_run(0, 0, num_iterations));
+    }
+}
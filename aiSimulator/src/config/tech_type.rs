@@ -53,13 +53,15 @@ impl BuildSpeed {
 pub fn map_to_tech_type(gen_type: &GeneratorType) -> TechType {
     match gen_type {
         GeneratorType::OnshoreWind => TechType::OnshoreWind,
-        GeneratorType::OffshoreWind => TechType::OffshoreWind,
+        GeneratorType::OffshoreWind | GeneratorType::FloatingOffshoreWind => TechType::OffshoreWind,
         GeneratorType::DomesticSolar | GeneratorType::CommercialSolar | GeneratorType::UtilitySolar => TechType::SolarPV,
         GeneratorType::GasCombinedCycle | GeneratorType::GasPeaker => TechType::Gas,
         GeneratorType::CoalPlant => TechType::Coal,
         GeneratorType::Nuclear => TechType::Nuclear,
         GeneratorType::HydroDam => TechType::Hydro,
-        GeneratorType::PumpedStorage | GeneratorType::BatteryStorage => TechType::Storage,
+        GeneratorType::PumpedStorage | GeneratorType::BatteryStorage |
+        GeneratorType::HydrogenElectrolyzer => TechType::Storage,
+        GeneratorType::HydrogenTurbine => TechType::Gas,
         GeneratorType::Biomass => TechType::Biomass,
         GeneratorType::TidalGenerator => TechType::Tidal,
         GeneratorType::WaveEnergy => TechType::Wave,
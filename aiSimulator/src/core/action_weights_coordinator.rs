@@ -11,6 +11,7 @@ pub use crate::ai::ActionResult;
 pub use crate::ai::ActionWeights;
 pub use crate::ai::score_metrics;
 pub use crate::ai::evaluate_action_impact;
+pub use crate::ai::ScoringContext;
 
 // Also re-export internal components that might be used directly
 pub use crate::ai::learning::serialization::SerializableWeights;
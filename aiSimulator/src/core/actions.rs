@@ -1,17 +1,24 @@
-use std::error::Error;
+use crate::error::SimulationError;
 use rand::Rng;
+use rand::rngs::StdRng;
 use crate::utils::map_handler::Map;
 use crate::models::generator::{Generator, GeneratorType};
 use super::action_weights::GridAction;
 use crate::models::carbon_offset::{CarbonOffset, CarbonOffsetType};
-use crate::data::poi::Coordinate;
+use crate::models::flexible_load::FlexibleLoad;
+use crate::data::poi::{Coordinate, POI};
+use crate::analysis::location_analysis::resource_quality;
+use crate::ai::learning::constants::{DEFER_MIN_COST_DECLINE, DEFER_REWARD_SCALE};
 use crate::config::constants::{
     DEFAULT_GENERATOR_SIZE,
+    DEFAULT_COST_MULTIPLIER,
+    MIN_CLOSURE_AGE,
     COAL_CO2_RATE,
     GAS_CC_CO2_RATE,
     GAS_PEAKER_CO2_RATE,
     BIOMASS_CO2_RATE,
     WIND_BASE_MAX_EFFICIENCY,
+    FLOATING_WIND_BASE_MAX_EFFICIENCY,
     UTILITY_SOLAR_BASE_MAX_EFFICIENCY,
     NUCLEAR_BASE_MAX_EFFICIENCY,
     GAS_CC_BASE_MAX_EFFICIENCY,
@@ -22,8 +29,6 @@ use crate::config::constants::{
     EMERGING_TECH_IMPROVEMENT_RATE,
     MATURE_TECH_IMPROVEMENT_RATE,
     BASE_YEAR,
-    MAP_MAX_X,
-    MAP_MAX_Y,
     FOREST_BASE_COST,
     WETLAND_BASE_COST,
     ACTIVE_CAPTURE_BASE_COST,
@@ -34,66 +39,66 @@ use crate::config::constants::{
     CARBON_CREDIT_OPERATING_COST,
     MIN_CONSTRUCTION_COST_MULTIPLIER,
     MAX_CONSTRUCTION_COST_MULTIPLIER,
+    EV_CHARGING_DEMAND_SHARE,
 };
 use crate::config::const_funcs::calc_decommission_cost;
+use crate::config::constants::END_YEAR;
+use crate::ai::actions::serializable_action::SerializableAction;
+use crate::ai::learning::weights::rng::RngSource;
+use serde::Deserialize;
+use std::path::Path;
 
-pub fn apply_action(map: &mut Map, action: &GridAction, year: u32) -> Result<(), Box<dyn Error + Send + Sync>> {
+/// `rng` should be the run's seeded generator (`ActionWeights::deterministic_rng`) so placements
+/// that involve a random draw, such as carbon offset sites, reproduce under `--seed`. Without one
+/// the thread RNG is used.
+pub fn apply_action(map: &mut Map, action: &GridAction, year: u32, rng: Option<&mut StdRng>) -> Result<(), SimulationError> {
     match action {
         GridAction::AddGenerator(gen_type, cost_multiplier_percent) => {
+            if map.get_config().is_build_banned(gen_type, year) {
+                crate::log!(Debug, "Skipping {:?} generator: new builds are banned in {}", gen_type, year);
+                return Ok(());
+            }
+            // National resource caps: a type at its installed limit is built as its fallback instead
+            if map.would_exceed_capacity_cap(gen_type, gen_type.get_base_power(year)) {
+                let fallback_type = fallback_generator_type(gen_type);
+                if fallback_type == *gen_type {
+                    crate::log!(Debug, "{:?} has reached its installed capacity cap, skipping", gen_type);
+                    return Ok(());
+                }
+                crate::log!(Debug, "{:?} has reached its installed capacity cap, building {:?} instead", gen_type, fallback_type);
+                return apply_action(map, &GridAction::AddGenerator(fallback_type, *cost_multiplier_percent), year, rng);
+            }
             let gen_size = DEFAULT_GENERATOR_SIZE;
             let cost_multiplier = (*cost_multiplier_percent as f64 / 100.0)
                 .clamp(MIN_CONSTRUCTION_COST_MULTIPLIER, MAX_CONSTRUCTION_COST_MULTIPLIER);
                 
             match map.find_best_generator_location(gen_type, gen_size as f64 / 100.0) {
                 Some(location) => {
-                    let base_efficiency = gen_type.get_base_efficiency(year);
-                    let initial_co2_output = match gen_type {
-                        GeneratorType::CoalPlant => COAL_CO2_RATE,
-                        GeneratorType::GasCombinedCycle => GAS_CC_CO2_RATE,
-                        GeneratorType::GasPeaker => GAS_PEAKER_CO2_RATE,
-                        GeneratorType::Biomass => BIOMASS_CO2_RATE,
-                        _ => 0.0,  // All other types have zero direct CO2 emissions
-                    } * (gen_size as f64 / 100.0);  // Scale by size
-                     
-                    let mut generator = Generator::new(
-                        format!("Gen_{}_{}_{}", gen_type.to_string(), year, map.get_generator_count()),
-                        location,
-                        gen_type.clone(),
-                        gen_type.get_base_cost(year),
-                        gen_type.get_base_power(year),
-                        gen_type.get_operating_cost(year),
-                        gen_type.get_lifespan(),
-                        gen_size as f64 / 100.0,
-                        initial_co2_output,
-                        calc_decommission_cost(gen_type.get_base_cost(year)),
-                    );
-                    
-                    // Set the construction cost multiplier
-                    generator.set_construction_cost_multiplier(cost_multiplier);
-                    
+                    let generator = build_generator(map, gen_type, location, year, cost_multiplier);
                     map.add_generator(generator);
                     Ok(())
                 },
                 None => {
                     // Fallback: Try a different generator type
-                    let fallback_type = match gen_type {
-                        GeneratorType::Nuclear => GeneratorType::GasCombinedCycle,
-                        GeneratorType::HydroDam | GeneratorType::PumpedStorage => GeneratorType::GasPeaker,
-                        GeneratorType::OffshoreWind => GeneratorType::OnshoreWind,
-                        GeneratorType::TidalGenerator | GeneratorType::WaveEnergy => GeneratorType::OffshoreWind,
-                        _ => GeneratorType::GasPeaker, // Default fallback
-                    };
+                    let fallback_type = fallback_generator_type(gen_type);
+                    if fallback_type == *gen_type {
+                        crate::log!(Debug, "No location available for {:?} generator, skipping", gen_type);
+                        return Ok(());
+                    }
                      
-                    println!("Falling back to {:?} generator instead of {:?}", fallback_type, gen_type);
-                    apply_action(map, &GridAction::AddGenerator(fallback_type, *cost_multiplier_percent), year)
+                    crate::log!(Debug, "Falling back to {:?} generator instead of {:?}", fallback_type, gen_type);
+                    apply_action(map, &GridAction::AddGenerator(fallback_type, *cost_multiplier_percent), year, rng)
                 }
             }
         },
         GridAction::UpgradeEfficiency(id) => {
+            let upgrade_cost_multiplier = map.get_generator_constraints().upgrade_cost_multiplier;
+            let inflation = map.get_config().inflation.clone();
             if let Some(generator) = map.get_generator_mut(id) {
                 if generator.is_active() {
                     let base_max = match generator.get_generator_type() {
                         GeneratorType::OnshoreWind | GeneratorType::OffshoreWind => WIND_BASE_MAX_EFFICIENCY,
+                        GeneratorType::FloatingOffshoreWind => FLOATING_WIND_BASE_MAX_EFFICIENCY,
                         GeneratorType::UtilitySolar => UTILITY_SOLAR_BASE_MAX_EFFICIENCY,
                         GeneratorType::Nuclear => NUCLEAR_BASE_MAX_EFFICIENCY,
                         GeneratorType::GasCombinedCycle => GAS_CC_BASE_MAX_EFFICIENCY,
@@ -105,12 +110,16 @@ pub fn apply_action(map: &mut Map, action: &GridAction, year: u32) -> Result<(),
                     let tech_improvement = match generator.get_generator_type() {
                         GeneratorType::OnshoreWind | GeneratorType::OffshoreWind |
                         GeneratorType::UtilitySolar => DEVELOPING_TECH_IMPROVEMENT_RATE,
+                        GeneratorType::FloatingOffshoreWind |
                         GeneratorType::TidalGenerator | GeneratorType::WaveEnergy => EMERGING_TECH_IMPROVEMENT_RATE,
                         _ => MATURE_TECH_IMPROVEMENT_RATE,
                     }.powi((year - BASE_YEAR) as i32);
                      
                     let max_efficiency = base_max * (1.0 + (1.0 - tech_improvement));
-                    generator.upgrade_efficiency(year, max_efficiency);
+                    // Plants already past this year's ceiling have nothing to gain and pay nothing
+                    if max_efficiency > generator.get_efficiency() {
+                        generator.upgrade_efficiency(year, max_efficiency, upgrade_cost_multiplier, &inflation);
+                    }
                 }
             }
             map.after_generator_modification();
@@ -120,7 +129,7 @@ pub fn apply_action(map: &mut Map, action: &GridAction, year: u32) -> Result<(),
             let constraints = map.get_generator_constraints().clone();
             if let Some(generator) = map.get_generator_mut(id) {
                 if generator.is_active() {
-                    generator.adjust_operation(*percentage, &constraints);
+                    generator.adjust_operation(*percentage, &constraints, year);
                 }
             }
             map.after_generator_modification();
@@ -139,9 +148,9 @@ pub fn apply_action(map: &mut Map, action: &GridAction, year: u32) -> Result<(),
             };
             
             // Get a random location within the map bounds
-            let mut rng = rand::thread_rng();
-            let x = rng.gen_range(0.0..MAP_MAX_X);
-            let y = rng.gen_range(0.0..MAP_MAX_Y);
+            let extent = map.get_config().map_extent;
+            let mut rng = RngSource::new(rng);
+            let (x, y) = (rng.gen_range(0.0..extent.max_x), rng.gen_range(0.0..extent.max_y));
             let location = Coordinate { x, y };
             
             // Calculate base cost based on type
@@ -180,25 +189,494 @@ pub fn apply_action(map: &mut Map, action: &GridAction, year: u32) -> Result<(),
         GridAction::CloseGenerator(id) => {
             if let Some(generator) = map.get_generator_mut(id) {
                 if generator.is_active() {
-                    let age = year - generator.commissioning_year;
-                    let min_age = match generator.get_generator_type() {
-                        GeneratorType::Nuclear => 1,
-                        GeneratorType::HydroDam => 1,
-                        GeneratorType::OnshoreWind | GeneratorType::OffshoreWind => 1,
-                        GeneratorType::UtilitySolar => 1,
-                        _ => 1,
-                    };
-                     
-                    if age >= min_age {
-                        generator.close_generator(year);
+                    let age = year.saturating_sub(generator.commissioning_year);
+                    if age >= MIN_CLOSURE_AGE {
+                        let closure_cost = generator.close_generator(year);
+                        generator.early_closure = Some((year, closure_cost));
                     }
                 }
             }
             map.after_generator_modification();
             Ok(())
         },
-        GridAction::DoNothing => {
+        GridAction::RetrofitGenerator(id, target_type) => {
+            if let Some(generator) = map.get_generator_mut(id) {
+                if generator.retrofit(target_type, year).is_none() {
+                    crate::log!(Debug, "Skipping retrofit of {} to {}: not an active plant that can convert", id, target_type);
+                }
+            }
+            map.after_generator_modification();
+            Ok(())
+        },
+        GridAction::AddHybridPlant(primary_type, secondary_type) => {
+            if map.get_config().is_build_banned(secondary_type, year) {
+                return apply_action(map, &GridAction::AddGenerator(primary_type.clone(), DEFAULT_COST_MULTIPLIER), year, rng);
+            }
+            if map.get_config().is_build_banned(primary_type, year) {
+                crate::log!(Debug, "Skipping {} hybrid plant: new builds are banned in {}", primary_type, year);
+                return Ok(());
+            }
+            let gen_size = DEFAULT_GENERATOR_SIZE;
+            let location = match map.find_best_generator_location(primary_type, gen_size as f64 / 100.0) {
+                Some(location) => location,
+                None => {
+                    crate::log!(Debug, "No site found for {} hybrid plant, building a standalone generator", primary_type);
+                    return apply_action(map, &GridAction::AddGenerator(primary_type.clone(), DEFAULT_COST_MULTIPLIER), year, rng);
+                }
+            };
+
+            let primary = build_generator(map, primary_type, location, year, 1.0);
+            let primary_id = primary.get_id().to_string();
+            map.add_generator(primary);
+
+            // The host may be cancelled (e.g. it would finish after the simulation end)
+            let (host_location, host_zone) = match map.get_generators().iter().find(|g| g.get_id() == primary_id) {
+                Some(host) => (host.get_coordinate().clone(), host.zone.clone()),
+                None => return Ok(()),
+            };
+
+            let mut secondary = build_generator(map, secondary_type, host_location, year, 1.0);
+            secondary.hybrid_host = Some(primary_id);
+            secondary.zone = host_zone;
+            map.add_generator(secondary);
+            Ok(())
+        },
+        GridAction::AddFlexibleLoad => {
+            // Programmes roll out to the largest settlement that doesn't have one yet
+            let settlement = map.get_settlements().iter()
+                .filter(|s| !map.get_flexible_loads().iter().any(|l| l.get_settlement() == s.get_name()))
+                .max_by(|a, b| a.get_power_usage().total_cmp(&b.get_power_usage()));
+            let Some(settlement) = settlement else {
+                crate::log!(Debug, "Skipping flexible load: every settlement already has managed charging");
+                return Ok(());
+            };
+            let load = FlexibleLoad::new(
+                format!("FlexLoad_{}_{}", year, map.get_flexible_loads().len()),
+                settlement.get_name().to_string(),
+                settlement.get_coordinate().clone(),
+                settlement.get_power_usage() * EV_CHARGING_DEMAND_SHARE,
+                map.get_config().flexible_load_shift_fraction,
+                year,
+            );
+            map.add_flexible_load(load);
             Ok(())
         },
+        GridAction::DoNothing | GridAction::DeferDecision => {
+            Ok(())
+        },
+    }
+}
+
+// Technologies whose cost curves decide whether waiting a year pays off
+const DEFER_KEY_TECHNOLOGIES: [GeneratorType; 4] = [
+    GeneratorType::OnshoreWind,
+    GeneratorType::OffshoreWind,
+    GeneratorType::UtilitySolar,
+    GeneratorType::BatteryStorage,
+];
+
+/// Reward for choosing DeferDecision in `year`: positive when next year's capital cost for the key
+/// technologies is on average more than DEFER_MIN_COST_DECLINE lower, negative otherwise. Deferring
+/// in the final year has nothing left to wait for and is always penalised.
+pub fn defer_decision_reward(year: u32) -> f64 {
+    if year >= END_YEAR {
+        return -DEFER_MIN_COST_DECLINE * DEFER_REWARD_SCALE;
+    }
+    let average_decline = DEFER_KEY_TECHNOLOGIES.iter()
+        .map(|gen_type| 1.0 - gen_type.get_base_cost(year + 1) / gen_type.get_base_cost(year))
+        .sum::<f64>() / DEFER_KEY_TECHNOLOGIES.len() as f64;
+    (average_decline - DEFER_MIN_COST_DECLINE) * DEFER_REWARD_SCALE
+}
+
+/// Weights are keyed by action kind, so sampled upgrade/adjust/close actions arrive without a
+/// generator id. This picks the target from the current map so the recorded plan stays concrete
+/// and replays exactly; actions that already name a generator pass through unchanged. When no
+/// generator qualifies the action becomes DoNothing rather than a silent no-op.
+pub fn bind_action_target(map: &Map, action: GridAction, year: u32) -> GridAction {
+    let by_value = |a: f64, b: f64| a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal);
+    let active = || map.get_generators().iter().filter(|g| g.is_active());
+    let emitters = || active().filter(move |g| g.get_co2_output_in_year(year) > 0.0);
+
+    match action {
+        GridAction::UpgradeEfficiency(id) if id.is_empty() => {
+            // The least efficient unit has the most to gain from an upgrade
+            match active().min_by(|a, b| by_value(a.get_efficiency(), b.get_efficiency())) {
+                Some(generator) => GridAction::UpgradeEfficiency(generator.get_id().to_string()),
+                None => GridAction::DoNothing,
+            }
+        },
+        GridAction::AdjustOperation(id, percentage) if id.is_empty() => {
+            match emitters().max_by(|a, b| by_value(a.get_co2_output_in_year(year), b.get_co2_output_in_year(year))) {
+                Some(generator) => GridAction::AdjustOperation(generator.get_id().to_string(), percentage),
+                None => GridAction::DoNothing,
+            }
+        },
+        GridAction::CloseGenerator(id) if id.is_empty() => {
+            // Units commissioned this year are too young for apply_action to close
+            match emitters()
+                .filter(|g| year.saturating_sub(g.commissioning_year) >= MIN_CLOSURE_AGE)
+                .max_by(|a, b| by_value(a.get_co2_output_in_year(year), b.get_co2_output_in_year(year)))
+            {
+                Some(generator) => GridAction::CloseGenerator(generator.get_id().to_string()),
+                None => GridAction::DoNothing,
+            }
+        },
+        GridAction::RetrofitGenerator(id, target_type) if id.is_empty() => {
+            // Converting the dirtiest eligible unit saves the most emissions
+            match emitters()
+                .filter(|g| g.get_generator_type().can_retrofit_to(&target_type))
+                .max_by(|a, b| by_value(a.get_co2_output_in_year(year), b.get_co2_output_in_year(year)))
+            {
+                Some(generator) => GridAction::RetrofitGenerator(generator.get_id().to_string(), target_type),
+                None => GridAction::DoNothing,
+            }
+        },
+        other => other,
+    }
+}
+
+// Type built instead when a requested type can't be sited or has hit its capacity cap
+fn fallback_generator_type(gen_type: &GeneratorType) -> GeneratorType {
+    match gen_type {
+        GeneratorType::Nuclear => GeneratorType::GasCombinedCycle,
+        GeneratorType::HydroDam | GeneratorType::PumpedStorage => GeneratorType::GasPeaker,
+        GeneratorType::OffshoreWind => GeneratorType::OnshoreWind,
+        GeneratorType::FloatingOffshoreWind => GeneratorType::OffshoreWind,
+        GeneratorType::TidalGenerator | GeneratorType::WaveEnergy => GeneratorType::OffshoreWind,
+        _ => GeneratorType::GasPeaker, // Default fallback
+    }
+}
+
+fn build_generator(map: &mut Map, gen_type: &GeneratorType, location: Coordinate, year: u32, cost_multiplier: f64) -> Generator {
+    let gen_size = DEFAULT_GENERATOR_SIZE;
+    let initial_co2_output = match gen_type {
+        GeneratorType::CoalPlant => COAL_CO2_RATE,
+        GeneratorType::GasCombinedCycle => GAS_CC_CO2_RATE,
+        GeneratorType::GasPeaker => GAS_PEAKER_CO2_RATE,
+        GeneratorType::Biomass => BIOMASS_CO2_RATE,
+        _ => 0.0,  // All other types have zero direct CO2 emissions
+    } * (gen_size as f64 / 100.0);  // Scale by size
+
+    let mut generator = Generator::new(
+        map.next_generator_id(gen_type, year),
+        location,
+        gen_type.clone(),
+        gen_type.get_base_cost(year),
+        gen_type.get_base_power(year),
+        gen_type.get_operating_cost(year),
+        gen_type.get_lifespan(),
+        gen_size as f64 / 100.0,
+        initial_co2_output,
+        calc_decommission_cost(gen_type.get_base_cost(year)),
+    );
+
+    // Set the construction cost multiplier
+    generator.set_construction_cost_multiplier(cost_multiplier);
+    generator.resource_quality = resource_quality(gen_type, &generator.coordinate, &map.get_config().map_extent);
+    generator
+}
+
+// One row of a plan CSV, in the layout written by `csv_export::write_action_plan`
+#[derive(Debug, Deserialize)]
+struct PlanCsvRow {
+    #[serde(rename = "Year")]
+    year: u32,
+    #[serde(rename = "Action Type")]
+    action_type: String,
+    #[serde(rename = "Generator Type", default)]
+    generator_type: Option<String>,
+    #[serde(rename = "Generator ID", default)]
+    generator_id: Option<String>,
+    #[serde(rename = "Operation Percentage", default)]
+    operation_percentage: Option<u8>,
+    #[serde(rename = "Offset Type", default)]
+    offset_type: Option<String>,
+    #[serde(rename = "Cost Multiplier (%)", default)]
+    cost_multiplier: Option<u16>,
+    #[serde(rename = "Secondary Generator Type", default)]
+    secondary_generator_type: Option<String>,
+}
+
+// One entry of a JSON plan: {"year": 2030, "action_type": "AddGenerator", "generator_type": "OnshoreWind", ...}
+#[derive(Deserialize)]
+struct PlanJsonEntry {
+    year: u32,
+    #[serde(flatten)]
+    action: SerializableAction,
+}
+
+/// Loads an externally authored build plan from CSV (the planner export layout) or JSON
+/// (an array of actions each carrying a `year`). Entries are returned sorted by year, keeping
+/// file order within a year. Unknown types and years outside the simulation range are errors.
+pub fn load_action_plan(path: impl AsRef<Path>) -> Result<Vec<(u32, GridAction)>, SimulationError> {
+    let path = path.as_ref();
+    let entries: Vec<(u32, SerializableAction)> = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => {
+            let file = std::fs::File::open(path)?;
+            let entries: Vec<PlanJsonEntry> = serde_json::from_reader(std::io::BufReader::new(file))?;
+            entries.into_iter().map(|entry| (entry.year, entry.action)).collect()
+        },
+        Some("csv") => {
+            let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_path(path)?;
+            let mut entries = Vec::new();
+            for row in reader.deserialize() {
+                let row: PlanCsvRow = row?;
+                let empty_to_none = |value: Option<String>| value.filter(|s| !s.is_empty());
+                entries.push((row.year, SerializableAction {
+                    action_type: row.action_type,
+                    generator_type: empty_to_none(row.generator_type),
+                    generator_id: empty_to_none(row.generator_id),
+                    operation_percentage: row.operation_percentage,
+                    offset_type: empty_to_none(row.offset_type),
+                    cost_multiplier: row.cost_multiplier,
+                    secondary_generator_type: empty_to_none(row.secondary_generator_type),
+                }));
+            }
+            entries
+        },
+        _ => {
+            return Err(SimulationError::DataLoad(
+                format!("Action plan {} must be a .csv or .json file", path.display()),
+            ));
+        }
+    };
+
+    let mut plan = Vec::with_capacity(entries.len());
+    for (index, (year, serializable)) in entries.iter().enumerate() {
+        if !(BASE_YEAR..=END_YEAR).contains(year) {
+            return Err(SimulationError::DataLoad(format!(
+                "Action plan {} entry {}: year {} is outside {}-{}",
+                path.display(), index + 1, year, BASE_YEAR, END_YEAR
+            )));
+        }
+        let action = GridAction::try_from(serializable).map_err(|e| SimulationError::DataLoad(
+            format!("Action plan {} entry {}: {}", path.display(), index + 1, e)
+        ))?;
+        plan.push((*year, action));
+    }
+    plan.sort_by_key(|(year, _)| *year);
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::simulation_config::SimulationConfig;
+    use crate::models::settlement::Settlement;
+    use rand::SeedableRng;
+    use crate::core::action_weights::ActionWeights;
+    use crate::ai::actions::grid_action_kind::GridActionKind;
+    use crate::core::simulation::run_simulation_with_plan;
+    use crate::test_support::{empty_map, scratch_path, small_map, test_generator, TEST_SITE};
+
+    #[test]
+    fn floating_offshore_costs_more_than_fixed_offshore() {
+        let mut map = small_map();
+        let site = Coordinate::new(2_000.0, 25_000.0);
+
+        for year in [2025, 2035, 2050] {
+            let fixed = build_generator(&mut map, &GeneratorType::OffshoreWind, site.clone(), year, 1.0);
+            let floating = build_generator(&mut map, &GeneratorType::FloatingOffshoreWind, site.clone(), year, 1.0);
+
+            let inflation = &map.get_config().inflation;
+            assert!(floating.get_current_cost(year, inflation) > fixed.get_current_cost(year, inflation), "year {}", year);
+        }
+    }
+
+    #[test]
+    fn solar_with_storage_is_firmer_than_standalone_solar() {
+        let year = 2025;
+        let weakest_hour = |action: GridAction| {
+            let mut map = empty_map(SimulationConfig::default());
+            map.add_settlement(Settlement::new("Midtown".to_string(), TEST_SITE, 50_000, 80.0));
+            apply_action(&mut map, &action, year, None).unwrap();
+            (0..24u8).map(|hour| map.calc_total_power_generation(year, Some(hour))).fold(f64::INFINITY, f64::min)
+        };
+
+        let solar = weakest_hour(GridAction::AddGenerator(GeneratorType::UtilitySolar, DEFAULT_COST_MULTIPLIER));
+        let hybrid = weakest_hour(GridAction::AddHybridPlant(GeneratorType::UtilitySolar, GeneratorType::BatteryStorage));
+
+        assert!(hybrid > solar, "weakest hour {} MW with storage vs {} MW without", hybrid, solar);
+    }
+
+    #[test]
+    fn loaded_two_action_plan_matches_applying_the_actions_by_hand() {
+        let path = scratch_path("two_action_plan.json");
+        std::fs::write(&path, r#"[
+            {"year": 2030, "action_type": "AdjustOperation", "generator_id": "Existing_Gas", "operation_percentage": 60},
+            {"year": 2026, "action_type": "AddGenerator", "generator_type": "OnshoreWind", "cost_multiplier": 100}
+        ]"#).unwrap();
+        let plan = load_action_plan(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(plan, vec![
+            (2026, GridAction::AddGenerator(GeneratorType::OnshoreWind, 100)),
+            (2030, GridAction::AdjustOperation("Existing_Gas".to_string(), 60)),
+        ]);
+
+        let mut planned = small_map();
+        let (_, yearly_metrics) = run_simulation_with_plan(&mut planned, &plan, false, false).unwrap();
+
+        let mut manual = small_map();
+        for (year, action) in &plan {
+            manual.current_year = *year;
+            apply_action(&mut manual, action, *year, None).unwrap();
+        }
+        let last = yearly_metrics.last().unwrap();
+        assert_eq!(last.total_power_generation, manual.calc_total_power_generation(END_YEAR, None));
+        assert_eq!(last.net_co2_emissions, manual.calc_net_co2_emissions(END_YEAR));
+        assert_eq!(planned.get_generators().len(), manual.get_generators().len());
+    }
+
+    #[test]
+    fn same_turbine_produces_more_at_a_windier_site() {
+        let year = 2025;
+        let output_at = |site: Coordinate| {
+            let mut map = empty_map(SimulationConfig::default());
+            let turbine = build_generator(&mut map, &GeneratorType::OnshoreWind, site, year, 1.0);
+            map.add_generator(turbine);
+            map.calc_total_power_generation(year, None)
+        };
+
+        // The west coast is the windy end of the map
+        let west = output_at(Coordinate::new(2_000.0, 25_000.0));
+        let east = output_at(Coordinate::new(48_000.0, 25_000.0));
+
+        assert!(east > 0.0);
+        assert!(west > east, "{} MW in the west vs {} MW in the east", west, east);
+    }
+
+    #[test]
+    fn closing_young_nuclear_costs_more_than_closing_an_old_wind_farm() {
+        let closure_cost = |generator: Generator, year: u32| {
+            let id = generator.get_id().to_string();
+            let mut map = empty_map(SimulationConfig::default());
+            map.add_generator(generator);
+            apply_action(&mut map, &GridAction::CloseGenerator(id), year, None).unwrap();
+            map.calc_yearly_closure_costs(year)
+        };
+
+        let nuclear = test_generator("Young_Nuclear", GeneratorType::Nuclear, TEST_SITE, 100.0, 0.0);
+        let mut wind = test_generator("Old_Wind", GeneratorType::OnshoreWind, TEST_SITE, 100.0, 0.0);
+        wind.eol = 25;
+
+        let young_nuclear = closure_cost(nuclear, BASE_YEAR + 2);
+        let old_wind = closure_cost(wind, BASE_YEAR + 23);
+
+        assert!(old_wind > 0.0);
+        assert!(young_nuclear > old_wind, "nuclear €{:.0} vs wind €{:.0}", young_nuclear, old_wind);
+    }
+
+    #[test]
+    fn sampled_upgrade_targets_a_real_active_generator() {
+        let year = 2030;
+        let mut map = small_map();
+        // A closed, very inefficient unit would be the obvious upgrade if closures were ignored
+        let mut closed = test_generator("Closed_Coal", GeneratorType::CoalPlant, Coordinate::new(26_000.0, 24_000.0), 300.0, 2.0e6);
+        closed.efficiency = 0.1;
+        map.add_generator(closed);
+        map.get_generator_mut("Closed_Coal").unwrap().close_generator(2028);
+
+        let mut weights = ActionWeights::new();
+        weights.set_rng(StdRng::seed_from_u64(21));
+        let upgrades: Vec<String> = (0..2_000)
+            .map(|_| bind_action_target(&map, weights.sample_action(year), year))
+            .filter_map(|action| match action {
+                GridAction::UpgradeEfficiency(id) => Some(id),
+                _ => None,
+            })
+            .collect();
+
+        assert!(!upgrades.is_empty());
+        assert!(upgrades.iter().all(|id| map.get_generators().iter().any(|g| g.get_id() == id && g.is_active())), "{:?}", upgrades);
+
+        // With nothing to upgrade the slot is an explicit DoNothing
+        let empty = empty_map(SimulationConfig::default());
+        assert_eq!(bind_action_target(&empty, GridAction::UpgradeEfficiency(String::new()), year), GridAction::DoNothing);
+    }
+
+    #[test]
+    fn coal_to_biomass_retrofit_cuts_co2_and_keeps_the_unit_in_place() {
+        let mut map = small_map();
+        let site = Coordinate::new(30_000.0, 20_000.0);
+        map.add_generator(test_generator("Old_Coal", GeneratorType::CoalPlant, site.clone(), 300.0, 2.0e6));
+        let year = 2030;
+        let old_coal = |map: &Map| map.get_generators().iter().find(|g| g.get_id() == "Old_Coal").unwrap().clone();
+        let co2_before = old_coal(&map).get_co2_output_in_year(year);
+
+        apply_action(&mut map, &GridAction::RetrofitGenerator("Old_Coal".to_string(), GeneratorType::Biomass), year, None).unwrap();
+
+        let retrofitted = old_coal(&map);
+        assert_eq!(retrofitted.get_generator_type(), &GeneratorType::Biomass);
+        assert!(retrofitted.get_co2_output_in_year(year) < co2_before);
+        assert!(retrofitted.is_active());
+        assert_eq!(retrofitted.get_coordinate().distance_to(&site), 0.0);
+    }
+
+    #[test]
+    fn seeded_runs_place_carbon_offsets_at_identical_coordinates() {
+        let offset_site = |seed: u64| {
+            let mut map = small_map();
+            let mut rng = StdRng::seed_from_u64(seed);
+            for _ in 0..3 {
+                apply_action(&mut map, &GridAction::AddCarbonOffset(CarbonOffsetType::Forest, 100), 2030, Some(&mut rng)).unwrap();
+            }
+            map.get_carbon_offsets().iter()
+                .map(|offset| (offset.get_coordinate().x, offset.get_coordinate().y))
+                .collect::<Vec<_>>()
+        };
+
+        let first = offset_site(21);
+        assert_eq!(first.len(), 3);
+        assert_eq!(first, offset_site(21));
+        assert_ne!(first, offset_site(22));
+    }
+
+    #[test]
+    fn deferring_is_rewarded_while_key_technology_costs_fall() {
+        let year = 2026;
+        let average_decline = DEFER_KEY_TECHNOLOGIES.iter()
+            .map(|gen_type| 1.0 - gen_type.get_base_cost(year + 1) / gen_type.get_base_cost(year))
+            .sum::<f64>() / DEFER_KEY_TECHNOLOGIES.len() as f64;
+        assert!(average_decline > DEFER_MIN_COST_DECLINE, "key technologies only fall {:.4} a year", average_decline);
+
+        assert!(defer_decision_reward(year) > 0.0);
+        // Nothing left to wait for in the final year
+        assert!(defer_decision_reward(END_YEAR) < 0.0);
+
+        let mut weights = ActionWeights::new();
+        let defer_weight = |weights: &ActionWeights| weights.get_year_weights(year)
+            .and_then(|year_weights| year_weights.get(&GridActionKind::DeferDecision).copied())
+            .unwrap_or(0.0);
+        weights.update_weights(&GridAction::DeferDecision, year, 0.0);
+        let before = defer_weight(&weights);
+        weights.update_weights(&GridAction::DeferDecision, year, defer_decision_reward(year));
+        assert!(defer_weight(&weights) > before);
+    }
+
+    #[test]
+    fn upgrade_efficiency_charges_the_year_its_upgrade_cost() {
+        let year = 2030;
+        let mut map = small_map();
+        // New plant starts above every upgrade ceiling, so model an older, less efficient unit
+        let mut wind = test_generator("Old_Wind", GeneratorType::OnshoreWind, TEST_SITE, 100.0, 0.0);
+        wind.efficiency = 0.3;
+        wind.initialize_construction(BASE_YEAR, 0.5, false);
+        map.add_generator(wind);
+        let multiplier = map.get_generator_constraints().upgrade_cost_multiplier;
+        let before = map.calc_yearly_upgrade_costs(year);
+
+        apply_action(&mut map, &GridAction::UpgradeEfficiency("Old_Wind".to_string()), year, None).unwrap();
+
+        let upgraded = map.get_generators().iter().find(|g| g.get_id() == "Old_Wind").unwrap();
+        let gain = upgraded.get_efficiency() - 0.3;
+        assert!(gain > 0.0);
+        let expected = upgraded.get_current_cost(year, &map.get_config().inflation) * gain * multiplier;
+        assert!(expected > 0.0);
+        assert!((map.calc_yearly_upgrade_costs(year) - before - expected).abs() < 1e-6 * expected);
+
+        // Already at this year's ceiling, so a second upgrade is free
+        apply_action(&mut map, &GridAction::UpgradeEfficiency("Old_Wind".to_string()), year, None).unwrap();
+        assert!((map.calc_yearly_upgrade_costs(year) - before - expected).abs() < 1e-6 * expected);
+        assert_eq!(map.calc_yearly_upgrade_costs(year + 1), 0.0);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,107 @@
+// Fixed training workload for performance regression checks
+
+use std::fmt;
+use std::time::{Duration, Instant};
+use rayon::prelude::*;
+use crate::ai::learning::weights::ActionWeights;
+use crate::core::iteration::run_iteration;
+use crate::error::SimulationError;
+use crate::utils::logging::{self, LogLevel};
+use crate::utils::map_handler::Map;
+
+#[derive(Debug, Clone)]
+pub struct BenchmarkReport {
+    pub iterations: usize,
+    pub threads: usize,
+    pub elapsed: Duration,
+    pub iterations_per_sec: f64,
+    pub peak_memory_bytes: Option<u64>,           // Peak resident set size, where the OS reports it
+    pub category_timings: Vec<(String, Duration, u64)>, // Total time and call count per logging category
+}
+
+impl fmt::Display for BenchmarkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Benchmark: {} iterations on {} threads in {:.2}s", self.iterations, self.threads, self.elapsed.as_secs_f64())?;
+        writeln!(f, "  Iterations/sec: {:.2}", self.iterations_per_sec)?;
+        match self.peak_memory_bytes {
+            Some(bytes) => writeln!(f, "  Peak memory: {:.1} MB", bytes as f64 / (1024.0 * 1024.0))?,
+            None => writeln!(f, "  Peak memory: unavailable")?,
+        }
+        for (category, total, count) in &self.category_timings {
+            writeln!(f, "  {}: {:.3}s over {} calls", category, total.as_secs_f64(), count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs `iterations` independent training iterations from fresh weights, spread over `threads`
+/// workers (0 uses every core). Seeds are fixed so the same map gives the same workload, and
+/// logging is held at Error for the run so console output doesn't skew the timing.
+pub fn run_benchmark(base_map: &Map, iterations: usize, threads: usize, seed: u64) -> Result<BenchmarkReport, SimulationError> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(|e| SimulationError::Config(format!("Failed to build benchmark thread pool: {}", e)))?;
+
+    let previous_level = logging::log_level();
+    let previous_timing = logging::is_timing_enabled();
+    logging::set_log_level(LogLevel::Error);
+    logging::set_timing_enabled(true);
+
+    let iterations = iterations.max(1);
+    let start = Instant::now();
+    let result = pool.install(|| {
+        (0..iterations).into_par_iter()
+            .try_for_each(|i| {
+                let mut map = base_map.clone();
+                let mut weights = ActionWeights::new();
+                run_iteration(i, &mut map, &mut weights, false, Some(seed.wrapping_add(i as u64)), false, None, false, false)
+                    .map(|_| ())
+            })
+    });
+    let elapsed = start.elapsed();
+
+    logging::set_log_level(previous_level);
+    logging::set_timing_enabled(previous_timing);
+    result?;
+
+    Ok(BenchmarkReport {
+        iterations,
+        threads: pool.current_num_threads(),
+        elapsed,
+        iterations_per_sec: iterations as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        peak_memory_bytes: peak_memory_bytes(),
+        category_timings: logging::category_totals(),
+    })
+}
+
+// High-water mark of the process's resident memory, read from /proc on Linux
+fn peak_memory_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status.lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))
+        .and_then(|value| value.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{small_map, LOG_SETTINGS};
+
+    #[test]
+    fn benchmark_completes_and_reports_a_positive_rate() {
+        // The benchmark changes the global log level while it runs
+        let _settings = LOG_SETTINGS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let level_before = logging::log_level();
+
+        let report = run_benchmark(&small_map(), 2, 1, 7).unwrap();
+
+        assert_eq!(report.iterations, 2);
+        assert_eq!(report.threads, 1);
+        assert!(report.elapsed > Duration::ZERO);
+        assert!(report.iterations_per_sec > 0.0 && report.iterations_per_sec.is_finite());
+        assert!(report.to_string().contains("Iterations/sec"));
+        assert_eq!(logging::log_level(), level_before);
+    }
+}
@@ -1,11 +1,14 @@
-use std::error::Error;
+use crate::error::SimulationError;
 use crate::utils::map_handler::Map;
 use super::action_weights::ActionWeights;
 use crate::analysis::metrics::SimulationResult;
 use super::action_weights::SimulationMetrics;
-use super::simulation::run_simulation;
+use super::simulation::{run_simulation, run_simulation_with_best_actions, run_simulation_with_plan};
+use super::action_weights::{GridAction, ScoringContext};
+use crate::analysis::metrics::YearlyMetrics;
 use crate::utils::logging;
 use crate::utils::logging::OperationCategory;
+use crate::config::constants::ZONE_TRANSFER_EPSILON;
 
 pub fn run_iteration(
     __iteration: usize,
@@ -17,26 +20,25 @@ pub fn run_iteration(
     optimization_mode: Option<&str>,
     enable_energy_sales: bool,
     enable_construction_delays: bool,
-) -> Result<SimulationResult, Box<dyn Error + Send + Sync>> {
+) -> Result<SimulationResult, SimulationError> {
     let _timing = logging::start_timing("run_iteration", OperationCategory::Simulation);
     
     // Clone the map to avoid modifying the original
     let mut map_clone = map.clone();
     
+    // Score against this map's config, whichever entry point built the weights
+    weights.set_scoring_context(ScoringContext::from_config(map.get_config()));
+    
     // Clear current run actions to prevent accumulation across simulations
     weights.clear_current_run_actions();
     weights.clear_replay_index();
     
-    if verbose_logging {
-        println!("🧹 VERBOSE: Cleared current run actions and replay index at start of iteration");
-    }
+    crate::log!(Debug, "🧹 VERBOSE: Cleared current run actions and replay index at start of iteration");
     
     // Set force_best_actions if replay_best_strategy is true
     if replay_best_strategy {
         weights.set_force_best_actions(true);
-        if verbose_logging {
-            println!("🔄 VERBOSE: Forcing use of best actions for this iteration");
-        }
+        crate::log!(Debug, "🔄 VERBOSE: Forcing use of best actions for this iteration");
     } else {
         weights.set_force_best_actions(false);
     }
@@ -54,23 +56,49 @@ pub fn run_iteration(
     )?;
     
     // Calculate metrics from the last yearly metrics instead of relying on weights
-    let metrics = if let Some(final_year_metrics) = yearly_metrics.last() {
+    let metrics = final_simulation_metrics(&yearly_metrics);
+    
+    // Create the simulation result
+    let result = SimulationResult {
+        metrics,
+        output: simulation_output,
+        actions: recorded_actions,
+        yearly_metrics,
+        retirements: map_clone.get_retirements().to_vec(),
+    };
+    
+    Ok(result)
+}
+
+// Collapse a run's yearly metrics into the final SimulationMetrics used for scoring
+pub(crate) fn final_simulation_metrics(yearly_metrics: &[YearlyMetrics]) -> SimulationMetrics {
+    if let Some(final_year_metrics) = yearly_metrics.last() {
+        // Reliable means covering demand on energy, having the firm capacity for the peak,
+        // and no zone being left short by its link capacities
+        let power_reliability = if final_year_metrics.power_balance >= 0.0
+            && final_year_metrics.firm_capacity_margin >= 0.0
+            && final_year_metrics.unserved_zone_demand <= ZONE_TRANSFER_EPSILON { 1.0 } else { 0.0 };
+
         // Only print diagnostic info if debug weights is enabled
         if crate::ai::learning::constants::is_debug_weights_enabled() {
             // Convert from yearly metrics to simulation metrics
-            println!("DIAGNOSTIC: Creating SimulationMetrics from final year metrics:");
-            println!("  - final_net_emissions: {}", final_year_metrics.net_co2_emissions);
-            println!("  - total_cost: {}", final_year_metrics.total_capital_cost);
-            println!("  - average_public_opinion: {}", final_year_metrics.average_public_opinion);
-            println!("  - power_reliability: {}", 
-                if final_year_metrics.power_balance >= 0.0 { 1.0 } else { 0.0 });
+            crate::log!(Debug, "DIAGNOSTIC: Creating SimulationMetrics from final year metrics:");
+            crate::log!(Debug, "  - final_net_emissions: {}", final_year_metrics.net_co2_emissions);
+            crate::log!(Debug, "  - total_cost: {}", final_year_metrics.total_capital_cost);
+            crate::log!(Debug, "  - average_public_opinion: {}", final_year_metrics.average_public_opinion);
+            crate::log!(Debug, "  - power_reliability: {}", power_reliability);
         }
         
         SimulationMetrics {
             final_net_emissions: final_year_metrics.net_co2_emissions,
             average_public_opinion: final_year_metrics.average_public_opinion,
             total_cost: final_year_metrics.total_capital_cost,
-            power_reliability: if final_year_metrics.power_balance >= 0.0 { 1.0 } else { 0.0 },
+            power_reliability,
+            yearly_net_emissions: yearly_metrics.iter().map(|m| m.net_co2_emissions).collect(),
+            cumulative_net_emissions: final_year_metrics.cumulative_net_emissions,
+            total_land_use: final_year_metrics.total_land_use,
+            yearly_renewable_share: yearly_metrics.iter().map(|m| m.renewable_share).collect(),
+            final_carbon_credit_offset: final_year_metrics.carbon_credit_offset,
         }
     } else {
         // If no yearly metrics, use default values (should never happen)
@@ -80,16 +108,138 @@ pub fn run_iteration(
             average_public_opinion: 0.0,
             total_cost: 0.0,
             power_reliability: 0.0,
+            yearly_net_emissions: Vec::new(),
+            cumulative_net_emissions: 0.0,
+            total_land_use: 0.0,
+            yearly_renewable_share: Vec::new(),
+            final_carbon_credit_offset: 0.0,
         }
-    };
-    
-    // Create the simulation result
-    let result = SimulationResult {
-        metrics,
+    }
+}
+
+/// Re-runs the best plan stored in `weights` on `map` with guaranteed replay, for regenerating
+/// outputs without training. The map is left in its final (2050) state for CSV export.
+pub fn replay_best_plan(
+    map: &mut Map,
+    weights: &mut ActionWeights,
+    seed: Option<u64>,
+    optimization_mode: Option<&str>,
+    enable_energy_sales: bool,
+    enable_construction_delays: bool,
+) -> Result<SimulationResult, SimulationError> {
+    let _timing = logging::start_timing("replay_best_plan", OperationCategory::Simulation);
+
+    if !weights.has_best_actions() {
+        return Err(SimulationError::Checkpoint("Weights have no best actions to replay".to_string()));
+    }
+
+    weights.clear_current_run_actions();
+    weights.clear_replay_index();
+
+    let (simulation_output, recorded_actions, yearly_metrics) = run_simulation_with_best_actions(
+        map,
+        weights,
+        seed,
+        false,
+        optimization_mode,
+        enable_energy_sales,
+        enable_construction_delays,
+    )?;
+
+    Ok(SimulationResult {
+        metrics: final_simulation_metrics(&yearly_metrics),
         output: simulation_output,
         actions: recorded_actions,
         yearly_metrics,
-    };
-    
-    Ok(result)
-}
\ No newline at end of file
+        retirements: map.get_retirements().to_vec(),
+    })
+}
+
+/// Applies a fixed plan (e.g. from `actions::load_action_plan`) to `map` and scores it the
+/// same way as a learned strategy, so expert plans can be benchmarked against the AI.
+pub fn evaluate_action_plan(
+    map: &mut Map,
+    plan: &[(u32, GridAction)],
+    enable_energy_sales: bool,
+    enable_construction_delays: bool,
+) -> Result<SimulationResult, SimulationError> {
+    let _timing = logging::start_timing("evaluate_action_plan", OperationCategory::Simulation);
+
+    let (recorded_actions, yearly_metrics) = run_simulation_with_plan(
+        map,
+        plan,
+        enable_energy_sales,
+        enable_construction_delays,
+    )?;
+
+    let metrics = final_simulation_metrics(&yearly_metrics);
+    let output = format!(
+        "Plan of {} actions: net emissions {:.2} tonnes, total cost €{:.2}, public opinion {:.3}",
+        plan.len(), metrics.final_net_emissions, metrics.total_cost, metrics.average_public_opinion
+    );
+
+    Ok(SimulationResult {
+        metrics,
+        output,
+        actions: recorded_actions,
+        yearly_metrics,
+        retirements: map.get_retirements().to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::config::constants::BASE_YEAR;
+    use crate::config::simulation_config::SimulationConfig;
+    use crate::data::poi::Coordinate;
+    use crate::models::generator::GeneratorType;
+    use crate::models::settlement::Settlement;
+    use crate::test_support::{empty_map, small_map, test_generator, TEST_SITE};
+
+    #[test]
+    fn replaying_a_plan_twice_gives_identical_yearly_metrics() {
+        let mut weights = ActionWeights::new();
+        weights.best_actions = Some(HashMap::from([
+            (2026, vec![GridAction::AddGenerator(GeneratorType::OnshoreWind, 100)]),
+            (2030, vec![GridAction::AdjustOperation("Existing_Gas".to_string(), 60)]),
+        ]));
+
+        let replay = || {
+            let mut map = small_map();
+            let result = replay_best_plan(&mut map, &mut weights.clone(), Some(5), None, true, false).unwrap();
+            serde_json::to_string(&result.yearly_metrics).unwrap()
+        };
+
+        assert_eq!(replay(), replay());
+        assert!(matches!(
+            replay_best_plan(&mut small_map(), &mut ActionWeights::new(), Some(5), None, true, false),
+            Err(SimulationError::Checkpoint(_))
+        ));
+    }
+
+    #[test]
+    fn solar_balanced_grid_fails_firm_capacity_while_nuclear_passes() {
+        // One town supplied by a single plant of `gen_type`, run to the final year with no actions
+        let evaluate = |gen_type: GeneratorType, power_out: f64| {
+            let mut map = empty_map(SimulationConfig::default());
+            map.add_settlement(Settlement::new("Midtown".to_string(), TEST_SITE, 50_000, 80.0));
+            let mut plant = test_generator("Plant", gen_type, Coordinate::new(30_000.0, 25_000.0), power_out, 0.0);
+            plant.initialize_construction(BASE_YEAR, 0.5, false);
+            map.add_generator(plant);
+            evaluate_action_plan(&mut map, &[], false, false).unwrap()
+        };
+
+        let solar = evaluate(GeneratorType::UtilitySolar, 1200.0);
+        let nuclear = evaluate(GeneratorType::Nuclear, 250.0);
+
+        let (solar_final, nuclear_final) = (solar.yearly_metrics.last().unwrap(), nuclear.yearly_metrics.last().unwrap());
+        // Both cover demand on energy, but only nuclear's capacity credit covers the peak
+        assert!(solar_final.power_balance >= 0.0 && nuclear_final.power_balance >= 0.0);
+        assert!(solar_final.firm_capacity_margin < 0.0);
+        assert!(nuclear_final.firm_capacity_margin >= 0.0);
+        assert_eq!(solar.metrics.power_reliability, 0.0);
+        assert_eq!(nuclear.metrics.power_reliability, 1.0);
+    }
+}
@@ -1,6 +1,8 @@
-use std::error::Error;
+use crate::error::SimulationError;
 use std::fs::File;
 use std::io::Write;
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::BinaryHeap;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
@@ -10,40 +12,41 @@ use crate::models::generator::GeneratorType;
 use crate::utils::csv_export::{self, CsvExporter};
 use crate::config::constants::{
     FOREST_BASE_COST, WETLAND_BASE_COST, ACTIVE_CAPTURE_BASE_COST, CARBON_CREDIT_BASE_COST,
-    FOREST_OPERATING_COST, WETLAND_OPERATING_COST, ACTIVE_CAPTURE_OPERATING_COST, CARBON_CREDIT_OPERATING_COST, MAX_ACCEPTABLE_EMISSIONS, MAX_ACCEPTABLE_COST,
+    FOREST_OPERATING_COST, WETLAND_OPERATING_COST, ACTIVE_CAPTURE_OPERATING_COST, CARBON_CREDIT_OPERATING_COST,
     DEVELOPING_TECH_IMPROVEMENT_RATE, EMERGING_TECH_IMPROVEMENT_RATE, MATURE_TECH_IMPROVEMENT_RATE, BASE_YEAR,
     COAL_CO2_RATE, GAS_CC_CO2_RATE, GAS_PEAKER_CO2_RATE, BIOMASS_CO2_RATE,
     END_YEAR, MAP_MAX_X, MAP_MAX_Y,
 };
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use parking_lot::RwLock;
 use crate::utils::map_handler::Map;
 use crate::ai::learning::weights::ActionWeights;
 use crate::core::action_weights::GridAction;
 use crate::analysis::metrics::SimulationResult;
-use crate::core::iteration::run_iteration;
+use crate::core::iteration::{run_iteration, evaluate_action_plan};
 use crate::utils::logging;
 use crate::utils::logging::OperationCategory;
 use crate::data::poi::POI;
-use crate::core::action_weights::evaluate_action_impact;
+use crate::core::action_weights::{evaluate_action_impact, ScoringContext};
 use crate::core::actions::apply_action;
 use chrono::Local;
 use serde::{Serialize, Deserialize};
 use crate::core::action_weights::SimulationMetrics;
-use crate::config::simulation_config::SimulationConfig;
+use crate::ai::FeasibilityReport;
+use crate::config::simulation_config::{OutputFormat, SimulationConfig};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
+use crate::analysis::uncertainty::run_availability_monte_carlo;
+use crate::analysis::reporting::export_fleet_age;
 use serde_json;
 
-const FULL_RUN_PERCENTAGE: usize = 10;
-const REPLAY_BEST_STRATEGY_IN_FULL_RUNS: bool = true;
-
 // Constants for the full simulation continuation prompt
 const FULL_SIM_INTERVAL: usize = 10000;  // Ask after this many full simulations
 const FULL_SIM_THRESHOLD_PERCENT: f64 = 5.0;  // Target percentage of best score (5%)
 
 // Generator efficiency constants
 const ONSHORE_OFFSHORE_WIND_EFFICIENCY: f64 = 0.45;
+const FLOATING_OFFSHORE_WIND_EFFICIENCY: f64 = 0.48;
 const UTILITY_SOLAR_EFFICIENCY: f64 = 0.40;
 const NUCLEAR_EFFICIENCY: f64 = 0.50;
 const GAS_COMBINED_CYCLE_EFFICIENCY: f64 = 0.60;
@@ -61,6 +64,190 @@ fn metrics_to_action_result(metrics: &crate::core::action_weights::SimulationMet
     }
 }
 
+// One merge of an iteration's results into the shared weights, in the order merges happened
+#[derive(Debug, Serialize)]
+struct MergeTraceRow {
+    merge_order: usize,
+    iteration: usize,
+    thread_id: usize,
+    score: f64,
+    best_score_after_merge: f64,
+}
+
+fn write_merge_trace(path: &Path, rows: &[MergeTraceRow]) -> Result<(), SimulationError> {
+    let mut writer = csv::Writer::from_path(path)?;
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+// A scored result held in the top-K shortlist; ordered so the heap's top is the lowest score
+struct RankedResult {
+    score: f64,
+    result: SimulationResult,
+}
+
+impl PartialEq for RankedResult {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == CmpOrdering::Equal
+    }
+}
+
+impl Eq for RankedResult {}
+
+impl PartialOrd for RankedResult {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RankedResult {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        other.score.total_cmp(&self.score)
+    }
+}
+
+// Bounded shortlist of the best-scoring distinct plans seen so far
+struct TopResults {
+    capacity: usize,
+    heap: BinaryHeap<RankedResult>,
+}
+
+impl TopResults {
+    fn new(capacity: usize) -> Self {
+        Self { capacity, heap: BinaryHeap::with_capacity(capacity + 1) }
+    }
+
+    fn offer(&mut self, result: &SimulationResult, score: f64) {
+        if self.capacity == 0 {
+            return;
+        }
+        let full = self.heap.len() >= self.capacity;
+        if full && self.heap.peek().is_some_and(|lowest| score <= lowest.score) {
+            return;
+        }
+        // Repeats of a plan already held would crowd out real alternatives
+        if self.heap.iter().any(|held| held.result.actions == result.actions) {
+            return;
+        }
+        self.heap.push(RankedResult { score, result: result.clone() });
+        if self.heap.len() > self.capacity {
+            self.heap.pop();
+        }
+    }
+
+    // Best first
+    fn into_ranked(self) -> Vec<RankedResult> {
+        self.heap.into_sorted_vec()
+    }
+}
+
+const PARTIAL_YEARLY_METRICS_FILE: &str = "partial_yearly_metrics.csv";
+const BEST_YEARLY_METRICS_HISTORY_FILE: &str = "best_yearly_metrics_history.csv";
+
+// Best plan seen so far, appended to PARTIAL_YEARLY_METRICS_FILE at each checkpoint so an
+// interrupted run still leaves the yearly results of its best plan behind
+#[derive(Default)]
+struct IncrementalBest {
+    best: Option<(f64, usize, Vec<csv_export::YearlyMetrics>)>,
+    flushed: bool,
+}
+
+impl IncrementalBest {
+    fn offer(&mut self, iteration: usize, score: f64, result: &SimulationResult) {
+        if self.best.as_ref().is_none_or(|(best_score, _, _)| score > *best_score) {
+            self.best = Some((score, iteration, csv_export::convert_yearly_metrics(&result.yearly_metrics)));
+            self.flushed = false;
+        }
+    }
+
+    // Appends only when the best has changed since the last flush. A failed write is
+    // reported but never stops the run.
+    fn flush(&mut self, run_dir: &str) {
+        if self.flushed {
+            return;
+        }
+        if let Some((score, iteration, yearly_metrics)) = &self.best {
+            let path = Path::new(run_dir).join(PARTIAL_YEARLY_METRICS_FILE);
+            match CsvExporter::append_yearly_metrics(&path, *iteration, *score, yearly_metrics) {
+                Ok(()) => self.flushed = true,
+                Err(e) => eprintln!("Warning: failed to flush partial yearly metrics to {}: {}", path.display(), e),
+            }
+        }
+    }
+}
+
+// On normal completion the partial file is renamed, so a leftover partial file marks an interrupted run
+fn finalize_incremental_best(run_dir: &str) -> Result<(), SimulationError> {
+    let run_dir = Path::new(run_dir);
+    CsvExporter::finalize_incremental_export(
+        run_dir.join(PARTIAL_YEARLY_METRICS_FILE),
+        run_dir.join(BEST_YEARLY_METRICS_HISTORY_FILE),
+    ).map_err(SimulationError::from)
+}
+
+// Each shortlisted plan goes to its own top_results/rank_N directory, rank 1 being the best
+fn export_top_results(run_dir: &str, ranked: &[RankedResult], output_format: OutputFormat) -> Result<(), SimulationError> {
+    let top_dir = Path::new(run_dir).join("top_results");
+    println!("\nTop {} plans:", ranked.len());
+    for (index, entry) in ranked.iter().enumerate() {
+        let rank = index + 1;
+        let metrics = &entry.result.metrics;
+        println!("  {}. score {:.4}: {:.2} tonnes net emissions, €{:.2}B, {:.1}% opinion",
+            rank, entry.score, metrics.final_net_emissions, metrics.total_cost / 1_000_000_000.0,
+            metrics.average_public_opinion * 100.0);
+
+        let rank_dir = top_dir.join(format!("rank_{}", rank));
+        std::fs::create_dir_all(&rank_dir)?;
+        if output_format.writes_json() {
+            let result_json = serde_json::json!({
+                "rank": rank,
+                "score": entry.score,
+                "metrics": metrics,
+                "yearly_metrics": entry.result.yearly_metrics,
+                "actions": entry.result.actions,
+            });
+            std::fs::write(rank_dir.join("result.json"), serde_json::to_string_pretty(&result_json)?)?;
+        }
+        if output_format.writes_csv() {
+            csv_export::write_action_plan(rank_dir.join("action_plan.csv"), &entry.result.actions)?;
+        }
+    }
+    println!("Top plans saved to: {}", top_dir.display());
+    Ok(())
+}
+
+// Written when the runtime budget ends a run early, so --continue picks up where it stopped
+fn save_final_checkpoint(run_dir: &str, weights: &ActionWeights, completed_through: usize) -> Result<(), SimulationError> {
+    let checkpoint_path = Path::new(run_dir).join(crate::ai::learning::constants::checkpoint_file_name("latest_weights"));
+    weights.save_to_file(checkpoint_path.to_str().unwrap())?;
+    std::fs::write(Path::new(run_dir).join("checkpoint_iteration.txt"), completed_through.to_string())?;
+    println!("Saved final checkpoint at iteration {} in {}", completed_through, run_dir);
+    Ok(())
+}
+
+// Initial weights for --resume-weights: exactly the given file, or an error if it's missing or unreadable
+fn load_resume_weights(path: &str) -> Result<ActionWeights, SimulationError> {
+    if !Path::new(path).exists() {
+        return Err(SimulationError::Checkpoint(format!(
+            "Resume weights file not found: {}",
+            path
+        )));
+    }
+    let weights = ActionWeights::load_from_file(path).map_err(|e| {
+        SimulationError::Checkpoint(format!("Failed to load resume weights from {}: {}", path, e))
+    })?;
+    println!("\n{}", "=".repeat(80));
+    println!("📊 RESUMING FROM WEIGHTS FILE: {} 📊", path);
+    if let Some((best_score, _)) = weights.get_best_metrics() {
+        println!("Best score from loaded weights: {:.4}", best_score);
+    }
+    println!("{}", "=".repeat(80));
+    Ok(weights)
+}
+
 // Add this helper function to prompt the user
 fn prompt_continue_full_simulations(best_score: f64, current_score: f64) -> bool {
     let percent_of_best = (current_score / best_score) * 100.0;
@@ -92,6 +279,110 @@ fn prompt_continue_full_simulations(best_score: f64, current_score: f64) -> bool
     input.trim().to_lowercase() == "y"
 }
 
+/// Snapshot of training progress handed to the progress callback every progress interval
+#[derive(Debug, Clone)]
+pub struct ProgressEvent {
+    pub completed_iterations: usize,
+    pub total_iterations: usize,
+    pub elapsed: Duration,
+    pub best_score: Option<f64>,
+    pub is_net_zero: bool,
+    pub best_metrics: Option<SimulationMetrics>,
+    pub feasibility: Option<FeasibilityReport>,  // Best run against the configured targets
+}
+
+pub type ProgressCallback = Arc<dyn Fn(ProgressEvent) + Send + Sync>;
+
+fn progress_event(weights: &ActionWeights, config: &SimulationConfig, completed: usize, total_iterations: usize, elapsed: Duration) -> ProgressEvent {
+    let best = weights.get_best_metrics();
+    let best_metrics = weights.get_simulation_metrics().cloned();
+    ProgressEvent {
+        completed_iterations: completed,
+        total_iterations,
+        elapsed,
+        best_score: best.map(|(score, _)| score),
+        is_net_zero: best.map(|(_, net_zero)| net_zero).unwrap_or(false),
+        feasibility: best_metrics.as_ref().map(|metrics| metrics.is_feasible(config)),
+        best_metrics,
+    }
+}
+
+// Default progress callback: the console progress report
+pub fn print_progress_event(event: &ProgressEvent) {
+    let iterations_per_second = event.completed_iterations as f64 / event.elapsed.as_secs_f64();
+    let remaining = event.total_iterations - event.completed_iterations;
+    let eta_seconds = if iterations_per_second > 0.0 {
+        remaining as f64 / iterations_per_second
+    } else {
+        0.0
+    };
+
+    // Get emissions metrics from the best metrics
+    let metrics_info = if let (Some(best), Some(feasibility)) = (&event.best_metrics, &event.feasibility) {
+        let emissions_status = feasibility.emissions_status();
+        let cost_status = feasibility.cost_status();
+
+        format!("\nMetrics Status:\n\
+        - Emissions: {} ({:.1} tonnes)\n\
+        - Cost: {} (€{:.1}B accumulated)\n\
+        - Public Opinion: {:.1}%\n\
+        - Power Reliability: {:.1}%",
+        emissions_status,
+        best.final_net_emissions,
+        cost_status,
+        best.total_cost / 1_000_000_000.0,
+        best.average_public_opinion * 100.0,
+        best.power_reliability * 100.0)
+    } else {
+        "\nMetrics Status: No data yet".to_string()
+    };
+
+    println!(
+        "\n{}\n\
+        📈 PROGRESS UPDATE 📈\n\
+        {}\n\
+        Iterations: {}/{} ({:.1}%)\n\
+        Speed: {:.1} iterations/sec\n\
+        ETA: {:.1} minutes\n\
+        \n\
+        Best Score: {:.9} {}\n\
+        Target: 1.0000 (Net Zero + Max Public Opinion){}\n\
+        \n\
+        Score Explanation:\n\
+        - Score < 1.0000: Working on reducing emissions\n\
+        - Score = 0.0000: Emissions at or above maximum\n\
+        - Score > 0.0000: Making progress on emissions\n\
+        - [NET ZERO]: Achieved net zero, score is now public opinion\n\
+        {}",
+        "=".repeat(80),
+        "=".repeat(80),
+        event.completed_iterations,
+        event.total_iterations,
+        (event.completed_iterations as f64 / event.total_iterations as f64) * 100.0,
+        iterations_per_second,
+        eta_seconds / 60.0,
+        event.best_score.unwrap_or(0.0),
+        if event.is_net_zero { "✅ [NET ZERO]" } else { "" },
+        metrics_info,
+        "=".repeat(80)
+    );
+}
+
+/// Scores a batch of fixed plans in parallel, each on its own clone of `base_map`,
+/// for comparing external plans or Monte Carlo replays outside the training loop. Plans replay
+/// without sampling, so the same plan always yields the same metrics; results keep input order.
+pub fn score_plans(base_map: &Map, plans: &[Vec<(u32, GridAction)>]) -> Result<Vec<SimulationMetrics>, SimulationError> {
+    let _timing = logging::start_timing("score_plans", OperationCategory::Simulation);
+
+    plans.par_iter()
+        .map(|plan| {
+            let mut map_clone = base_map.clone();
+            let result = evaluate_action_plan(&mut map_clone, plan, false, base_map.enable_construction_delays)?;
+            Ok(result.metrics)
+        })
+        .collect()
+}
+
 pub fn run_multi_simulation(
     base_map: &Map,
     num_iterations: usize,
@@ -110,9 +401,23 @@ pub fn run_multi_simulation(
     debug_weights: bool,
     enable_construction_delays: bool,
     track_weight_history: bool,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    // Configure debug weights output
+    resume_weights: Option<&str>,
+    binary_checkpoints: bool,
+    progress_callback: Option<ProgressCallback>,
+) -> Result<(), SimulationError> {
+    let progress_callback: ProgressCallback = progress_callback
+        .unwrap_or_else(|| Arc::new(|event: ProgressEvent| print_progress_event(&event)));
+
+    // Weights debugging raises the log level to Debug
     crate::ai::learning::constants::set_debug_weights(debug_weights);
+    crate::ai::learning::constants::set_binary_checkpoints(binary_checkpoints);
+    let scoring = ScoringContext::from_config(base_map.get_config());
+    let prune_weight_threshold = base_map.get_config().prune_weight_threshold;
+    let output_format = base_map.get_config().output_format;
+    let full_run_strategy = base_map.get_config().full_run_strategy;
+    let replay_best_in_full_runs = base_map.get_config().replay_best_in_full_runs;
+    let max_runtime = base_map.get_config().max_runtime_secs.map(Duration::from_secs);
+    let merge_trace: Option<Mutex<Vec<MergeTraceRow>>> = base_map.get_config().trace_merges.then(|| Mutex::new(Vec::new()));
     
     let _timing = logging::start_timing("run_multi_simulation", OperationCategory::Simulation);
     
@@ -143,8 +448,10 @@ pub fn run_multi_simulation(
     };
     
     let result = (|| {
-        // Create checkpoint directory if it doesn't exist
-        std::fs::create_dir_all(checkpoint_dir)?;
+        // Create checkpoint directory if it doesn't exist; with no output nothing goes in it
+        if output_format.writes_files() {
+            std::fs::create_dir_all(checkpoint_dir)?;
+        }
          
         // Load location analysis cache
         let mut base_map = base_map.clone();
@@ -156,15 +463,34 @@ pub fn run_multi_simulation(
         // Initialize progress tracking
         let completed_iterations = Arc::new(AtomicUsize::new(0));
         let start_time = Instant::now();
+
+        // Set once the runtime budget is spent; iterations already running finish, no new ones start
+        let runtime_stop = Arc::new(AtomicBool::new(false));
+        let runtime_exhausted = || {
+            if runtime_stop.load(Ordering::Relaxed) {
+                return true;
+            }
+            let Some(limit) = max_runtime else { return false };
+            if start_time.elapsed() < limit {
+                return false;
+            }
+            if !runtime_stop.swap(true, Ordering::Relaxed) {
+                println!("\n⏱️ Runtime budget of {}s reached after {} iterations; finishing in-flight work",
+                    limit.as_secs(), completed_iterations.load(Ordering::Relaxed));
+            }
+            true
+        };
          
         // Create timestamp directory after loading weights
         let now = Local::now();
         let timestamp = format!("2024{}", now.format("%m%d_%H%M%S"));
         let run_dir = format!("{}/{}", checkpoint_dir, timestamp);
-        std::fs::create_dir_all(&run_dir)?;
+        if output_format.writes_files() {
+            std::fs::create_dir_all(&run_dir)?;
+        }
         
         // Create weight history file if tracking is enabled
-        let weight_history_path = if track_weight_history {
+        let weight_history_path = if track_weight_history && output_format.writes_files() {
             let history_path = Path::new(&run_dir).join("weight_history.json");
             if !history_path.exists() {
                 let mut file = File::create(&history_path)?;
@@ -176,7 +502,7 @@ pub fn run_multi_simulation(
         };
 
         // Function to save weight history
-        let save_weight_history = |weights: &ActionWeights, iteration: usize| -> Result<(), Box<dyn Error + Send + Sync>> {
+        let save_weight_history = |weights: &ActionWeights, iteration: usize| -> Result<(), SimulationError> {
             if let Some(history_path) = &weight_history_path {
                 let mut history: Vec<serde_json::Value> = if history_path.exists() {
                     let contents = std::fs::read_to_string(history_path)?;
@@ -206,8 +532,17 @@ pub fn run_multi_simulation(
             Ok(())
         };
 
+        // Fresh runs start from the configured deployment prior when there is one
+        let fresh_weights = || match &base_map.get_config().deployment_prior {
+            Some(prior) => ActionWeights::new().with_prior(prior),
+            None => ActionWeights::new(),
+        };
+
         // Load or create initial weights
-        let initial_weights = if continue_from_checkpoint {
+        let initial_weights = if let Some(path) = resume_weights {
+            // An explicit weights file bypasses the checkpoint directory scan entirely
+            load_resume_weights(path)?
+        } else if continue_from_checkpoint {
             // Try to find the most recent checkpoint
             let entries: Vec<_> = std::fs::read_dir(checkpoint_dir)?
                 .filter_map(|entry| entry.ok())
@@ -242,9 +577,11 @@ pub fn run_multi_simulation(
                 let mut merged_weights = ActionWeights::new();
                 let mut found_weights = false;
                  
-                // First load the shared weights if they exist
-                let shared_weights_path = checkpoint_dir.join("latest_weights.json");
-                if shared_weights_path.exists() {
+                // First load the shared weights if they exist, in whichever format they were written
+                let shared_weights_path = ["latest_weights.bin", "latest_weights.json"].iter()
+                    .map(|name| checkpoint_dir.join(name))
+                    .find(|path| path.exists());
+                if let Some(shared_weights_path) = shared_weights_path {
                     println!("Loading shared weights from: {:?}", shared_weights_path);
                     if let Ok(weights) = ActionWeights::load_from_file(shared_weights_path.to_str().unwrap()) {
                         merged_weights = weights;
@@ -257,10 +594,10 @@ pub fn run_multi_simulation(
                     if let Ok(entry) = entry {
                         let path = entry.path();
                         if let Some(filename) = path.file_name().and_then(|f| f.to_str()) {
-                            if filename.starts_with("thread_") && filename.ends_with("_weights.json") {
+                            if filename.starts_with("thread_") && (filename.ends_with("_weights.json") || filename.ends_with("_weights.bin")) {
                                 println!("Loading thread weights from: {:?}", path);
                                 if let Ok(thread_weights) = ActionWeights::load_from_file(path.to_str().unwrap()) {
-                                    merged_weights.update_weights_from(&thread_weights);
+                                    merged_weights.merge(&thread_weights);
                                     found_weights = true;
                                 }
                             }
@@ -278,17 +615,22 @@ pub fn run_multi_simulation(
                     merged_weights
                 } else {
                     println!("No weights found in latest directory, starting fresh");
-                    ActionWeights::new()
+                    fresh_weights()
                 }
             } else {
                 println!("No checkpoint directories found, starting fresh");
-                ActionWeights::new()
+                fresh_weights()
             }
         } else {
             println!("Starting fresh simulation (--no-continue specified)");
-            ActionWeights::new()
+            fresh_weights()
         };
 
+        let mut initial_weights = initial_weights;
+        initial_weights.set_exploration_schedule(base_map.get_config().exploration_schedule);
+        initial_weights.set_noop_max_share(base_map.get_config().noop_max_share);
+        initial_weights.set_scoring_context(scoring.clone());
+
         // Create a clone of initial weights for later use in sequential mode
         let initial_weights_clone = initial_weights.clone();
          
@@ -299,89 +641,24 @@ pub fn run_multi_simulation(
         let progress_counter = completed_iterations.clone();
         let total_iterations = num_iterations;
         let action_weights_for_progress: Arc<RwLock<ActionWeights>> = Arc::clone(&action_weights);
+        let progress_callback_for_thread = Arc::clone(&progress_callback);
+        let progress_stop = Arc::clone(&runtime_stop);
+        let config_for_progress = base_map.get_config().clone();
          
         std::thread::spawn(move || {
-            while progress_counter.load(Ordering::Relaxed) < total_iterations {
+            while progress_counter.load(Ordering::Relaxed) < total_iterations && !progress_stop.load(Ordering::Relaxed) {
                 std::thread::sleep(Duration::from_secs(progress_interval as u64));
                 let completed = progress_counter.load(Ordering::Relaxed);
-                let elapsed = start_time.elapsed();
-                let iterations_per_second = completed as f64 / elapsed.as_secs_f64();
-                let remaining = total_iterations - completed;
-                let eta_seconds = if iterations_per_second > 0.0 {
-                    remaining as f64 / iterations_per_second
-                } else {
-                    0.0
-                };
-
-                // Get best score and metrics from the shared weights
-                let weights = action_weights_for_progress.read();
-                let (best_score, is_net_zero) = weights.get_best_metrics()
-                    .unwrap_or((0.0, false));
-                 
-                // Get emissions metrics from the best metrics
-                let metrics_info = if let Some(best) = weights.get_simulation_metrics() {
-                    let emissions_status = if best.final_net_emissions <= 0.0 {
-                        "✅ NET ZERO ACHIEVED".to_string()
-                    } else {
-                        format!("⚠ {:.1}% above target",
-                            (best.final_net_emissions / MAX_ACCEPTABLE_EMISSIONS) * 100.0)
-                    };
-                     
-                    let cost_status = if best.total_cost <= MAX_ACCEPTABLE_COST {
-                        "✅ WITHIN BUDGET".to_string()
-                    } else {
-                        format!("❌ {:.1}% OVER BUDGET",
-                            ((best.total_cost - MAX_ACCEPTABLE_COST) / MAX_ACCEPTABLE_COST) * 100.0)
-                    };
-                     
-                    format!("\nMetrics Status:\n\
-                    - Emissions: {} ({:.1} tonnes)\n\
-                    - Cost: {} (€{:.1}B accumulated)\n\
-                    - Public Opinion: {:.1}%\n\
-                    - Power Reliability: {:.1}%",
-                    emissions_status,
-                    best.final_net_emissions,
-                    cost_status,
-                    best.total_cost / 1_000_000_000.0,
-                    best.average_public_opinion * 100.0,
-                    best.power_reliability * 100.0)
-                } else {
-                    "\nMetrics Status: No data yet".to_string()
-                };
-                 
-                println!(
-                    "\n{}\n\
-                    📈 PROGRESS UPDATE 📈\n\
-                    {}\n\
-                    Iterations: {}/{} ({:.1}%)\n\
-                    Speed: {:.1} iterations/sec\n\
-                    ETA: {:.1} minutes\n\
-                    \n\
-                    Best Score: {:.9} {}\n\
-                    Target: 1.0000 (Net Zero + Max Public Opinion){}\n\
-                    \n\
-                    Score Explanation:\n\
-                    - Score < 1.0000: Working on reducing emissions\n\
-                    - Score = 0.0000: Emissions at or above maximum\n\
-                    - Score > 0.0000: Making progress on emissions\n\
-                    - [NET ZERO]: Achieved net zero, score is now public opinion\n\
-                    {}",
-                    "=".repeat(80),
-                    "=".repeat(80),
-                    completed,
-                    total_iterations,
-                    (completed as f64 / total_iterations as f64) * 100.0,
-                    iterations_per_second,
-                    eta_seconds / 60.0,
-                    best_score,
-                    if is_net_zero { "✅ [NET ZERO]" } else { "" },
-                    metrics_info,
-                    "=".repeat(80)
-                );
+                let event = progress_event(&action_weights_for_progress.read(), &config_for_progress, completed, total_iterations, start_time.elapsed());
+                progress_callback_for_thread(event);
             }
         });
 
         let mut best_result: Option<SimulationResult> = None;
+        let keep_top_results = base_map.get_config().keep_top_results;
+        let mut top_results = TopResults::new(keep_top_results);
+        let incremental_csv = output_format.writes_csv() && enable_csv_export;
+        let incremental_best = Mutex::new(IncrementalBest::default());
         let start_iteration = if continue_from_checkpoint {
             let entries: Vec<_> = std::fs::read_dir(checkpoint_dir)?
                 .filter_map(|entry| entry.ok())
@@ -424,7 +701,11 @@ pub fn run_multi_simulation(
         if parallel {
             let results: Vec<_> = (start_iteration..num_iterations)
                 .into_par_iter()
-                .map(|i| -> Result<SimulationResult, Box<dyn Error + Send + Sync>> {
+                .map(|i| -> Result<Option<SimulationResult>, SimulationError> {
+                    if runtime_exhausted() {
+                        return Ok(None);
+                    }
+                    
                     // Create a new map instance with shared static data
                     let mut map_clone = Map::new_with_static_data(static_data.clone());
                      
@@ -434,25 +715,19 @@ pub fn run_multi_simulation(
                     map_clone.set_carbon_offsets(base_map.get_carbon_offsets().to_vec());
 
                     // Set simulation mode based on global progress
-                    let final_full_sim_count = (num_iterations * FULL_RUN_PERCENTAGE) / 100;
                     let total_completed = completed_iterations.load(Ordering::Relaxed);
                      
                     // A run should be a full run if:
                     // 1. Full simulation is forced by command line
-                    // 2. We're in the final X% of iterations (based on FULL_RUN_PERCENTAGE)
+                    // 2. The configured full run strategy selects this iteration
                     // 3. Cache isn't loaded (forcing full sim)
                     let is_full_run = force_full_simulation ||
                         !cache_loaded ||
-                        total_completed >= num_iterations.saturating_sub(final_full_sim_count);
+                        full_run_strategy.is_full_run(i, total_completed, num_iterations);
                      
                     // Set simulation mode
                     map_clone.set_simulation_mode(!is_full_run);
                      
-                    // if total_completed == num_iterations.saturating_sub(final_full_sim_count) {
-                    //     println!("\nSwitching to full simulation mode for final {} iterations ({:.1}% of total)",
-                    //         final_full_sim_count, FULL_RUN_PERCENTAGE as f64);
-                    // }
-                     
                     // Create local weights and immediately drop the read lock
                     let mut local_weights = {
                         let weights = action_weights.read();
@@ -461,7 +736,7 @@ pub fn run_multi_simulation(
                      
                     // Determine if we should replay the best strategy
                     let replay_best_strategy = is_full_run &&
-                        REPLAY_BEST_STRATEGY_IN_FULL_RUNS &&
+                        replay_best_in_full_runs &&
                         local_weights.has_best_actions();
                      
                     // Log if we're replaying the best strategy
@@ -470,6 +745,10 @@ pub fn run_multi_simulation(
                     // }
                      
                     let result = run_iteration(i, &mut map_clone, &mut local_weights, replay_best_strategy, seed, verbose_logging, optimization_mode, enable_energy_sales, enable_construction_delays)?;
+                    if incremental_csv {
+                        let score = crate::ai::score_metrics(&result.metrics, optimization_mode, &scoring);
+                        incremental_best.lock().unwrap().offer(i + 1, score, &result);
+                    }
                      
                     // Track full simulation results for the user prompt functionality
                     if is_full_run {
@@ -477,7 +756,7 @@ pub fn run_multi_simulation(
                         full_sim_data.count += 1;
                         
                         // Get the score for this simulation
-                        let score = crate::ai::score_metrics(&result.metrics, optimization_mode);
+                        let score = crate::ai::score_metrics(&result.metrics, optimization_mode, &scoring);
                         
                         // Update the best full sim score if this is better
                         if score > full_sim_data.best_score {
@@ -504,14 +783,28 @@ pub fn run_multi_simulation(
                         // If we're handling deficit actions, also apply deficit contrast learning
                         weights.apply_deficit_contrast_learning();
                         
+                        // Recorded while the write lock is held, so rows follow the real merge order
+                        if let Some(trace) = &merge_trace {
+                            let mut trace = trace.lock().unwrap();
+                            let merge_order = trace.len();
+                            trace.push(MergeTraceRow {
+                                merge_order,
+                                iteration: i + 1,
+                                thread_id: rayon::current_thread_index().unwrap_or(0),
+                                score: crate::ai::score_metrics(&result.metrics, optimization_mode, &scoring),
+                                best_score_after_merge: weights.get_simulation_metrics()
+                                    .map_or(0.0, |best| crate::ai::score_metrics(best, optimization_mode, &scoring)),
+                            });
+                        }
+                        
                         weights.get_simulation_metrics().cloned()
                     };
                     
                     // Print iteration results at the end of the iteration
-                    let current_score = crate::ai::score_metrics(&result.metrics, optimization_mode);
+                    let current_score = crate::ai::score_metrics(&result.metrics, optimization_mode, &scoring);
                     let thread_id = rayon::current_thread_index().unwrap_or(0);
                     if let Some(best_metrics) = &best_metrics_after_update {
-                        let best_score = crate::ai::score_metrics(best_metrics, optimization_mode);
+                        let best_score = crate::ai::score_metrics(best_metrics, optimization_mode, &scoring);
                         if (i + 1) % 100 == 0 {  // Only print every 100 iterations
                             println!("\n🔄 Iteration {} completed (Thread {}): ", i + 1, thread_id);
                             println!("  Current result: Score {:.6} (Emissions: {:.1} tonnes, Cost: €{:.1}B, Opinion: {:.1}%)",
@@ -541,17 +834,21 @@ pub fn run_multi_simulation(
                     completed_iterations.fetch_add(1, Ordering::Relaxed);
                      
                     // Save checkpoint at intervals
-                    if (i + 1) % checkpoint_interval == 0 {
+                    if output_format.writes_files() && (i + 1) % checkpoint_interval == 0 {
                         let thread_id = rayon::current_thread_index().unwrap_or(0);
-                        let weights = action_weights.write();
+                        let mut weights = action_weights.write();
+                        if let Some(threshold) = prune_weight_threshold {
+                            local_weights.prune_weights(threshold);
+                            weights.prune_weights(threshold);
+                        }
                          
                         // Save thread-specific weights
                         let thread_weights_path = Path::new(&run_dir)
-                            .join(format!("thread_{}_weights.json", thread_id));
+                            .join(crate::ai::learning::constants::checkpoint_file_name(&format!("thread_{}_weights", thread_id)));
                         local_weights.save_to_file(thread_weights_path.to_str().unwrap())?;
                          
                         // Save shared weights
-                        let checkpoint_path = Path::new(&run_dir).join("latest_weights.json");
+                        let checkpoint_path = Path::new(&run_dir).join(crate::ai::learning::constants::checkpoint_file_name("latest_weights"));
                         weights.save_to_file(checkpoint_path.to_str().unwrap())?;
                          
                         // Save weight history if enabled
@@ -563,6 +860,10 @@ pub fn run_multi_simulation(
                         let iteration_path = Path::new(&run_dir).join("checkpoint_iteration.txt");
                         std::fs::write(iteration_path, (i + 1).to_string())?;
                          
+                        if incremental_csv {
+                            incremental_best.lock().unwrap().flush(&run_dir);
+                        }
+                         
                         // println!("Saved checkpoint at iteration {} in {} (thread {})", i + 1, run_dir, thread_id);
                     }
                      
@@ -582,7 +883,7 @@ pub fn run_multi_simulation(
                             let best_score = {
                                 let weights = action_weights.read();
                                 if let Some(best_metrics) = weights.get_simulation_metrics() {
-                                    crate::ai::score_metrics(best_metrics, optimization_mode)
+                                    crate::ai::score_metrics(best_metrics, optimization_mode, &scoring)
                                 } else {
                                     // If no best metrics yet, use a default score of 1.0
                                     1.0
@@ -606,23 +907,29 @@ pub fn run_multi_simulation(
                     }
                      
                     // Return the result (clone not needed anymore since we're returning it)
-                    Ok(result)
+                    Ok(Some(result))
                 })
                 .collect::<Result<Vec<_>, _>>()?;
+            
+            if runtime_stop.load(Ordering::Relaxed) && output_format.writes_files() {
+                let completed = start_iteration + completed_iterations.load(Ordering::Relaxed);
+                save_final_checkpoint(&run_dir, &action_weights.read(), completed)?;
+            }
+            if incremental_csv {
+                finalize_incremental_best(&run_dir)?;
+            }
              
             // Find the best result from all results AFTER the parallel execution completes
-            for result in results {
+            for result in results.into_iter().flatten() {
+                top_results.offer(&result, crate::ai::score_metrics(&result.metrics, optimization_mode, &scoring));
                 if best_result.as_ref().map_or(true, |best| {
-                    evaluate_action_impact(&metrics_to_action_result(&result.metrics), &metrics_to_action_result(&best.metrics), optimization_mode) > 0.0
+                    evaluate_action_impact(&metrics_to_action_result(&result.metrics), &metrics_to_action_result(&best.metrics), optimization_mode, &scoring) > 0.0
                 }) {
                     best_result = Some(result);
                 }
             }
         } else {
-            // Sequential implementation
-            let action_weights = Arc::new(parking_lot::RwLock::new(ActionWeights::new()));
-            
-            // Load initial weights from the shared weights that were loaded earlier
+            // Sequential implementation, on the same shared weights the progress monitor reads
             {
                 let mut weights = action_weights.write();
                 // Use the initial weights that were loaded earlier in the function
@@ -631,9 +938,18 @@ pub fn run_multi_simulation(
             
             // Create a timestamp directory for this run
             let run_dir = format!("{}/{}", checkpoint_dir, Local::now().format("%Y%m%d_%H%M%S"));
-            std::fs::create_dir_all(&run_dir)?;
+            if output_format.writes_files() {
+                std::fs::create_dir_all(&run_dir)?;
+            }
             
             for i in start_iteration..num_iterations {
+                if runtime_exhausted() {
+                    if output_format.writes_files() {
+                        save_final_checkpoint(&run_dir, &action_weights.read(), i)?;
+                    }
+                    break;
+                }
+                
                 // Check if we should continue with full simulations
                 if force_full_simulation {
                     let full_sim_data = full_sim_tracking.lock().unwrap();
@@ -652,25 +968,19 @@ pub fn run_multi_simulation(
                 map_clone.set_carbon_offsets(base_map.get_carbon_offsets().to_vec());
 
                 // Set simulation mode based on global progress
-                let final_full_sim_count = (num_iterations * FULL_RUN_PERCENTAGE) / 100;
                 let total_completed = completed_iterations.load(Ordering::Relaxed);
                  
                 // A run should be a full run if:
                 // 1. Full simulation is forced by command line
-                // 2. We're in the final X% of iterations (based on FULL_RUN_PERCENTAGE)
+                // 2. The configured full run strategy selects this iteration
                 // 3. Cache isn't loaded (forcing full sim)
                 let is_full_run = force_full_simulation ||
                     !cache_loaded ||
-                    total_completed >= num_iterations.saturating_sub(final_full_sim_count);
+                    full_run_strategy.is_full_run(i, total_completed, num_iterations);
                  
                 // Set simulation mode
                 map_clone.set_simulation_mode(!is_full_run);
                  
-                // if total_completed == num_iterations.saturating_sub(final_full_sim_count) {
-                //     println!("\nSwitching to full simulation mode for final {} iterations ({:.1}% of total)",
-                //         final_full_sim_count, FULL_RUN_PERCENTAGE as f64);
-                // }
-                 
                 // Create local weights and immediately drop the read lock
                 let mut local_weights = {
                     let weights = action_weights.read();
@@ -679,7 +989,7 @@ pub fn run_multi_simulation(
                  
                 // Determine if we should replay the best strategy
                 let replay_best_strategy = is_full_run &&
-                    REPLAY_BEST_STRATEGY_IN_FULL_RUNS &&
+                    replay_best_in_full_runs &&
                     local_weights.has_best_actions();
                  
                 // // Log if we're replaying the best strategy
@@ -695,7 +1005,7 @@ pub fn run_multi_simulation(
                     full_sim_data.count += 1;
                     
                     // Get the score for this simulation
-                    let score = crate::ai::score_metrics(&result.metrics, optimization_mode);
+                    let score = crate::ai::score_metrics(&result.metrics, optimization_mode, &scoring);
                     
                     // Update the best full sim score if this is better
                     if score > full_sim_data.best_score {
@@ -708,7 +1018,7 @@ pub fn run_multi_simulation(
                         let best_score = {
                             let weights = action_weights.read();
                             if let Some(best_metrics) = weights.get_simulation_metrics() {
-                                crate::ai::score_metrics(best_metrics, optimization_mode)
+                                crate::ai::score_metrics(best_metrics, optimization_mode, &scoring)
                             } else {
                                 // If no best metrics yet, use a default score of 1.0
                                 1.0
@@ -748,14 +1058,27 @@ pub fn run_multi_simulation(
                     // If we're handling deficit actions, also apply deficit contrast learning
                     weights.apply_deficit_contrast_learning();
                     
+                    if let Some(trace) = &merge_trace {
+                        let mut trace = trace.lock().unwrap();
+                        let merge_order = trace.len();
+                        trace.push(MergeTraceRow {
+                            merge_order,
+                            iteration: i + 1,
+                            thread_id: 0,
+                            score: crate::ai::score_metrics(&result.metrics, optimization_mode, &scoring),
+                            best_score_after_merge: weights.get_simulation_metrics()
+                                .map_or(0.0, |best| crate::ai::score_metrics(best, optimization_mode, &scoring)),
+                        });
+                    }
+                    
                     weights.get_simulation_metrics().cloned()
                 };
                 
                 // Print iteration results at the end of the iteration
-                let current_score = crate::ai::score_metrics(&result.metrics, optimization_mode);
+                let current_score = crate::ai::score_metrics(&result.metrics, optimization_mode, &scoring);
                 let thread_id = 0;  // In sequential mode, thread is always 0
                 if let Some(best_metrics) = &best_metrics_after_update {
-                    let best_score = crate::ai::score_metrics(best_metrics, optimization_mode);
+                    let best_score = crate::ai::score_metrics(best_metrics, optimization_mode, &scoring);
                     if (i + 1) % 100 == 0 {  // Only print every 100 iterations
                         println!("\n🔄 Iteration {} completed (Thread {}): ", i + 1, thread_id);
                         println!("  Current result: Score {:.6} (Emissions: {:.1} tonnes, Cost: €{:.1}B, Opinion: {:.1}%)",
@@ -783,17 +1106,24 @@ pub fn run_multi_simulation(
                  
                 // Store each result for later comparison
                 let curr_result = result.clone();
+                if incremental_csv {
+                    let score = crate::ai::score_metrics(&curr_result.metrics, optimization_mode, &scoring);
+                    incremental_best.lock().unwrap().offer(i + 1, score, &curr_result);
+                }
                  
                 // Increment completed iterations counter
                 completed_iterations.fetch_add(1, Ordering::Relaxed);
                  
                 // Save checkpoint at intervals
-                if (i + 1) % checkpoint_interval == 0 {
-                    let checkpoint_path = Path::new(&run_dir).join("latest_weights.json");
+                if output_format.writes_files() && (i + 1) % checkpoint_interval == 0 {
+                    let checkpoint_path = Path::new(&run_dir).join(crate::ai::learning::constants::checkpoint_file_name("latest_weights"));
                      
                     // Get a write lock to save the weights
                     {
-                        let weights = action_weights.write();
+                        let mut weights = action_weights.write();
+                        if let Some(threshold) = prune_weight_threshold {
+                            weights.prune_weights(threshold);
+                        }
                         weights.save_to_file(checkpoint_path.to_str().unwrap())?;
                         
                         // Save weight history if enabled
@@ -806,18 +1136,41 @@ pub fn run_multi_simulation(
                     let iteration_path = Path::new(&run_dir).join("checkpoint_iteration.txt");
                     std::fs::write(iteration_path, (i + 1).to_string())?;
                      
+                    if incremental_csv {
+                        incremental_best.lock().unwrap().flush(&run_dir);
+                    }
+                     
                     // println!("Saved checkpoint at iteration {} in {}", i + 1, run_dir);
                 }
                  
                 // Check if this result is better than our best result
+                top_results.offer(&curr_result, crate::ai::score_metrics(&curr_result.metrics, optimization_mode, &scoring));
                 if best_result.as_ref().map_or(true, |best| {
-                    evaluate_action_impact(&metrics_to_action_result(&curr_result.metrics), &metrics_to_action_result(&best.metrics), optimization_mode) > 0.0
+                    evaluate_action_impact(&metrics_to_action_result(&curr_result.metrics), &metrics_to_action_result(&best.metrics), optimization_mode, &scoring) > 0.0
                 }) {
                     best_result = Some(curr_result);
                 }
             }
+            if incremental_csv {
+                finalize_incremental_best(&run_dir)?;
+            }
         }
          
+        // Final event so callers always see the finished state, even on runs shorter than one interval
+        progress_callback(progress_event(
+            &action_weights.read(),
+            base_map.get_config(),
+            completed_iterations.load(Ordering::Relaxed),
+            total_iterations,
+            start_time.elapsed(),
+        ));
+
+        if let Some(trace) = merge_trace.as_ref().filter(|_| output_format.writes_csv()) {
+            let trace_path = Path::new(&run_dir).join("merge_trace.csv");
+            write_merge_trace(&trace_path, &trace.lock().unwrap())?;
+            println!("Wrote merge trace to {}", trace_path.display());
+        }
+
         if let Some(best) = best_result {
             println!("\n{}", "=".repeat(80));
             println!("🏆 BEST SIMULATION RESULTS SUMMARY 🏆");
@@ -825,18 +1178,9 @@ pub fn run_multi_simulation(
             println!("Final net emissions: {:.2} tonnes", best.metrics.final_net_emissions);
              
             // Add emoji indicators for success/failure
-            let emissions_status = if best.metrics.final_net_emissions <= 0.0 {
-                "✅ NET ZERO ACHIEVED".to_string()
-            } else {
-                "❌ NET ZERO NOT ACHIEVED".to_string()
-            };
-             
-            let cost_status = if best.metrics.total_cost <= MAX_ACCEPTABLE_COST {
-                "✅ WITHIN BUDGET".to_string()
-            } else {
-                format!("❌ {:.1}% OVER BUDGET",
-                    ((best.metrics.total_cost - MAX_ACCEPTABLE_COST) / (MAX_ACCEPTABLE_COST)) * 100.0)
-            };
+            let feasibility = best.metrics.is_feasible(base_map.get_config());
+            let emissions_status = feasibility.emissions_status();
+            let cost_status = feasibility.cost_status();
              
             println!("Emissions Status: {}", emissions_status);
             println!("Average public opinion: {:.1}%", best.metrics.average_public_opinion * 100.0);
@@ -847,16 +1191,17 @@ pub fn run_multi_simulation(
                 total_cost_billions, cost_status);
              
             println!("Power reliability: {:.1}%", best.metrics.power_reliability * 100.0);
+            println!("Feasible: {}", if feasibility.is_feasible() { "✅ YES" } else { "❌ NO" });
             println!("{}", "=".repeat(80));
              
             // Use our enhanced CSV exporter for more detailed data export
             let csv_export_dir = Path::new(&run_dir).join("enhanced_csv");
-            std::fs::create_dir_all(&csv_export_dir)?;
             
-            if enable_csv_export {
+            if output_format.writes_csv() && enable_csv_export {
+                std::fs::create_dir_all(&csv_export_dir)?;
                 // Create a CSV exporter instance
                 let _timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
-                let csv_exporter = csv_export::CsvExporter::new(&csv_export_dir, verbose_logging);
+                let csv_exporter = csv_export::CsvExporter::new(&csv_export_dir);
                 
                 // Create a new map and apply all the best actions to it
                 let mut final_map = base_map.clone();
@@ -886,18 +1231,24 @@ pub fn run_multi_simulation(
 
                 println!("Applying all actions to map for CSV export...");
                 for (year, action) in &best.actions {
-                    if let Err(e) = apply_action(&mut final_map, action, *year) {
+                    // Stamp new plant with the year it was built so its age is right
+                    final_map.current_year = *year;
+                    if let Err(e) = apply_action(&mut final_map, action, *year, None) {
                         println!("Warning: Failed to apply action {:?} for year {}: {}", action, year, e);
                     }
                 }
                 
+                if let Err(e) = export_fleet_age(&final_map, END_YEAR, &csv_export_dir.join("fleet_age.csv")) {
+                    println!("Warning: Failed to export fleet age profile: {}", e);
+                }
+                
                 // Export all simulation results with detailed data using the final map
-                println!("DIAGNOSTIC: Metrics before CSV export:");
-                println!("  - final_net_emissions: {}", best.metrics.final_net_emissions);
-                println!("  - total_cost: {}", best.metrics.total_cost);
-                println!("  - average_public_opinion: {}", best.metrics.average_public_opinion);
-                println!("  - power_reliability: {}", best.metrics.power_reliability);
-                println!("  - Number of yearly metrics: {}", best.yearly_metrics.len());
+                crate::log!(Debug, "DIAGNOSTIC: Metrics before CSV export:");
+                crate::log!(Debug, "  - final_net_emissions: {}", best.metrics.final_net_emissions);
+                crate::log!(Debug, "  - total_cost: {}", best.metrics.total_cost);
+                crate::log!(Debug, "  - average_public_opinion: {}", best.metrics.average_public_opinion);
+                crate::log!(Debug, "  - power_reliability: {}", best.metrics.power_reliability);
+                crate::log!(Debug, "  - Number of yearly metrics: {}", best.yearly_metrics.len());
                 
                 if let Ok(()) = csv_exporter.export_simulation_results(
                     &final_map,
@@ -981,6 +1332,7 @@ pub fn run_multi_simulation(
                                 if let Some(gen) = generator {
                                     let base_max = match gen.get_generator_type() {
                                         GeneratorType::OnshoreWind | GeneratorType::OffshoreWind => ONSHORE_OFFSHORE_WIND_EFFICIENCY,
+                                        GeneratorType::FloatingOffshoreWind => FLOATING_OFFSHORE_WIND_EFFICIENCY,
                                         GeneratorType::UtilitySolar => UTILITY_SOLAR_EFFICIENCY,
                                         GeneratorType::Nuclear => NUCLEAR_EFFICIENCY,
                                         GeneratorType::GasCombinedCycle => GAS_COMBINED_CYCLE_EFFICIENCY,
@@ -991,17 +1343,18 @@ pub fn run_multi_simulation(
                                     let tech_improvement = match gen.get_generator_type() {
                                         GeneratorType::OnshoreWind | GeneratorType::OffshoreWind |
                                         GeneratorType::UtilitySolar => DEVELOPING_TECH_IMPROVEMENT_RATE,
+                                        GeneratorType::FloatingOffshoreWind |
                                         GeneratorType::TidalGenerator | GeneratorType::WaveEnergy => EMERGING_TECH_IMPROVEMENT_RATE,
                                         _ => MATURE_TECH_IMPROVEMENT_RATE,
                                     }.powi((year - BASE_YEAR) as i32);
                                     let max_efficiency = base_max * (1.0 + (1.0 - tech_improvement));
-                                    let upgrade_cost = gen.get_current_cost(*year) * (max_efficiency - gen.get_efficiency()) * 2.0;
+                                    let upgrade_cost = gen.upgrade_cost(*year, max_efficiency, base_map.get_generator_constraints().upgrade_cost_multiplier, &base_map.get_config().inflation);
                                     
                                     (
                                         String::from("Upgrade Efficiency"),
                                         gen.get_generator_type().to_string(),
                                         upgrade_cost,            // capital cost
-                                        gen.get_current_operating_cost(*year), // operating cost
+                                        gen.get_current_operating_cost(*year, &base_map.get_config().inflation), // operating cost
                                         gen.get_coordinate().x,             // location_x
                                         gen.get_coordinate().y,             // location_y
                                         gen.get_generator_type().to_string(), // generator type
@@ -1025,7 +1378,7 @@ pub fn run_multi_simulation(
                                         String::from("Adjust Operation"),
                                         gen.get_generator_type().to_string(),
                                         0.0,                     // capital cost (no cost for adjustment)
-                                        gen.get_current_operating_cost(*year), // operating cost
+                                        gen.get_current_operating_cost(*year, &base_map.get_config().inflation), // operating cost
                                         gen.get_coordinate().x,             // location_x
                                         gen.get_coordinate().y,             // location_y
                                         gen.get_generator_type().to_string(), // generator type
@@ -1073,7 +1426,7 @@ pub fn run_multi_simulation(
                                     0.85,                    // Default efficiency (as seen in actions.rs)
                                     0.0,                     // No direct CO2 output (it reduces CO2)
                                     100,                     // Always fully operational
-                                    30,                      // Standard offset lifespan
+                                    offset_type.lifespan(),  // Offset lifespan
                                     String::from("New Offset"), // previous state
                                     format!("Added new {} carbon offset", offset_type.to_string()) // impact
                                 )
@@ -1101,9 +1454,78 @@ pub fn run_multi_simulation(
                                     continue; // Skip if generator not found
                                 }
                             },
-                            GridAction::DoNothing => {
+                            GridAction::RetrofitGenerator(id, target) => {
+                                if let Some(gen) = base_map.get_generators().iter().find(|g| g.get_id() == id) {
+                                    // Convert a copy so the row shows the plant as it runs afterwards
+                                    let mut converted = gen.clone();
+                                    let retrofit_cost = converted.retrofit(target, *year).unwrap_or(0.0);
+                                    (
+                                        String::from("RetrofitGenerator"),
+                                        target.to_string(),
+                                        retrofit_cost,           // capital cost (conversion)
+                                        converted.get_current_operating_cost(*year, &base_map.get_config().inflation), // operating cost
+                                        gen.get_coordinate().x,   // location_x
+                                        gen.get_coordinate().y,   // location_y
+                                        converted.get_generator_type().to_string(), // generator type
+                                        converted.get_current_power_output(None), // power output
+                                        converted.get_efficiency(), // efficiency
+                                        converted.get_co2_output(), // co2 output
+                                        converted.get_operation_percentage() as i32, // operation percentage
+                                        converted.eol,            // lifespan
+                                        gen.get_generator_type().to_string(), // previous state
+                                        format!("Retrofitted {} from {} to {}", gen.get_id(), gen.get_generator_type(), target) // impact
+                                    )
+                                } else {
+                                    continue; // Skip if generator not found
+                                }
+                            },
+                            GridAction::AddHybridPlant(primary, secondary) => {
+                                let co2_output = match primary {
+                                    GeneratorType::CoalPlant => COAL_CO2_RATE,
+                                    GeneratorType::GasCombinedCycle => GAS_CC_CO2_RATE,
+                                    GeneratorType::GasPeaker => GAS_PEAKER_CO2_RATE,
+                                    GeneratorType::Biomass => BIOMASS_CO2_RATE,
+                                    _ => 0.0,
+                                } * primary.get_base_power(*year);
+                                
+                                (
+                                    String::from("AddHybridPlant"),
+                                    format!("{}+{}", primary, secondary),
+                                    primary.get_base_cost(*year) + secondary.get_base_cost(*year), // capital cost
+                                    primary.get_operating_cost(*year) + secondary.get_operating_cost(*year), // operating cost
+                                    0.0,                            // location_x (will be set during actual creation)
+                                    0.0,                            // location_y (will be set during actual creation)
+                                    primary.to_string(),            // generator type of the host
+                                    primary.get_base_power(*year),  // host power output
+                                    primary.get_base_efficiency(*year), // efficiency
+                                    co2_output,                     // host co2 output
+                                    100,                            // Default to 100% operation
+                                    primary.get_lifespan(),         // lifespan
+                                    String::from("New Generator"),  // previous state
+                                    format!("Added {} with co-located {}", primary, secondary) // impact
+                                )
+                            },
+                            GridAction::AddFlexibleLoad => {
+                                (
+                                    String::from("AddFlexibleLoad"),
+                                    String::from("Managed EV charging"),
+                                    0.0,             // capital cost (sized to the settlement when applied)
+                                    0.0,             // operating cost
+                                    0.0,             // location_x
+                                    0.0,             // location_y
+                                    String::new(),   // generator type
+                                    0.0,             // power output
+                                    0.0,             // efficiency
+                                    0.0,             // co2 output
+                                    0,               // operation percentage
+                                    0,               // lifespan
+                                    String::new(),   // previous state
+                                    String::from("Shifted EV charging from deficit to surplus hours")
+                                )
+                            },
+                            GridAction::DoNothing | GridAction::DeferDecision => {
                                 (
-                                    String::from("Do Nothing"),
+                                    String::from(if matches!(action, GridAction::DeferDecision) { "Defer Decision" } else { "Do Nothing" }),
                                     String::new(), // no details
                                     0.0,             // capital cost
                                     0.0,             // operating cost
@@ -1143,7 +1565,7 @@ pub fn run_multi_simulation(
                      
                     println!("Basic action history saved to: {}", actions_filename.display());
                 }
-            } else {
+            } else if output_format.writes_csv() {
                 println!("\nCSV export is disabled. Use --enable-csv-export to generate detailed CSV files.");
                 
                 // Create a minimal results file with basic info even when CSV export is disabled
@@ -1154,14 +1576,63 @@ pub fn run_multi_simulation(
                 file.write_all(format!("Average Public Opinion (%),{:.2}\n", best.metrics.average_public_opinion * 100.0).as_bytes())?;
                 file.write_all(format!("Total Cost (€),{:.2}\n", best.metrics.total_cost).as_bytes())?;
                 file.write_all(format!("Power Reliability (%),{:.2}\n", best.metrics.power_reliability * 100.0).as_bytes())?;
+                file.write_all(format!("Net Zero,{}\n", feasibility.net_zero).as_bytes())?;
+                file.write_all(format!("Within Budget,{}\n", feasibility.within_budget).as_bytes())?;
+                file.write_all(format!("Reliable,{}\n", feasibility.reliable).as_bytes())?;
+                file.write_all(format!("Feasible,{}\n", feasibility.is_feasible()).as_bytes())?;
                 println!("Basic simulation summary saved to: {}", csv_filename.display());
             }
             
+            if output_format.writes_json() {
+                let result_path = Path::new(&run_dir).join("best_result.json");
+                let result_json = serde_json::json!({
+                    "metrics": best.metrics,
+                    "feasibility": feasibility,
+                    "yearly_metrics": best.yearly_metrics,
+                    "actions": best.actions,
+                });
+                std::fs::write(&result_path, serde_json::to_string_pretty(&result_json)?)?;
+                println!("Best result saved to: {}", result_path.display());
+            }
+            
+            if keep_top_results > 1 && output_format.writes_files() {
+                export_top_results(&run_dir, &top_results.into_ranked(), output_format)?;
+            }
+            
             // Save final weights in the run directory
-            let final_weights_path = Path::new(&run_dir).join("best_weights.json");
             let weights = action_weights.write();
-            weights.save_to_file(final_weights_path.to_str().unwrap())?;
-            println!("Final weights saved to: {}", final_weights_path.display());
+            if output_format.writes_files() {
+                let final_weights_path = Path::new(&run_dir).join("best_weights.json");
+                weights.save_to_file(final_weights_path.to_str().unwrap())?;
+                println!("Final weights saved to: {}", final_weights_path.display());
+            }
+            
+            // Put uncertainty bands on the best plan by replaying it under sampled renewable availability
+            if base_map.get_config().renewable_uncertainty.draws > 0 {
+                let plan = weights.export_plan();
+                let summary = run_availability_monte_carlo(&base_map, &plan, seed, enable_energy_sales);
+                summary.print();
+                
+                if output_format.writes_json() {
+                    let uncertainty_path = Path::new(&run_dir).join("uncertainty_summary.json");
+                    std::fs::write(&uncertainty_path, serde_json::to_string_pretty(&summary)?)?;
+                    println!("Uncertainty summary saved to: {}", uncertainty_path.display());
+                }
+            }
+            
+            // Explain the best plan by attributing emissions and cost changes to each action
+            let impacts = weights.attribute_impact(&base_map, enable_energy_sales);
+            ActionWeights::print_impact_attribution(&impacts, 10);
+            if output_format.writes_csv() && enable_csv_export && !impacts.is_empty() {
+                let impact_path = Path::new(&run_dir).join("action_impact.csv");
+                crate::utils::csv_export::write_action_impacts(&impact_path, &impacts)?;
+                println!("Action impact attribution saved to: {}", impact_path.display());
+
+                let abatement = crate::analysis::analysis::marginal_abatement_costs(&base_map, &weights.export_plan())?;
+                let abatement_path = Path::new(&run_dir).join("marginal_abatement_cost.csv");
+                crate::utils::csv_export::write_abatement_costs(&abatement_path, &abatement)?;
+                println!("Marginal abatement costs saved to: {}", abatement_path.display());
+            }
             
             // After all iterations, check if we need to run additional full simulations
             if force_full_simulation {
@@ -1170,7 +1641,7 @@ pub fn run_multi_simulation(
                 // Get the current best score from the weights
                 let best_score = {
                     if let Some(best_metrics) = weights.get_simulation_metrics() {
-                        crate::ai::score_metrics(best_metrics, optimization_mode)
+                        crate::ai::score_metrics(best_metrics, optimization_mode, &scoring)
                     } else {
                         // If no best metrics yet, use a default score of 1.0
                         1.0
@@ -1224,6 +1695,9 @@ pub fn run_multi_simulation(
                             debug_weights,
                             enable_construction_delays,
                             track_weight_history,
+                            None, // pick up the weights this run just saved
+                            binary_checkpoints,
+                            Some(progress_callback),
                         );
                     }
                 }
@@ -1239,4 +1713,261 @@ pub fn run_multi_simulation(
     }
      
     result
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::metrics_calculation::calculate_yearly_metrics;
+    use crate::config::simulation_config::OutputFormat;
+    use crate::config::constants::DEFAULT_COST_MULTIPLIER;
+    use crate::test_support::{scratch_path, small_map, test_metrics};
+
+    #[test]
+    fn resuming_keeps_the_saved_best_metrics() {
+        let path = scratch_path("resume_weights.json");
+        let mut saved = ActionWeights::new();
+        saved.update_best_strategy(test_metrics(2.5e6, 3.0e10, 0.7));
+        saved.save_to_file(path.to_str().unwrap()).unwrap();
+
+        let resumed = load_resume_weights(path.to_str().unwrap()).unwrap();
+
+        let best = resumed.best_metrics.as_ref().expect("best metrics survive the round trip");
+        assert_eq!(best.final_net_emissions, 2.5e6);
+        assert_eq!(best.total_cost, 3.0e10);
+        assert_eq!(best.average_public_opinion, 0.7);
+        assert_eq!(resumed.get_best_metrics(), saved.get_best_metrics());
+
+        let missing = scratch_path("missing_weights.json");
+        assert!(matches!(load_resume_weights(missing.to_str().unwrap()), Err(SimulationError::Checkpoint(_))));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn progress_callback_fires_with_a_best_score() {
+        let mut map = small_map();
+        let mut config = map.get_config().clone();
+        config.output_format = OutputFormat::None;
+        map.set_config(config);
+        let checkpoint_dir = scratch_path("progress_checkpoints");
+        let checkpoint_dir = checkpoint_dir.to_str().unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        let callback: ProgressCallback = Arc::new(move |event: ProgressEvent| sink.lock().unwrap().push(event));
+
+        run_multi_simulation(
+            &map, 2, false, false, checkpoint_dir, 100, 3600, checkpoint_dir, false, Some(9),
+            false, None, true, false, false, false, false, None, false, Some(callback),
+        ).unwrap();
+
+        let events = events.lock().unwrap();
+        let last = events.last().expect("the callback fired at least once");
+        assert_eq!(last.completed_iterations, 2);
+        assert!(last.best_score.is_some());
+        let _ = std::fs::remove_dir_all(checkpoint_dir);
+    }
+
+    #[test]
+    fn merge_trace_has_one_row_per_iteration_on_a_serial_run() {
+        let mut map = small_map();
+        let mut config = map.get_config().clone();
+        config.output_format = OutputFormat::Csv;
+        config.trace_merges = true;
+        map.set_config(config);
+        let checkpoint_dir = scratch_path("merge_trace_checkpoints");
+        let quiet: ProgressCallback = Arc::new(|_: ProgressEvent| {});
+
+        run_multi_simulation(
+            &map, 3, false, false, checkpoint_dir.to_str().unwrap(), 100, 3600, checkpoint_dir.to_str().unwrap(), false, Some(4),
+            false, None, true, false, false, false, false, None, false, Some(quiet),
+        ).unwrap();
+
+        // The trace lands in the run's timestamped directory
+        let run_dir = std::fs::read_dir(&checkpoint_dir).unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.join("merge_trace.csv").exists())
+            .expect("a run directory with a merge trace");
+        let mut reader = csv::Reader::from_path(run_dir.join("merge_trace.csv")).unwrap();
+        let mut iterations: Vec<usize> = reader.deserialize::<(usize, usize, usize, f64, f64)>()
+            .map(|row| row.unwrap().1)
+            .collect();
+        iterations.sort_unstable();
+        assert_eq!(iterations, vec![1, 2, 3]);
+        let _ = std::fs::remove_dir_all(checkpoint_dir);
+    }
+
+    #[test]
+    fn output_format_none_writes_no_files() {
+        let mut map = small_map();
+        let mut config = map.get_config().clone();
+        config.output_format = OutputFormat::None;
+        config.trace_merges = true;
+        map.set_config(config);
+        let checkpoint_dir = scratch_path("no_output_checkpoints");
+        let quiet: ProgressCallback = Arc::new(|_: ProgressEvent| {});
+
+        // CSV export and weight history both requested, and still nothing may be written
+        run_multi_simulation(
+            &map, 2, false, false, checkpoint_dir.to_str().unwrap(), 1, 3600, checkpoint_dir.to_str().unwrap(), false, Some(6),
+            false, None, true, true, false, false, true, None, false, Some(quiet),
+        ).unwrap();
+
+        assert!(!checkpoint_dir.exists(), "{} was created", checkpoint_dir.display());
+    }
+
+    #[test]
+    fn tiny_runtime_budget_halts_early_and_still_writes_outputs() {
+        let mut map = small_map();
+        let mut config = map.get_config().clone();
+        config.output_format = OutputFormat::Json;
+        config.max_runtime_secs = Some(1);
+        map.set_config(config);
+        let checkpoint_dir = scratch_path("runtime_budget_checkpoints");
+        let quiet: ProgressCallback = Arc::new(|_: ProgressEvent| {});
+        let num_iterations = 1_000_000;
+
+        run_multi_simulation(
+            &map, num_iterations, false, false, checkpoint_dir.to_str().unwrap(), 100_000, 3600, checkpoint_dir.to_str().unwrap(), false, Some(5),
+            false, None, true, false, false, false, false, None, false, Some(quiet),
+        ).unwrap();
+
+        let run_dirs: Vec<_> = std::fs::read_dir(&checkpoint_dir).unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        let written = |name: &str| run_dirs.iter().map(|dir| dir.join(name)).find(|path| path.exists());
+
+        let stopped_at: usize = std::fs::read_to_string(written("checkpoint_iteration.txt").expect("a final checkpoint"))
+            .unwrap().trim().parse().unwrap();
+        assert!(stopped_at > 0 && stopped_at < num_iterations, "stopped at iteration {}", stopped_at);
+        assert!(written("best_result.json").is_some());
+        assert!(written("best_weights.json").is_some());
+        let _ = std::fs::remove_dir_all(checkpoint_dir);
+    }
+
+    #[test]
+    fn top_three_results_land_in_distinct_directories_best_first() {
+        let mut map = small_map();
+        let mut config = map.get_config().clone();
+        config.output_format = OutputFormat::Json;
+        config.keep_top_results = 3;
+        // Without a location cache every run is a full run, which would otherwise replay the best
+        // plan. A fixed seed would also repeat one plan, so the run is unseeded.
+        config.replay_best_in_full_runs = false;
+        map.set_config(config);
+        let checkpoint_dir = scratch_path("top_results_checkpoints");
+        let quiet: ProgressCallback = Arc::new(|_: ProgressEvent| {});
+
+        run_multi_simulation(
+            &map, 8, false, false, checkpoint_dir.to_str().unwrap(), 100, 3600, checkpoint_dir.to_str().unwrap(), false, None,
+            false, None, true, false, false, false, false, None, false, Some(quiet),
+        ).unwrap();
+
+        let top_dir = std::fs::read_dir(&checkpoint_dir).unwrap()
+            .map(|entry| entry.unwrap().path().join("top_results"))
+            .find(|path| path.exists())
+            .expect("a run directory with top results");
+        let ranked: Vec<serde_json::Value> = (1..=3)
+            .map(|rank| {
+                let contents = std::fs::read_to_string(top_dir.join(format!("rank_{}", rank)).join("result.json")).unwrap();
+                serde_json::from_str(&contents).unwrap()
+            })
+            .collect();
+        assert!(!top_dir.join("rank_4").exists());
+
+        let scores: Vec<f64> = ranked.iter().map(|result| result["score"].as_f64().unwrap()).collect();
+        assert!(scores.windows(2).all(|pair| pair[0] >= pair[1]), "{:?}", scores);
+        for (i, a) in ranked.iter().enumerate() {
+            for b in &ranked[i + 1..] {
+                assert_ne!(a["actions"], b["actions"]);
+            }
+        }
+        let _ = std::fs::remove_dir_all(checkpoint_dir);
+    }
+
+    #[test]
+    fn interrupted_run_leaves_a_valid_partial_csv() {
+        let map = small_map();
+        let result = |emissions: f64| {
+            let mut previous = None;
+            let yearly_metrics: Vec<_> = (2025..=2027)
+                .map(|year| {
+                    let metrics = calculate_yearly_metrics(&map, year, emissions, 0.0, true, previous.as_ref());
+                    previous = Some(metrics.clone());
+                    metrics
+                })
+                .collect();
+            SimulationResult {
+                metrics: test_metrics(emissions, 1.0e9, 0.6),
+                output: String::new(),
+                actions: Vec::new(),
+                yearly_metrics,
+                retirements: Vec::new(),
+            }
+        };
+        let run_dir = scratch_path("interrupted_run");
+        std::fs::create_dir_all(&run_dir).unwrap();
+        let run_dir_str = run_dir.to_str().unwrap();
+
+        // Checkpoints after iterations 1, 2 and 3; iteration 2 was no improvement, so nothing is appended for it
+        let mut incremental = IncrementalBest::default();
+        for (iteration, score, emissions) in [(1, 0.5, 2.0e6), (2, 0.4, 3.0e6), (3, 0.7, 1.0e6)] {
+            incremental.offer(iteration, score, &result(emissions));
+            incremental.flush(run_dir_str);
+        }
+
+        // The run stops here without finalizing
+        let partial = run_dir.join(PARTIAL_YEARLY_METRICS_FILE);
+        let mut reader = csv::Reader::from_path(&partial).unwrap();
+        let rows: Vec<csv::StringRecord> = reader.records().map(|row| row.expect("a well-formed row")).collect();
+        let iterations: Vec<&str> = rows.iter().map(|row| &row[0]).collect();
+        assert_eq!(iterations, ["1", "1", "1", "3", "3", "3"]);
+        let years: Vec<&str> = rows.iter().map(|row| &row[2]).collect();
+        assert_eq!(years, ["2025", "2026", "2027", "2025", "2026", "2027"]);
+
+        // A run that does complete moves the file to its final name
+        finalize_incremental_best(run_dir_str).unwrap();
+        assert!(!partial.exists());
+        assert!(run_dir.join(BEST_YEARLY_METRICS_HISTORY_FILE).exists());
+        let _ = std::fs::remove_dir_all(run_dir);
+    }
+
+    #[test]
+    fn scoring_is_deterministic_and_keeps_the_input_order() {
+        let map = small_map();
+        let wind_plan = vec![(2026, GridAction::AddGenerator(GeneratorType::OnshoreWind, DEFAULT_COST_MULTIPLIER))];
+        let gas_closure = vec![(2030, GridAction::CloseGenerator("Existing_Gas".to_string()))];
+        let plans = vec![wind_plan.clone(), Vec::new(), gas_closure.clone(), wind_plan];
+
+        let first = score_plans(&map, &plans).unwrap();
+        let second = score_plans(&map, &plans).unwrap();
+        assert_eq!(first.len(), plans.len());
+
+        let summary = |metrics: &SimulationMetrics| (metrics.final_net_emissions, metrics.total_cost, metrics.average_public_opinion);
+        for (a, b) in first.iter().zip(&second) {
+            assert_eq!(summary(a), summary(b));
+        }
+        // The same plan scores the same wherever it sits in the batch
+        assert_eq!(summary(&first[0]), summary(&first[3]));
+
+        // Each slot holds the score of the plan at that index
+        for (plan, metrics) in plans.iter().zip(&first) {
+            let alone = score_plans(&map, std::slice::from_ref(plan)).unwrap();
+            assert_eq!(summary(&alone[0]), summary(metrics));
+        }
+        assert_ne!(summary(&first[1]), summary(&first[0]));
+        assert_ne!(summary(&first[1]), summary(&first[2]));
+    }
+
+    #[test]
+    fn scoring_keeps_the_base_map_flexible_loads() {
+        let mut map = small_map();
+        apply_action(&mut map, &GridAction::AddFlexibleLoad, BASE_YEAR, None).unwrap();
+        let plan = vec![(2026, GridAction::AddGenerator(GeneratorType::OnshoreWind, DEFAULT_COST_MULTIPLIER))];
+
+        let scored = score_plans(&map, std::slice::from_ref(&plan)).unwrap();
+        let replayed = evaluate_action_plan(&mut map.clone(), &plan, false, map.enable_construction_delays).unwrap();
+
+        assert_eq!(scored[0].total_cost, replayed.metrics.total_cost);
+    }
+}
@@ -1,9 +1,11 @@
-use std::error::Error;
+use crate::error::SimulationError;
 use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
 use crate::utils::map_handler::Map;
 use super::action_weights::ActionWeights;
-use super::action_weights::{GridAction, ActionResult, evaluate_action_impact};
+use super::action_weights::{GridAction, ActionResult, ScoringContext, evaluate_action_impact, score_metrics};
+use super::iteration::final_simulation_metrics;
+use crate::analysis::metrics::SimulationResult;
 use crate::analysis::metrics::YearlyMetrics;
 use crate::utils::logging::{self, OperationCategory, PowerCalcType};
 use crate::utils::logging::WeightsUpdateType;
@@ -11,13 +13,38 @@ use crate::config::const_funcs;
 use crate::analysis::metrics_calculation::{calculate_yearly_metrics, calculate_average_opinion};
 use crate::analysis::reporting::{print_yearly_summary, print_generator_details};
 use crate::config::constants::{MAX_ACCEPTABLE_COST, BASE_YEAR, END_YEAR, DEFAULT_COST_MULTIPLIER};
-use super::actions::apply_action;
+use super::actions::{apply_action, bind_action_target, defer_decision_reward};
 use crate::models::generator::GeneratorType;
 use chrono::Local;
+use rayon::prelude::*;
 use std::fs::File;
 use std::io::Write;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
 
 
+// Grow each settlement's population and power usage for the given year
+pub fn update_population(map: &mut Map, year: u32) {
+    if year <= BASE_YEAR {
+        return;
+    }
+
+    let _timing = logging::start_timing("update_population", OperationCategory::Simulation);
+    let per_capita_usage = const_funcs::calc_power_usage_per_capita(year) +
+        const_funcs::calc_electrification_usage_per_capita(&map.get_config().electrification, year);
+    for settlement in map.get_settlements_mut() {
+        let current_pop = settlement.get_population();
+        // Follow known census data where it covers the year, otherwise apply the Irish
+        // population growth rate (roughly 1% per year)
+        let new_pop = settlement.known_population(year)
+            .unwrap_or_else(|| (current_pop as f64 * 1.01).round() as u32);
+        settlement.update_population(new_pop);
+         
+        // Also update power usage based on new population and per capita usage
+        let new_usage = (new_pop as f64) * per_capita_usage;
+        settlement.update_power_usage(new_usage);
+    }
+}
 
 pub fn run_simulation(
     map: &mut Map,
@@ -28,8 +55,36 @@ pub fn run_simulation(
     enable_energy_sales: bool,
     enable_construction_delays: bool,
     iteration: usize,
-) -> Result<(String, Vec<(u32, GridAction)>, Vec<YearlyMetrics>), Box<dyn Error + Send + Sync>> {
+) -> Result<(String, Vec<(u32, GridAction)>, Vec<YearlyMetrics>), SimulationError> {
     let _timing = logging::start_timing("run_simulation", OperationCategory::Simulation);
+
+    let flags = RunFlags { verbose_logging, optimization_mode, enable_energy_sales, enable_construction_delays };
+    simulate_years(map, action_weights, seed, flags, iteration, BASE_YEAR..=END_YEAR, None)
+}
+
+// Switches that stay fixed for a whole run
+#[derive(Debug, Clone, Copy)]
+struct RunFlags<'a> {
+    verbose_logging: bool,
+    optimization_mode: Option<&'a str>,
+    enable_energy_sales: bool,
+    enable_construction_delays: bool,
+}
+
+// Simulates `years` on `map` as `run_simulation` does; `previous_metrics` is the year before
+// the range, for runs picking up from an already simulated state. The output string is only
+// filled in once END_YEAR is simulated.
+fn simulate_years(
+    map: &mut Map,
+    action_weights: Option<&mut ActionWeights>,
+    seed: Option<u64>,
+    flags: RunFlags,
+    iteration: usize,
+    years: RangeInclusive<u32>,
+    previous_metrics: Option<&YearlyMetrics>,
+) -> Result<(String, RecordedActions, Vec<YearlyMetrics>), SimulationError> {
+    let RunFlags { verbose_logging, optimization_mode, enable_energy_sales, enable_construction_delays } = flags;
+    let first_year = *years.start();
      
     // Set construction delays flag
     map.set_enable_construction_delays(enable_construction_delays);
@@ -38,9 +93,6 @@ pub fn run_simulation(
     let mut recorded_actions = Vec::new();
     let mut yearly_metrics_collection = Vec::new();
      
-    let total_upgrade_costs = 0.0;
-    let total_closure_costs = 0.0;
-     
     let mut local_weights = match action_weights.as_deref() {
         Some(weights) => weights.clone(),
         None => ActionWeights::new(),
@@ -77,7 +129,7 @@ pub fn run_simulation(
     // At the beginning of the simulation, diagnose best actions if we have them
     if let Some(weights) = &action_weights {
         // Only print simulation start messages every 100 iterations
-        if iteration % 100 == 0 {
+        if iteration.is_multiple_of(100) && first_year == BASE_YEAR {
             println!("\n=== STARTING SIMULATION ===");
             if let Some(seed_value) = seed {
                 println!("Seed: {}", seed_value);
@@ -86,7 +138,7 @@ pub fn run_simulation(
         }
     }
      
-    for year in BASE_YEAR..=END_YEAR {
+    for year in years {
         let _year_timing = logging::start_timing(&format!("simulate_year_{}", year), OperationCategory::Simulation);
          
         // Update the current year in the map
@@ -94,30 +146,37 @@ pub fn run_simulation(
         
         // Update construction status for all generators and offsets
         map.update_construction_status();
+        
+        // Committed projects join the fleet before the AI acts, and are not part of its plan
+        let scheduled = map.add_scheduled_builds(year);
+        if !scheduled.is_empty() {
+            crate::log!(Info, "Year {}: Added {} scheduled builds", year, scheduled.len());
+        }
+        
+        // Retire plant at end of life; the lost capacity shows up in the deficit check below.
+        // Retirements go into the map's retirement log, not the recorded plan or the learned weights.
+        let (retired, lost_output) = map.retire_end_of_life_generators(year);
+        if !retired.is_empty() {
+            crate::log!(Info, "Year {}: Retired {} generators at end of life ({:.1} MW lost)", year, retired.len(), lost_output);
+        }
+        
+        // Legislated phase-outs: forced closures, and no weight on banned new builds
+        let (phased_out, phased_out_output) = map.retire_phased_out_generators(year);
+        if !phased_out.is_empty() {
+            crate::log!(Info, "Year {}: Retired {} generators under phase-out ({:.1} MW lost)", year, phased_out.len(), phased_out_output);
+        }
+        local_weights.zero_generator_type_weights(year, &map.get_config().banned_build_types(year));
          
         if action_weights.is_none() {
             println!("\nStarting year {}", year);
              
-            if year > BASE_YEAR {
+            if year > first_year {
                 local_weights.print_top_actions(year - 1, 5);
             }
         }
          
         // Update population for each settlement based on the current year
-        if year > BASE_YEAR {
-            let _timing = logging::start_timing("update_population", OperationCategory::Simulation);
-            for settlement in map.get_settlements_mut() {
-                let current_pop = settlement.get_population();
-                // Apply Irish population growth rate (roughly 1% per year)
-                let new_pop = (current_pop as f64 * 1.01).round() as u32;
-                settlement.update_population(new_pop);
-                 
-                // Also update power usage based on new population and per capita usage
-                let per_capita_usage = const_funcs::calc_power_usage_per_capita(year);
-                let new_usage = (new_pop as f64) * per_capita_usage;
-                settlement.update_power_usage(new_usage);
-            }
-        }
+        update_population(map, year);
          
         let current_state = {
             let _timing = logging::start_timing("calculate_current_state",
@@ -140,24 +199,17 @@ pub fn run_simulation(
             handle_power_deficit(map, -current_state.power_balance, year, &mut local_weights, optimization_mode)?;
         }
 
-        let mut rng = rand::thread_rng();
         let num_additional_actions = if action_weights.is_some() {
             // Check if we're forcing replay of best actions
             if local_weights.force_best_actions {
                 // When replaying best actions, use the exact number of actions from the best simulation
                 if let Some(best_actions) = local_weights.best_actions.as_ref().and_then(|ba| ba.get(&year)) {
                     let count = best_actions.len();
-                    // Only print debug info if debug weights is enabled
-                    if verbose_logging && crate::ai::learning::constants::is_debug_weights_enabled() {
-                        println!("🔄 REPLAY: Using exact count of {} best actions for year {}", count, year);
-                    }
+                    crate::log!(Trace, "🔄 REPLAY: Using exact count of {} best actions for year {}", count, year);
                     count
                 } else {
                     // If no best actions for this year, use 0
-                    // Only print debug info if debug weights is enabled
-                    if verbose_logging && crate::ai::learning::constants::is_debug_weights_enabled() {
-                        println!("🔄 REPLAY: No best actions for year {}, using 0", year);
-                    }
+                    crate::log!(Trace, "🔄 REPLAY: No best actions for year {}, using 0", year);
                     0
                 }
             } else {
@@ -173,7 +225,7 @@ pub fn run_simulation(
                     let count = local_weights.sample_additional_actions(year) as usize;
                     
                     // Print detailed diagnostic information
-                    println!("Year {}: Planning {} additional actions (plus {} deficit actions = {} total)",
+                    crate::log!(Debug, "Year {}: Planning {} additional actions (plus {} deficit actions = {} total)",
                             year, count, deficit_actions_count, count + deficit_actions_count);
                     
                     count
@@ -183,37 +235,68 @@ pub fn run_simulation(
                 }
             }
         } else {
-            rng.gen_range(0..=20)
+            local_weights.rng().gen_range(0..=20)
         };
 
+        let annual_capex_limit = map.get_config().annual_capex_limit;
         for _ in 0..num_additional_actions {
             let _timing = logging::start_timing("apply_additional_action", OperationCategory::Simulation);
-            let action = local_weights.sample_action(year);
-            apply_action(map, &action, year)?;
+            
+            // Build-rate limit: once this year's capital spend reaches the cap, the year ends with DoNothing
+            if annual_capex_limit.is_some_and(|limit| map.calc_yearly_capital_cost(year) >= limit) {
+                if verbose_logging {
+                    println!("Year {}: Annual capex limit reached, skipping remaining actions", year);
+                }
+                recorded_actions.push((year, GridAction::DoNothing));
+                local_weights.record_action(year, GridAction::DoNothing);
+                break;
+            }
+            
+            let mut action = bind_action_target(map, local_weights.sample_action(year), year);
+            
+            // Past a type's annual build cap, further builds of it this year become DoNothing
+            let built_types = match &action {
+                GridAction::AddGenerator(gen_type, _) => vec![gen_type],
+                GridAction::AddHybridPlant(primary, secondary) => vec![primary, secondary],
+                _ => Vec::new(),
+            };
+            if let Some(gen_type) = built_types.into_iter().find(|gen_type| map.is_build_cap_reached(gen_type, year)).cloned() {
+                if verbose_logging {
+                    println!("Year {}: Build cap for {} reached, doing nothing instead", year, gen_type);
+                }
+                action = GridAction::DoNothing;
+            }
+            
+            apply_action(map, &action, year, local_weights.deterministic_rng.as_mut())?;
             recorded_actions.push((year, action.clone()));
+            
+            // Waiting is judged on the cost curves straight away rather than on the final outcome
+            if matches!(action, GridAction::DeferDecision) {
+                local_weights.update_weights(&action, year, defer_decision_reward(year));
+            }
              
             // Record action in the weights with debug output
             // println!("📝 DEBUG: Recording action for year {}: {:?}", year, action);
             local_weights.record_action(year, action);
         }
 
+        store_surplus_as_hydrogen(map, year);
+
         // Calculate yearly metrics
         // Get the previous year's metrics if available
-        let previous_metrics = if year > BASE_YEAR {
-            yearly_metrics_collection.last()
-        } else {
-            None
-        };
+        let previous_metrics = yearly_metrics_collection.last().or(previous_metrics);
         
         let yearly_metrics = calculate_yearly_metrics(
             map, 
             year, 
-            total_upgrade_costs, 
-            total_closure_costs, 
+            map.calc_yearly_upgrade_costs(year),
+            map.calc_yearly_closure_costs(year),
             enable_energy_sales,
             previous_metrics
         );
          
+        map.record_opinion_trend(&yearly_metrics);
+        
         // Collect yearly metrics for CSV export
         yearly_metrics_collection.push(yearly_metrics.clone());
         
@@ -261,6 +344,9 @@ pub fn run_simulation(
             if let Err(e) = writeln!(file, "  Net Emissions: {:.2} tonnes", yearly_metrics.net_co2_emissions) {
                 eprintln!("Error writing to state log file: {}", e);
             }
+            if let Err(e) = writeln!(file, "  Emissions Intensity: {:.2} gCO2/kWh", yearly_metrics.emissions_intensity_g_per_kwh) {
+                eprintln!("Error writing to state log file: {}", e);
+            }
             if let Err(e) = writeln!(file, "  Public Opinion: {:.3}", yearly_metrics.average_public_opinion) {
                 eprintln!("Error writing to state log file: {}", e);
             }
@@ -316,13 +402,95 @@ pub fn run_simulation(
     Ok((output, recorded_actions, yearly_metrics_collection))
 }
 
+// Net emissions, opinion, balance and spend for the map as it stands this year
+fn deficit_action_state(map: &Map, year: u32) -> ActionResult {
+    ActionResult {
+        net_emissions: map.calc_net_co2_emissions(year),
+        public_opinion: calculate_average_opinion(map, year),
+        power_balance: map.calc_total_power_generation(year, None) - map.calc_total_power_usage(year),
+        total_cost: map.calc_total_capital_cost(year),
+    }
+}
+
+// What-if evaluation: the state `action` would lead to this year, with the map rolled back afterwards
+pub fn what_if_action_state(map: &mut Map, action: &GridAction, year: u32) -> Result<ActionResult, SimulationError> {
+    let snapshot = map.snapshot();
+    let result = apply_action(map, action, year, None).map(|()| deficit_action_state(map, year));
+    map.restore(snapshot);
+    result
+}
+
+// Sample `count` deficit actions, score each AddGenerator candidate with a what-if evaluation in
+// parallel and return the one with the best marginal improvement. Each worker clones the map once
+// and rolls it back between candidates. Candidates are sorted before scoring so the choice only
+// depends on the seeded samples, not on thread scheduling.
+fn select_parallel_deficit_action(
+    map: &Map,
+    year: u32,
+    count: usize,
+    action_weights: &mut ActionWeights,
+    current_state: &ActionResult,
+    scoring_mode: Option<&str>,
+    scoring: &ScoringContext,
+) -> Option<GridAction> {
+    let _timing = logging::start_timing(
+        "select_parallel_deficit_action",
+        OperationCategory::WeightsUpdate { subcategory: WeightsUpdateType::ActionUpdate },
+    );
+
+    let mut candidates: Vec<GridAction> = (0..count)
+        .map(|_| action_weights.sample_deficit_action(year))
+        .filter(|action| matches!(action, GridAction::AddGenerator(_, _)))
+        .collect();
+    candidates.sort_by_key(|action| action.to_string());
+    candidates.dedup();
+
+    let chunk_size = candidates.len().div_ceil(rayon::current_num_threads()).max(1);
+    let scored: Vec<(f64, f64)> = candidates.par_chunks(chunk_size)
+        .map(|chunk| {
+            let mut trial = map.clone();
+            chunk.iter()
+                .map(|action| match what_if_action_state(&mut trial, action, year) {
+                    Ok(new_state) => {
+                        (evaluate_action_impact(current_state, &new_state, scoring_mode, scoring), new_state.power_balance)
+                    }
+                    Err(_) => (f64::NEG_INFINITY, f64::NEG_INFINITY),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .flatten()
+        .collect();
+
+    // Strict comparison keeps the earliest candidate on ties; power balance breaks score ties
+    let mut best: Option<(usize, (f64, f64))> = None;
+    for (index, score) in scored.into_iter().enumerate() {
+        if !score.0.is_finite() {
+            continue;
+        }
+        if best.is_none_or(|(_, best_score)| score.0 > best_score.0 || (score.0 == best_score.0 && score.1 > best_score.1)) {
+            best = Some((index, score));
+        }
+    }
+    best.map(|(index, _)| candidates[index].clone())
+}
+
+// Whatever the year ends with spare is banked in electrolyzer tanks for later deficits
+fn store_surplus_as_hydrogen(map: &mut Map, year: u32) {
+    let surplus = map.calc_total_power_generation(year, None) - map.calc_total_power_usage(year);
+    if surplus > 0.0 {
+        map.charge_hydrogen_storage(surplus);
+    }
+}
+
 pub fn handle_power_deficit(
     map: &mut Map,
     deficit: f64,
     year: u32,
     action_weights: &mut ActionWeights,
-    __optimization_mode: Option<&str>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
+    optimization_mode: Option<&str>,
+) -> Result<(), SimulationError> {
     let _timing = logging::start_timing(
         "handle_power_deficit",
         OperationCategory::PowerCalculation { subcategory: PowerCalcType::Balance },
@@ -334,8 +502,20 @@ pub fn handle_power_deficit(
 
     // We'll add generation until the deficit is met.
     // If several attempts have produced no improvement (reliability issues), force a storage action.
-    let mut attempts: u32 = 0;
-    const MAX_TRIES_BEFORE_STORAGE_OVERRIDE: u32 = 5; // after 5 tries, switch to storage
+    // Placement can keep failing on a constrained map, so attempts are capped and the remainder
+    // is covered by emergency capacity rather than looping forever.
+    let mut attempts: usize = 0;
+    const MAX_TRIES_BEFORE_STORAGE_OVERRIDE: usize = 5; // after 5 tries, switch to storage
+    let max_attempts = map.get_config().max_deficit_attempts;
+    
+    // Under a budget cap, cheap capacity matters more than the usual balance of metrics
+    let budget_capped = optimization_mode == Some("budget_capped");
+    let scoring_mode = if budget_capped { optimization_mode } else { None };
+    let scoring = ScoringContext::from_config(map.get_config());
+
+    // Replays must consume recorded deficit actions one at a time, so they stay sequential
+    let parallel_candidates = map.get_config().parallel_deficit_candidates;
+    let use_parallel_candidates = parallel_candidates > 1 && !action_weights.force_best_actions;
 
     // Calculate the initial state to use for evaluating deficit handling actions
     let initial_state = {
@@ -356,6 +536,13 @@ pub fn handle_power_deficit(
     };
 
     while remaining_deficit > 0.0 {
+        if attempts >= max_attempts {
+            crate::log!(Warn, "Year {}: {:.2} MW deficit still open after {} attempts, adding emergency capacity",
+                year, remaining_deficit, attempts);
+            let added = map.add_emergency_capacity(remaining_deficit, year);
+            crate::log!(Warn, "  Emergency gas peaker added {:.2} MW", added);
+            break;
+        }
         attempts += 1;
 
         // Sample an AddGenerator action using the weighted method, but from deficit-specific weights
@@ -365,7 +552,13 @@ pub fn handle_power_deficit(
                 "sample_deficit_action",
                 OperationCategory::WeightsUpdate { subcategory: WeightsUpdateType::ActionUpdate },
             );
-            action_weights.sample_deficit_action(year)
+            if use_parallel_candidates {
+                let current_state = deficit_action_state(map, year);
+                select_parallel_deficit_action(map, year, parallel_candidates, action_weights, &current_state, scoring_mode, &scoring)
+                    .unwrap_or_else(|| action_weights.sample_deficit_action(year))
+            } else {
+                action_weights.sample_deficit_action(year)
+            }
         } else {
             // After several tries, force a storage action
             let _timing = logging::start_timing(
@@ -394,13 +587,20 @@ pub fn handle_power_deficit(
             }
         };
 
+        // A type at its annual build cap can't help this year, so the attempt is spent
+        if let GridAction::AddGenerator(gen_type, _) = &action {
+            if map.is_build_cap_reached(gen_type, year) {
+                continue;
+            }
+        }
+
         // Only add a generator if the sampled action is an AddGenerator.
         if let GridAction::AddGenerator(_, _) = action {
             let _timing = logging::start_timing(
                 "apply_generator_action",
                 OperationCategory::Simulation,
             );
-            apply_action(map, &action, year)?;
+            apply_action(map, &action, year, action_weights.deterministic_rng.as_mut())?;
              
             // Record the action in both deficit-specific weights system and regular action record
             action_weights.record_deficit_action(year, action.clone());
@@ -428,7 +628,7 @@ pub fn handle_power_deficit(
 
             // Calculate improvement based on all metrics using evaluate_action_impact
             // This uses the same logic as regular action assessment
-            let overall_improvement = evaluate_action_impact(&current_state, &new_state, None);
+            let overall_improvement = evaluate_action_impact(&current_state, &new_state, scoring_mode, &scoring);
              
             // Calculate specific improvements for different metrics
              
@@ -439,8 +639,8 @@ pub fn handle_power_deficit(
                 0.0
             };
              
-            // Cost improvement: Important if we have achieved close to net zero emissions
-            let cost_improvement = if new_state.net_emissions < 1000.0 {
+            // Cost improvement: Important if we have achieved close to net zero emissions (always under a budget cap)
+            let cost_improvement = if budget_capped || new_state.net_emissions < 1000.0 {
                 let cost_change = new_state.total_cost - current_state.total_cost;
                 -cost_change / current_state.total_cost.abs().max(1.0)
             } else {
@@ -464,10 +664,17 @@ pub fn handle_power_deficit(
             // Combined improvement, with focus on overall result with a balance of metrics
             // Primary focus is on the overall evaluation (70%)
             // Secondary focus is on specific metrics that align with our goals (emissions, cost, opinion)
-            let combined_improvement = overall_improvement * 0.7 +
-                emissions_improvement * 0.15 +
-                cost_improvement * 0.1 +
-                opinion_improvement * 0.05;
+            let combined_improvement = if budget_capped {
+                overall_improvement * 0.5 +
+                    emissions_improvement * 0.1 +
+                    cost_improvement * 0.35 +
+                    opinion_improvement * 0.05
+            } else {
+                overall_improvement * 0.7 +
+                    emissions_improvement * 0.15 +
+                    cost_improvement * 0.1 +
+                    opinion_improvement * 0.05
+            };
 
             {
                 let _timing = logging::start_timing(
@@ -502,7 +709,7 @@ pub fn handle_power_deficit(
     };
      
     // Evaluate overall success using the standard action impact evaluation
-    let overall_success = evaluate_action_impact(&initial_state, &final_state, None);
+    let overall_success = evaluate_action_impact(&initial_state, &final_state, scoring_mode, &scoring);
      
     // If we successfully handled the deficit and our metrics improved, provide a bonus
     if final_state.power_balance >= 0.0 && overall_success > 0.0 &&
@@ -530,7 +737,7 @@ pub fn run_simulation_with_best_actions(
     optimization_mode: Option<&str>,
     enable_energy_sales: bool,
     enable_construction_delays: bool,
-) -> Result<(String, Vec<(u32, GridAction)>, Vec<YearlyMetrics>), Box<dyn Error + Send + Sync>> {
+) -> Result<(String, Vec<(u32, GridAction)>, Vec<YearlyMetrics>), SimulationError> {
     let _timing = logging::start_timing("run_simulation_with_best_actions", OperationCategory::Simulation);
 
     // Set construction delays flag
@@ -540,9 +747,6 @@ pub fn run_simulation_with_best_actions(
     let mut recorded_actions = Vec::new();
     let mut yearly_metrics_collection = Vec::new();
      
-    let total_upgrade_costs = 0.0;
-    let total_closure_costs = 0.0;
-     
     let mut final_year_metrics: Option<YearlyMetrics> = None;
      
     // Set the flag to guarantee best action replays (100% probability)
@@ -565,22 +769,20 @@ pub fn run_simulation_with_best_actions(
         
         // Update construction status for all generators and offsets
         map.update_construction_status();
+        map.add_scheduled_builds(year);
+        
+        let (retired, lost_output) = map.retire_end_of_life_generators(year);
+        if !retired.is_empty() {
+            println!("Year {}: Retired {} generators at end of life ({:.1} MW lost)", year, retired.len(), lost_output);
+        }
+        
+        let (phased_out, phased_out_output) = map.retire_phased_out_generators(year);
+        if !phased_out.is_empty() {
+            println!("Year {}: Retired {} generators under phase-out ({:.1} MW lost)", year, phased_out.len(), phased_out_output);
+        }
          
         // Update population for each settlement based on the current year
-        if year > 2025 {
-            let _timing = crate::utils::logging::start_timing("update_population", OperationCategory::Simulation);
-            for settlement in map.get_settlements_mut() {
-                let current_pop = settlement.get_population();
-                // Apply Irish population growth rate (roughly 1% per year)
-                let new_pop = (current_pop as f64 * 1.01).round() as u32;
-                settlement.update_population(new_pop);
-                 
-                // Also update power usage based on new population and per capita usage
-                let per_capita_usage = crate::config::const_funcs::calc_power_usage_per_capita(year);
-                let new_usage = (new_pop as f64) * per_capita_usage;
-                settlement.update_power_usage(new_usage);
-            }
-        }
+        update_population(map, year);
          
         // Calculate current state before actions
         let current_state = {
@@ -604,15 +806,15 @@ pub fn run_simulation_with_best_actions(
         }
          
         // Debug: Print information about best actions availability
-        println!("DEBUG: Has best actions overall: {}", weights.has_best_actions());
+        crate::log!(Debug, "DEBUG: Has best actions overall: {}", weights.has_best_actions());
          
         // Get and apply the best actions for this year
-        if let Some(best_actions) = weights.get_best_actions_for_year(year) {
+        if let Some(best_actions) = weights.get_best_actions_for_year(year).cloned() {
             println!("Year {}: Applying {} best actions", year, best_actions.len());
              
             // Apply each of the best actions
             for action in best_actions {
-                crate::core::actions::apply_action(map, action, year)?;
+                crate::core::actions::apply_action(map, &action, year, weights.deterministic_rng.as_mut())?;
                 recorded_actions.push((year, action.clone()));
             }
         } else {
@@ -620,12 +822,12 @@ pub fn run_simulation_with_best_actions(
         }
 
         // Get and apply the best deficit actions for this year
-        if let Some(best_deficit_actions) = weights.get_best_deficit_actions_for_year(year) {
+        if let Some(best_deficit_actions) = weights.get_best_deficit_actions_for_year(year).cloned() {
             println!("Year {}: Applying {} best deficit actions", year, best_deficit_actions.len());
              
             // Apply each of the best deficit actions
             for action in best_deficit_actions {
-                crate::core::actions::apply_action(map, &action, year)?;
+                crate::core::actions::apply_action(map, &action, year, weights.deterministic_rng.as_mut())?;
                 recorded_actions.push((year, action.clone()));
             }
         }
@@ -656,6 +858,7 @@ pub fn run_simulation_with_best_actions(
                 }
             }
         }
+        store_surplus_as_hydrogen(map, year);
 
         // Calculate and save yearly metrics
         // Get the previous year's metrics if available
@@ -668,11 +871,12 @@ pub fn run_simulation_with_best_actions(
         let metrics = crate::analysis::metrics_calculation::calculate_yearly_metrics(
             map, 
             year, 
-            total_upgrade_costs, 
-            total_closure_costs, 
+            map.calc_yearly_upgrade_costs(year),
+            map.calc_yearly_closure_costs(year),
             enable_energy_sales,
             previous_metrics
         );
+        map.record_opinion_trend(&metrics);
         yearly_metrics_collection.push(metrics.clone());
          
         crate::analysis::reporting::print_yearly_summary(&metrics);
@@ -705,4 +909,444 @@ pub fn run_simulation_with_best_actions(
     }
      
     Ok((output, recorded_actions, yearly_metrics_collection))
-}
\ No newline at end of file
+}
+
+// Year-tagged actions in the order they were applied
+pub type RecordedActions = Vec<(u32, GridAction)>;
+
+/// Receding-horizon planning: each year, samples `iterations_per_year` plans from the current
+/// state through END_YEAR, learning from each like a normal iteration, then commits only that
+/// year of the best one to `map` and moves on. Later years are re-planned from the committed
+/// state instead of being fixed up front.
+pub fn run_rolling_horizon(
+    map: &mut Map,
+    weights: &mut ActionWeights,
+    iterations_per_year: usize,
+    seed: Option<u64>,
+    optimization_mode: Option<&str>,
+    enable_energy_sales: bool,
+    enable_construction_delays: bool,
+) -> Result<SimulationResult, SimulationError> {
+    let _timing = logging::start_timing("run_rolling_horizon", OperationCategory::Simulation);
+
+    struct YearCandidate {
+        score: f64,
+        map: Map,
+        actions: RecordedActions,
+        metrics: Vec<YearlyMetrics>,
+        run_actions: HashMap<u32, Vec<GridAction>>,
+        deficit_actions: HashMap<u32, Vec<GridAction>>,
+    }
+
+    let scoring = ScoringContext::from_config(map.get_config());
+    let flags = RunFlags { verbose_logging: false, optimization_mode, enable_energy_sales, enable_construction_delays };
+    let mut committed_actions: RecordedActions = Vec::new();
+    let mut committed_metrics: Vec<YearlyMetrics> = Vec::new();
+    let mut committed_run_actions = HashMap::new();
+    let mut committed_deficit_actions = HashMap::new();
+
+    for year in BASE_YEAR..=END_YEAR {
+        let mut best: Option<YearCandidate> = None;
+
+        for i in 0..iterations_per_year.max(1) {
+            let trial_seed = seed.map(|s| s.wrapping_add(((year - BASE_YEAR) as u64) << 32).wrapping_add(2 * i as u64));
+
+            // Each trial is a whole run as far as learning is concerned, committed years included
+            weights.clear_replay_index();
+            weights.set_force_best_actions(false);
+            weights.current_run_actions = committed_run_actions.clone();
+            weights.current_deficit_actions = committed_deficit_actions.clone();
+
+            let mut trial = map.clone();
+            let (_, actions, year_metrics) = simulate_years(
+                &mut trial, Some(weights), trial_seed, flags, i, year..=year, committed_metrics.last(),
+            )?;
+            let after_year = trial.clone();
+            let run_actions = weights.current_run_actions.clone();
+            let deficit_actions = weights.current_deficit_actions.clone();
+
+            let mut full_metrics = committed_metrics.clone();
+            full_metrics.extend(year_metrics.iter().cloned());
+            if year < END_YEAR {
+                let (_, _, rest) = simulate_years(
+                    &mut trial, Some(weights), trial_seed.map(|s| s.wrapping_add(1)), flags, i, (year + 1)..=END_YEAR, full_metrics.last(),
+                )?;
+                full_metrics.extend(rest);
+            }
+
+            let metrics = final_simulation_metrics(&full_metrics);
+            weights.apply_contrast_learning(&metrics);
+            weights.update_best_strategy(metrics.clone());
+
+            let score = score_metrics(&metrics, optimization_mode, &scoring);
+            if best.as_ref().is_none_or(|candidate| score > candidate.score) {
+                best = Some(YearCandidate { score, map: after_year, actions, metrics: year_metrics, run_actions, deficit_actions });
+            }
+        }
+
+        let chosen = best.expect("at least one rolling horizon trial per year");
+        *map = chosen.map;
+        committed_actions.extend(chosen.actions);
+        committed_metrics.extend(chosen.metrics);
+        committed_run_actions = chosen.run_actions;
+        committed_deficit_actions = chosen.deficit_actions;
+    }
+
+    let metrics = final_simulation_metrics(&committed_metrics);
+    let output = format!(
+        "Rolling horizon plan of {} actions: net emissions {:.2} tonnes, total cost €{:.2}, public opinion {:.3}",
+        committed_actions.len(), metrics.final_net_emissions, metrics.total_cost, metrics.average_public_opinion
+    );
+
+    Ok(SimulationResult {
+        metrics,
+        output,
+        actions: committed_actions,
+        yearly_metrics: committed_metrics,
+        retirements: map.get_retirements().to_vec(),
+    })
+}
+
+// Runs a fixed, externally authored plan year by year with no learning or deficit handling,
+// so any shortfall in the plan shows up in the yearly power balance.
+pub fn run_simulation_with_plan(
+    map: &mut Map,
+    plan: &[(u32, GridAction)],
+    enable_energy_sales: bool,
+    enable_construction_delays: bool,
+) -> Result<(RecordedActions, Vec<YearlyMetrics>), SimulationError> {
+    let _timing = logging::start_timing("run_simulation_with_plan", OperationCategory::Simulation);
+
+    map.set_enable_construction_delays(enable_construction_delays);
+
+    let mut recorded_actions = Vec::new();
+    let mut yearly_metrics_collection: Vec<YearlyMetrics> = Vec::new();
+
+    for year in BASE_YEAR..=END_YEAR {
+        map.current_year = year;
+        map.update_construction_status();
+        map.add_scheduled_builds(year);
+
+        map.retire_end_of_life_generators(year);
+        map.retire_phased_out_generators(year);
+
+        update_population(map, year);
+
+        for (_, action) in plan.iter().filter(|(plan_year, _)| *plan_year == year) {
+            let action = bind_action_target(map, action.clone(), year);
+            apply_action(map, &action, year, None)?;
+            recorded_actions.push((year, action));
+        }
+        store_surplus_as_hydrogen(map, year);
+
+        let metrics = calculate_yearly_metrics(
+            map,
+            year,
+            map.calc_yearly_upgrade_costs(year),
+            map.calc_yearly_closure_costs(year),
+            enable_energy_sales,
+            yearly_metrics_collection.last(),
+        );
+        map.record_opinion_trend(&metrics);
+        yearly_metrics_collection.push(metrics);
+    }
+
+    Ok((recorded_actions, yearly_metrics_collection))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use crate::data::poi::{Coordinate, POI};
+    use crate::config::simulation_config::{Electrification, ExclusionZone, ScheduledBuild, SimulationConfig};
+    use crate::models::settlement::Settlement;
+    use crate::test_support::{empty_map, small_map, test_generator, TEST_SITE};
+    use crate::utils::map_handler::RetirementReason;
+
+    #[test]
+    fn ten_year_generator_is_retired_outside_the_plan() {
+        let mut map = small_map();
+        let mut generator = test_generator("Short_Lived_Gas", GeneratorType::GasCombinedCycle, Coordinate::new(26_000.0, 24_000.0), 400.0, 1.2e6);
+        generator.eol = 10;
+        map.add_generator(generator);
+
+        let (actions, _) = run_simulation_with_plan(&mut map, &[], false, false).unwrap();
+
+        let generator = map.get_generators().iter().find(|g| g.get_id() == "Short_Lived_Gas").unwrap();
+        assert!(generator.get_end_of_life_year() < BASE_YEAR + 11);
+        assert!(!generator.is_active());
+        assert!(actions.iter().all(|(_, action)| !matches!(action, GridAction::CloseGenerator(_))));
+
+        let retirement = map.get_retirements().iter().find(|r| r.generator_id == "Short_Lived_Gas").unwrap();
+        assert_eq!(retirement.year, BASE_YEAR + 10);
+        assert_eq!(retirement.reason, RetirementReason::EndOfLife);
+    }
+
+    #[test]
+    fn rolling_horizon_replans_every_year_across_the_full_span() {
+        let mut map = small_map();
+        let mut weights = ActionWeights::new();
+
+        let result = run_rolling_horizon(&mut map, &mut weights, 1, Some(7), None, false, false).unwrap();
+
+        let years: Vec<u32> = result.yearly_metrics.iter().map(|m| m.year).collect();
+        assert_eq!(years, (BASE_YEAR..=END_YEAR).collect::<Vec<_>>());
+        assert!(result.yearly_metrics.iter().all(|m| {
+            m.total_power_usage > 0.0 && m.net_co2_emissions.is_finite() && m.total_cost.is_finite()
+        }));
+        // One full-horizon trial is planned from each year's committed state
+        assert_eq!(weights.iteration_count as usize, years.len());
+        assert!(result.actions.iter().all(|(year, _)| (BASE_YEAR..=END_YEAR).contains(year)));
+        assert_eq!(map.current_year, END_YEAR);
+    }
+
+    fn coal_phased_out_in_2030() -> Map {
+        let mut map = small_map();
+        let mut config = map.get_config().clone();
+        config.force_retire_by_year.insert(GeneratorType::CoalPlant, 2030);
+        map.set_config(config);
+        map.add_generator(test_generator("Old_Coal", GeneratorType::CoalPlant, Coordinate::new(26_000.0, 24_000.0), 300.0, 2.0e6));
+        map
+    }
+
+    fn no_active_coal(map: &Map) -> bool {
+        map.get_generators().iter().all(|g| g.get_generator_type() != &GeneratorType::CoalPlant || !g.is_active())
+    }
+
+    #[test]
+    fn no_coal_survives_its_forced_retirement_year_regardless_of_weights() {
+        // A fixed plan that insists on new coal after the deadline
+        let mut map = coal_phased_out_in_2030();
+        let plan = [(2035, GridAction::AddGenerator(GeneratorType::CoalPlant, 100))];
+        run_simulation_with_plan(&mut map, &plan, false, false).unwrap();
+
+        assert!(no_active_coal(&map));
+        let retirement = map.get_retirements().iter().find(|r| r.generator_id == "Old_Coal").unwrap();
+        assert_eq!(retirement.year, 2030);
+        assert_eq!(retirement.reason, RetirementReason::PhaseOut);
+
+        // Learned weights that favour nothing but coal
+        let mut map = coal_phased_out_in_2030();
+        let mut weights = ActionWeights::new().with_prior(&HashMap::from([(GeneratorType::CoalPlant, 1.0)]));
+        weights.set_rng(StdRng::seed_from_u64(11));
+        let (_, actions, _) = run_simulation(&mut map, Some(&mut weights), Some(11), false, None, false, false, 0).unwrap();
+
+        assert!(no_active_coal(&map));
+        assert!(actions.iter().all(|(year, action)| *year < 2030 || !matches!(action, GridAction::AddGenerator(GeneratorType::CoalPlant, _))));
+    }
+
+    // Busiest year across a few seeded runs, since a single sampled run can spread its builds out
+    fn most_builds_in_one_year(annual_capex_limit: Option<f64>) -> usize {
+        let mut map = small_map();
+        let mut config = map.get_config().clone();
+        config.annual_capex_limit = annual_capex_limit;
+        map.set_config(config);
+
+        (0..5u64)
+            .map(|seed| {
+                let mut map = map.clone();
+                let mut weights = ActionWeights::new().with_prior(&HashMap::from([(GeneratorType::OnshoreWind, 1.0)]));
+                weights.set_rng(StdRng::seed_from_u64(seed));
+                let (_, actions, _) = run_simulation(&mut map, Some(&mut weights), Some(seed), false, None, false, false, 0).unwrap();
+                (BASE_YEAR..=END_YEAR)
+                    .map(|year| actions.iter().filter(|(y, action)| *y == year && matches!(action, GridAction::AddGenerator(..))).count())
+                    .max()
+                    .unwrap()
+            })
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn sampling_stops_adding_generators_once_the_annual_capex_limit_is_hit() {
+        // Any one build spends more than a euro, so nothing follows it in the same year
+        assert!(most_builds_in_one_year(Some(1.0)) <= 1);
+        assert!(most_builds_in_one_year(None) > 1);
+    }
+
+    // Most offshore wind units commissioned in any one year, across a few seeded runs
+    fn most_offshore_builds_in_one_year(cap: Option<usize>) -> usize {
+        let mut map = small_map();
+        if let Some(cap) = cap {
+            let mut config = map.get_config().clone();
+            config.max_builds_per_year.insert(GeneratorType::OffshoreWind, cap);
+            map.set_config(config);
+        }
+
+        (0..5u64)
+            .map(|seed| {
+                let mut map = map.clone();
+                let mut weights = ActionWeights::new().with_prior(&HashMap::from([(GeneratorType::OffshoreWind, 1.0)]));
+                weights.set_rng(StdRng::seed_from_u64(seed));
+                run_simulation(&mut map, Some(&mut weights), Some(seed), false, None, false, false, 0).unwrap();
+                (BASE_YEAR..=END_YEAR)
+                    .map(|year| map.get_generators().iter()
+                        .filter(|g| g.get_generator_type() == &GeneratorType::OffshoreWind && g.commissioning_year == year)
+                        .count())
+                    .max()
+                    .unwrap()
+            })
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn offshore_wind_build_cap_is_never_exceeded_in_a_year() {
+        assert!(most_offshore_builds_in_one_year(Some(1)) <= 1);
+        assert!(most_offshore_builds_in_one_year(None) > 1);
+    }
+
+    fn balance_after_deficit_handling(parallel_deficit_candidates: usize) -> (f64, f64) {
+        let mut map = empty_map(SimulationConfig::builder().parallel_deficit_candidates(parallel_deficit_candidates).build());
+        map.add_settlement(Settlement::new("Midtown".to_string(), TEST_SITE, 50_000, 80.0));
+        let year = BASE_YEAR;
+        let balance = |map: &Map| map.calc_total_power_generation(year, None) - map.calc_total_power_usage(year);
+        let before = balance(&map);
+
+        let mut weights = ActionWeights::new();
+        weights.set_rng(StdRng::seed_from_u64(5));
+        handle_power_deficit(&mut map, -before, year, &mut weights, None).unwrap();
+        (before, balance(&map))
+    }
+
+    #[test]
+    fn parallel_deficit_handling_closes_the_same_deficit_as_sequential() {
+        let (sequential_before, sequential_after) = balance_after_deficit_handling(1);
+        let (parallel_before, parallel_after) = balance_after_deficit_handling(4);
+
+        assert!(sequential_before < 0.0);
+        assert_eq!(parallel_before, sequential_before);
+        assert!(sequential_after >= 0.0, "sequential left {} MW", sequential_after);
+        assert!(parallel_after >= 0.0, "parallel left {} MW", parallel_after);
+    }
+
+    #[test]
+    fn deficit_handling_terminates_on_a_fully_excluded_map() {
+        let mut config = SimulationConfig::builder().max_deficit_attempts(20).build();
+        config.exclusion_zones.push(ExclusionZone::Circle {
+            name: "Everywhere".to_string(),
+            center: TEST_SITE,
+            radius: 1.0e6,
+        });
+        let mut map = empty_map(config);
+        map.add_settlement(Settlement::new("Midtown".to_string(), TEST_SITE, 50_000, 80.0));
+        let year = BASE_YEAR;
+        let deficit = map.calc_total_power_usage(year) - map.calc_total_power_generation(year, None);
+        assert!(deficit > 0.0);
+
+        let mut weights = ActionWeights::new();
+        weights.set_rng(StdRng::seed_from_u64(8));
+        handle_power_deficit(&mut map, deficit, year, &mut weights, None).unwrap();
+
+        // Nothing can be sited, so the emergency fallback is what closes the gap
+        let balance = map.calc_total_power_generation(year, None) - map.calc_total_power_usage(year);
+        assert!(balance >= -1e-6, "{} MW still open", -balance);
+        assert!(map.get_generators().iter().any(|g| g.get_id().starts_with("Emergency_")));
+    }
+
+    #[test]
+    fn population_follows_census_points_and_grows_past_the_last_one() {
+        let census = BTreeMap::from([(2025, 50_000), (2030, 60_000), (2040, 55_000)]);
+        let mut map = empty_map(SimulationConfig::default());
+        map.add_settlement(Settlement::new("Midtown".to_string(), TEST_SITE, 50_000, 80.0).with_population_points(census.clone()));
+        let mut population_by_year = BTreeMap::new();
+        for year in BASE_YEAR..=2041 {
+            update_population(&mut map, year);
+            population_by_year.insert(year, map.get_settlements()[0].get_population());
+        }
+
+        for (year, population) in census {
+            assert_eq!(population_by_year[&year], population, "population in {}", year);
+        }
+        assert_eq!(population_by_year[&2035], 57_500);
+        assert_eq!(population_by_year[&2041], (55_000.0_f64 * 1.01).round() as u32);
+    }
+
+    #[test]
+    fn aggressive_electrification_raises_2050_usage_above_the_baseline() {
+        let usage_in_2050 = |electrification: Electrification| {
+            let mut map = empty_map(SimulationConfig::builder().electrification(electrification).build());
+            map.add_settlement(Settlement::new("Midtown".to_string(), TEST_SITE, 50_000, 80.0));
+            for year in BASE_YEAR..=END_YEAR {
+                update_population(&mut map, year);
+            }
+            map.calc_total_power_usage(END_YEAR)
+        };
+
+        let baseline = usage_in_2050(Electrification::default());
+        let aggressive = usage_in_2050(Electrification {
+            ev_adoption: vec![(BASE_YEAR, 0.05), (2040, 0.8), (END_YEAR, 1.0)],
+            heat_pump_adoption: vec![(BASE_YEAR, 0.05), (2040, 0.7), (END_YEAR, 0.9)],
+            ..Electrification::default()
+        });
+
+        assert!(aggressive > baseline, "{} MW with electrification vs {} MW without", aggressive, baseline);
+    }
+
+    #[test]
+    fn scheduled_offshore_farm_joins_the_fleet_in_its_year_without_any_action() {
+        let offshore_site = Coordinate::new(2_000.0, 25_000.0);
+        let farm = test_generator("Committed_Offshore", GeneratorType::OffshoreWind, offshore_site, 500.0, 0.0);
+        let mut config = small_map().get_config().clone();
+        config.scheduled_builds.push(ScheduledBuild { year: 2028, generator: farm });
+        let mut baseline = small_map();
+        let mut scheduled = small_map();
+        scheduled.set_config(config);
+
+        let (_, baseline_metrics) = run_simulation_with_plan(&mut baseline, &[], false, false).unwrap();
+        let (actions, scheduled_metrics) = run_simulation_with_plan(&mut scheduled, &[], false, false).unwrap();
+
+        assert!(actions.is_empty());
+        let farm = scheduled.get_generators().iter().find(|g| g.get_id() == "Committed_Offshore")
+            .expect("scheduled farm in the fleet");
+        assert_eq!(farm.commissioning_year, 2028);
+        assert!(farm.is_active());
+        for (without, with) in baseline_metrics.iter().zip(&scheduled_metrics) {
+            if with.year < 2028 {
+                assert_eq!(with.total_power_generation, without.total_power_generation, "farm generating in {}", with.year);
+            } else {
+                assert!(with.total_power_generation > without.total_power_generation, "farm missing in {}", with.year);
+            }
+        }
+    }
+
+    #[test]
+    fn fixed_plan_charges_closure_costs_in_the_closure_year() {
+        let plan = vec![(2030, GridAction::CloseGenerator("Existing_Gas".to_string()))];
+        let mut closed = small_map();
+        let mut kept = small_map();
+
+        let (_, closed_metrics) = run_simulation_with_plan(&mut closed, &plan, false, false).unwrap();
+        let (_, kept_metrics) = run_simulation_with_plan(&mut kept, &[], false, false).unwrap();
+
+        let closure_cost = closed.calc_yearly_closure_costs(2030);
+        assert!(closure_cost > 0.0);
+        let capex_in = |metrics: &[YearlyMetrics]| metrics.iter().find(|m| m.year == 2030).unwrap().capex;
+        assert!((capex_in(&closed_metrics) - capex_in(&kept_metrics) - closure_cost).abs() < 1e-6 * closure_cost);
+    }
+
+    #[test]
+    fn fixed_plan_stores_surplus_in_a_planned_electrolyzer() {
+        let plan = vec![(BASE_YEAR, GridAction::AddGenerator(GeneratorType::HydrogenElectrolyzer, 100))];
+        let mut map = small_map();
+        assert!(map.calc_total_power_generation(BASE_YEAR, None) > map.calc_total_power_usage(BASE_YEAR));
+
+        run_simulation_with_plan(&mut map, &plan, false, false).unwrap();
+
+        assert!(map.stored_hydrogen() > 0.0);
+    }
+
+    #[test]
+    fn best_action_replay_follows_census_population() {
+        let census = BTreeMap::from([(BASE_YEAR, 50_000), (END_YEAR, 52_000)]);
+        let mut map = empty_map(SimulationConfig::default());
+        map.add_settlement(Settlement::new("Midtown".to_string(), TEST_SITE, 50_000, 80.0).with_population_points(census));
+        map.add_generator(test_generator("Existing_Gas", GeneratorType::GasCombinedCycle, Coordinate::new(24_000.0, 26_000.0), 400.0, 1.2e6));
+        let mut weights = ActionWeights::new();
+
+        run_simulation_with_best_actions(&mut map, &mut weights, Some(7), false, None, false, false).unwrap();
+
+        assert_eq!(map.get_settlements()[0].get_population(), 52_000);
+    }
+}
@@ -1,11 +1,14 @@
 use std::fs::File;
 use std::io::Read;
-use csv::ReaderBuilder;
+use csv::{ReaderBuilder, StringRecord};
+use serde::{Deserialize, Serialize};
 use crate::models::generator::{Generator, GeneratorType};
-use super::poi::Coordinate;
+use super::poi::{Coordinate, MapExtent};
+use crate::error::SimulationError;
 use crate::config::constants::*;
 use crate::config::constants::{IRELAND_MIN_LAT, IRELAND_MAX_LAT, IRELAND_MIN_LON, IRELAND_MAX_LON};
 use crate::config::const_funcs::{calc_generator_cost, calc_operating_cost, calc_initial_co2_output, calc_decommission_cost, transform_lat_lon_to_grid, is_location_on_land, is_coastal_location};
+use crate::config::simulation_config::InflationModel;
 
 #[derive(Debug)]
 pub enum GeneratorLoadError {
@@ -15,6 +18,59 @@ pub enum GeneratorLoadError {
     InvalidCapacity(String),
     InvalidCoordinate(String),
     InvalidLocation(String),
+    MissingColumn(String),
+    Row { line: u64, error: Box<GeneratorLoadError> },
+}
+
+// Which CSV header holds each generator field, so differently laid out datasets load unchanged.
+// Defaults match the bundled ireland_generators.csv.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeneratorColumnMapping {
+    pub capacity: String,   // Rated capacity in MW
+    pub latitude: String,
+    pub longitude: String,
+    pub fuel_type: String,  // Fuel name, e.g. "Gas", "Wind"
+}
+
+impl Default for GeneratorColumnMapping {
+    fn default() -> Self {
+        Self {
+            capacity: "capacity_mw".to_string(),
+            latitude: "latitude".to_string(),
+            longitude: "longitude".to_string(),
+            fuel_type: "primary_fuel".to_string(),
+        }
+    }
+}
+
+// Positions of the mapped columns within one file's header row
+struct ColumnIndices {
+    capacity: usize,
+    latitude: usize,
+    longitude: usize,
+    fuel_type: usize,
+}
+
+impl ColumnIndices {
+    fn resolve(headers: &StringRecord, columns: &GeneratorColumnMapping) -> Result<Self, GeneratorLoadError> {
+        let find = |name: &str| headers.iter().position(|header| header.trim() == name);
+        let missing: Vec<&str> = [&columns.capacity, &columns.latitude, &columns.longitude, &columns.fuel_type]
+            .into_iter()
+            .filter(|name| find(name).is_none())
+            .map(|name| name.as_str())
+            .collect();
+        if !missing.is_empty() {
+            return Err(GeneratorLoadError::MissingColumn(missing.join(", ")));
+        }
+
+        Ok(Self {
+            capacity: find(&columns.capacity).unwrap(),
+            latitude: find(&columns.latitude).unwrap(),
+            longitude: find(&columns.longitude).unwrap(),
+            fuel_type: find(&columns.fuel_type).unwrap(),
+        })
+    }
 }
 
 impl From<std::io::Error> for GeneratorLoadError {
@@ -38,6 +94,8 @@ impl std::fmt::Display for GeneratorLoadError {
             GeneratorLoadError::InvalidCapacity(s) => write!(f, "Invalid capacity: {}", s),
             GeneratorLoadError::InvalidCoordinate(s) => write!(f, "Invalid coordinate: {}", s),
             GeneratorLoadError::InvalidLocation(s) => write!(f, "Invalid location for generator type: {}", s),
+            GeneratorLoadError::MissingColumn(s) => write!(f, "Missing required column(s): {}", s),
+            GeneratorLoadError::Row { line, error } => write!(f, "line {}: {}", line, error),
         }
     }
 }
@@ -56,7 +114,7 @@ fn map_fuel_type_to_generator_type(fuel: &str) -> Result<GeneratorType, Generato
     }
 }
 
-fn transform_coordinates(lat: f64, lon: f64) -> Result<Coordinate, GeneratorLoadError> {
+fn transform_coordinates(lat: f64, lon: f64, extent: &MapExtent) -> Result<Coordinate, GeneratorLoadError> {
     // First check if the coordinates are within Ireland's bounds
     if lat < IRELAND_MIN_LAT || lat > IRELAND_MAX_LAT || 
        lon < IRELAND_MIN_LON || lon > IRELAND_MAX_LON {
@@ -87,19 +145,17 @@ fn transform_coordinates(lat: f64, lon: f64) -> Result<Coordinate, GeneratorLoad
         ))?;
     
     // Verify the coordinate is valid and within expected range
-    if coordinate.x < 0.0 || coordinate.x > MAP_MAX_X || 
-       coordinate.y < 0.0 || coordinate.y > MAP_MAX_Y {
+    if !extent.contains(&coordinate) {
         println!("WARNING: Transformed grid coordinates outside valid range: ({:.2}, {:.2})", 
                  coordinate.x, coordinate.y);
                  
-        // Clamp to valid range
-        let x_valid = coordinate.x.max(0.0).min(MAP_MAX_X);
-        let y_valid = coordinate.y.max(0.0).min(MAP_MAX_Y);
+        // Clamp to the configured map extent
+        let clamped = extent.clamp(coordinate.x, coordinate.y);
         
         println!("Clamped grid coordinates: ({:.2}, {:.2}) -> ({:.2}, {:.2})",
-                 coordinate.x, coordinate.y, x_valid, y_valid);
+                 coordinate.x, coordinate.y, clamped.x, clamped.y);
                  
-        return Ok(Coordinate::new(x_valid, y_valid));
+        return Ok(clamped);
     }
     
     // Additional check for values near zero which might indicate transformation issues
@@ -119,6 +175,7 @@ fn normalize_capacity(capacity: f64, gen_type: &GeneratorType) -> f64 {
     let max_power = match gen_type {
         GeneratorType::OnshoreWind => MAX_ONSHORE_WIND_POWER,
         GeneratorType::OffshoreWind => MAX_OFFSHORE_WIND_POWER,
+        GeneratorType::FloatingOffshoreWind => MAX_FLOATING_OFFSHORE_WIND_POWER,
         GeneratorType::CoalPlant => MAX_COAL_POWER,
         GeneratorType::GasCombinedCycle => MAX_GAS_CC_POWER,
         GeneratorType::GasPeaker => MAX_GAS_PEAKER_POWER,
@@ -130,78 +187,155 @@ fn normalize_capacity(capacity: f64, gen_type: &GeneratorType) -> f64 {
     (capacity / max_power).clamp(MIN_GENERATOR_SIZE, MAX_GENERATOR_SIZE)
 }
 
-pub fn load_generators(csv_path: &str, year: u32) -> Result<Vec<Generator>, GeneratorLoadError> {
+pub fn load_generators(
+    csv_path: &str,
+    year: u32,
+    extent: &MapExtent,
+    columns: &GeneratorColumnMapping,
+    inflation: &InflationModel,
+) -> Result<Vec<Generator>, SimulationError> {
     let mut file = File::open(csv_path)?;
     let mut contents = String::new();
     file.read_to_string(&mut contents)?;
 
+    let (generators, row_errors) = parse_generators(&contents, year, extent, columns, inflation)?;
+    for error in &row_errors {
+        eprintln!("WARNING: Skipping generator in {}: {}", csv_path, error);
+    }
+    Ok(generators)
+}
+
+// Parses every row it can; a malformed row is reported as a `Row` error with its line number and
+// skipped, while a header missing one of the mapped columns fails the whole load
+pub fn parse_generators(
+    contents: &str,
+    year: u32,
+    extent: &MapExtent,
+    columns: &GeneratorColumnMapping,
+    inflation: &InflationModel,
+) -> Result<(Vec<Generator>, Vec<GeneratorLoadError>), GeneratorLoadError> {
     let mut reader = ReaderBuilder::new()
         .has_headers(true)
         .from_reader(contents.as_bytes());
+    let indices = ColumnIndices::resolve(reader.headers()?, columns)?;
 
     let mut generators = Vec::new();
-    let mut id_counter = 0;
+    let mut row_errors = Vec::new();
 
     for result in reader.records() {
-        let record = result?;
+        let parsed = match result {
+            Ok(record) => {
+                let line = record.position().map_or(0, |position| position.line());
+                parse_generator_row(&record, &indices, year, extent, inflation, generators.len())
+                    .map_err(|error| GeneratorLoadError::Row { line, error: Box::new(error) })
+            }
+            Err(error) => {
+                let line = error.position().map_or(0, |position| position.line());
+                Err(GeneratorLoadError::Row { line, error: Box::new(error.into()) })
+            }
+        };
+        match parsed {
+            Ok(generator) => generators.push(generator),
+            Err(error) => row_errors.push(error),
+        }
+    }
+
+    Ok((generators, row_errors))
+}
+
+fn parse_generator_row(
+    record: &StringRecord,
+    indices: &ColumnIndices,
+    year: u32,
+    extent: &MapExtent,
+    inflation: &InflationModel,
+    id_counter: usize,
+) -> Result<Generator, GeneratorLoadError> {
+    // Parse required fields
+    let capacity: f64 = record.get(indices.capacity)
+        .ok_or_else(|| GeneratorLoadError::InvalidCapacity("Missing capacity".to_string()))?
+        .trim()
+        .parse()
+        .map_err(|_| GeneratorLoadError::InvalidCapacity("Invalid capacity format".to_string()))?;
         
-        // Parse required fields
-        let capacity: f64 = record.get(0)
-            .ok_or_else(|| GeneratorLoadError::InvalidCapacity("Missing capacity".to_string()))?
-            .parse()
-            .map_err(|_| GeneratorLoadError::InvalidCapacity("Invalid capacity format".to_string()))?;
-            
-        let latitude: f64 = record.get(1)
-            .ok_or_else(|| GeneratorLoadError::InvalidCoordinate("Missing latitude".to_string()))?
-            .parse()
-            .map_err(|_| GeneratorLoadError::InvalidCoordinate("Invalid latitude format".to_string()))?;
-            
-        let longitude: f64 = record.get(2)
-            .ok_or_else(|| GeneratorLoadError::InvalidCoordinate("Missing longitude".to_string()))?
-            .parse()
-            .map_err(|_| GeneratorLoadError::InvalidCoordinate("Invalid longitude format".to_string()))?;
-            
-        let fuel_type = record.get(3)
-            .ok_or_else(|| GeneratorLoadError::InvalidFuelType("Missing fuel type".to_string()))?;
-
-        // Transform and validate the data
-        let gen_type = map_fuel_type_to_generator_type(fuel_type)?;
-        let location = transform_coordinates(latitude, longitude)?;
+    let latitude: f64 = record.get(indices.latitude)
+        .ok_or_else(|| GeneratorLoadError::InvalidCoordinate("Missing latitude".to_string()))?
+        .trim()
+        .parse()
+        .map_err(|_| GeneratorLoadError::InvalidCoordinate("Invalid latitude format".to_string()))?;
         
-        let size = normalize_capacity(capacity, &gen_type);
-        let is_coastal = is_location_on_land(&location) && is_coastal_location(&location);
-
-        // Calculate derived values using const_funcs
-        let base_cost = calc_generator_cost(
-            &gen_type,
-            gen_type.get_base_cost(year),
-            year,
-            false, // Would need settlement data to determine if urban
-            is_coastal,
-            false, // Would need terrain data for river check
-        );
-
-        let operating_cost = calc_operating_cost(&gen_type, gen_type.get_operating_cost(year), year);
-        let initial_co2_output = calc_initial_co2_output(&gen_type, size);
-        let decommission_cost = calc_decommission_cost(base_cost);
-
-        // Create the generator
-        let generator = Generator::new(
-            format!("Existing_{}_{}", gen_type.to_string(), id_counter),
-            location,
-            gen_type.clone(),
-            base_cost,
-            capacity,
-            operating_cost,
-            gen_type.get_lifespan(),
-            size,
-            initial_co2_output,
-            decommission_cost,
-        );
-
-        generators.push(generator);
-        id_counter += 1;
-    }
+    let longitude: f64 = record.get(indices.longitude)
+        .ok_or_else(|| GeneratorLoadError::InvalidCoordinate("Missing longitude".to_string()))?
+        .trim()
+        .parse()
+        .map_err(|_| GeneratorLoadError::InvalidCoordinate("Invalid longitude format".to_string()))?;
+        
+    let fuel_type = record.get(indices.fuel_type)
+        .ok_or_else(|| GeneratorLoadError::InvalidFuelType("Missing fuel type".to_string()))?
+        .trim();
 
-    Ok(generators)
-} 
\ No newline at end of file
+    // Transform and validate the data
+    let gen_type = map_fuel_type_to_generator_type(fuel_type)?;
+    let location = transform_coordinates(latitude, longitude, extent)?;
+    
+    let size = normalize_capacity(capacity, &gen_type);
+    let is_coastal = is_location_on_land(&location) && is_coastal_location(&location);
+
+    // Calculate derived values using const_funcs
+    let base_cost = calc_generator_cost(
+        &gen_type,
+        gen_type.get_base_cost(year),
+        year,
+        false, // Would need settlement data to determine if urban
+        is_coastal,
+        false, // Would need terrain data for river check
+        inflation,
+    );
+
+    let operating_cost = calc_operating_cost(&gen_type, gen_type.get_operating_cost(year), year, inflation);
+    let initial_co2_output = calc_initial_co2_output(&gen_type, size);
+    let decommission_cost = calc_decommission_cost(base_cost);
+
+    Ok(Generator::new(
+        format!("Existing_{}_{}", gen_type, id_counter),
+        location,
+        gen_type.clone(),
+        base_cost,
+        capacity,
+        operating_cost,
+        gen_type.get_lifespan(),
+        size,
+        initial_co2_output,
+        decommission_cost,
+    ))
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_column_order_loads_through_the_mapping() {
+        let columns = GeneratorColumnMapping {
+            capacity: "Installed MW".to_string(),
+            latitude: "Lat".to_string(),
+            longitude: "Lon".to_string(),
+            fuel_type: "Fuel".to_string(),
+        };
+        let csv = "Fuel,Lon,Station,Installed MW,Lat\n\
+                   Wind,-9.5,Atlantic,120,53.8\n\
+                   Gas,-6.2,Harbour,not-a-number,53.3\n\
+                   Gas,-6.2,Harbour,400,53.3\n";
+
+        let (generators, row_errors) = parse_generators(csv, 2025, &MapExtent::default(), &columns, &InflationModel::default()).unwrap();
+
+        let types: Vec<&GeneratorType> = generators.iter().map(|g| g.get_generator_type()).collect();
+        assert_eq!(types, vec![&GeneratorType::OnshoreWind, &GeneratorType::GasCombinedCycle]);
+        assert!(matches!(row_errors.as_slice(), [GeneratorLoadError::Row { line: 3, .. }]), "{:?}", row_errors);
+
+        // The default layout's headers aren't in this file
+        assert!(matches!(
+            parse_generators(csv, 2025, &MapExtent::default(), &GeneratorColumnMapping::default(), &InflationModel::default()),
+            Err(GeneratorLoadError::MissingColumn(_))
+        ));
+    }
+}
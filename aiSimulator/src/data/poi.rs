@@ -1,5 +1,6 @@
+use std::cmp::Ordering;
 use serde::{Deserialize, Serialize};
-use crate::config::constants::{MAP_MAX_X, MAP_MAX_Y};
+use crate::config::constants::{MAP_MAX_X, MAP_MAX_Y, LOCATION_SCORE_TIE_EPSILON};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Coordinate {
@@ -7,18 +8,61 @@ pub struct Coordinate {
     pub y: f64,
 }
 
+// Simulated region in grid metres, spanning (0,0) to (max_x, max_y)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MapExtent {
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+impl Default for MapExtent {
+    fn default() -> Self {
+        Self { max_x: MAP_MAX_X, max_y: MAP_MAX_Y }
+    }
+}
+
+impl MapExtent {
+    pub fn contains(&self, coordinate: &Coordinate) -> bool {
+        (0.0..=self.max_x).contains(&coordinate.x) && (0.0..=self.max_y).contains(&coordinate.y)
+    }
+
+    pub fn clamp(&self, x: f64, y: f64) -> Coordinate {
+        Coordinate { x: x.clamp(0.0, self.max_x), y: y.clamp(0.0, self.max_y) }
+    }
+
+    pub fn center(&self) -> Coordinate {
+        Coordinate { x: self.max_x / 2.0, y: self.max_y / 2.0 }
+    }
+}
+
 impl Coordinate {
+    // Clamped to the default Irish extent; use MapExtent::clamp for a configured map
     pub fn new(x: f64, y: f64) -> Self {
-        let x = x.clamp(0.0, MAP_MAX_X);
-        let y = y.clamp(0.0, MAP_MAX_Y);
-        Self { x, y }
+        MapExtent::default().clamp(x, y)
     }
 
+    // Straight-line distance in metres (grid units are metres)
     pub fn distance_to(&self, other: &Coordinate) -> f64 {
         let dx = self.x - other.x;
         let dy = self.y - other.y;
         (dx * dx + dy * dy).sqrt()
     }
+
+    // Stable ordering for breaking ties between locations: lowest x, then lowest y
+    pub fn cmp_position(&self, other: &Coordinate) -> Ordering {
+        self.x.total_cmp(&other.x).then(self.y.total_cmp(&other.y))
+    }
+}
+
+// Whether a candidate location should replace the current best. Scores within
+// LOCATION_SCORE_TIE_EPSILON are ties and go to the lower position, so the
+// result doesn't depend on scan order or float noise.
+pub fn is_better_location(score: f64, location: &Coordinate, best_score: f64, best_location: Option<&Coordinate>) -> bool {
+    if (score - best_score).abs() <= LOCATION_SCORE_TIE_EPSILON {
+        best_location.is_some_and(|best| location.cmp_position(best) == Ordering::Less)
+    } else {
+        score > best_score
+    }
 }
 
 pub trait POI {
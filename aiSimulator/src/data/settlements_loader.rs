@@ -1,9 +1,11 @@
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::BufReader;
 use serde::Deserialize;
 
 use crate::models::settlement::Settlement;
 use crate::config::const_funcs;
+use crate::error::SimulationError;
 
 #[derive(Debug, Deserialize)]
 pub struct SettlementData {
@@ -13,6 +15,8 @@ pub struct SettlementData {
     pub population: u32,
     pub grid_x: f64,
     pub grid_y: f64,
+    #[serde(default)]
+    pub population_points: BTreeMap<u32, u32>, // Census counts or projections keyed by year
 }
 
 #[derive(Debug, Deserialize)]
@@ -20,18 +24,27 @@ pub struct SettlementsList {
     pub settlements: Vec<SettlementData>,
 }
 
-pub fn load_settlements(path: &str, base_year: u32) -> Result<Vec<Settlement>, Box<dyn std::error::Error>> {
+pub fn load_settlements(path: &str, base_year: u32) -> Result<Vec<Settlement>, SimulationError> {
     let file = File::open(path)?;
     let reader = BufReader::new(file);
-    let settlements_list: SettlementsList = serde_json::from_reader(reader)?;
+    let settlements_list: SettlementsList = serde_json::from_reader(reader)
+        .map_err(|e| SimulationError::DataLoad(format!("Invalid settlements file {}: {}", path, e)))?;
 
     let mut settlements_vec = Vec::new();
-    for s in settlements_list.settlements {
+    for mut s in settlements_list.settlements {
          let initial_power_usage = (s.population as f64) * const_funcs::calc_power_usage_per_capita(base_year);
          
          // Transform lat/lon to grid coordinates
          if let Some(grid_coord) = const_funcs::transform_lat_lon_to_grid(s.lat, s.lon) {
-             let settlement = Settlement::new(s.name, grid_coord, s.population, initial_power_usage);
+             // Known points replace the flat growth model up to the last of them; the base
+             // year anchors interpolation towards the first
+             let settlement = if s.population_points.is_empty() {
+                 Settlement::new(s.name, grid_coord, s.population, initial_power_usage)
+             } else {
+                 s.population_points.entry(base_year).or_insert(s.population);
+                 Settlement::new(s.name, grid_coord, s.population, initial_power_usage)
+                     .with_population_points(s.population_points)
+             };
              settlements_vec.push(settlement);
          } else {
              eprintln!("Warning: Settlement {} has coordinates outside the valid range: ({}, {})", 
@@ -0,0 +1,83 @@
+// Structured error type shared by the loaders and simulation entry points
+
+use std::fmt;
+
+use crate::data::generators_loader::GeneratorLoadError;
+
+#[derive(Debug)]
+pub enum SimulationError {
+    /// Filesystem failure (missing file, permissions, failed write)
+    Io(std::io::Error),
+    /// A checkpoint or weights file exists but can't be used
+    Checkpoint(String),
+    /// Invalid configuration or command line options
+    Config(String),
+    /// Input data (settlements, generators) couldn't be loaded
+    DataLoad(String),
+    /// JSON (or other format) encoding/decoding failure
+    Serialization(String),
+    /// Failure raised while running the simulation itself
+    Simulation(String),
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationError::Io(e) => write!(f, "IO error: {}", e),
+            SimulationError::Checkpoint(s) => write!(f, "Checkpoint error: {}", s),
+            SimulationError::Config(s) => write!(f, "Configuration error: {}", s),
+            SimulationError::DataLoad(s) => write!(f, "Data load error: {}", s),
+            SimulationError::Serialization(s) => write!(f, "Serialization error: {}", s),
+            SimulationError::Simulation(s) => write!(f, "Simulation error: {}", s),
+        }
+    }
+}
+
+impl std::error::Error for SimulationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SimulationError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SimulationError {
+    fn from(err: std::io::Error) -> Self {
+        SimulationError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SimulationError {
+    fn from(err: serde_json::Error) -> Self {
+        SimulationError::Serialization(err.to_string())
+    }
+}
+
+impl From<csv::Error> for SimulationError {
+    fn from(err: csv::Error) -> Self {
+        SimulationError::DataLoad(err.to_string())
+    }
+}
+
+impl From<GeneratorLoadError> for SimulationError {
+    fn from(err: GeneratorLoadError) -> Self {
+        match err {
+            GeneratorLoadError::IoError(e) => SimulationError::Io(e),
+            other => SimulationError::DataLoad(other.to_string()),
+        }
+    }
+}
+
+// The CSV exporter and a few helpers still return boxed errors
+impl From<Box<dyn std::error::Error>> for SimulationError {
+    fn from(err: Box<dyn std::error::Error>) -> Self {
+        SimulationError::Simulation(err.to_string())
+    }
+}
+
+impl From<Box<dyn std::error::Error + Send + Sync>> for SimulationError {
+    fn from(err: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        SimulationError::Simulation(err.to_string())
+    }
+}
@@ -139,7 +139,8 @@ impl MetalLocationSearch {
                         let penalty_radius = match gen_type {
                             GeneratorType::Nuclear => 12000.0,
                             GeneratorType::CoalPlant | GeneratorType::GasCombinedCycle => 8000.0,
-                            GeneratorType::OnshoreWind | GeneratorType::OffshoreWind => 5000.0,
+                            GeneratorType::OnshoreWind | GeneratorType::OffshoreWind |
+                            GeneratorType::FloatingOffshoreWind => 5000.0,
                             GeneratorType::HydroDam | GeneratorType::PumpedStorage => 7000.0,
                             GeneratorType::TidalGenerator | GeneratorType::WaveEnergy => 6000.0,
                             _ => 3000.0,
@@ -152,6 +153,7 @@ impl MetalLocationSearch {
                     // Coastal proximity for relevant generator types
                     if matches!(gen_type, 
                         GeneratorType::OffshoreWind | 
+                        GeneratorType::FloatingOffshoreWind | 
                         GeneratorType::TidalGenerator | 
                         GeneratorType::WaveEnergy) {
                         let min_coastal_distance = coastline_points.iter()
@@ -165,7 +167,7 @@ impl MetalLocationSearch {
                     // Apply size penalty
                     score *= 1.0 - (size_penalty as f64 * 0.1);
                     
-                    if score > best_score {
+                    if crate::data::poi::is_better_location(score, &location, best_score, best_location.as_ref()) {
                         best_score = score;
                         best_location = Some(location);
                     }
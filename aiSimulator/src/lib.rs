@@ -1,5 +1,8 @@
 // Main module declarations for organized aiSimulator
 
+// Shared error type
+pub mod error;
+
 // Core simulation modules
 pub mod core {
     pub mod simulation;
@@ -7,6 +10,7 @@ pub mod core {
     pub mod iteration;
     pub mod actions;
     pub mod action_weights_coordinator;
+    pub mod benchmark;
     // Re-export with the old name for backward compatibility
     pub use self::action_weights_coordinator as action_weights;
 }
@@ -28,6 +32,7 @@ pub mod models {
     pub mod generator;
     pub mod power_storage;
     pub mod carbon_offset;
+    pub mod flexible_load;
 }
 
 // Data loaders
@@ -44,12 +49,15 @@ pub mod analysis {
     pub mod location_analysis;
     pub mod analysis;
     pub mod reporting;
+    pub mod uncertainty;
 }
 
 // Utility functions
 pub mod utils {
     pub mod map_handler;
     pub mod spatial_index;
+    pub mod grid_zones;
+    pub mod fingerprint;
     pub mod logging;
     pub mod csv_export;
     pub mod traits;
@@ -65,9 +73,14 @@ pub mod cli {
     pub mod cli;
 }
 
+// Fixtures for the unit tests
+#[cfg(test)]
+pub(crate) mod test_support;
+
 // Re-export commonly used modules
 pub use crate::core::simulation;
 pub use crate::core::multi_simulation;
 pub use crate::core::actions;
 pub use crate::models::generator;
 pub use crate::utils::map_handler::Map;
+pub use crate::error::SimulationError;
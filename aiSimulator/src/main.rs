@@ -2,6 +2,7 @@
 extern crate lazy_static;
 
 use std::error::Error;
+use std::path::Path;
 
 use clap::Parser;
 use rand::{Rng, SeedableRng};
@@ -9,6 +10,9 @@ use rand::rngs::StdRng;
 
 // Import using updated module structure
 use eirgrid::core::multi_simulation::run_multi_simulation;
+use eirgrid::core::iteration::{replay_best_plan, evaluate_action_plan};
+use eirgrid::core::actions::load_action_plan;
+use eirgrid::core::benchmark::run_benchmark;
 use eirgrid::core::action_weights::{ SimulationMetrics, ActionResult};
 
 use eirgrid::models::generator::{Generator, GeneratorType};
@@ -22,7 +26,10 @@ use eirgrid::data::poi::Coordinate;
 
 use eirgrid::utils::map_handler::Map;
 use eirgrid::utils::logging::{self, OperationCategory, FileIOType};
-use eirgrid::cli::cli::Args;
+use eirgrid::cli::cli::{Args, Command};
+use eirgrid::ai::learning::weights::ActionWeights;
+use eirgrid::utils::csv_export;
+use eirgrid::SimulationError;
 
 // Constants
 const SIMULATION_START_YEAR: u32 = 2025;
@@ -34,6 +41,12 @@ fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
      
     // Initialize logging with timing and debug logging parameters
     logging::init_logging(args.enable_timing(), args.debug_logging());
+    logging::set_log_level(args.log_level());
+
+    // Subcommands run instead of the training loop
+    if let Some(command) = args.command() {
+        return run_command(command, &args);
+    }
      
     println!("EirGrid Power System Simulator (2025-2050)");
     println!("Debug logging: {}, CSV export: {}, Weights debugging: {}", 
@@ -41,11 +54,10 @@ fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
              if args.enable_csv_export() { "enabled" } else { "disabled" },
              if args.debug_weights() { "enabled" } else { "disabled" });
      
-    let config = SimulationConfig::default();
-    let mut map = Map::new(config);
+    let mut map = Map::new(build_config(&args));
      
     // Initialize the map, now with seed support
-    initialize_map(&mut map, args.seed());
+    initialize_map(&mut map, args.seed())?;
      
     run_multi_simulation(
         &map,
@@ -59,19 +71,161 @@ fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
         args.force_full_simulation(),
         args.seed(),
         args.verbose_state_logging(),
-        if args.cost_only() { Some("cost_only") } else { None },
+        optimization_mode(&args),
         args.enable_energy_sales(),
         args.enable_csv_export(),
         args.debug_weights(),
         args.enable_construction_delays(),
         args.track_weight_history(),
+        args.resume_weights(),
+        args.binary_checkpoints(),
+        None,
     )?;
 
+    if let Some(path) = args.profile() {
+        logging::write_profile(Path::new(path))?;
+        println!("Profile written to {}", path);
+    }
+
+    Ok(())
+}
+
+fn build_config(args: &Args) -> SimulationConfig {
+    let builder = SimulationConfig::builder()
+        .uncertainty_draws(args.uncertainty_draws())
+        .renewable_correlation(args.renewable_correlation())
+        .cost_cap(args.cost_cap())
+        .carbon_budget(args.carbon_budget())
+        .net_zero_tolerance(args.net_zero_tolerance())
+        .net_zero_real_reductions_only(args.real_reductions_only())
+        .renewable_mandate(args.renewable_mandate())
+        .embodied_carbon_factor(args.embodied_carbon_factor())
+        .capacity_payment_rate(args.capacity_payment())
+        .inflation_rate(args.inflation_rate())
+        .inflation_overrides(args.inflation_overrides())
+        .annual_capex_limit(args.annual_capex_limit())
+        .score_weights(args.score_weights())
+        .parallel_deficit_candidates(args.deficit_candidates())
+        .max_deficit_attempts(args.max_deficit_attempts())
+        .prune_weight_threshold(args.prune_weights())
+        .trace_merges(args.trace_merges())
+        .max_runtime_secs(args.max_runtime())
+        .keep_top_results(args.top_k())
+        .output_format(args.output_format())
+        .full_run_strategy(args.full_run_strategy())
+        .replay_best_in_full_runs(!args.no_full_run_replay())
+        .opinion_weighting(args.opinion_weighting())
+        .exploration_schedule(args.exploration_schedule())
+        .noop_max_share(args.noop_max_share());
+    args.max_builds_per_year().into_iter()
+        .fold(builder, |builder, (gen_type, cap)| builder.max_builds_per_year(gen_type, cap))
+        .build()
+}
+
+fn optimization_mode(args: &Args) -> Option<&'static str> {
+    if args.score_weights().is_some() {
+        Some("weighted")
+    } else if args.cost_only() {
+        Some("cost_only")
+    } else if args.trajectory_scoring() {
+        Some("trajectory")
+    } else if !args.renewable_mandate().is_empty() {
+        Some("renewable_mandate")
+    } else if args.min_footprint() {
+        Some("min_footprint")
+    } else if args.cost_cap().is_some() {
+        Some("budget_capped")
+    } else {
+        None
+    }
+}
+
+fn run_command(command: &Command, args: &Args) -> Result<(), Box<dyn Error + Send + Sync>> {
+    match command {
+        Command::Planner { weights, output } => {
+            let weights = ActionWeights::load_from_file(weights)?;
+            let plan = weights.export_plan();
+
+            if plan.is_empty() {
+                println!("No best actions stored in weights file");
+                return Ok(());
+            }
+
+            match output {
+                Some(path) => {
+                    csv_export::write_action_plan(path, &plan).map_err(SimulationError::from)?;
+                    println!("Wrote {} planned actions to {}", plan.len(), path);
+                },
+                None => {
+                    for (year, action) in &plan {
+                        println!("{},{}", year, action);
+                    }
+                }
+            }
+        }
+        Command::Replay { weights, seed, output } => {
+            let mut weights = ActionWeights::load_from_file(weights)?;
+
+            let mut map = Map::new(build_config(args));
+            initialize_map(&mut map, *seed)?;
+
+            let result = replay_best_plan(
+                &mut map,
+                &mut weights,
+                *seed,
+                optimization_mode(args),
+                args.enable_energy_sales(),
+                args.enable_construction_delays(),
+            )?;
+
+            std::fs::create_dir_all(output)?;
+            let exporter = csv_export::CsvExporter::new(output);
+            exporter.export_simulation_results(
+                &map,
+                &result.actions,
+                &result.metrics,
+                &csv_export::convert_yearly_metrics(&result.yearly_metrics),
+            ).map_err(SimulationError::from)?;
+            println!("Replayed {} actions; enhanced CSVs written to {}", result.actions.len(), output);
+        }
+        Command::Evaluate { plan, seed, output } => {
+            let plan = load_action_plan(plan)?;
+
+            let mut map = Map::new(build_config(args));
+            initialize_map(&mut map, *seed)?;
+
+            let result = evaluate_action_plan(
+                &mut map,
+                &plan,
+                args.enable_energy_sales(),
+                args.enable_construction_delays(),
+            )?;
+
+            std::fs::create_dir_all(output)?;
+            let exporter = csv_export::CsvExporter::new(output);
+            exporter.export_simulation_results(
+                &map,
+                &result.actions,
+                &result.metrics,
+                &csv_export::convert_yearly_metrics(&result.yearly_metrics),
+            ).map_err(SimulationError::from)?;
+            println!("{}", result.output);
+            println!("Enhanced CSVs written to {}", output);
+        }
+        Command::Benchmark { iterations, threads, seed } => {
+            let mut map = Map::new(build_config(args));
+            initialize_map(&mut map, Some(*seed))?;
+
+            let report = run_benchmark(&map, *iterations, *threads, *seed)?;
+            print!("{}", report);
+        }
+    }
+
     Ok(())
 }
 
 // Modified to accept a seed parameter
-fn initialize_map(map: &mut Map, seed: Option<u64>) {
+fn initialize_map(map: &mut Map, seed: Option<u64>) -> Result<(), SimulationError> {
     let _timing = logging::start_timing("initialize_map",
         OperationCategory::FileIO { subcategory: FileIOType::DataLoad });
      
@@ -115,7 +269,9 @@ fn initialize_map(map: &mut Map, seed: Option<u64>) {
     }
      
     // Load existing generators from CSV, with deterministic fallbacks if needed
-    match generators_loader::load_generators("aiSimulator/assets/ireland_generators.csv", SIMULATION_START_YEAR) {
+    let extent = map.get_config().map_extent;
+    let generator_columns = map.get_config().generator_columns.clone();
+    match generators_loader::load_generators("aiSimulator/assets/ireland_generators.csv", SIMULATION_START_YEAR, &extent, &generator_columns, &map.get_config().inflation) {
         Ok(loaded_generators) => {
             let num_generators = loaded_generators.len();
             for generator in loaded_generators {
@@ -190,6 +346,8 @@ fn initialize_map(map: &mut Map, seed: Option<u64>) {
             }
         }
     }
+
+    map.validate()
 }
 
 // Fix the helper function for converting SimulationMetrics to ActionResult
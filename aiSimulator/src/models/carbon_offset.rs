@@ -1,10 +1,11 @@
 use serde::{Deserialize, Serialize};
 use crate::data::poi::{POI, Coordinate};
-use crate::config::const_funcs::calc_inflation_factor;
+use crate::config::simulation_config::InflationModel;
 use std::str::FromStr;
 use std::fmt;
 use crate::config::const_funcs::{calc_carbon_offset_planning_time, calc_carbon_offset_construction_time};
 use crate::config::constants::*;
+use crate::utils::traits::GridAsset;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum CarbonOffsetType {
@@ -28,6 +29,27 @@ impl FromStr for CarbonOffsetType {
     }
 }
 
+impl CarbonOffsetType {
+    // One-off construction emissions in tonnes CO2 per tonne a year of removal capacity
+    pub fn embodied_co2_intensity(&self) -> f64 {
+        match self {
+            CarbonOffsetType::Forest | CarbonOffsetType::Wetland => NATURAL_OFFSET_EMBODIED_CO2,
+            CarbonOffsetType::ActiveCapture => ACTIVE_CAPTURE_EMBODIED_CO2,
+            CarbonOffsetType::CarbonCredit => 0.0,
+        }
+    }
+
+    // Years an offset of this type keeps sequestering/offsetting after commissioning
+    pub fn lifespan(&self) -> u32 {
+        match self {
+            CarbonOffsetType::Forest => FOREST_OFFSET_LIFESPAN,
+            CarbonOffsetType::Wetland => WETLAND_OFFSET_LIFESPAN,
+            CarbonOffsetType::ActiveCapture => ACTIVE_CAPTURE_OFFSET_LIFESPAN,
+            CarbonOffsetType::CarbonCredit => CARBON_CREDIT_OFFSET_LIFESPAN,
+        }
+    }
+}
+
 impl fmt::Display for CarbonOffsetType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -185,17 +207,17 @@ impl CarbonOffset {
         self.construction_status == ConstructionStatus::Operational
     }
 
-    pub fn get_current_cost(&self, year: u32) -> f64 {
+    pub fn get_current_cost(&self, year: u32, inflation: &InflationModel) -> f64 {
         // Calculate base cost with inflation
-        let inflation_factor = (1.0 + INFLATION_RATE).powi((year - BASE_YEAR) as i32);
+        let inflation_factor = inflation.factor(year);
         let base_cost = self.base_cost * inflation_factor;
         
         // Apply the construction cost multiplier
         base_cost * self.construction_cost_multiplier
     }
 
-    pub fn get_current_operating_cost(&self, year: u32) -> f64 {
-        let inflation = calc_inflation_factor(year);
+    pub fn get_current_operating_cost(&self, year: u32, inflation: &InflationModel) -> f64 {
+        let inflation = inflation.factor(year);
         let efficiency_factor = match self.offset_type {
             CarbonOffsetType::ActiveCapture => 0.97f64, // 3% efficiency improvement
             CarbonOffsetType::Forest => 1.0f64,        // Stable maintenance costs
@@ -207,14 +229,27 @@ impl CarbonOffset {
         self.base_operating_cost * inflation * efficiency_factor.powf(years_from_base)
     }
 
+    // Years the offset keeps sequestering/offsetting after commissioning
+    pub fn get_lifespan(&self) -> u32 {
+        self.offset_type.lifespan()
+    }
+
+    pub fn get_commissioning_year(&self) -> u32 {
+        self.commissioning_year
+    }
+
+    // An offset past its lifespan no longer contributes to net emissions
+    pub fn is_expired(&self, year: u32) -> bool {
+        year >= self.commissioning_year + self.get_lifespan()
+    }
+
     pub fn calc_carbon_offset(&self, year: u32) -> f64 {
+        if self.is_expired(year) {
+            return 0.0;
+        }
+
         // Get base offset value first
-        let base_offset = match self.offset_type {
-            CarbonOffsetType::Forest => self.size * FOREST_SEQUESTRATION_RATE,
-            CarbonOffsetType::ActiveCapture => self.size * ACTIVE_CAPTURE_MULTIPLIER,
-            CarbonOffsetType::CarbonCredit => self.size * CARBON_CREDIT_MULTIPLIER,
-            CarbonOffsetType::Wetland => self.size * WETLAND_SEQUESTRATION_RATE,
-        };
+        let base_offset = self.annual_capacity();
         
         // Apply effectiveness based on construction status
         match self.construction_status {
@@ -222,8 +257,8 @@ impl CarbonOffset {
                 // Fully operational - calculate with maturity factor
                 let maturity_factor = match self.offset_type {
                     CarbonOffsetType::Forest | CarbonOffsetType::Wetland => {
-                        // Natural solutions take time to mature
-                        let years_from_start = (year - self.construction_complete_year) as f64;
+                        // Natural solutions take time to mature (ramp-up over the first years)
+                        let years_from_start = year.saturating_sub(self.construction_complete_year) as f64;
                         (1.0 - (CARBON_OFFSET_MATURITY_FACTOR * years_from_start).exp()).clamp(0.0, 1.0)
                     },
                     _ => 1.0, // Other solutions work at full capacity immediately
@@ -259,6 +294,21 @@ impl CarbonOffset {
         }
     }
 
+    // Tonnes a year removed at full maturity and efficiency
+    pub fn annual_capacity(&self) -> f64 {
+        match self.offset_type {
+            CarbonOffsetType::Forest => self.size * FOREST_SEQUESTRATION_RATE,
+            CarbonOffsetType::ActiveCapture => self.size * ACTIVE_CAPTURE_MULTIPLIER,
+            CarbonOffsetType::CarbonCredit => self.size * CARBON_CREDIT_MULTIPLIER,
+            CarbonOffsetType::Wetland => self.size * WETLAND_SEQUESTRATION_RATE,
+        }
+    }
+
+    // Tonnes CO2 emitted building this offset, charged once in its commissioning year
+    pub fn embodied_co2(&self) -> f64 {
+        self.annual_capacity() * self.offset_type.embodied_co2_intensity()
+    }
+
     pub fn get_power_consumption(&self) -> f64 {
         self.power_consumption
     }
@@ -313,6 +363,10 @@ impl CarbonOffset {
     pub fn get_offset_type(&self) -> &CarbonOffsetType {
         &self.offset_type
     }
+
+    pub fn get_size(&self) -> f64 {
+        self.size
+    }
 }
 
 impl POI for CarbonOffset {
@@ -323,4 +377,42 @@ impl POI for CarbonOffset {
     fn get_id(&self) -> &str {
         &self.id
     }
-} 
\ No newline at end of file
+}
+
+impl GridAsset for CarbonOffset {
+    fn annual_cost(&self, year: u32, inflation: &InflationModel) -> f64 {
+        self.get_current_cost(year, inflation)
+    }
+
+    fn co2_impact(&self, year: u32) -> f64 {
+        -self.calc_carbon_offset(year)
+    }
+
+    fn coordinate(&self) -> &Coordinate {
+        &self.coordinate
+    }
+
+    fn is_active(&self) -> bool {
+        self.is_operational()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::simulation_config::SimulationConfig;
+    use crate::test_support::{empty_map, TEST_SITE};
+
+    #[test]
+    fn forest_offset_stops_contributing_after_its_lifespan() {
+        let mut map = empty_map(SimulationConfig::default());
+        map.add_carbon_offset(CarbonOffset::new(
+            "Forest_1".to_string(), TEST_SITE, CarbonOffsetType::Forest, 1.0e6, 1.0e4, 100.0, 0.9,
+        ));
+
+        let last_year = 2025 + FOREST_OFFSET_LIFESPAN - 1;
+        assert!(map.calc_total_carbon_offset(2026) > 0.0);
+        assert!(map.calc_total_carbon_offset(last_year) > 0.0);
+        assert_eq!(map.calc_total_carbon_offset(last_year + 1), 0.0);
+    }
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use crate::data::poi::Coordinate;
+use crate::config::simulation_config::InflationModel;
+use crate::config::constants::FLEXIBLE_LOAD_COST_PER_MW;
+use crate::utils::traits::GridAsset;
+
+// A managed EV charging programme in one settlement. Smart charging doesn't change how much
+// energy the cars take, only when: `shiftable_fraction` of `demand` can be moved out of hours
+// the grid is short into hours it has spare output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlexibleLoad {
+    id: String,
+    settlement: String,
+    coordinate: Coordinate,
+    demand: f64,             // MW of EV charging brought under managed control
+    shiftable_fraction: f64, // Share of `demand` that can move to another hour
+    build_year: u32,
+}
+
+impl FlexibleLoad {
+    pub fn new(id: String, settlement: String, coordinate: Coordinate, demand: f64, shiftable_fraction: f64, build_year: u32) -> Self {
+        Self {
+            id,
+            settlement,
+            coordinate,
+            demand,
+            shiftable_fraction: shiftable_fraction.clamp(0.0, 1.0),
+            build_year,
+        }
+    }
+
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn get_settlement(&self) -> &str {
+        &self.settlement
+    }
+
+    pub fn get_coordinate(&self) -> &Coordinate {
+        &self.coordinate
+    }
+
+    pub fn get_demand(&self) -> f64 {
+        self.demand
+    }
+
+    pub fn get_build_year(&self) -> u32 {
+        self.build_year
+    }
+
+    // MW that can be moved between hours
+    pub fn shiftable_demand(&self) -> f64 {
+        self.demand * self.shiftable_fraction
+    }
+
+    // Chargers, metering and the aggregation platform, priced per MW under control
+    pub fn get_current_cost(&self, year: u32, inflation: &InflationModel) -> f64 {
+        self.demand * FLEXIBLE_LOAD_COST_PER_MW * inflation.factor(year)
+    }
+}
+
+impl GridAsset for FlexibleLoad {
+    fn annual_cost(&self, year: u32, inflation: &InflationModel) -> f64 {
+        self.get_current_cost(year, inflation)
+    }
+
+    fn co2_impact(&self, _year: u32) -> f64 {
+        0.0
+    }
+
+    fn coordinate(&self) -> &Coordinate {
+        &self.coordinate
+    }
+
+    fn is_active(&self) -> bool {
+        true
+    }
+}
@@ -3,8 +3,10 @@ use serde::{Deserialize, Serialize};
 use crate::data::poi::{POI, Coordinate};
 use crate::config::constants::*;
 use crate::config::const_funcs::{calc_generator_cost, calc_operating_cost, calc_cost_opinion, calc_type_opinion, calc_planning_permission_time, calc_construction_time};
-use crate::config::simulation_config::GeneratorConstraints;
+use crate::config::simulation_config::{GeneratorConstraints, InflationModel};
+use crate::analysis::uncertainty::AvailabilityFactors;
 use super::power_storage::PowerStorageSystem;
+use crate::utils::traits::GridAsset;
 use std::str::FromStr;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -12,6 +14,7 @@ pub enum GeneratorType {
     // Wind variations
     OnshoreWind,
     OffshoreWind,
+    FloatingOffshoreWind, // Deep-water sites beyond fixed-foundation reach
     
     // Solar variations
     DomesticSolar,
@@ -33,30 +36,19 @@ pub enum GeneratorType {
     BatteryStorage,  // New type for battery storage
     TidalGenerator,
     WaveEnergy,
+
+    // Hydrogen pathway: electrolyzers store surplus power, turbines burn it in deficits
+    HydrogenElectrolyzer,
+    HydrogenTurbine,
 }
 
 impl FromStr for GeneratorType {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "OnshoreWind" => Ok(GeneratorType::OnshoreWind),
-            "OffshoreWind" => Ok(GeneratorType::OffshoreWind),
-            "DomesticSolar" => Ok(GeneratorType::DomesticSolar),
-            "CommercialSolar" => Ok(GeneratorType::CommercialSolar),
-            "UtilitySolar" => Ok(GeneratorType::UtilitySolar),
-            "Nuclear" => Ok(GeneratorType::Nuclear),
-            "CoalPlant" => Ok(GeneratorType::CoalPlant),
-            "GasCombinedCycle" => Ok(GeneratorType::GasCombinedCycle),
-            "GasPeaker" => Ok(GeneratorType::GasPeaker),
-            "Biomass" => Ok(GeneratorType::Biomass),
-            "HydroDam" => Ok(GeneratorType::HydroDam),
-            "PumpedStorage" => Ok(GeneratorType::PumpedStorage),
-            "BatteryStorage" => Ok(GeneratorType::BatteryStorage),
-            "TidalGenerator" => Ok(GeneratorType::TidalGenerator),
-            "WaveEnergy" => Ok(GeneratorType::WaveEnergy),
-            _ => Err(format!("Unknown generator type: {}", s)),
-        }
+        GeneratorType::iter()
+            .find(|gen_type| gen_type.to_string() == s)
+            .ok_or_else(|| format!("Unknown generator type: {}", s))
     }
 }
 
@@ -65,6 +57,7 @@ impl fmt::Display for GeneratorType {
         match self {
             GeneratorType::OnshoreWind => write!(f, "OnshoreWind"),
             GeneratorType::OffshoreWind => write!(f, "OffshoreWind"),
+            GeneratorType::FloatingOffshoreWind => write!(f, "FloatingOffshoreWind"),
             GeneratorType::DomesticSolar => write!(f, "DomesticSolar"),
             GeneratorType::CommercialSolar => write!(f, "CommercialSolar"),
             GeneratorType::UtilitySolar => write!(f, "UtilitySolar"),
@@ -78,21 +71,161 @@ impl fmt::Display for GeneratorType {
             GeneratorType::BatteryStorage => write!(f, "BatteryStorage"),
             GeneratorType::TidalGenerator => write!(f, "TidalGenerator"),
             GeneratorType::WaveEnergy => write!(f, "WaveEnergy"),
+            GeneratorType::HydrogenElectrolyzer => write!(f, "HydrogenElectrolyzer"),
+            GeneratorType::HydrogenTurbine => write!(f, "HydrogenTurbine"),
         }
     }
 }
 
 impl GeneratorType {
+    /// Every variant, in declaration order. Loop over this instead of listing types by hand
+    /// so a new variant can't be missed.
+    pub const ALL: [GeneratorType; 18] = [
+        GeneratorType::OnshoreWind,
+        GeneratorType::OffshoreWind,
+        GeneratorType::FloatingOffshoreWind,
+        GeneratorType::DomesticSolar,
+        GeneratorType::CommercialSolar,
+        GeneratorType::UtilitySolar,
+        GeneratorType::Nuclear,
+        GeneratorType::CoalPlant,
+        GeneratorType::GasCombinedCycle,
+        GeneratorType::GasPeaker,
+        GeneratorType::Biomass,
+        GeneratorType::HydroDam,
+        GeneratorType::PumpedStorage,
+        GeneratorType::BatteryStorage,
+        GeneratorType::TidalGenerator,
+        GeneratorType::WaveEnergy,
+        GeneratorType::HydrogenElectrolyzer,
+        GeneratorType::HydrogenTurbine,
+    ];
+
+    pub fn all() -> &'static [GeneratorType] {
+        &Self::ALL
+    }
+
+    pub fn iter() -> impl Iterator<Item = GeneratorType> {
+        Self::ALL.iter().cloned()
+    }
+
+    // Counts towards the renewable share; storage and hydrogen only move energy others generated
+    pub fn is_renewable(&self) -> bool {
+        matches!(self,
+            GeneratorType::OnshoreWind |
+            GeneratorType::OffshoreWind |
+            GeneratorType::FloatingOffshoreWind |
+            GeneratorType::DomesticSolar |
+            GeneratorType::CommercialSolar |
+            GeneratorType::UtilitySolar |
+            GeneratorType::Biomass |
+            GeneratorType::HydroDam |
+            GeneratorType::TidalGenerator |
+            GeneratorType::WaveEnergy
+        )
+    }
+
     pub fn is_intermittent(&self) -> bool {
         matches!(self,
             GeneratorType::OnshoreWind |
             GeneratorType::OffshoreWind |
+            GeneratorType::FloatingOffshoreWind |
             GeneratorType::DomesticSolar |
             GeneratorType::CommercialSolar |
             GeneratorType::UtilitySolar
         )
     }
 
+    // Conversions an existing plant can undergo in place; only coal to biomass is modelled
+    pub fn can_retrofit_to(&self, target: &GeneratorType) -> bool {
+        matches!((self, target), (GeneratorType::CoalPlant, GeneratorType::Biomass))
+    }
+
+    // Fraction of operating cost spent on bought-in fuel. Hydrogen turbines burn stored hydrogen,
+    // which is already paid for through the electrolyzers' running costs.
+    pub fn fuel_cost_share(&self) -> f64 {
+        match self {
+            GeneratorType::CoalPlant => COAL_FUEL_COST_SHARE,
+            GeneratorType::GasCombinedCycle => GAS_CC_FUEL_COST_SHARE,
+            GeneratorType::GasPeaker => GAS_PEAKER_FUEL_COST_SHARE,
+            GeneratorType::Biomass => BIOMASS_FUEL_COST_SHARE,
+            GeneratorType::Nuclear => NUCLEAR_FUEL_COST_SHARE,
+            _ => 0.0,
+        }
+    }
+
+    // Land occupied per MW of capacity in km²
+    pub fn land_intensity(&self) -> f64 {
+        match self {
+            GeneratorType::OnshoreWind => ONSHORE_WIND_LAND_INTENSITY,
+            GeneratorType::UtilitySolar => UTILITY_SOLAR_LAND_INTENSITY,
+            GeneratorType::Nuclear => NUCLEAR_LAND_INTENSITY,
+            GeneratorType::CoalPlant => COAL_LAND_INTENSITY,
+            GeneratorType::GasCombinedCycle |
+            GeneratorType::GasPeaker => GAS_LAND_INTENSITY,
+            GeneratorType::Biomass => BIOMASS_LAND_INTENSITY,
+            GeneratorType::HydroDam => HYDRO_DAM_LAND_INTENSITY,
+            GeneratorType::PumpedStorage => PUMPED_STORAGE_LAND_INTENSITY,
+            GeneratorType::BatteryStorage => BATTERY_LAND_INTENSITY,
+            GeneratorType::HydrogenElectrolyzer |
+            GeneratorType::HydrogenTurbine => HYDROGEN_LAND_INTENSITY,
+            GeneratorType::OffshoreWind |
+            GeneratorType::FloatingOffshoreWind |
+            GeneratorType::DomesticSolar |
+            GeneratorType::CommercialSolar |
+            GeneratorType::TidalGenerator |
+            GeneratorType::WaveEnergy => 0.0,
+        }
+    }
+
+    // One-off construction emissions in tonnes CO2 per MW of capacity
+    pub fn embodied_co2_intensity(&self) -> f64 {
+        match self {
+            GeneratorType::OnshoreWind => ONSHORE_WIND_EMBODIED_CO2,
+            GeneratorType::OffshoreWind => OFFSHORE_WIND_EMBODIED_CO2,
+            GeneratorType::FloatingOffshoreWind => FLOATING_OFFSHORE_WIND_EMBODIED_CO2,
+            GeneratorType::DomesticSolar |
+            GeneratorType::CommercialSolar |
+            GeneratorType::UtilitySolar => SOLAR_EMBODIED_CO2,
+            GeneratorType::Nuclear => NUCLEAR_EMBODIED_CO2,
+            GeneratorType::CoalPlant => COAL_EMBODIED_CO2,
+            GeneratorType::GasCombinedCycle |
+            GeneratorType::GasPeaker => GAS_EMBODIED_CO2,
+            GeneratorType::Biomass => BIOMASS_EMBODIED_CO2,
+            GeneratorType::HydroDam => HYDRO_DAM_EMBODIED_CO2,
+            GeneratorType::PumpedStorage => PUMPED_STORAGE_EMBODIED_CO2,
+            GeneratorType::BatteryStorage => BATTERY_EMBODIED_CO2,
+            GeneratorType::TidalGenerator |
+            GeneratorType::WaveEnergy => MARINE_EMBODIED_CO2,
+            GeneratorType::HydrogenElectrolyzer |
+            GeneratorType::HydrogenTurbine => HYDROGEN_EMBODIED_CO2,
+        }
+    }
+
+    // Electrolyzers draw power rather than supply it, so they earn no credit
+    pub fn capacity_credit(&self) -> f64 {
+        match self {
+            GeneratorType::OnshoreWind |
+            GeneratorType::OffshoreWind |
+            GeneratorType::FloatingOffshoreWind => WIND_CAPACITY_CREDIT,
+            GeneratorType::DomesticSolar |
+            GeneratorType::CommercialSolar |
+            GeneratorType::UtilitySolar => SOLAR_CAPACITY_CREDIT,
+            GeneratorType::Nuclear => NUCLEAR_CAPACITY_CREDIT,
+            GeneratorType::CoalPlant |
+            GeneratorType::GasCombinedCycle |
+            GeneratorType::GasPeaker |
+            GeneratorType::Biomass |
+            GeneratorType::HydrogenTurbine => THERMAL_CAPACITY_CREDIT,
+            GeneratorType::HydroDam => HYDRO_DAM_CAPACITY_CREDIT,
+            GeneratorType::PumpedStorage => PUMPED_STORAGE_CAPACITY_CREDIT,
+            GeneratorType::BatteryStorage => BATTERY_CAPACITY_CREDIT,
+            GeneratorType::TidalGenerator |
+            GeneratorType::WaveEnergy => MARINE_CAPACITY_CREDIT,
+            GeneratorType::HydrogenElectrolyzer => 0.0,
+        }
+    }
+
     pub fn is_storage(&self) -> bool {
         matches!(self,
             GeneratorType::PumpedStorage |
@@ -100,11 +233,20 @@ impl GeneratorType {
         )
     }
 
+    // Hydrogen units only move stored energy, so they add nothing to the standing balance
+    pub fn is_hydrogen(&self) -> bool {
+        matches!(self,
+            GeneratorType::HydrogenElectrolyzer |
+            GeneratorType::HydrogenTurbine
+        )
+    }
+
     pub fn get_size_constraints(&self) -> (f64, f64) {
         match *self {
             // Wind constraints
             GeneratorType::OnshoreWind => (ONSHORE_WIND_MIN_SIZE, MAX_GENERATOR_SIZE),
             GeneratorType::OffshoreWind => (OFFSHORE_WIND_MIN_SIZE, MAX_GENERATOR_SIZE),
+            GeneratorType::FloatingOffshoreWind => (FLOATING_OFFSHORE_WIND_MIN_SIZE, MAX_GENERATOR_SIZE),
             
             // Solar constraints
             GeneratorType::DomesticSolar => (DOMESTIC_SOLAR_MIN_SIZE, DOMESTIC_SOLAR_MAX_SIZE),
@@ -126,6 +268,8 @@ impl GeneratorType {
             GeneratorType::TidalGenerator => (TIDAL_MIN_SIZE, TIDAL_MAX_SIZE),
             GeneratorType::WaveEnergy => (WAVE_MIN_SIZE, WAVE_MAX_SIZE),
             GeneratorType::BatteryStorage => (BATTERY_MIN_SIZE, BATTERY_MAX_SIZE),
+            GeneratorType::HydrogenElectrolyzer |
+            GeneratorType::HydrogenTurbine => (HYDROGEN_MIN_SIZE, HYDROGEN_MAX_SIZE),
         }
     }
 
@@ -142,6 +286,7 @@ impl GeneratorType {
     pub fn requires_water(&self) -> bool {
         match self {
             GeneratorType::OffshoreWind |
+            GeneratorType::FloatingOffshoreWind |
             GeneratorType::TidalGenerator |
             GeneratorType::WaveEnergy => true,
             GeneratorType::HydroDam |
@@ -157,6 +302,7 @@ impl GeneratorType {
         match *self {
             GeneratorType::OnshoreWind => 1.0,
             GeneratorType::OffshoreWind => 1.0,
+            GeneratorType::FloatingOffshoreWind => 1.0,
             GeneratorType::DomesticSolar => 1.0,
             GeneratorType::CommercialSolar => 1.0,
             GeneratorType::UtilitySolar => 1.0,
@@ -168,6 +314,8 @@ impl GeneratorType {
             GeneratorType::HydroDam => 1.0,
             GeneratorType::PumpedStorage => 1.0,
             GeneratorType::BatteryStorage => 1.0,
+            GeneratorType::HydrogenElectrolyzer => 1.0,
+            GeneratorType::HydrogenTurbine => 1.0,
             GeneratorType::TidalGenerator => {
                 // Efficiency improves significantly over time as technology matures
                 let years_from_base = (year - BASE_YEAR) as f64;
@@ -185,6 +333,7 @@ impl GeneratorType {
         match *self {
             GeneratorType::OnshoreWind => WIND_COST_REDUCTION,
             GeneratorType::OffshoreWind => WIND_COST_REDUCTION,
+            GeneratorType::FloatingOffshoreWind => WIND_COST_REDUCTION,
             GeneratorType::DomesticSolar => SOLAR_COST_REDUCTION,
             GeneratorType::CommercialSolar => SOLAR_COST_REDUCTION,
             GeneratorType::UtilitySolar => SOLAR_COST_REDUCTION,
@@ -198,6 +347,8 @@ impl GeneratorType {
             GeneratorType::BatteryStorage => 0.97,
             GeneratorType::TidalGenerator => 0.95,
             GeneratorType::WaveEnergy => 0.95,
+            GeneratorType::HydrogenElectrolyzer => 0.95,
+            GeneratorType::HydrogenTurbine => 0.99,
         }
     }
 
@@ -205,6 +356,7 @@ impl GeneratorType {
         match *self {
             GeneratorType::OnshoreWind => WIND_BASE_OPINION,
             GeneratorType::OffshoreWind => WIND_BASE_OPINION,
+            GeneratorType::FloatingOffshoreWind => WIND_BASE_OPINION,
             GeneratorType::DomesticSolar => SOLAR_BASE_OPINION,
             GeneratorType::CommercialSolar => SOLAR_BASE_OPINION,
             GeneratorType::UtilitySolar => SOLAR_BASE_OPINION,
@@ -218,6 +370,8 @@ impl GeneratorType {
             GeneratorType::BatteryStorage => BATTERY_BASE_OPINION,
             GeneratorType::TidalGenerator => TIDAL_BASE_OPINION,
             GeneratorType::WaveEnergy => WAVE_BASE_OPINION,
+            GeneratorType::HydrogenElectrolyzer => HYDROGEN_BASE_OPINION,
+            GeneratorType::HydrogenTurbine => HYDROGEN_BASE_OPINION,
         }
     }
 
@@ -225,6 +379,7 @@ impl GeneratorType {
         match *self {
             GeneratorType::OnshoreWind => WIND_OPINION_CHANGE,
             GeneratorType::OffshoreWind => WIND_OPINION_CHANGE,
+            GeneratorType::FloatingOffshoreWind => WIND_OPINION_CHANGE,
             GeneratorType::DomesticSolar => SOLAR_OPINION_CHANGE,
             GeneratorType::CommercialSolar => SOLAR_OPINION_CHANGE,
             GeneratorType::UtilitySolar => SOLAR_OPINION_CHANGE,
@@ -238,6 +393,8 @@ impl GeneratorType {
             GeneratorType::BatteryStorage => MARINE_OPINION_CHANGE,
             GeneratorType::TidalGenerator => TIDAL_OPINION_CHANGE,
             GeneratorType::WaveEnergy => WAVE_OPINION_CHANGE,
+            GeneratorType::HydrogenElectrolyzer => HYDROGEN_OPINION_CHANGE,
+            GeneratorType::HydrogenTurbine => HYDROGEN_OPINION_CHANGE,
         }
     }
 
@@ -249,6 +406,9 @@ impl GeneratorType {
             // Offshore wind: assume ~€4M per MW; for an 800 MW plant, roughly €3.2B total.
             GeneratorType::OffshoreWind => 4_000_000.0,       
 
+            // Floating offshore wind: moorings and floating substructures push this to ~€5.5M per MW.
+            GeneratorType::FloatingOffshoreWind => 5_500_000.0,
+
             // Domestic solar: for a small (10 MW) rooftop-type installation, about €1M per MW.
             GeneratorType::DomesticSolar => 10_000_000.0,       // 10 MW * €1M/MW
 
@@ -290,6 +450,12 @@ impl GeneratorType {
 
             // Wave energy: typically higher on a per MW basis – around €8M per MW; for 100 MW, ~€800M.
             GeneratorType::WaveEnergy => 800_000_000.0,
+
+            // Electrolyzers with tank storage: roughly €1.5M per MW; for 300 MW, ~€450M.
+            GeneratorType::HydrogenElectrolyzer => 450_000_000.0,
+
+            // Hydrogen-ready turbines cost a little more than gas peakers; for 400 MW, ~€360M.
+            GeneratorType::HydrogenTurbine => 360_000_000.0,
         };
 
         let years_from_base = (year - BASE_YEAR) as f64;
@@ -301,6 +467,7 @@ impl GeneratorType {
         match *self {
             GeneratorType::OnshoreWind => MAX_ONSHORE_WIND_POWER,
             GeneratorType::OffshoreWind => MAX_OFFSHORE_WIND_POWER,
+            GeneratorType::FloatingOffshoreWind => MAX_FLOATING_OFFSHORE_WIND_POWER,
             GeneratorType::DomesticSolar => MAX_DOMESTIC_SOLAR_POWER,
             GeneratorType::CommercialSolar => MAX_COMMERCIAL_SOLAR_POWER,
             GeneratorType::UtilitySolar => MAX_UTILITY_SOLAR_POWER,
@@ -314,6 +481,8 @@ impl GeneratorType {
             GeneratorType::BatteryStorage => MAX_BATTERY_STORAGE_POWER,
             GeneratorType::TidalGenerator => MAX_TIDAL_POWER,
             GeneratorType::WaveEnergy => MAX_WAVE_POWER,
+            GeneratorType::HydrogenElectrolyzer => MAX_HYDROGEN_ELECTROLYZER_POWER,
+            GeneratorType::HydrogenTurbine => MAX_HYDROGEN_TURBINE_POWER,
         }
     }
 
@@ -321,6 +490,7 @@ impl GeneratorType {
         let base_cost = match *self {
             GeneratorType::OnshoreWind => ONSHORE_WIND_OPERATING_COST,
             GeneratorType::OffshoreWind => OFFSHORE_WIND_OPERATING_COST,
+            GeneratorType::FloatingOffshoreWind => FLOATING_OFFSHORE_WIND_OPERATING_COST,
             GeneratorType::DomesticSolar => DOMESTIC_SOLAR_OPERATING_COST,
             GeneratorType::CommercialSolar => UTILITY_SOLAR_OPERATING_COST,
             GeneratorType::UtilitySolar => UTILITY_SOLAR_OPERATING_COST,
@@ -334,6 +504,8 @@ impl GeneratorType {
             GeneratorType::BatteryStorage => BATTERY_STORAGE_OPERATING_COST,
             GeneratorType::TidalGenerator => TIDAL_OPERATING_COST,
             GeneratorType::WaveEnergy => WAVE_OPERATING_COST,
+            GeneratorType::HydrogenElectrolyzer => HYDROGEN_ELECTROLYZER_OPERATING_COST,
+            GeneratorType::HydrogenTurbine => HYDROGEN_TURBINE_OPERATING_COST,
         };
 
         let years_from_base = (year - BASE_YEAR) as f64;
@@ -345,6 +517,7 @@ impl GeneratorType {
         match *self {
             GeneratorType::OnshoreWind => 25,
             GeneratorType::OffshoreWind => 25,
+            GeneratorType::FloatingOffshoreWind => 25,
             GeneratorType::DomesticSolar => 25,
             GeneratorType::CommercialSolar => 25,
             GeneratorType::UtilitySolar => 30,
@@ -358,6 +531,80 @@ impl GeneratorType {
             GeneratorType::BatteryStorage => 15,
             GeneratorType::TidalGenerator => 25,
             GeneratorType::WaveEnergy => 20,
+            GeneratorType::HydrogenElectrolyzer => 20,
+            GeneratorType::HydrogenTurbine => 30,
+        }
+    }
+
+    // Expected fraction of the year lost to unplanned outages
+    pub fn get_forced_outage_rate(&self) -> f64 {
+        match *self {
+            GeneratorType::OnshoreWind => 0.03,
+            GeneratorType::OffshoreWind => 0.04,
+            GeneratorType::FloatingOffshoreWind => 0.05,
+            GeneratorType::DomesticSolar => 0.01,
+            GeneratorType::CommercialSolar => 0.01,
+            GeneratorType::UtilitySolar => 0.01,
+            GeneratorType::Nuclear => 0.05,
+            GeneratorType::CoalPlant => 0.08,
+            GeneratorType::GasCombinedCycle => 0.05,
+            GeneratorType::GasPeaker => 0.06,
+            GeneratorType::Biomass => 0.07,
+            GeneratorType::HydroDam => 0.02,
+            GeneratorType::PumpedStorage => 0.03,
+            GeneratorType::BatteryStorage => 0.02,
+            GeneratorType::TidalGenerator => 0.05,
+            GeneratorType::WaveEnergy => 0.08,
+            GeneratorType::HydrogenElectrolyzer => 0.04,
+            GeneratorType::HydrogenTurbine => 0.06,
+        }
+    }
+
+    // Fractional efficiency lost per year of service
+    pub fn get_annual_degradation_rate(&self) -> f64 {
+        match *self {
+            GeneratorType::OnshoreWind => 0.005,
+            GeneratorType::OffshoreWind => 0.006,
+            GeneratorType::FloatingOffshoreWind => 0.006,
+            GeneratorType::DomesticSolar => 0.005,  // Panel output loss is ~0.5%/yr
+            GeneratorType::CommercialSolar => 0.005,
+            GeneratorType::UtilitySolar => 0.005,
+            GeneratorType::Nuclear => 0.001,
+            GeneratorType::CoalPlant => 0.003,
+            GeneratorType::GasCombinedCycle => 0.003,
+            GeneratorType::GasPeaker => 0.003,
+            GeneratorType::Biomass => 0.004,
+            GeneratorType::HydroDam => 0.001,
+            GeneratorType::PumpedStorage => 0.001,
+            GeneratorType::BatteryStorage => 0.02,  // Cell capacity fade
+            GeneratorType::TidalGenerator => 0.005,
+            GeneratorType::WaveEnergy => 0.006,
+            GeneratorType::HydrogenElectrolyzer => 0.01,  // Stack degradation
+            GeneratorType::HydrogenTurbine => 0.003,
+        }
+    }
+
+    // Weeks per year taken offline for scheduled maintenance
+    pub fn get_planned_maintenance_weeks(&self) -> f64 {
+        match *self {
+            GeneratorType::OnshoreWind => 1.5,
+            GeneratorType::OffshoreWind => 2.5,
+            GeneratorType::FloatingOffshoreWind => 3.0,
+            GeneratorType::DomesticSolar => 0.5,
+            GeneratorType::CommercialSolar => 0.5,
+            GeneratorType::UtilitySolar => 0.5,
+            GeneratorType::Nuclear => 6.0,  // Refuelling outages
+            GeneratorType::CoalPlant => 5.0,
+            GeneratorType::GasCombinedCycle => 4.0,
+            GeneratorType::GasPeaker => 2.0,
+            GeneratorType::Biomass => 4.0,
+            GeneratorType::HydroDam => 2.0,
+            GeneratorType::PumpedStorage => 3.0,
+            GeneratorType::BatteryStorage => 0.5,
+            GeneratorType::TidalGenerator => 3.0,
+            GeneratorType::WaveEnergy => 3.0,
+            GeneratorType::HydrogenElectrolyzer => 2.0,
+            GeneratorType::HydrogenTurbine => 2.0,
         }
     }
 }
@@ -400,6 +647,46 @@ pub struct Generator {
     
     // Cost multiplier for construction speedup
     pub construction_cost_multiplier: f64,
+    
+    // Outage modelling
+    #[serde(default)]
+    pub forced_outage_rate: f64,        // Expected fraction of the year on unplanned outage
+    #[serde(default)]
+    pub planned_maintenance_weeks: f64, // Scheduled maintenance weeks per year
+    #[serde(skip)]
+    pub sampled_forced_outage: Option<f64>, // Realised forced outage for the current Monte Carlo draw
+
+    // Explicit grid zone; falls back to the configured zone bounds when unset
+    #[serde(default)]
+    pub zone: Option<String>,
+
+    // ID of the generator this unit shares a site with when built as part of a hybrid plant
+    #[serde(default)]
+    pub hybrid_host: Option<String>,
+
+    // Year of the first operation change this year and the operation percentage it started from
+    #[serde(skip)]
+    pub ramp_baseline: Option<(u32, u8)>,
+
+    // Output multiplier for the wind or solar resource at this site, fixed at placement
+    #[serde(default = "default_resource_quality")]
+    pub resource_quality: f64,
+
+    // Year the generator was closed early by a CloseGenerator action and what it cost
+    #[serde(default)]
+    pub early_closure: Option<(u32, f64)>,
+
+    // Year the generator was converted by a RetrofitGenerator action and what it cost
+    #[serde(default)]
+    pub retrofit: Option<(u32, f64)>,
+
+    // Year and cost of each UpgradeEfficiency applied to the generator
+    #[serde(default)]
+    pub upgrade_costs: Vec<(u32, f64)>,
+}
+
+fn default_resource_quality() -> f64 {
+    DEFAULT_RESOURCE_QUALITY
 }
 
 impl Generator {
@@ -418,9 +705,13 @@ impl Generator {
         let size = size.clamp(MIN_GENERATOR_SIZE, MAX_GENERATOR_SIZE);
         let storage = if generator_type.is_storage() {
             Some(PowerStorageSystem::new(power_out * size))
+        } else if generator_type == GeneratorType::HydrogenElectrolyzer {
+            Some(PowerStorageSystem::hydrogen(power_out * size))
         } else {
             None
         };
+        let forced_outage_rate = generator_type.get_forced_outage_rate();
+        let planned_maintenance_weeks = generator_type.get_planned_maintenance_weeks();
         Self {
             id,
             coordinate,
@@ -445,6 +736,16 @@ impl Generator {
             construction_start_year: 0,
             construction_complete_year: 0,
             construction_cost_multiplier: 1.0,
+            forced_outage_rate,
+            planned_maintenance_weeks,
+            sampled_forced_outage: None,
+            zone: None,
+            hybrid_host: None,
+            ramp_baseline: None,
+            resource_quality: DEFAULT_RESOURCE_QUALITY,
+            early_closure: None,
+            upgrade_costs: Vec::new(),
+            retrofit: None,
         }
     }
 
@@ -516,12 +817,16 @@ impl Generator {
         false
     }
 
+    pub fn is_hybrid_sub_unit(&self) -> bool {
+        self.hybrid_host.is_some()
+    }
+
     pub fn is_active(&self) -> bool {
         self.is_active && self.construction_status == ConstructionStatus::Operational
     }
 
     pub fn get_current_power_output(&self, hour: Option<u8>) -> f64 {
-        if !self.is_active() {
+        if !self.is_active() || self.generator_type.is_hydrogen() {
             return 0.0;
         }
 
@@ -540,6 +845,9 @@ impl Generator {
                     GeneratorType::OnshoreWind | GeneratorType::OffshoreWind => {
                         base_output * WIND_CAPACITY_FACTOR
                     },
+                    GeneratorType::FloatingOffshoreWind => {
+                        base_output * FLOATING_WIND_CAPACITY_FACTOR
+                    },
                     GeneratorType::UtilitySolar |
                     GeneratorType::CommercialSolar |
                     GeneratorType::DomesticSolar => {
@@ -553,10 +861,30 @@ impl Generator {
         }
     }
 
+    // Combined derate on top of get_current_power_output: site resource quality, ageing,
+    // maintenance and outages, and the renewable availability of the current draw
+    pub fn derate_factor(&self, year: u32, availability: &AvailabilityFactors) -> f64 {
+        self.resource_quality
+            * self.get_degradation_factor(year)
+            * self.get_availability_factor()
+            * availability.factor_for(&self.generator_type)
+    }
+
+    // Output (MW) in a given hour after size, operation, efficiency and every derate.
+    // All generation totals and metrics go through this so they agree with each other.
+    pub fn effective_power_at(&self, year: u32, hour: Option<u8>, availability: &AvailabilityFactors) -> f64 {
+        self.get_current_power_output(hour) * self.derate_factor(year, availability)
+    }
+
+    // Average effective output (MW) over the year
+    pub fn effective_power(&self, year: u32, availability: &AvailabilityFactors) -> f64 {
+        self.effective_power_at(year, None, availability)
+    }
+
     fn calculate_intermittent_output(&self, hour: u8) -> f64 {
         let base_output = self.power_out * self.efficiency * self.operation_percentage;
         match self.generator_type {
-            GeneratorType::OnshoreWind | GeneratorType::OffshoreWind => {
+            GeneratorType::OnshoreWind | GeneratorType::OffshoreWind | GeneratorType::FloatingOffshoreWind => {
                 base_output
             },
             GeneratorType::DomesticSolar |
@@ -575,44 +903,53 @@ impl Generator {
         }
     }
 
+    // Fraction of capacity actually available after maintenance and forced outages.
+    // Uses the sampled outage during Monte Carlo draws, the expected rate otherwise.
+    pub fn get_availability_factor(&self) -> f64 {
+        let forced_outage = self.sampled_forced_outage.unwrap_or(self.forced_outage_rate);
+        let maintenance = (self.planned_maintenance_weeks / WEEKS_PER_YEAR).clamp(0.0, 1.0);
+        ((1.0 - forced_outage) * (1.0 - maintenance)).clamp(0.0, 1.0)
+    }
+
     pub fn get_storage_capacity(&self) -> f64 {
         self.storage.as_ref().map_or(0.0, |s| s.capacity)
     }
 
-    pub fn get_current_cost(&self, year: u32) -> f64 {
+    pub fn get_current_cost(&self, year: u32, inflation: &InflationModel) -> f64 {
         let base_cost = calc_generator_cost(
             &self.generator_type,
             self.base_cost,
             year,
             self.generator_type.can_be_urban(),
             self.generator_type.requires_water(),
-            self.generator_type.requires_water()
+            self.generator_type.requires_water(),
+            inflation,
         );
         
         // Apply the construction cost multiplier
         base_cost * self.construction_cost_multiplier
     }
 
-    pub fn get_current_operating_cost(&self, year: u32) -> f64 {
+    pub fn get_current_operating_cost(&self, year: u32, inflation: &InflationModel) -> f64 {
         if !self.is_active() {
             return 0.0;
         }
-        let base_cost = calc_operating_cost(&self.generator_type, self.base_operating_cost, year);
+        let base_cost = calc_operating_cost(&self.generator_type, self.base_operating_cost, year, inflation);
         base_cost * self.operation_percentage
     }
 
-    pub fn calc_cost_opinion(&self, year: u32) -> f64 {
-        calc_cost_opinion(self.get_current_cost(year), year)
+    pub fn calc_cost_opinion(&self, year: u32, inflation: &InflationModel) -> f64 {
+        calc_cost_opinion(self.get_current_cost(year, inflation), year, inflation)
     }
 
     pub fn calc_type_opinion(&self, year: u32) -> f64 {
         calc_type_opinion(&self.generator_type, year)
     }
 
-    pub fn calc_cost_over_time(&self, years: u32) -> f64 {
+    pub fn calc_cost_over_time(&self, years: u32, inflation: &InflationModel) -> f64 {
         let current_year = 2025 + years;
-        self.get_current_cost(current_year) + 
-            (0..years).map(|y| self.get_current_operating_cost(2025 + y)).sum::<f64>()
+        self.get_current_cost(current_year, inflation) + 
+            (0..years).map(|y| self.get_current_operating_cost(2025 + y, inflation)).sum::<f64>()
     }
 
     pub fn get_co2_output(&self) -> f64 {
@@ -641,40 +978,77 @@ impl Generator {
         self.efficiency < max_efficiency
     }
 
-    pub fn upgrade_efficiency(&mut self, year: u32, new_efficiency: f64) -> f64 {
-        let efficiency_increase = new_efficiency - self.efficiency;
-        let upgrade_cost = self.base_cost * efficiency_increase * EFFICIENCY_UPGRADE_COST_FACTOR;
+    // Cost of raising efficiency to `new_efficiency` in `year`: the plant's current build cost
+    // scaled by the efficiency gained and the configured upgrade cost multiplier
+    pub fn upgrade_cost(&self, year: u32, new_efficiency: f64, cost_multiplier: f64, inflation: &InflationModel) -> f64 {
+        self.get_current_cost(year, inflation) * (new_efficiency - self.efficiency).max(0.0) * cost_multiplier
+    }
+
+    pub fn upgrade_efficiency(&mut self, year: u32, new_efficiency: f64, cost_multiplier: f64, inflation: &InflationModel) -> f64 {
+        let upgrade_cost = self.upgrade_cost(year, new_efficiency, cost_multiplier, inflation);
         self.efficiency = new_efficiency;
         self.upgrade_history.push((year, new_efficiency));
+        self.upgrade_costs.push((year, upgrade_cost));
         upgrade_cost
     }
 
-    pub fn adjust_operation(&mut self, new_percentage: u8, __constraints: &GeneratorConstraints) -> bool {
-        let min_percentage = match self.generator_type {
-            GeneratorType::Nuclear => NUCLEAR_MIN_OPERATION,
-            GeneratorType::HydroDam | GeneratorType::PumpedStorage => HYDRO_MIN_OPERATION,
-            GeneratorType::OnshoreWind | GeneratorType::OffshoreWind |
-            GeneratorType::UtilitySolar => 0,
-            _ => DEFAULT_MIN_OPERATION,
-        };
-        
-        let clamped_percentage = new_percentage.clamp(min_percentage, MAX_OPERATION_PERCENTAGE);
-        
+    // Operation changes are held to the type's minimum stable level, and the total change
+    // within a year is limited to its ramp rate measured from where the year started.
+    pub fn adjust_operation(&mut self, new_percentage: u8, constraints: &GeneratorConstraints, year: u32) -> bool {
         if !self.is_active() {
             return false;
         }
 
+        let min_percentage = constraints.min_stable_operation.get(&self.generator_type).copied()
+            .unwrap_or(match self.generator_type {
+                GeneratorType::Nuclear => NUCLEAR_MIN_OPERATION,
+                GeneratorType::HydroDam | GeneratorType::PumpedStorage => HYDRO_MIN_OPERATION,
+                GeneratorType::OnshoreWind | GeneratorType::OffshoreWind |
+                GeneratorType::FloatingOffshoreWind | GeneratorType::UtilitySolar => 0,
+                _ => DEFAULT_MIN_OPERATION,
+            })
+            .min(MAX_OPERATION_PERCENTAGE);
+        
+        let (mut lower, mut upper) = (min_percentage, MAX_OPERATION_PERCENTAGE);
+        if let Some(&ramp_limit) = constraints.ramp_limits.get(&self.generator_type) {
+            let baseline = match self.ramp_baseline {
+                Some((baseline_year, baseline)) if baseline_year == year => baseline,
+                _ => self.get_operation_percentage(),
+            };
+            self.ramp_baseline = Some((year, baseline));
+            lower = lower.max(baseline.saturating_sub(ramp_limit));
+            upper = upper.min(baseline.saturating_add(ramp_limit)).max(lower);
+        }
+        
+        let clamped_percentage = new_percentage.clamp(lower, upper);
         self.operation_percentage = clamped_percentage as f64 / 100.0;
         true
     }
 
+    // Cost of closing in `year`: the stranded share of the build cost for the life left,
+    // plus site cleanup, which is far higher for nuclear and coal
+    pub fn decommission_cost(&self, year: u32) -> f64 {
+        let remaining_fraction = if self.eol > 0 {
+            (self.get_end_of_life_year().saturating_sub(year) as f64 / self.eol as f64).min(1.0)
+        } else {
+            0.0
+        };
+        let stranded_cost = self.base_cost * CLOSURE_COST_FACTOR * remaining_fraction;
+
+        let cleanup_multiplier = match self.generator_type {
+            GeneratorType::Nuclear => NUCLEAR_DECOMMISSION_MULTIPLIER,
+            GeneratorType::CoalPlant => COAL_DECOMMISSION_MULTIPLIER,
+            _ => 1.0,
+        };
+        stranded_cost + self.decommission_cost * cleanup_multiplier
+    }
+
     pub fn close_generator(&mut self, year: u32) -> f64 {
         if !self.is_active() {
             return 0.0;
         }
 
-        let years_remaining = (self.eol as i32 - (year - BASE_YEAR) as i32).max(0) as f64;
-        let closure_cost = self.base_cost * CLOSURE_COST_FACTOR * (years_remaining / self.eol as f64);
+        let closure_cost = self.decommission_cost(year);
         
         self.is_active = false;
         self.operation_percentage = 0.0;
@@ -682,10 +1056,102 @@ impl Generator {
         closure_cost
     }
 
+    pub fn retrofit_cost(&self) -> f64 {
+        self.base_cost * COAL_TO_BIOMASS_RETROFIT_COST_RATIO
+    }
+
+    // Convert the plant in place, keeping its site, size and capital. Emissions and running costs
+    // move to the new fuel's rates. Returns the conversion cost, or None for an unsupported target.
+    pub fn retrofit(&mut self, target: &GeneratorType, year: u32) -> Option<f64> {
+        if !self.is_active() || !self.generator_type.can_retrofit_to(target) {
+            return None;
+        }
+
+        let cost = self.retrofit_cost();
+        self.co2_out *= BIOMASS_CO2_RATE / COAL_CO2_RATE;
+        self.base_operating_cost *= BIOMASS_OPERATING_COST / COAL_OPERATING_COST;
+        self.efficiency *= COAL_TO_BIOMASS_EFFICIENCY_FACTOR;
+        self.generator_type = target.clone();
+        self.retrofit = Some((year, cost));
+        Some(cost)
+    }
+
     pub fn get_efficiency(&self) -> f64 {
         self.efficiency
     }
 
+    // Pre-existing generators have no commissioning year, so fall back to the build year
+    pub fn service_start_year(&self) -> u32 {
+        if self.commissioning_year > 0 {
+            self.commissioning_year
+        } else {
+            self.get_build_year()
+        }
+    }
+
+    pub fn get_end_of_life_year(&self) -> u32 {
+        self.service_start_year() + self.eol
+    }
+
+    pub fn is_past_end_of_life(&self, year: u32) -> bool {
+        self.eol > 0 && year >= self.get_end_of_life_year()
+    }
+
+    // Multiplier on efficiency from ageing since commissioning. The latest upgrade
+    // restores part of the ageing accumulated before it.
+    pub fn get_degradation_factor(&self, year: u32) -> f64 {
+        let start_year = self.service_start_year();
+        let age = year.saturating_sub(start_year) as f64;
+        let recovered = self.upgrade_history.last()
+            .map_or(0.0, |(upgrade_year, _)| upgrade_year.saturating_sub(start_year) as f64 * UPGRADE_DEGRADATION_RECOVERY);
+        let effective_age = (age - recovered).max(0.0);
+
+        (1.0 - self.generator_type.get_annual_degradation_rate()).powf(effective_age)
+    }
+
+    pub fn get_effective_efficiency(&self, year: u32) -> f64 {
+        self.efficiency * self.get_degradation_factor(year)
+    }
+
+    // Cost of one more MWh: the fuel share of the year's operating cost spread over a year of
+    // generation at the aged efficiency, so worn thermal plant sits higher in the merit order
+    // and fuel-free renewables bid in at zero
+    pub fn marginal_cost(&self, year: u32, inflation: &InflationModel) -> f64 {
+        let annual_output = self.power_out * self.get_effective_efficiency(year) * HOURS_PER_YEAR;
+        if annual_output <= 0.0 {
+            return f64::INFINITY;
+        }
+        let operating_cost = calc_operating_cost(&self.generator_type, self.base_operating_cost, year, inflation);
+        operating_cost * self.generator_type.fuel_cost_share() / annual_output
+    }
+
+    // Emissions with the aged efficiency: worn plant burns more fuel for the same output
+    pub fn get_co2_output_in_year(&self, year: u32) -> f64 {
+        if !self.is_active() {
+            return 0.0;
+        }
+
+        self.co2_out * self.operation_percentage * (1.0 - (self.get_effective_efficiency(year) - BASE_EFFICIENCY))
+    }
+
+    // MW this unit can be counted on at the demand peak, as opposed to its average output
+    pub fn firm_capacity(&self) -> f64 {
+        if !self.is_active() {
+            return 0.0;
+        }
+        self.power_out * self.generator_type.capacity_credit()
+    }
+
+    // Yearly capacity payment at `rate` per MW of firm capacity, before inflation
+    pub fn capacity_payment(&self, rate: f64) -> f64 {
+        self.firm_capacity() * rate
+    }
+
+    // Tonnes CO2 emitted building this unit, charged once in its commissioning year
+    pub fn embodied_co2(&self) -> f64 {
+        self.power_out * self.generator_type.embodied_co2_intensity()
+    }
+
     pub fn get_build_year(&self) -> u32 {
         // Extract year from the ID for generators built during simulation
         if self.id.starts_with("Gen_") {
@@ -709,7 +1175,7 @@ impl Generator {
             GeneratorType::Nuclear => NUCLEAR_MIN_OPERATION,
             GeneratorType::HydroDam | GeneratorType::PumpedStorage => HYDRO_MIN_OPERATION,
             GeneratorType::OnshoreWind | GeneratorType::OffshoreWind |
-            GeneratorType::UtilitySolar => 0,
+            GeneratorType::FloatingOffshoreWind | GeneratorType::UtilitySolar => 0,
             _ => DEFAULT_MIN_OPERATION,
         }
     }
@@ -765,4 +1231,104 @@ impl POI for Generator {
     fn get_id(&self) -> &str {
         &self.id
     }
-} 
\ No newline at end of file
+}
+
+// Storage units are generators carrying a PowerStorageSystem, so they are covered here too
+impl GridAsset for Generator {
+    fn annual_cost(&self, year: u32, inflation: &InflationModel) -> f64 {
+        // The fleet present at the start was paid for before the plan began
+        if self.id.starts_with("Existing_") {
+            return 0.0;
+        }
+        self.get_current_cost(year, inflation)
+    }
+
+    fn co2_impact(&self, year: u32) -> f64 {
+        self.get_co2_output_in_year(year)
+    }
+
+    fn coordinate(&self) -> &Coordinate {
+        &self.coordinate
+    }
+
+    fn is_active(&self) -> bool {
+        Generator::is_active(self)
+    }
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::simulation_config::SimulationConfig;
+    use crate::test_support::{test_generator, TEST_SITE};
+
+    #[test]
+    fn forced_outage_rate_removes_its_share_of_output() {
+        let year = 2025;
+        let mut reliable = test_generator("Reliable", GeneratorType::Nuclear, TEST_SITE, 100.0, 0.0);
+        reliable.forced_outage_rate = 0.0;
+        reliable.planned_maintenance_weeks = 0.0;
+        reliable.initialize_construction(year, 0.5, false);
+        let mut outage_prone = reliable.clone();
+        outage_prone.forced_outage_rate = 0.1;
+
+        let full = reliable.effective_power(year, &AvailabilityFactors::default());
+        let derated = outage_prone.effective_power(year, &AvailabilityFactors::default());
+
+        assert!(full > 0.0);
+        assert!((derated / full - 0.9).abs() < 1e-9, "derated {} of {}", derated, full);
+    }
+
+    #[test]
+    fn twenty_year_old_panel_is_less_efficient_than_a_new_one() {
+        let mut old_panel = test_generator("Old_Solar", GeneratorType::UtilitySolar, TEST_SITE, 50.0, 0.0);
+        old_panel.initialize_construction(2025, 0.5, false);
+        let mut new_panel = old_panel.clone();
+        new_panel.initialize_construction(2045, 0.5, false);
+
+        let old_efficiency = old_panel.get_effective_efficiency(2045);
+        let new_efficiency = new_panel.get_effective_efficiency(2045);
+
+        // Around 0.5% a year compounds to roughly a 10% loss over two decades
+        assert!(old_efficiency < 0.95 * new_efficiency, "old {} vs new {}", old_efficiency, new_efficiency);
+        assert!(old_panel.effective_power(2045, &AvailabilityFactors::default())
+            < new_panel.effective_power(2045, &AvailabilityFactors::default()));
+    }
+
+    #[test]
+    fn coal_operation_respects_its_ramp_limit_and_minimum_stable_level() {
+        let constraints = SimulationConfig::default().generator_constraints;
+        let mut coal = test_generator("Coal", GeneratorType::CoalPlant, TEST_SITE, 300.0, 2.0e6);
+        coal.initialize_construction(2025, 0.5, false);
+        coal.operation_percentage = 0.2;
+
+        assert!(coal.adjust_operation(100, &constraints, 2030));
+        assert_eq!(coal.get_operation_percentage(), 20 + constraints.ramp_limits[&GeneratorType::CoalPlant]);
+
+        // A later year starts from the new level, and never drops below the stable minimum
+        assert!(coal.adjust_operation(0, &constraints, 2031));
+        assert_eq!(coal.get_operation_percentage(), constraints.min_stable_operation[&GeneratorType::CoalPlant]);
+    }
+
+    #[test]
+    fn effective_power_is_the_product_of_every_derate() {
+        let mut wind = test_generator("Wind", GeneratorType::OnshoreWind, TEST_SITE, 200.0, 0.0);
+        wind.initialize_construction(2025, 0.5, false);
+        wind.efficiency = 0.9;
+        wind.operation_percentage = 0.8;
+        wind.resource_quality = 0.85;
+        wind.forced_outage_rate = 0.05;
+        wind.planned_maintenance_weeks = 2.6;
+        let availability = AvailabilityFactors { wind: 0.7, ..AvailabilityFactors::default() };
+
+        // Ten years at onshore wind's 0.5% a year
+        let degradation = 0.995f64.powi(10);
+        let outages = 0.95 * (1.0 - 2.6 / WEEKS_PER_YEAR);
+        let expected = 200.0 * 0.9 * 0.8 * WIND_CAPACITY_FACTOR * 0.85 * degradation * outages * 0.7;
+
+        let effective = wind.effective_power(2035, &availability);
+        assert!((effective - expected).abs() < 1e-9 * expected, "effective {} vs {}", effective, expected);
+        // Each hour is derated the same way
+        let hourly = wind.effective_power_at(2035, Some(12), &availability);
+        assert!((hourly - expected / WIND_CAPACITY_FACTOR).abs() < 1e-9 * expected);
+    }
+}
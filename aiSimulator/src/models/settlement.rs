@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use crate::data::poi::{POI, Coordinate};
 // use crate::config::const_funcs::{calc_population_growth, calc_power_usage_per_capita};
 // use crate::config::constants::{MAP_MAX_X, MAP_MAX_Y};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,12 +11,16 @@ pub struct SettlementData {
     coordinate: Coordinate,
     base_population: u32,
     base_power_usage: f64,
+    #[serde(default)]
+    population_points: BTreeMap<u32, u32>, // Known census/projection populations by year
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SettlementState {
     current_population: u32,
     current_power_usage: f64,
+    #[serde(default)]
+    zone: Option<String>,
 }
 
 // Custom serialization for Settlement to handle Arc
@@ -66,16 +71,36 @@ impl Settlement {
             coordinate,
             base_population: population,
             base_power_usage: power_usage,
+            population_points: BTreeMap::new(),
         });
         
         let state = SettlementState {
             current_population: population,
             current_power_usage: power_usage,
+            zone: None,
         };
 
         Settlement { data, state }
     }
 
+    pub fn with_population_points(mut self, points: BTreeMap<u32, u32>) -> Self {
+        Arc::make_mut(&mut self.data).population_points = points;
+        self
+    }
+
+    // Population interpolated linearly between the known data points. None outside their span,
+    // where the caller falls back to its growth model.
+    pub fn known_population(&self, year: u32) -> Option<u32> {
+        let points = &self.data.population_points;
+        let (&after_year, &after_pop) = points.range(year..).next()?;
+        if after_year == year {
+            return Some(after_pop);
+        }
+        let (&before_year, &before_pop) = points.range(..year).next_back()?;
+        let fraction = (year - before_year) as f64 / (after_year - before_year) as f64;
+        Some((before_pop as f64 + (after_pop as f64 - before_pop as f64) * fraction).round() as u32)
+    }
+
     pub fn get_name(&self) -> &str {
         &self.data.name
     }
@@ -100,6 +125,14 @@ impl Settlement {
         self.state.current_power_usage = new_usage;
     }
 
+    pub fn get_zone(&self) -> Option<&str> {
+        self.state.zone.as_deref()
+    }
+
+    pub fn set_zone(&mut self, zone: Option<String>) {
+        self.state.zone = zone;
+    }
+
     pub fn calc_range_opinion(&self, generator_coord: &Coordinate) -> f64 {
         let distance = self.data.coordinate.distance_to(generator_coord);
         1.0 / (1.0 + distance / 10000.0)
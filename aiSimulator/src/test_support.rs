@@ -0,0 +1,67 @@
+// Fixtures shared by the unit tests
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::ai::metrics::simulation_metrics::SimulationMetrics;
+use crate::config::constants::BASE_YEAR;
+use crate::config::simulation_config::SimulationConfig;
+use crate::data::poi::Coordinate;
+use crate::models::generator::{Generator, GeneratorType};
+use crate::models::settlement::Settlement;
+use crate::utils::map_handler::Map;
+
+pub const TEST_SITE: Coordinate = Coordinate { x: 25_000.0, y: 25_000.0 };
+
+// The log level and sink are process-wide; tests that change either hold this for their duration
+pub static LOG_SETTINGS: Mutex<()> = Mutex::new(());
+
+// A generator with round-number costs and a 40-year life
+pub fn test_generator(id: &str, generator_type: GeneratorType, coordinate: Coordinate, power_out: f64, co2_out: f64) -> Generator {
+    Generator::new(id.to_string(), coordinate, generator_type, 4.0e8, power_out, 2.0e7, 40, 1.0, co2_out, 4.0e7)
+}
+
+// Empty map for `config` at BASE_YEAR, with construction delays off so new plant runs at once
+pub fn empty_map(config: SimulationConfig) -> Map {
+    let mut map = Map::new(config);
+    map.set_enable_construction_delays(false);
+    map.current_year = BASE_YEAR;
+    map
+}
+
+// One 80 MW town supplied by a 400 MW gas plant ("Existing_Gas")
+pub fn small_map() -> Map {
+    let mut map = empty_map(SimulationConfig::default());
+    map.add_settlement(Settlement::new("Midtown".to_string(), TEST_SITE, 50_000, 80.0));
+    map.add_generator(test_generator(
+        "Existing_Gas",
+        GeneratorType::GasCombinedCycle,
+        Coordinate::new(24_000.0, 26_000.0),
+        400.0,
+        1.2e6,
+    ));
+    map
+}
+
+// Final metrics of a reliable run with the given headline numbers and no yearly history
+pub fn test_metrics(final_net_emissions: f64, total_cost: f64, average_public_opinion: f64) -> SimulationMetrics {
+    SimulationMetrics {
+        final_net_emissions,
+        average_public_opinion,
+        total_cost,
+        power_reliability: 1.0,
+        yearly_net_emissions: Vec::new(),
+        cumulative_net_emissions: final_net_emissions,
+        total_land_use: 0.0,
+        yearly_renewable_share: Vec::new(),
+        final_carbon_credit_offset: 0.0,
+    }
+}
+
+// Fresh per-process path in the system temp dir; whatever was there is removed
+pub fn scratch_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("eirgrid_test_{}_{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&path);
+    let _ = std::fs::remove_file(&path);
+    path
+}
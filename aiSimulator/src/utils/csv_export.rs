@@ -1,4 +1,4 @@
-use std::fs::File;
+use std::fs::{File, OpenOptions};
 use std::path::{Path, PathBuf};
 use std::io::Write;
 use std::error::Error;
@@ -6,11 +6,15 @@ use chrono::Local;
 
 use super::map_handler::Map;
 use crate::core::action_weights::{GridAction, SimulationMetrics};
+use crate::ai::actions::serializable_action::SerializableAction;
+use crate::ai::learning::weights::diagnostics::ActionImpact;
+use crate::analysis::analysis::{AbatementCost, SensitivityResult};
 use crate::models::settlement::Settlement;
 use crate::models::carbon_offset::CarbonOffset;
 use crate::models::carbon_offset::CarbonOffsetType;
 use crate::config::constants::{
     WIND_BASE_MAX_EFFICIENCY,
+    FLOATING_WIND_BASE_MAX_EFFICIENCY,
     UTILITY_SOLAR_BASE_MAX_EFFICIENCY,
     NUCLEAR_BASE_MAX_EFFICIENCY,
     GAS_CC_BASE_MAX_EFFICIENCY,
@@ -102,16 +106,173 @@ pub struct ImprovementRecord {
     pub timestamp: String,
 }
 
+/// Write a year-ordered action plan (as produced by `ActionWeights::export_plan`) to CSV
+pub fn write_action_plan(path: impl AsRef<Path>, plan: &[(u32, GridAction)]) -> Result<(), Box<dyn Error>> {
+    let mut file = File::create(path.as_ref())?;
+
+    writeln!(
+        file,
+        "Year,Action Type,Generator Type,Generator ID,Operation Percentage,Offset Type,Cost Multiplier (%),Secondary Generator Type"
+    )?;
+
+    for (year, action) in plan {
+        let serializable = SerializableAction::from(action);
+        writeln!(
+            file,
+            "{},{},{},{},{},{},{},{}",
+            year,
+            serializable.action_type,
+            serializable.generator_type.unwrap_or_default(),
+            serializable.generator_id.unwrap_or_default(),
+            serializable.operation_percentage.map(|p| p.to_string()).unwrap_or_default(),
+            serializable.offset_type.unwrap_or_default(),
+            serializable.cost_multiplier.map(|m| m.to_string()).unwrap_or_default(),
+            serializable.secondary_generator_type.unwrap_or_default()
+        )?;
+    }
+
+    Ok(())
+}
+
+// One row per swept value with deltas against the baseline, ready for a tornado chart
+pub fn write_sensitivity_results(path: impl AsRef<Path>, results: &[SensitivityResult]) -> Result<(), Box<dyn Error>> {
+    let mut file = File::create(path.as_ref())?;
+
+    writeln!(
+        file,
+        "Parameter,Value,Final Net Emissions (tonnes),Total Cost (€),Average Public Opinion,Power Reliability,\
+        Emissions Change (tonnes),Cost Change (€),Opinion Change"
+    )?;
+
+    for result in results {
+        writeln!(
+            file,
+            "{},{},{},{},{},{},{},{},{}",
+            result.parameter,
+            result.value,
+            result.metrics.final_net_emissions,
+            result.metrics.total_cost,
+            result.metrics.average_public_opinion,
+            result.metrics.power_reliability,
+            result.emissions_change(),
+            result.cost_change(),
+            result.opinion_change()
+        )?;
+    }
+
+    Ok(())
+}
+
+// Ranked action attribution, same action columns as write_action_plan
+pub fn write_action_impacts(path: impl AsRef<Path>, impacts: &[ActionImpact]) -> Result<(), Box<dyn Error>> {
+    let mut file = File::create(path.as_ref())?;
+
+    writeln!(
+        file,
+        "Rank,Year,Action Type,Generator Type,Generator ID,Operation Percentage,Offset Type,Cost Multiplier (%),Emissions Change (tonnes),Cost Change (€)"
+    )?;
+
+    for (rank, impact) in impacts.iter().enumerate() {
+        let serializable = SerializableAction::from(&impact.action);
+        writeln!(
+            file,
+            "{},{},{},{},{},{},{},{},{},{}",
+            rank + 1,
+            impact.year,
+            serializable.action_type,
+            serializable.generator_type.unwrap_or_default(),
+            serializable.generator_id.unwrap_or_default(),
+            serializable.operation_percentage.map(|p| p.to_string()).unwrap_or_default(),
+            serializable.offset_type.unwrap_or_default(),
+            serializable.cost_multiplier.map(|m| m.to_string()).unwrap_or_default(),
+            impact.emissions_change,
+            impact.cost_change
+        )?;
+    }
+
+    Ok(())
+}
+
+/// MAC curve data, one row per plan action in the order `marginal_abatement_costs` ranks them
+pub fn write_abatement_costs(path: impl AsRef<Path>, costs: &[AbatementCost]) -> Result<(), Box<dyn Error>> {
+    let mut file = File::create(path.as_ref())?;
+
+    writeln!(
+        file,
+        "Rank,Year,Action Type,Generator Type,Generator ID,Operation Percentage,Offset Type,Cost Multiplier (%),Incremental Cost (€),Emissions Avoided (tonnes),Cost per Tonne (€/t),Increases Emissions"
+    )?;
+
+    for (rank, cost) in costs.iter().enumerate() {
+        let serializable = SerializableAction::from(&cost.action);
+        writeln!(
+            file,
+            "{},{},{},{},{},{},{},{},{},{},{},{}",
+            rank + 1,
+            cost.year,
+            serializable.action_type,
+            serializable.generator_type.unwrap_or_default(),
+            serializable.generator_id.unwrap_or_default(),
+            serializable.operation_percentage.map(|p| p.to_string()).unwrap_or_default(),
+            serializable.offset_type.unwrap_or_default(),
+            serializable.cost_multiplier.map(|m| m.to_string()).unwrap_or_default(),
+            cost.incremental_cost,
+            cost.emissions_avoided,
+            cost.cost_per_tonne.map(|c| c.to_string()).unwrap_or_default(),
+            cost.increases_emissions()
+        )?;
+    }
+
+    Ok(())
+}
+
+const YEARLY_SUMMARY_HEADER: &str = "Year,Population,PowerUsage,PowerGeneration,PowerBalance,PublicOpinion,YearlyCapitalCost,TotalCapitalCost,Inflation,CO2Emissions,CarbonOffset,NetEmissions,YearlyRevenue,TotalRevenue,ActiveGenerators,YearlyUpgradeCosts,YearlyClosureCosts,YearlyTotalCost,TotalCost,StorageStateOfCharge,StorageEnergyCharged,StorageEnergyDischarged,UnservedZoneDemand,Capex,Opex,FuelCost,CarbonCost,Revenue,EmissionsIntensity_gPerKWh,CumulativeNetEmissions";
+
+/// One row of the yearly summary table, in YEARLY_SUMMARY_HEADER column order
+fn yearly_summary_row(metrics: &YearlyMetrics) -> String {
+    format!(
+        "{},{},{:.2},{:.2},{:.2},{:.4},{:.2},{:.2},{:.4},{:.2},{:.2},{:.2},{:.2},{:.2},{},{:.2},{:.2},{:.2},{:.2},{:.4},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2}",
+        metrics.year,
+        metrics.total_population,
+        metrics.total_power_usage,
+        metrics.total_power_generation,
+        metrics.power_balance,
+        metrics.average_public_opinion,
+        metrics.yearly_capital_cost,
+        metrics.total_capital_cost,
+        metrics.inflation_factor,
+        metrics.total_co2_emissions,
+        metrics.total_carbon_offset,
+        metrics.net_co2_emissions,
+        metrics.yearly_carbon_credit_revenue,
+        metrics.total_carbon_credit_revenue,
+        metrics.active_generators,
+        metrics.yearly_upgrade_costs,
+        metrics.yearly_closure_costs,
+        metrics.yearly_total_cost,
+        metrics.total_cost,
+        metrics.storage_state_of_charge,
+        metrics.storage_energy_charged,
+        metrics.storage_energy_discharged,
+        metrics.unserved_zone_demand,
+        metrics.capex,
+        metrics.opex,
+        metrics.fuel_cost,
+        metrics.carbon_cost,
+        metrics.revenue,
+        metrics.emissions_intensity_g_per_kwh,
+        metrics.cumulative_net_emissions
+    )
+}
+
 /// Main struct for handling CSV export
 pub struct CsvExporter {
     output_dir: PathBuf,
     timestamp: String,
-    verbose_logging: bool,
 }
 
 impl CsvExporter {
     /// Create a new CSV exporter with the specified output directory
-    pub fn new(output_dir: impl AsRef<Path>, verbose_logging: bool) -> Self {
+    pub fn new(output_dir: impl AsRef<Path>) -> Self {
         let now = Local::now();
         let timestamp = now.format("%Y%m%d_%H%M%S").to_string();
 
@@ -122,7 +283,6 @@ impl CsvExporter {
         Self {
             output_dir: full_path,
             timestamp,
-            verbose_logging,
         }
     }
 
@@ -143,14 +303,68 @@ impl CsvExporter {
         // Export generator operation time logs
         self.export_generator_operation_logs(map, yearly_metrics)?;
 
+        // Export each settlement's population and demand over time
+        self.export_settlement_demand(yearly_metrics)?;
+
         // Only print success message if verbose logging is enabled
-        if self.verbose_logging {
-            println!("CSV export completed successfully to: {}", self.output_dir.display());
-        }
+        crate::log!(Info, "CSV export completed successfully to: {}", self.output_dir.display());
         
         Ok(())
     }
 
+    /// Export one row per settlement and year with its population and projected power usage
+    pub fn export_settlement_demand(
+        &self,
+        yearly_metrics: &[YearlyMetrics],
+    ) -> Result<(), Box<dyn Error>> {
+        let mut file = File::create(self.output_dir.join("settlement_demand.csv"))?;
+        writeln!(file, "Year,Settlement,Population,Power Usage (MW)")?;
+
+        for metrics in yearly_metrics {
+            for (name, population, power_usage) in &metrics.settlement_demand {
+                writeln!(file, "{},\"{}\",{},{:.6}", metrics.year, name.replace('"', "\"\""), population, power_usage)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Append the current best plan's yearly rows to `path`, tagged with the iteration and
+    /// score it was found at. Each call writes whole rows and syncs them to disk, so an
+    /// interrupted run leaves a readable file holding every snapshot flushed so far.
+    pub fn append_yearly_metrics(
+        path: impl AsRef<Path>,
+        iteration: usize,
+        score: f64,
+        yearly_metrics: &[YearlyMetrics],
+    ) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        let write_header = !path.exists();
+        let mut block = String::new();
+        if write_header {
+            block.push_str(&format!("Iteration,Score,{}\n", YEARLY_SUMMARY_HEADER));
+        }
+        for metrics in yearly_metrics {
+            block.push_str(&format!("{},{:.6},{}\n", iteration, score, yearly_summary_row(metrics)));
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(block.as_bytes())?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    /// Move a completed run's incremental file to `final_path`, marking it finished
+    pub fn finalize_incremental_export(
+        path: impl AsRef<Path>,
+        final_path: impl AsRef<Path>,
+    ) -> Result<(), Box<dyn Error>> {
+        if path.as_ref().exists() {
+            std::fs::rename(path, final_path)?;
+        }
+        Ok(())
+    }
+
     /// Export improvement history to CSV
     pub fn export_improvement_history(
         &self,
@@ -158,9 +372,7 @@ impl CsvExporter {
     ) -> Result<(), Box<dyn Error>> {
         // Skip if no improvements are provided
         if improvements.is_empty() {
-            if self.verbose_logging {
-                println!("No improvement history to export");
-            }
+            crate::log!(Info, "No improvement history to export");
             return Ok(());
         }
 
@@ -203,10 +415,8 @@ impl CsvExporter {
             prev_score = record.score;
         }
 
-        if self.verbose_logging {
-            println!("Successfully exported {} improvement records to: {}", 
-                improvements.len(), improvements_path.display());
-        }
+        crate::log!(Info, "Successfully exported {} improvement records to: {}", 
+            improvements.len(), improvements_path.display());
 
         Ok(())
     }
@@ -263,7 +473,8 @@ impl CsvExporter {
                         *year,
                         gen_type.can_be_urban(),
                         gen_type.requires_water(),
-                        gen_type.requires_water()
+                        gen_type.requires_water(),
+                        &map.get_config().inflation,
                     );
                     
                     // Apply cost multiplier
@@ -285,6 +496,7 @@ impl CsvExporter {
                         let gen_type = generator.get_generator_type();
                         let base_max = match gen_type {
                             GeneratorType::OnshoreWind | GeneratorType::OffshoreWind => WIND_BASE_MAX_EFFICIENCY,
+                            GeneratorType::FloatingOffshoreWind => FLOATING_WIND_BASE_MAX_EFFICIENCY,
                             GeneratorType::UtilitySolar => UTILITY_SOLAR_BASE_MAX_EFFICIENCY,
                             GeneratorType::Nuclear => NUCLEAR_BASE_MAX_EFFICIENCY,
                             GeneratorType::GasCombinedCycle => GAS_CC_BASE_MAX_EFFICIENCY,
@@ -296,20 +508,15 @@ impl CsvExporter {
                         let tech_improvement = match gen_type {
                             GeneratorType::OnshoreWind | GeneratorType::OffshoreWind |
                             GeneratorType::UtilitySolar => DEVELOPING_TECH_IMPROVEMENT_RATE,
+                            GeneratorType::FloatingOffshoreWind => EMERGING_TECH_IMPROVEMENT_RATE,
                             GeneratorType::TidalGenerator | GeneratorType::WaveEnergy => EMERGING_TECH_IMPROVEMENT_RATE,
                             _ => MATURE_TECH_IMPROVEMENT_RATE,
                         }.powi((*year - BASE_YEAR) as i32);
                         
                         let max_efficiency = base_max * (1.0 + (1.0 - tech_improvement));
                         
-                        // Calculate current efficiency
-                        let current_efficiency = generator.get_efficiency();
-                        
-                        // Calculate remaining potential improvement
-                        let potential_improvement = (max_efficiency - current_efficiency).max(0.0);
-                        
-                        // Cost is proportional to potential improvement * base cost
-                        generator.get_current_cost(*year) * potential_improvement * 2.0
+                        // Same cost model apply_action charges in the simulation
+                        generator.upgrade_cost(*year, max_efficiency, map.get_generator_constraints().upgrade_cost_multiplier, &map.get_config().inflation)
                     } else {
                         0.0
                     };
@@ -341,7 +548,7 @@ impl CsvExporter {
                     };
                     
                     // Apply inflation to match how actual costs are calculated
-                    let inflation_factor = crate::config::const_funcs::calc_inflation_factor(*year);
+                    let inflation_factor = map.get_config().inflation.factor(*year);
                     let adjusted_cost = base_offset_cost * inflation_factor;
                     
                     // Apply cost multiplier
@@ -358,12 +565,8 @@ impl CsvExporter {
                 },
                 GridAction::CloseGenerator(id) => {
                     // Calculate closure cost
-                    let closure_cost = if let Some(generator) = generator_map.get(id.as_str()) {
-                        let years_remaining = (generator.eol as i32 - (*year as i32 - 2025).max(0)) as f64;
-                        generator.get_current_cost(*year) * 0.3 * (years_remaining / generator.eol as f64)
-                    } else {
-                        0.0
-                    };
+                    let closure_cost = generator_map.get(id.as_str())
+                        .map_or(0.0, |generator| generator.decommission_cost(*year));
                     
                     (
                     "CloseGenerator",
@@ -374,6 +577,38 @@ impl CsvExporter {
                         format!("{:.2}", closure_cost),
                     )
                 },
+                GridAction::RetrofitGenerator(id, target) => {
+                    let retrofit_cost = generator_map.get(id.as_str())
+                        .map_or(0.0, |generator| generator.retrofit_cost());
+
+                    (
+                    "RetrofitGenerator",
+                    target.to_string(),
+                    id.clone(),
+                    String::new(),
+                    String::new(),
+                        format!("{:.2}", retrofit_cost),
+                    )
+                },
+                GridAction::AddHybridPlant(primary, secondary) => {
+                    let hybrid_cost = primary.get_base_cost(*year) + secondary.get_base_cost(*year);
+                    (
+                    "AddHybridPlant",
+                    format!("{}+{}", primary, secondary),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                        format!("{:.2}", hybrid_cost),
+                    )
+                },
+                GridAction::AddFlexibleLoad => (
+                    "AddFlexibleLoad",
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                ),
                 GridAction::DoNothing => (
                     "DoNothing",
                     String::new(),
@@ -382,6 +617,14 @@ impl CsvExporter {
                     String::new(),
                     "0.00".to_string(),
                 ),
+                GridAction::DeferDecision => (
+                    "DeferDecision",
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    "0.00".to_string(),
+                ),
             };
             
             writeln!(
@@ -394,37 +637,10 @@ impl CsvExporter {
         // Add yearly summary metrics
         writeln!(summary_file, "")?;
         writeln!(summary_file, "Yearly Summary Metrics")?;
-        writeln!(
-            summary_file,
-            "Year,Population,PowerUsage,PowerGeneration,PowerBalance,PublicOpinion,YearlyCapitalCost,TotalCapitalCost,Inflation,CO2Emissions,CarbonOffset,NetEmissions,YearlyRevenue,TotalRevenue,ActiveGenerators,YearlyUpgradeCosts,YearlyClosureCosts,YearlyTotalCost,TotalCost"
-        )?;
+        writeln!(summary_file, "{}", YEARLY_SUMMARY_HEADER)?;
         
         for metrics in yearly_metrics {
-            // Basic financial and operational metrics
-            let formatted_line = format!(
-                "{},{},{:.2},{:.2},{:.2},{:.4},{:.2},{:.2},{:.4},{:.2},{:.2},{:.2},{:.2},{:.2},{},{:.2},{:.2},{:.2},{:.2}",
-                metrics.year,
-                metrics.total_population,
-                metrics.total_power_usage,
-                metrics.total_power_generation,
-                metrics.power_balance,
-                metrics.average_public_opinion,
-                metrics.yearly_capital_cost,
-                metrics.total_capital_cost,
-                metrics.inflation_factor,
-                metrics.total_co2_emissions,
-                metrics.total_carbon_offset,
-                metrics.net_co2_emissions,
-                metrics.yearly_carbon_credit_revenue,
-                metrics.total_carbon_credit_revenue,
-                metrics.active_generators,
-                metrics.yearly_upgrade_costs,
-                metrics.yearly_closure_costs,
-                metrics.yearly_total_cost,
-                metrics.total_cost
-            );
-            
-            writeln!(summary_file, "{}", formatted_line)?;
+            writeln!(summary_file, "{}", yearly_summary_row(metrics))?;
         }
         
         Ok(())
@@ -462,9 +678,7 @@ impl CsvExporter {
         let settlements = map.get_settlements();
         
         // Only print details if verbose logging is enabled
-        if self.verbose_logging {
-            println!("Total settlements to export: {}", settlements.len());
-        }
+        crate::log!(Debug, "Total settlements to export: {}", settlements.len());
         
         // Create settlements CSV file
         let settlements_file_path = details_dir.join("settlements.csv");
@@ -477,9 +691,7 @@ impl CsvExporter {
         )?;
         
         // Only print details if verbose logging is enabled
-        if self.verbose_logging {
-            println!("Exporting data for {} settlements across {} years", settlements.len(), END_YEAR - BASE_YEAR + 1);
-        }
+        crate::log!(Info, "Exporting data for {} settlements across {} years", settlements.len(), END_YEAR - BASE_YEAR + 1);
         
         // For each year
         for year in BASE_YEAR..=END_YEAR {
@@ -521,9 +733,7 @@ impl CsvExporter {
         }
         
         // Only print success message if verbose logging is enabled
-        if self.verbose_logging {
-            println!("Successfully exported settlement data for all years.");
-        }
+        crate::log!(Info, "Successfully exported settlement data for all years.");
         
         Ok(())
     }
@@ -539,9 +749,7 @@ impl CsvExporter {
         let generators = map.get_generators();
         
         // Only print details if verbose logging is enabled
-        if self.verbose_logging {
-            println!("Exporting data for {} generators across years {}-{}", generators.len(), BASE_YEAR, END_YEAR);
-        }
+        crate::log!(Info, "Exporting data for {} generators across years {}-{}", generators.len(), BASE_YEAR, END_YEAR);
         
         // Create generators CSV file
         let generators_file_path = details_dir.join("generators.csv");
@@ -599,8 +807,14 @@ impl CsvExporter {
         
         // Helper function to extract generator type from ID
         let extract_generator_type = |id: &str| -> String {
+            // IDs from the simulation and the loader embed the exact type: "Gen_Type_Year_N", "Existing_Type_N"
+            if let Some(gen_type) = id.split('_').nth(1).and_then(|part| GeneratorType::from_str(part).ok()) {
+                return gen_type.to_string();
+            }
             if id.contains("Onshore") || id.contains("OnshoreWind") {
                 "OnshoreWind".to_string()
+            } else if id.contains("Floating") || id.contains("FloatingOffshoreWind") {
+                "FloatingOffshoreWind".to_string()
             } else if id.contains("Offshore") || id.contains("OffshoreWind") {
                 "OffshoreWind".to_string()
             } else if id.contains("DomesticSolar") {
@@ -619,6 +833,10 @@ impl CsvExporter {
                 "GasPeaker".to_string()
             } else if id.contains("Biomass") {
                 "Biomass".to_string()
+            } else if id.contains("HydrogenElectrolyzer") {
+                "HydrogenElectrolyzer".to_string()
+            } else if id.contains("HydrogenTurbine") {
+                "HydrogenTurbine".to_string()
             } else if id.contains("Hydro") || id.contains("HydroDam") {
                 "HydroDam".to_string()
             } else if id.contains("PumpedStorage") {
@@ -656,6 +874,7 @@ impl CsvExporter {
             match gen_type {
                 "OnshoreWind" => 50.0,
                 "OffshoreWind" => 200.0,
+                "FloatingOffshoreWind" => 200.0,
                 "DomesticSolar" => 0.01,
                 "CommercialSolar" => 0.5,
                 "UtilitySolar" => 50.0,
@@ -669,6 +888,8 @@ impl CsvExporter {
                 "BatteryStorage" => 50.0,
                 "TidalGenerator" => 30.0,
                 "WaveEnergy" => 20.0,
+                "HydrogenElectrolyzer" => 100.0,
+                "HydrogenTurbine" => 150.0,
                 _ => 100.0,
             }
         };
@@ -676,9 +897,10 @@ impl CsvExporter {
         // Helper function to get default CO2 output based on generator type (tonnes per year at 100% operation)
         let get_default_co2_output = |gen_type: &str, power_output: f64| -> f64 {
             match gen_type {
-                "OnshoreWind" | "OffshoreWind" | "DomesticSolar" | "CommercialSolar" | 
+                "OnshoreWind" | "OffshoreWind" | "FloatingOffshoreWind" | "DomesticSolar" | "CommercialSolar" | 
                 "UtilitySolar" | "HydroDam" | "PumpedStorage" | "BatteryStorage" | 
-                "TidalGenerator" | "WaveEnergy" | "Nuclear" => 0.0,
+                "TidalGenerator" | "WaveEnergy" | "Nuclear" |
+                "HydrogenElectrolyzer" | "HydrogenTurbine" => 0.0,
                 "CoalPlant" => power_output * 3.0 * 8760.0 / 1000.0, // ~3 kg CO2/kWh
                 "GasCombinedCycle" => power_output * 0.4 * 8760.0 / 1000.0, // ~0.4 kg CO2/kWh
                 "GasPeaker" => power_output * 0.5 * 8760.0 / 1000.0, // ~0.5 kg CO2/kWh
@@ -726,12 +948,12 @@ impl CsvExporter {
                     .unwrap_or(&(generator.get_operation_percentage() as f64 / 100.0)) * 100.0;
                 
                 // Calculate costs - ensure we handle potential Inf values
-                let capital_cost = match generator.get_current_cost(year) {
+                let capital_cost = match generator.get_current_cost(year, &map.get_config().inflation) {
                     cost if cost.is_finite() => cost,
                     _ => 0.0 // Default to 0 if we get inf or NaN
                 };
                 
-                let operating_cost = match generator.get_current_operating_cost(year) {
+                let operating_cost = match generator.get_current_operating_cost(year, &map.get_config().inflation) {
                     cost if cost.is_finite() => cost,
                     _ => 0.0 // Default to 0 if we get inf or NaN
                 };
@@ -741,6 +963,7 @@ impl CsvExporter {
                 // Calculate reliability factor based on generator type
                 let reliability_factor = match generator.get_generator_type() {
                     GeneratorType::OnshoreWind | GeneratorType::OffshoreWind => 0.35, 
+                    GeneratorType::FloatingOffshoreWind => 0.40,
                     GeneratorType::DomesticSolar | GeneratorType::CommercialSolar | 
                     GeneratorType::UtilitySolar => 0.25,
                     GeneratorType::Nuclear => 0.95,
@@ -753,6 +976,8 @@ impl CsvExporter {
                     GeneratorType::BatteryStorage => 0.98,
                     GeneratorType::TidalGenerator => 0.45,
                     GeneratorType::WaveEnergy => 0.40,
+                    GeneratorType::HydrogenElectrolyzer => 0.0,
+                    GeneratorType::HydrogenTurbine => 0.90,
                 };
                 
                 // Get construction speed and planning/construction durations
@@ -773,10 +998,8 @@ impl CsvExporter {
                 let sanitized_id = sanitize_id(generator_id);
                 
                 // Add debugging for non-transformed coordinate values
-                if self.verbose_logging {
-                    println!("Generator {} coordinates - Grid: ({:.2}, {:.2}), Geo: ({:.6}, {:.6})",
-                        generator_id, coordinate.x, coordinate.y, lon, lat);
-                }
+                crate::log!(Debug, "Generator {} coordinates - Grid: ({:.2}, {:.2}), Geo: ({:.6}, {:.6})",
+                    generator_id, coordinate.x, coordinate.y, lon, lat);
                 
                 // Write generator data to CSV, including planning and construction times
                 writeln!(
@@ -810,33 +1033,19 @@ impl CsvExporter {
                     if !processed_generators.contains(id) {
                         // We found a generator in the yearly metrics that's not in the current map state
                         
-                        // First check if this is an "Existing_" generator that we might have a record for
-                        // in a previous year's state. If so, we should use its real coordinates.
-                        let mut found_existing_generator = false;
-                        let mut existing_generator_coordinates = None;
-                        
-                        if id.starts_with("Existing_") {
-                            // Search through the original generators list to see if we can find this generator
-                            for generator in generators.iter() {
-                                if generator.get_id() == id {
-                                    // Found the generator! Use its real coordinates
-                                    let real_coordinate = generator.get_coordinate();
-                                    
-                                    if self.verbose_logging {
-                                        println!("Found real coordinates ({:.2}, {:.2}) for existing generator {}", 
-                                            real_coordinate.x, real_coordinate.y, id);
-                                    }
-                                    
-                                    // Store the real coordinates for use below
-                                    existing_generator_coordinates = Some((real_coordinate.x, real_coordinate.y));
-                                    found_existing_generator = true;
-                                    break;
-                                }
-                            }
+                        // Look the generator up by its exact ID so we use its real coordinates, rather
+                        // than synthetic ones or those of another generator with a similar name
+                        let existing_generator_coordinates = generator_map.get(id.as_str()).map(|generator| {
+                            let real_coordinate = generator.get_coordinate();
                             
-                            if !found_existing_generator && self.verbose_logging {
-                                println!("WARNING: Could not find real coordinates for existing generator {}", id);
-                            }
+                            crate::log!(Debug, "Found real coordinates ({:.2}, {:.2}) for generator {}", 
+                                real_coordinate.x, real_coordinate.y, id);
+                            
+                            (real_coordinate.x, real_coordinate.y)
+                        });
+                        
+                        if existing_generator_coordinates.is_none() {
+                            crate::log!(Debug, "Could not find real coordinates for generator {}", id);
                         }
                         
                         // Parse information from the ID
@@ -863,10 +1072,8 @@ impl CsvExporter {
                                 let x = 5000.0 + (id_hash % 100) as f64 / 100.0 * (MAP_MAX_X - 10000.0);
                                 let y = 5000.0 + ((id_hash / 100) % 100) as f64 / 100.0 * (MAP_MAX_Y - 10000.0);
                                 
-                                if self.verbose_logging {
-                                    println!("Generated synthetic grid coordinates ({:.2}, {:.2}) for generator {}", 
-                                        x, y, id);
-                                }
+                                crate::log!(Debug, "Generated synthetic grid coordinates ({:.2}, {:.2}) for generator {}", 
+                                    x, y, id);
                                 
                                 (x, y)
                             }
@@ -878,6 +1085,7 @@ impl CsvExporter {
                         // Calculate reliability factor based on generator type
                         let reliability_factor = match gen_type.as_str() {
                             "OnshoreWind" | "OffshoreWind" => 0.35, 
+                            "FloatingOffshoreWind" => 0.40,
                             "DomesticSolar" | "CommercialSolar" | "UtilitySolar" => 0.25,
                             "Nuclear" => 0.95,
                             "CoalPlant" => 0.90,
@@ -889,13 +1097,15 @@ impl CsvExporter {
                             "BatteryStorage" => 0.98,
                             "TidalGenerator" => 0.45,
                             "WaveEnergy" => 0.40,
+                            "HydrogenElectrolyzer" => 0.0,
+                            "HydrogenTurbine" => 0.90,
                             _ => 0.75,
                         };
                         
                         // Estimate size based on power output and type
                         let size = match gen_type.as_str() {
                             "OnshoreWind" => power_output / 3.0, // ~3MW per turbine
-                            "OffshoreWind" => power_output / 8.0, // ~8MW per turbine
+                            "OffshoreWind" | "FloatingOffshoreWind" => power_output / 8.0, // ~8MW per turbine
                             "DomesticSolar" => power_output * 8.0, // kW to panel area (m²)
                             "CommercialSolar" => power_output * 6.0, // kW to panel area (m²)
                             "UtilitySolar" => power_output * 2.0, // MW to hectares
@@ -906,6 +1116,7 @@ impl CsvExporter {
                         let capital_cost = match gen_type.as_str() {
                             "OnshoreWind" => power_output * 1_500_000.0, // €1.5M per MW
                             "OffshoreWind" => power_output * 3_500_000.0, // €3.5M per MW
+                            "FloatingOffshoreWind" => power_output * 5_500_000.0, // €5.5M per MW
                             "DomesticSolar" => power_output * 1_000_000.0, // €1M per MW
                             "CommercialSolar" => power_output * 800_000.0, // €800k per MW
                             "UtilitySolar" => power_output * 600_000.0, // €600k per MW
@@ -919,6 +1130,8 @@ impl CsvExporter {
                             "BatteryStorage" => power_output * 400_000.0, // €400k per MW
                             "TidalGenerator" => power_output * 5_000_000.0, // €5M per MW
                             "WaveEnergy" => power_output * 4_000_000.0, // €4M per MW
+                            "HydrogenElectrolyzer" => power_output * 1_500_000.0, // €1.5M per MW
+                            "HydrogenTurbine" => power_output * 900_000.0, // €900k per MW
                             _ => power_output * 2_000_000.0, // €2M per MW (default)
                         };
                         
@@ -929,10 +1142,8 @@ impl CsvExporter {
                         let sanitized_id = sanitize_id(id);
                         
                         // Add debugging for non-transformed coordinate values
-                        if self.verbose_logging {
-                            println!("Generator {} coordinates - Grid: ({:.2}, {:.2}), Geo: ({:.6}, {:.6})",
-                                id, x, y, lon, lat);
-                        }
+                        crate::log!(Debug, "Generator {} coordinates - Grid: ({:.2}, {:.2}), Geo: ({:.6}, {:.6})",
+                            id, x, y, lon, lat);
                         
                         // Write generator data to CSV with the information we have
                         // Also add planning and construction time estimates based on the type
@@ -1044,12 +1255,12 @@ impl CsvExporter {
                 let negative_emissions = -co2_offset; // Explicitly show negative emissions
                 
                 // Handle potential infinite values
-                let cost = match offset.get_current_cost(year) {
+                let cost = match offset.get_current_cost(year, &map.get_config().inflation) {
                     cost if cost.is_finite() => cost,
                     _ => 0.0
                 };
                 
-                let operating_cost = match offset.get_current_operating_cost(year) {
+                let operating_cost = match offset.get_current_operating_cost(year, &map.get_config().inflation) {
                     cost if cost.is_finite() => cost,
                     _ => 0.0
                 };
@@ -1185,7 +1396,7 @@ impl CsvExporter {
                             },
                             
                             // Wind varies by season but less by hour
-                            GeneratorType::OnshoreWind | GeneratorType::OffshoreWind => {
+                            GeneratorType::OnshoreWind | GeneratorType::OffshoreWind | GeneratorType::FloatingOffshoreWind => {
                                 // Higher in winter, lower in summer
                                 let seasonal_factor = match month {
                                     1 | 2 | 11 | 12 => 0.9, // Winter
@@ -1276,6 +1487,20 @@ impl CsvExporter {
                                     _ => 0.4, // Mixed operation during other times
                                 }
                             },
+
+                            // Electrolyzers soak up overnight surplus, hydrogen turbines cover peaks
+                            GeneratorType::HydrogenElectrolyzer => {
+                                match hour {
+                                    0..=5 | 22..=23 => 0.8,
+                                    _ => 0.2,
+                                }
+                            },
+                            GeneratorType::HydrogenTurbine => {
+                                match hour {
+                                    7..=9 | 17..=20 => 0.8,
+                                    _ => 0.1,
+                                }
+                            },
                         };
                         
                         // Calculate actual output for this hour
@@ -1388,15 +1613,27 @@ pub struct YearlyMetrics {
     pub total_co2_emissions: f64,
     pub total_carbon_offset: f64,
     pub net_co2_emissions: f64,
+    pub cumulative_net_emissions: f64,
+    pub emissions_intensity_g_per_kwh: f64,
     pub yearly_carbon_credit_revenue: f64,
     pub total_carbon_credit_revenue: f64,
     pub yearly_energy_sales_revenue: f64,
     pub total_energy_sales_revenue: f64,
+    pub storage_state_of_charge: f64,
+    pub storage_energy_charged: f64,
+    pub storage_energy_discharged: f64,
+    pub unserved_zone_demand: f64,
     pub generator_efficiencies: Vec<(String, f64)>,
     pub generator_operations: Vec<(String, f64)>,
+    pub settlement_demand: Vec<(String, u32, f64)>,
     pub active_generators: usize,
     pub yearly_upgrade_costs: f64,
     pub yearly_closure_costs: f64,
+    pub capex: f64,
+    pub opex: f64,
+    pub fuel_cost: f64,
+    pub carbon_cost: f64,
+    pub revenue: f64,
     pub yearly_total_cost: f64,
     pub total_cost: f64,
 }
@@ -1421,17 +1658,29 @@ where
             total_co2_emissions: m.get_total_co2_emissions(),
             total_carbon_offset: m.get_total_carbon_offset(),
             net_co2_emissions: m.get_net_co2_emissions(),
+            cumulative_net_emissions: m.get_cumulative_net_emissions(),
+            emissions_intensity_g_per_kwh: m.get_emissions_intensity_g_per_kwh(),
             yearly_carbon_credit_revenue: m.get_yearly_carbon_credit_revenue(),
             total_carbon_credit_revenue: m.get_total_carbon_credit_revenue(),
             generator_efficiencies: m.get_generator_efficiencies(),
             generator_operations: m.get_generator_operations(),
+            settlement_demand: m.get_settlement_demand(),
             active_generators: m.get_active_generators(),
             yearly_upgrade_costs: m.get_yearly_upgrade_costs(),
             yearly_closure_costs: m.get_yearly_closure_costs(),
+            capex: m.get_capex(),
+            opex: m.get_opex(),
+            fuel_cost: m.get_fuel_cost(),
+            carbon_cost: m.get_carbon_cost(),
+            revenue: m.get_revenue(),
             yearly_total_cost: m.get_yearly_total_cost(),
             total_cost: m.get_total_cost(),
             yearly_energy_sales_revenue: m.get_yearly_energy_sales_revenue(),
             total_energy_sales_revenue: m.get_total_energy_sales_revenue(),
+            storage_state_of_charge: m.get_storage_state_of_charge(),
+            storage_energy_charged: m.get_storage_energy_charged(),
+            storage_energy_discharged: m.get_storage_energy_discharged(),
+            unserved_zone_demand: m.get_unserved_zone_demand(),
         }
     }).collect()
 }
@@ -1450,16 +1699,76 @@ pub trait YearlyMetricsLike {
     fn get_total_co2_emissions(&self) -> f64;
     fn get_total_carbon_offset(&self) -> f64;
     fn get_net_co2_emissions(&self) -> f64;
+    fn get_cumulative_net_emissions(&self) -> f64;
+    fn get_emissions_intensity_g_per_kwh(&self) -> f64;
     fn get_yearly_carbon_credit_revenue(&self) -> f64;
     fn get_total_carbon_credit_revenue(&self) -> f64;
     fn get_generator_efficiencies(&self) -> Vec<(String, f64)>;
     fn get_generator_operations(&self) -> Vec<(String, f64)>;
+    fn get_settlement_demand(&self) -> Vec<(String, u32, f64)>;
     fn get_active_generators(&self) -> usize;
     fn get_yearly_upgrade_costs(&self) -> f64;
     fn get_yearly_closure_costs(&self) -> f64;
+    fn get_capex(&self) -> f64;
+    fn get_opex(&self) -> f64;
+    fn get_fuel_cost(&self) -> f64;
+    fn get_carbon_cost(&self) -> f64;
+    fn get_revenue(&self) -> f64;
     fn get_yearly_total_cost(&self) -> f64;
     fn get_total_cost(&self) -> f64;
     fn get_yearly_energy_sales_revenue(&self) -> f64;
     fn get_total_energy_sales_revenue(&self) -> f64;
+    fn get_storage_state_of_charge(&self) -> f64;
+    fn get_storage_energy_charged(&self) -> f64;
+    fn get_storage_energy_discharged(&self) -> f64;
+    fn get_unserved_zone_demand(&self) -> f64;
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::metrics_calculation::calculate_yearly_metrics;
+    use crate::config::constants::{BASE_YEAR, END_YEAR};
+    use crate::config::simulation_config::SimulationConfig;
+    use crate::core::simulation::update_population;
+    use crate::data::settlements_loader::load_settlements;
+    use crate::test_support::{empty_map, scratch_path};
+
+    #[test]
+    fn settlement_demand_has_a_row_per_settlement_and_year() {
+        let settlements_path = scratch_path("settlements.json");
+        std::fs::write(&settlements_path, r#"{"settlements": [
+            {"name": "Dublin", "lat": 53.35, "lon": -6.26, "population": 1200000, "grid_x": 0.0, "grid_y": 0.0},
+            {"name": "Cork", "lat": 51.90, "lon": -8.47, "population": 210000, "grid_x": 0.0, "grid_y": 0.0}
+        ]}"#).unwrap();
+        let settlements = load_settlements(settlements_path.to_str().unwrap(), BASE_YEAR).unwrap();
+        assert_eq!(settlements.len(), 2);
+        let dublin_loaded_usage = settlements.iter().find(|s| s.get_name() == "Dublin").unwrap().get_power_usage();
+
+        let mut map = empty_map(SimulationConfig::default());
+        for settlement in settlements {
+            map.add_settlement(settlement);
+        }
+        let mut yearly_metrics = Vec::new();
+        for year in BASE_YEAR..=END_YEAR {
+            update_population(&mut map, year);
+            yearly_metrics.push(calculate_yearly_metrics(&map, year, 0.0, 0.0, false, yearly_metrics.last()));
+        }
+        let output_dir = scratch_path("settlement_demand");
+        let exporter = CsvExporter::new(&output_dir);
+
+        exporter.export_settlement_demand(&convert_yearly_metrics(&yearly_metrics)).unwrap();
+
+        let mut reader = csv::Reader::from_path(exporter.output_dir.join("settlement_demand.csv")).unwrap();
+        let rows: Vec<csv::StringRecord> = reader.records().map(|row| row.unwrap()).collect();
+        assert_eq!(rows.len(), 2 * (END_YEAR - BASE_YEAR + 1) as usize);
+        let dublin_2025 = rows.iter()
+            .find(|row| &row[0] == BASE_YEAR.to_string().as_str() && &row[1] == "Dublin")
+            .expect("a Dublin row for the base year");
+        let usage: f64 = dublin_2025[3].parse().unwrap();
+        assert!((usage - dublin_loaded_usage).abs() < 1e-6, "{} vs {}", usage, dublin_loaded_usage);
+        let _ = std::fs::remove_file(settlements_path);
+        let _ = std::fs::remove_dir_all(output_dir);
+    }
+}
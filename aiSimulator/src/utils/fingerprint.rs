@@ -0,0 +1,68 @@
+// Stable hashing for regression fingerprints of simulation state
+
+use std::hash::Hasher;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+// FNV-1a, so fingerprints stay the same across Rust releases (unlike DefaultHasher)
+pub struct StableHasher(u64);
+
+impl StableHasher {
+    pub fn new() -> Self {
+        Self(FNV_OFFSET_BASIS)
+    }
+
+    // Strings are terminated so ("ab", "c") and ("a", "bc") hash differently
+    pub fn write_str(&mut self, value: &str) {
+        self.write(value.as_bytes());
+        self.write_u8(0xff);
+    }
+
+    pub fn write_f64(&mut self, value: f64) {
+        self.write_u64(value.to_bits());
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(value as u8);
+    }
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    // Fixed endianness so fingerprints match across platforms
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+}
+
+// Hash each item on its own and add the results, so the total ignores collection order
+pub fn unordered_hash<T>(items: impl IntoIterator<Item = T>, hash_item: impl Fn(&mut StableHasher, T)) -> u64 {
+    items.into_iter()
+        .map(|item| {
+            let mut hasher = StableHasher::new();
+            hash_item(&mut hasher, item);
+            hasher.finish()
+        })
+        .fold(0u64, u64::wrapping_add)
+}
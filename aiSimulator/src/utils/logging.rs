@@ -1,13 +1,117 @@
 use lazy_static::lazy_static;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 use tracing::Level;
 use tracing_subscriber::{EnvFilter, prelude::*};
 use tracing_timing::{Builder, Histogram};
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 use std::sync::Arc;
 use parking_lot::RwLock;
 use std::time::{Duration, Instant};
 use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Verbosity of the simulator's own diagnostics, quietest first. Messages at or below the
+/// current level are written; the default is `Warn` so embedding the library stays quiet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    const ALL: [LogLevel; 5] = [LogLevel::Error, LogLevel::Warn, LogLevel::Info, LogLevel::Debug, LogLevel::Trace];
+
+    /// Level implied by the older boolean CLI flags: any debug flag means Debug, verbose state
+    /// logging means Trace, and a plain run logs at Info
+    pub fn from_flags(debug_logging: bool, debug_weights: bool, verbose_state_logging: bool) -> Self {
+        if verbose_state_logging {
+            LogLevel::Trace
+        } else if debug_logging || debug_weights {
+            LogLevel::Debug
+        } else {
+            LogLevel::Info
+        }
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "error" => Ok(LogLevel::Error),
+            "warn" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            "trace" => Ok(LogLevel::Trace),
+            other => Err(format!("Unknown log level '{}' (expected error, warn, info, debug or trace)", other)),
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Receives every message that passes the level check, instead of stdout/stderr
+pub type LogSink = Box<dyn Fn(LogLevel, &str) + Send + Sync>;
+
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Warn as u8);
+
+pub fn set_log_level(level: LogLevel) {
+    LOG_LEVEL.store(level as u8, Ordering::SeqCst);
+}
+
+pub fn log_level() -> LogLevel {
+    LogLevel::ALL[LOG_LEVEL.load(Ordering::SeqCst) as usize]
+}
+
+pub fn log_enabled(level: LogLevel) -> bool {
+    level <= log_level()
+}
+
+/// Routes log output to `sink`, or back to stdout/stderr with `None`
+pub fn set_log_sink(sink: Option<LogSink>) {
+    *LOG_SINK.write() = sink;
+}
+
+/// Writes one message regardless of level; use the `log!` macro, which checks the level first
+/// and skips formatting when the message would be dropped
+pub fn write_log(level: LogLevel, args: fmt::Arguments) {
+    if let Some(sink) = LOG_SINK.read().as_ref() {
+        sink(level, &args.to_string());
+    } else if level <= LogLevel::Warn {
+        eprintln!("{}", args);
+    } else {
+        println!("{}", args);
+    }
+}
+
+/// `log!(Debug, "...", args)` prints like `println!` when the current `LogLevel` allows it
+#[macro_export]
+macro_rules! log {
+    ($level:ident, $($arg:tt)*) => {
+        if $crate::utils::logging::log_enabled($crate::utils::logging::LogLevel::$level) {
+            $crate::utils::logging::write_log($crate::utils::logging::LogLevel::$level, format_args!($($arg)*));
+        }
+    };
+}
 
 // Define categories for different types of operations
 #[derive(Hash, Eq, PartialEq, Clone, Debug)]
@@ -105,15 +209,20 @@ impl OperationCategory {
     }
 }
 
+// Inclusive time and call count per call stack, keyed by the folded "outer;inner" path
+type StackTimings = HashMap<String, (OperationCategory, Duration, usize)>;
+
 thread_local! {
     static TIMING_STACK: RefCell<Vec<(String, OperationCategory, Instant)>> = RefCell::new(Vec::new());
 }
 
 lazy_static! {
     static ref TIMING_ENABLED: AtomicBool = AtomicBool::new(false);
+    static ref LOG_SINK: RwLock<Option<LogSink>> = RwLock::new(None);
     static ref FUNCTION_TIMINGS: Arc<RwLock<HashMap<String, Histogram<u64>>>> = Arc::new(RwLock::new(HashMap::new()));
     static ref CATEGORY_TIMINGS: Arc<RwLock<HashMap<OperationCategory, Histogram<u64>>>> = Arc::new(RwLock::new(HashMap::new()));
     static ref HIERARCHICAL_TIMINGS: Arc<RwLock<HashMap<String, (Duration, usize, Vec<String>)>>> = Arc::new(RwLock::new(HashMap::new()));
+    static ref STACK_TIMINGS: Arc<RwLock<StackTimings>> = Arc::new(RwLock::new(HashMap::new()));
 }
 
 pub struct TimingGuard {
@@ -168,6 +277,18 @@ fn record_timing_end(function_name: &str, duration: Duration, category: &Operati
                     entry.2.push(parent_name.clone());
                 }
             }
+
+            let folded = stack.iter()
+                .map(|(name, _, _)| name.as_str())
+                .chain(std::iter::once(function_name))
+                .collect::<Vec<_>>()
+                .join(";");
+            let mut stacks = STACK_TIMINGS.write();
+            let stack_entry = stacks
+                .entry(folded)
+                .or_insert((category.clone(), Duration::from_nanos(0), 0));
+            stack_entry.1 += duration;
+            stack_entry.2 += 1;
         }
     });
 
@@ -236,6 +357,23 @@ pub fn is_timing_enabled() -> bool {
     TIMING_ENABLED.load(Ordering::SeqCst)
 }
 
+/// Turns timing collection on or off without touching the tracing subscriber
+pub fn set_timing_enabled(enabled: bool) {
+    TIMING_ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+/// Total time and call count per operation category, slowest first
+pub fn category_totals() -> Vec<(String, Duration, u64)> {
+    let mut totals: Vec<_> = CATEGORY_TIMINGS.read().iter()
+        .map(|(category, histogram)| {
+            let total_ns = histogram.mean() * histogram.len() as f64;
+            (category.as_str(), Duration::from_nanos(total_ns as u64), histogram.len())
+        })
+        .collect();
+    totals.sort_by_key(|(_, total, _)| std::cmp::Reverse(*total));
+    totals
+}
+
 pub fn print_timing_report() {
     if !is_timing_enabled() {
         return;
@@ -298,4 +436,141 @@ pub fn print_timing_report() {
     }
 
     println!("==========================\n");
-} 
\ No newline at end of file
+}
+
+// Self time of each recorded stack: its inclusive time less that of its direct callees
+fn stack_self_times() -> Vec<(String, OperationCategory, Duration, Duration, usize)> {
+    let stacks = STACK_TIMINGS.read();
+    let mut entries: Vec<_> = stacks.iter()
+        .map(|(path, (category, total, count))| {
+            let children: Duration = stacks.iter()
+                .filter(|(child, _)| {
+                    child.strip_prefix(path.as_str())
+                        .and_then(|rest| rest.strip_prefix(';'))
+                        .is_some_and(|rest| !rest.contains(';'))
+                })
+                .map(|(_, (_, child_total, _))| *child_total)
+                .sum();
+            (path.clone(), category.clone(), *total, total.saturating_sub(children), *count)
+        })
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    entries
+}
+
+/// Write the recorded timings for external profiling tools: `path` gets folded stacks
+/// ("outer;inner self_microseconds", the input format of flamegraph.pl and inferno), and
+/// a sibling .json file gets the per-operation totals and call counts.
+pub fn write_profile(path: &Path) -> io::Result<()> {
+    let entries = stack_self_times();
+
+    let mut folded = BufWriter::new(File::create(path)?);
+    for (stack, _, _, self_time, _) in &entries {
+        writeln!(folded, "{} {}", stack, self_time.as_micros())?;
+    }
+    folded.flush()?;
+
+    let operations: Vec<_> = HIERARCHICAL_TIMINGS.read().iter()
+        .map(|(function_name, (total, count, parents))| serde_json::json!({
+            "function": function_name,
+            "total_secs": total.as_secs_f64(),
+            "count": count,
+            "callers": parents,
+        }))
+        .collect();
+    let stacks: Vec<_> = entries.iter()
+        .map(|(stack, category, total, self_time, count)| serde_json::json!({
+            "stack": stack,
+            "category": category.as_str(),
+            "total_secs": total.as_secs_f64(),
+            "self_secs": self_time.as_secs_f64(),
+            "count": count,
+        }))
+        .collect();
+    let summary = serde_json::json!({ "operations": operations, "stacks": stacks });
+    let json_file = File::create(path.with_extension("json"))?;
+    serde_json::to_writer_pretty(BufWriter::new(json_file), &summary)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::learning::constants::set_debug_weights;
+    use crate::config::simulation_config::SimulationConfig;
+    use crate::core::simulation::run_simulation;
+    use crate::models::settlement::Settlement;
+    use crate::test_support::{empty_map, scratch_path, LOG_SETTINGS, TEST_SITE};
+    use std::sync::Mutex;
+
+    #[test]
+    fn profile_of_a_short_run_covers_the_simulation_and_deficit_handling() {
+        // No generation, so the run has to handle a deficit
+        let mut map = empty_map(SimulationConfig::default());
+        map.add_settlement(Settlement::new("Midtown".to_string(), TEST_SITE, 50_000, 80.0));
+        let previous_timing = is_timing_enabled();
+        set_timing_enabled(true);
+        run_simulation(&mut map, None, Some(1), false, None, false, false, 0).unwrap();
+        set_timing_enabled(previous_timing);
+        let path = scratch_path("profile.folded");
+
+        write_profile(&path).unwrap();
+
+        let folded = std::fs::read_to_string(&path).unwrap();
+        let summary: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path.with_extension("json")).unwrap()).unwrap();
+        let functions: Vec<&str> = summary["operations"].as_array().unwrap().iter()
+            .map(|operation| operation["function"].as_str().unwrap())
+            .collect();
+        for name in ["run_simulation", "handle_power_deficit"] {
+            assert!(functions.contains(&name), "{} missing from {:?}", name, functions);
+            assert!(folded.lines().any(|line| line.contains(name)), "{} missing from the folded stacks", name);
+        }
+        let _ = std::fs::remove_file(path.with_extension("json"));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn warn_level_sends_only_warnings_and_errors_to_the_sink() {
+        let _settings = LOG_SETTINGS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let previous_level = log_level();
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let sink_messages = Arc::clone(&captured);
+        set_log_sink(Some(Box::new(move |level, message: &str| {
+            // Other tests may log while the sink is installed, so keep only this test's messages
+            if message.starts_with("sink test") {
+                sink_messages.lock().unwrap().push((level, message.to_string()));
+            }
+        })));
+        set_log_level(LogLevel::Warn);
+
+        crate::log!(Error, "sink test error {}", 1);
+        crate::log!(Warn, "sink test warn");
+        crate::log!(Info, "sink test info");
+        crate::log!(Debug, "sink test debug {}", 2);
+        crate::log!(Trace, "sink test trace");
+
+        set_log_level(previous_level);
+        set_log_sink(None);
+        let captured = captured.lock().unwrap().clone();
+        assert_eq!(captured, vec![
+            (LogLevel::Error, "sink test error 1".to_string()),
+            (LogLevel::Warn, "sink test warn".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn turning_debug_weights_off_restores_the_level_it_raised_from() {
+        let _settings = LOG_SETTINGS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let previous_level = log_level();
+        set_log_level(LogLevel::Warn);
+
+        set_debug_weights(true);
+        let raised = log_level();
+        set_debug_weights(false);
+        let restored = log_level();
+
+        set_log_level(previous_level);
+        assert_eq!(raised, LogLevel::Debug);
+        assert_eq!(restored, LogLevel::Warn);
+    }
+}
@@ -1,28 +1,32 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 use anyhow::Result;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 use super::logging;
 use super::logging::{
     OperationCategory, FileIOType, PowerCalcType, LocationSearchType
 };
 
-use crate::models::generator::{Generator, GeneratorType};
+use crate::models::generator::{Generator, GeneratorType, ConstructionStatus};
 use crate::models::settlement::Settlement;
-use crate::models::carbon_offset::CarbonOffset;
-use crate::data::poi::{POI, Coordinate};
+use crate::models::carbon_offset::{CarbonOffset, CarbonOffsetType};
+use crate::models::flexible_load::FlexibleLoad;
+use super::traits::GridAsset;
+use crate::data::poi::{POI, Coordinate, is_better_location};
+use crate::error::SimulationError;
 use crate::config::constants::{
     TRANSMISSION_LOSS_WEIGHT,
     PUBLIC_OPINION_WEIGHT,
     CONSTRUCTION_COST_WEIGHT,
     GRID_CELL_SIZE,
-    MAP_MAX_X,
-    MAP_MAX_Y,
     ENABLE_CONSTRUCTION_DELAYS,
     END_YEAR,
+    HYDROGEN_ELECTROLYSIS_EFFICIENCY,
+    GAS_PEAKER_CO2_RATE,
 };
 use crate::config::const_funcs::{
+    calc_decommission_cost,
     is_point_inside_polygon, 
     calc_planning_permission_time,
     calc_construction_time,
@@ -31,8 +35,13 @@ use crate::config::const_funcs::{
 };
 use crate::config::simulation_config::{SimulationConfig, GeneratorConstraints};
 use crate::models::power_storage::calculate_max_intermittent_capacity;
-use super::spatial_index::{SpatialIndex, GeneratorSuitabilityType};
+use super::spatial_index::{SpatialIndex, GeneratorSuitabilityType, SpatialItemKind};
+use super::grid_zones::{ZoneBalance, zone_for_coordinate, resolve_zone_transfers};
+use super::fingerprint::{StableHasher, unordered_hash};
+use std::hash::Hasher;
 use crate::gpu::metal_location_search::MetalLocationSearch;
+use crate::analysis::uncertainty::AvailabilityFactors;
+use crate::analysis::metrics::{OpinionTrend, YearlyMetrics};
 
 // Define trait for location analysis functionality
 pub trait LocationAnalysisSource {
@@ -68,15 +77,16 @@ impl LocationAnalysis {
         let step_size = GRID_CELL_SIZE * 2.0;
         
         // Calculate number of steps in each direction
-        let x_steps = (MAP_MAX_X / step_size).ceil() as i32;
-        let y_steps = (MAP_MAX_Y / step_size).ceil() as i32;
+        let extent = map.get_config().map_extent;
+        let x_steps = (extent.max_x / step_size).ceil() as i32;
+        let y_steps = (extent.max_y / step_size).ceil() as i32;
 
         // Analyze grid points
         for i in -x_steps..=x_steps {
             for j in -y_steps..=y_steps {
                 let x = i as f64 * step_size;
                 let y = j as f64 * step_size;
-                let coordinate = Coordinate::new(x, y);
+                let coordinate = extent.clamp(x, y);
 
                 let mut suitable_types = Vec::new();
                 let mut suitability_scores = HashMap::new();
@@ -85,6 +95,7 @@ impl LocationAnalysis {
                 for generator_type in [
                     GeneratorType::OnshoreWind,
                     GeneratorType::OffshoreWind,
+                    GeneratorType::FloatingOffshoreWind,
                     GeneratorType::DomesticSolar,
                     GeneratorType::CommercialSolar,
                     GeneratorType::UtilitySolar,
@@ -98,6 +109,8 @@ impl LocationAnalysis {
                     GeneratorType::BatteryStorage,
                     GeneratorType::TidalGenerator,
                     GeneratorType::WaveEnergy,
+                    GeneratorType::HydrogenElectrolyzer,
+                    GeneratorType::HydrogenTurbine,
                 ].iter() {
                     let suitability = map.calculate_generator_suitability(&coordinate, generator_type);
                     
@@ -280,6 +293,31 @@ pub struct MapStaticData {
     coastline_points: Vec<Coordinate>,
 }
 
+// The part of a Map that actions change: generators (with their storage), offsets and flexible
+// loads. Occupancy, the storage cache and the point-item index are rebuilt from these on restore,
+// so the quadtree, settlements and static data are never copied.
+#[derive(Debug, Clone)]
+pub struct MapSnapshot {
+    generators: Vec<Generator>,
+    carbon_offsets: Vec<CarbonOffset>,
+    flexible_loads: Vec<FlexibleLoad>,
+    next_generator_number: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RetirementReason {
+    EndOfLife,
+    PhaseOut,
+}
+
+// A closure the simulation forced, kept apart from the AI's plan actions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Retirement {
+    pub year: u32,
+    pub generator_id: String,
+    pub reason: RetirementReason,
+}
+
 // Remove automatic derive for Map
 #[derive(Debug, Clone)]
 pub struct Map {
@@ -287,6 +325,7 @@ pub struct Map {
     pub generators: Vec<Generator>,
     pub settlements: Vec<Settlement>,
     pub carbon_offsets: Vec<CarbonOffset>,
+    pub flexible_loads: Vec<FlexibleLoad>,          // Managed EV charging that can move between hours
     pub grid_occupancy: HashMap<(i32, i32), f64>,
     pub spatial_index: SpatialIndex,
     pub metal_location_search: Option<MetalLocationSearch>,
@@ -295,6 +334,10 @@ pub struct Map {
     pub use_fast_simulation: bool,
     pub storage_cache: Vec<usize>, // Indices of storage generators, sorted by efficiency
     pub enable_construction_delays: bool,
+    pub availability_factors: AvailabilityFactors, // Renewable availability for the current Monte Carlo draw
+    pub opinion_trend: Option<OpinionTrend>,       // Last year's emissions and spend for opinion dynamics
+    pub next_generator_number: usize,              // Suffix for the next new generator ID, never reused even after removals
+    pub retirements: Vec<Retirement>,              // End-of-life and phase-out closures, in the order they happened
 }
 
 // Custom serialization implementation
@@ -304,12 +347,15 @@ impl Serialize for Map {
         S: serde::Serializer,
     {
         use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("Map", 5)?;
+        let mut state = serializer.serialize_struct("Map", 8)?;
         state.serialize_field("static_data", &*self.static_data)?;
         state.serialize_field("generators", &self.generators)?;
         state.serialize_field("settlements", &self.settlements)?;
         state.serialize_field("carbon_offsets", &self.carbon_offsets)?;
+        state.serialize_field("flexible_loads", &self.flexible_loads)?;
         state.serialize_field("grid_occupancy", &self.grid_occupancy)?;
+        state.serialize_field("next_generator_number", &self.next_generator_number)?;
+        state.serialize_field("retirements", &self.retirements)?;
         state.end()
     }
 }
@@ -326,24 +372,40 @@ impl<'de> Deserialize<'de> for Map {
             generators: Vec<Generator>,
             settlements: Vec<Settlement>,
             carbon_offsets: Vec<CarbonOffset>,
+            #[serde(default)]
+            flexible_loads: Vec<FlexibleLoad>,
             grid_occupancy: HashMap<(i32, i32), f64>,
+            #[serde(default)]
+            next_generator_number: usize,
+            #[serde(default)]
+            retirements: Vec<Retirement>,
         }
 
         let helper = Helper::deserialize(deserializer)?;
-        Ok(Map {
+        let spatial_index = SpatialIndex::new(&helper.static_data.config.map_extent);
+        // Maps saved before the counter existed resume from the generator count
+        let next_generator_number = helper.next_generator_number.max(helper.generators.len());
+        let mut map = Map {
             static_data: Arc::new(helper.static_data),
             generators: helper.generators,
             settlements: helper.settlements,
             carbon_offsets: helper.carbon_offsets,
+            flexible_loads: helper.flexible_loads,
             grid_occupancy: helper.grid_occupancy,
-            spatial_index: SpatialIndex::new(),
+            spatial_index,
             metal_location_search: None,
             location_analysis: None,
             current_year: 2024,
             use_fast_simulation: true,
             storage_cache: Vec::new(),
             enable_construction_delays: ENABLE_CONSTRUCTION_DELAYS,
-        })
+            availability_factors: AvailabilityFactors::default(),
+            opinion_trend: None,
+            next_generator_number,
+            retirements: helper.retirements,
+        };
+        map.index_items();
+        Ok(map)
     }
 }
 
@@ -374,6 +436,7 @@ impl Map {
             })
             .collect();
 
+        let spatial_index = SpatialIndex::new(&config.map_extent);
         let static_data = Arc::new(MapStaticData {
             config,
             coastline_points,
@@ -384,14 +447,19 @@ impl Map {
             generators: Vec::new(),
             settlements: Vec::new(),
             carbon_offsets: Vec::new(),
+            flexible_loads: Vec::new(),
             grid_occupancy: HashMap::new(),
-            spatial_index: SpatialIndex::new(),
+            spatial_index,
             metal_location_search,
             location_analysis: None,
             current_year: 2024,
             use_fast_simulation: true,
             storage_cache: Vec::new(),
             enable_construction_delays: ENABLE_CONSTRUCTION_DELAYS,
+            availability_factors: AvailabilityFactors::default(),
+            opinion_trend: None,
+            next_generator_number: 0,
+            retirements: Vec::new(),
         };
 
         map.initialize_spatial_index();
@@ -404,19 +472,25 @@ impl Map {
             println!("Warning: Metal-based location search not available, falling back to CPU implementation");
         }
 
+        let spatial_index = SpatialIndex::new(&static_data.config.map_extent);
         Self {
             static_data,
             generators: Vec::new(),
             settlements: Vec::new(),
             carbon_offsets: Vec::new(),
+            flexible_loads: Vec::new(),
             grid_occupancy: HashMap::new(),
-            spatial_index: SpatialIndex::new(),
+            spatial_index,
             metal_location_search,
             location_analysis: None,
             current_year: 2024,
             use_fast_simulation: true,
             storage_cache: Vec::new(),
             enable_construction_delays: ENABLE_CONSTRUCTION_DELAYS,
+            availability_factors: AvailabilityFactors::default(),
+            opinion_trend: None,
+            next_generator_number: 0,
+            retirements: Vec::new(),
         }
     }
 
@@ -463,7 +537,7 @@ impl Map {
                 
                 let suitability_type = match generator.get_generator_type() {
                     GeneratorType::OnshoreWind => GeneratorSuitabilityType::Onshore,
-                    GeneratorType::OffshoreWind => GeneratorSuitabilityType::Offshore,
+                    GeneratorType::OffshoreWind | GeneratorType::FloatingOffshoreWind => GeneratorSuitabilityType::Offshore,
                     GeneratorType::TidalGenerator | GeneratorType::WaveEnergy => GeneratorSuitabilityType::Coastal,
                     _ => GeneratorSuitabilityType::Rural,
                 };
@@ -487,9 +561,10 @@ impl Map {
         }
 
         // Initialize rural areas with lower base suitability
+        let extent = self.get_config().map_extent;
         self.spatial_index.update_region(
-            &Coordinate::new(MAP_MAX_X / 2.0, MAP_MAX_Y / 2.0),
-            (MAP_MAX_X.powi(2) + MAP_MAX_Y.powi(2)).sqrt() / 2.0,
+            &extent.center(),
+            (extent.max_x.powi(2) + extent.max_y.powi(2)).sqrt() / 2.0,
             GeneratorSuitabilityType::Rural,
             0.5, // Reduced from 0.8 to provide more flexibility
         );
@@ -503,6 +578,39 @@ impl Map {
                 0.5, // Reduced from 0.7
             );
         }
+
+        self.index_items();
+    }
+
+    // Rebuild the point-item index used by range queries
+    fn index_items(&mut self) {
+        self.spatial_index.clear_items();
+        for generator in &self.generators {
+            self.spatial_index.insert_item(SpatialItemKind::Generator, generator.get_id(), generator.get_coordinate());
+        }
+        for settlement in &self.settlements {
+            self.spatial_index.insert_item(SpatialItemKind::Settlement, settlement.get_id(), settlement.get_coordinate());
+        }
+    }
+
+    /// Generators within `radius` of `coord`, ordered by id. Closed generators
+    /// are left out unless `include_inactive` is set.
+    pub fn generators_near(&self, coord: &Coordinate, radius: f64, include_inactive: bool) -> Vec<&Generator> {
+        self.spatial_index.query_radius(coord, radius)
+            .into_iter()
+            .filter(|(kind, _)| *kind == SpatialItemKind::Generator)
+            .filter_map(|(_, id)| self.generators.iter().find(|g| g.get_id() == id))
+            .filter(|g| include_inactive || g.is_active)
+            .collect()
+    }
+
+    /// Settlements within `radius` of `coord`, ordered by id.
+    pub fn settlements_near(&self, coord: &Coordinate, radius: f64) -> Vec<&Settlement> {
+        self.spatial_index.query_radius(coord, radius)
+            .into_iter()
+            .filter(|(kind, _)| *kind == SpatialItemKind::Settlement)
+            .filter_map(|(_, id)| self.settlements.iter().find(|s| s.get_id() == id))
+            .collect()
     }
 
     pub fn get_static_data(&self) -> Arc<MapStaticData> {
@@ -538,6 +646,13 @@ impl Map {
         });
     }
 
+    pub fn set_config(&mut self, config: SimulationConfig) {
+        self.static_data = Arc::new(MapStaticData {
+            config,
+            coastline_points: self.static_data.coastline_points.clone(),
+        });
+    }
+
     pub fn load_from_json(path: &str) -> Result<Self> {
         let content = fs::read_to_string(path)?;
         let map: Map = serde_json::from_str(&content)?;
@@ -550,7 +665,43 @@ impl Map {
         Ok(())
     }
 
+    // Captures the action-mutable state so an exploratory action can be undone with `restore`
+    pub fn snapshot(&self) -> MapSnapshot {
+        MapSnapshot {
+            generators: self.generators.clone(),
+            carbon_offsets: self.carbon_offsets.clone(),
+            flexible_loads: self.flexible_loads.clone(),
+            next_generator_number: self.next_generator_number,
+        }
+    }
+
+    // Siting suitability hints written into the quadtree are left as they are; placement never
+    // reads them back. Fast-mode space reservations in the location analysis aren't rolled back either.
+    pub fn restore(&mut self, snapshot: MapSnapshot) {
+        self.generators = snapshot.generators;
+        self.carbon_offsets = snapshot.carbon_offsets;
+        self.flexible_loads = snapshot.flexible_loads;
+        self.next_generator_number = snapshot.next_generator_number;
+        self.update_grid_occupancy();
+        self.update_storage_cache();
+        self.index_items();
+    }
+
+    // Reserves an ID for a generator built during the simulation. The numeric suffix only ever
+    // grows, so closing and removing generators can't make a later build reuse an ID.
+    pub fn next_generator_id(&mut self, gen_type: &GeneratorType, year: u32) -> String {
+        let id = format!("Gen_{}_{}_{}", gen_type, year, self.next_generator_number);
+        self.next_generator_number += 1;
+        id
+    }
+
     pub fn add_generator(&mut self, mut generator: Generator) {
+        // IDs key lookups everywhere (actions, exports, hybrids), so a duplicate would alias two plants
+        if self.generators.iter().any(|g| g.get_id() == generator.get_id()) {
+            eprintln!("WARNING: Rejected generator {}: a generator with that ID already exists", generator.get_id());
+            return;
+        }
+        
         // Initialize construction status with current year and public opinion
         let current_year = self.current_year;
         let public_opinion = self.calculate_public_opinion_at_location(&generator.coordinate);
@@ -608,25 +759,27 @@ impl Map {
                             
                             // FIXED: Generate coordinates distributed within valid range
                             // Using a more sensible formula that never gives negative values
-                            let x = 5000.0 + (id_hash % 100) as f64 / 100.0 * (MAP_MAX_X - 10000.0);
-                            let y = 5000.0 + ((id_hash / 100) % 100) as f64 / 100.0 * (MAP_MAX_Y - 10000.0);
+                            let extent = self.get_config().map_extent;
+                            let x = 5000.0 + (id_hash % 100) as f64 / 100.0 * (extent.max_x - 10000.0);
+                            let y = 5000.0 + ((id_hash / 100) % 100) as f64 / 100.0 * (extent.max_y - 10000.0);
                             
                             println!("Fallback coordinates for {}: ({:.2}, {:.2})", 
                                 generator.get_id(), x, y);
                             
                             // Ensure coordinates are valid
-                            let valid_x = x.max(0.0).min(MAP_MAX_X);
-                            let valid_y = y.max(0.0).min(MAP_MAX_Y);
+                            let valid_x = x.max(0.0).min(extent.max_x);
+                            let valid_y = y.max(0.0).min(extent.max_y);
                             
                             if valid_x != x || valid_y != y {
                                 println!("WARNING: Adjusted invalid coordinates from ({:.2}, {:.2}) to ({:.2}, {:.2})",
                                     x, y, valid_x, valid_y);
                             }
                             
-                            generator.coordinate = Coordinate::new(valid_x, valid_y);
+                            generator.coordinate = extent.clamp(valid_x, valid_y);
                         }
                     }
                     
+                    self.spatial_index.insert_item(SpatialItemKind::Generator, generator.get_id(), generator.get_coordinate());
                     self.generators.push(generator);
                 } else if analysis.any_types_exhausted() {
                     // If we've exhausted locations for any generator type, switch to full mode
@@ -689,7 +842,7 @@ impl Map {
                     println!("Full mode fallback coordinates for {}: ({:.2}, {:.2})", 
                              generator.get_id(), valid_x, valid_y);
                     
-                    generator.coordinate = Coordinate::new(valid_x, valid_y);
+                    generator.coordinate = Coordinate { x: valid_x, y: valid_y };
                 }
             }
         }
@@ -704,8 +857,57 @@ impl Map {
         *self.grid_occupancy.entry((grid_x, grid_y)).or_insert(0.0) += size;
         
         // Add to the generators list and update indices
+        self.spatial_index.insert_item(SpatialItemKind::Generator, generator.get_id(), generator.get_coordinate());
+        self.generators.push(generator);
+        self.after_generator_modification();
+    }
+
+    // Last resort for deficit handling once sampled builds keep failing to close the gap: a gas
+    // peaker at the largest settlement, operational at once and sized to `deficit`. It skips the
+    // site search and exclusion zones, so it always lands. Returns the MW of output added.
+    pub fn add_emergency_capacity(&mut self, deficit: f64, year: u32) -> f64 {
+        let gen_type = GeneratorType::GasPeaker;
+        let location = self.settlements.iter()
+            .max_by_key(|s| s.get_population())
+            .map(|s| s.get_coordinate().clone())
+            .unwrap_or_else(|| {
+                let extent = self.get_config().map_extent;
+                extent.clamp(extent.max_x / 2.0, extent.max_y / 2.0)
+            });
+        let id = format!("Emergency_{}", self.next_generator_id(&gen_type, year));
+        let build = |scale: f64| {
+            let base_cost = gen_type.get_base_cost(year) * scale;
+            Generator::new(
+                id.clone(),
+                location.clone(),
+                gen_type.clone(),
+                base_cost,
+                gen_type.get_base_power(year) * scale,
+                gen_type.get_operating_cost(year) * scale,
+                gen_type.get_lifespan(),
+                scale,
+                GAS_PEAKER_CO2_RATE * scale,
+                calc_decommission_cost(base_cost),
+            )
+        };
+
+        // Size from what one standard unit actually adds to the grid once derated
+        let public_opinion = self.calculate_public_opinion_at_location(&location);
+        let mut unit = build(1.0);
+        unit.initialize_construction(year, public_opinion, false);
+        let unit_gain = self.effective_generator_output(&unit, year, None).max(f64::EPSILON);
+
+        let mut generator = build(deficit / unit_gain);
+        generator.initialize_construction(year, public_opinion, false);
+        if self.generators.iter().any(|g| g.get_id() == id) {
+            eprintln!("WARNING: Rejected generator {}: a generator with that ID already exists", id);
+            return 0.0;
+        }
+        let added = self.effective_generator_output(&generator, year, None);
+        self.spatial_index.insert_item(SpatialItemKind::Generator, &id, &location);
         self.generators.push(generator);
         self.after_generator_modification();
+        added
     }
 
     // Modify find_suitable_location_from_analysis to use the optimized lookup
@@ -733,6 +935,7 @@ impl Map {
             let generator = self.generators.remove(index);
             let coord = generator.get_coordinate();
             let size = generator.size;
+            self.spatial_index.remove_item(SpatialItemKind::Generator, generator.get_id(), coord);
             
             // Update grid occupancy
             let grid_x = (coord.x / GRID_CELL_SIZE).floor() as i32;
@@ -755,7 +958,7 @@ impl Map {
             // Restore suitability in the surrounding area
             let suitability_type = match generator.get_generator_type() {
                 GeneratorType::OnshoreWind => GeneratorSuitabilityType::Onshore,
-                GeneratorType::OffshoreWind => GeneratorSuitabilityType::Offshore,
+                GeneratorType::OffshoreWind | GeneratorType::FloatingOffshoreWind => GeneratorSuitabilityType::Offshore,
                 GeneratorType::TidalGenerator | GeneratorType::WaveEnergy => GeneratorSuitabilityType::Coastal,
                 _ => GeneratorSuitabilityType::Rural,
             };
@@ -779,6 +982,7 @@ impl Map {
     }
 
     pub fn add_settlement(&mut self, settlement: Settlement) {
+        self.spatial_index.insert_item(SpatialItemKind::Settlement, settlement.get_id(), settlement.get_coordinate());
         self.settlements.push(settlement);
     }
 
@@ -810,6 +1014,10 @@ impl Map {
         self.carbon_offsets.push(offset);
     }
 
+    pub fn add_flexible_load(&mut self, load: FlexibleLoad) {
+        self.flexible_loads.push(load);
+    }
+
     pub fn calc_total_population(&self, __year: u32) -> u32 {
         self.settlements.iter()
             .map(|s| s.get_population())
@@ -823,7 +1031,143 @@ impl Map {
             .sum::<f64>();
 
         // Add growth factor based on year
-        settlement_usage * (1.0 + (__year as f64 - 2024.0) * 0.02)
+        settlement_usage * Self::demand_growth_factor(__year)
+    }
+
+    fn demand_growth_factor(year: u32) -> f64 {
+        1.0 + (year as f64 - 2024.0) * 0.02
+    }
+
+    // Output after ageing, outages and the current renewable availability draw
+    fn effective_generator_output(&self, generator: &Generator, year: u32, hour: Option<u8>) -> f64 {
+        match (&generator.hybrid_host, hour) {
+            // Co-located storage only covers its host's dip below its average output in the
+            // hour. Charging from the host's surplus hours isn't modelled: the unit is assumed
+            // to have been topped up and draws nothing from the host's output.
+            (Some(host_id), Some(_)) if generator.get_generator_type().is_storage() => {
+                match self.generators.iter().find(|g| g.get_id() == host_id) {
+                    Some(host) => {
+                        let shortfall = (host.get_current_power_output(None) - host.get_current_power_output(hour)).max(0.0);
+                        generator.get_current_power_output(hour).min(shortfall)
+                            * generator.derate_factor(year, &self.availability_factors)
+                    }
+                    None => generator.effective_power_at(year, hour, &self.availability_factors),
+                }
+            }
+            _ => generator.effective_power_at(year, hour, &self.availability_factors),
+        }
+    }
+
+    pub fn generator_zone(&self, generator: &Generator) -> String {
+        generator.zone.clone()
+            .unwrap_or_else(|| zone_for_coordinate(&self.get_config().grid_zones.zones, &generator.coordinate))
+    }
+
+    pub fn settlement_zone(&self, settlement: &Settlement) -> String {
+        settlement.get_zone()
+            .map(str::to_string)
+            .unwrap_or_else(|| zone_for_coordinate(&self.get_config().grid_zones.zones, settlement.get_coordinate()))
+    }
+
+    pub fn assign_generator_zone(&mut self, id: &str, zone: &str) -> bool {
+        match self.get_generator_mut(id) {
+            Some(generator) => {
+                generator.zone = Some(zone.to_string());
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn assign_settlement_zone(&mut self, name: &str, zone: &str) -> bool {
+        match self.settlements.iter_mut().find(|s| s.get_name() == name) {
+            Some(settlement) => {
+                settlement.set_zone(Some(zone.to_string()));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn calc_zone_power_usage(&self, year: u32) -> BTreeMap<String, f64> {
+        let growth = Self::demand_growth_factor(year);
+        let mut usage = BTreeMap::new();
+        for settlement in &self.settlements {
+            *usage.entry(self.settlement_zone(settlement)).or_insert(0.0) += settlement.get_power_usage() * growth;
+        }
+        usage
+    }
+
+    pub fn calc_zone_power_generation(&self, year: u32, hour: Option<u8>) -> BTreeMap<String, f64> {
+        let mut generation = BTreeMap::new();
+        for generator in &self.generators {
+            *generation.entry(self.generator_zone(generator)).or_insert(0.0) += self.effective_generator_output(generator, year, hour);
+        }
+        generation
+    }
+
+    // Per-zone balance after routing surplus over the configured link capacities.
+    // A zone can still be short here even when the national total is in surplus.
+    pub fn calc_zone_balances(&self, year: u32, hour: Option<u8>) -> BTreeMap<String, ZoneBalance> {
+        resolve_zone_transfers(
+            &self.calc_zone_power_usage(year),
+            &self.calc_zone_power_generation(year, hour),
+            &self.get_config().grid_zones.links,
+        )
+    }
+
+    pub fn calc_unserved_zone_demand(&self, year: u32, hour: Option<u8>) -> f64 {
+        self.calc_zone_balances(year, hour).values().map(|b| b.unserved).sum()
+    }
+
+    // MW of managed EV charging that can be moved between hours
+    pub fn calc_shiftable_demand(&self) -> f64 {
+        self.flexible_loads.iter().map(|l| l.shiftable_demand()).sum()
+    }
+
+    // Balance for each hour of a representative day after smart charging moves flexible demand
+    // out of deficit hours into surplus ones. No hour moves more than the shiftable MW, and the
+    // energy moved out of deficit hours equals what surplus hours take on.
+    pub fn calc_hourly_power_balance(&self, year: u32) -> Vec<f64> {
+        let demand = self.calc_total_power_usage(year);
+        let mut balance: Vec<f64> = (0..24)
+            .map(|hour| self.calc_total_power_generation(year, Some(hour)) - demand)
+            .collect();
+
+        let shiftable = self.calc_shiftable_demand();
+        let relief: f64 = balance.iter().map(|b| (-b).clamp(0.0, shiftable)).sum();
+        let headroom: f64 = balance.iter().map(|b| b.clamp(0.0, shiftable)).sum();
+        let moved = relief.min(headroom);
+        if moved <= 0.0 {
+            return balance;
+        }
+
+        // Whichever side has less room sets how much moves; the other side is scaled down evenly
+        for b in balance.iter_mut() {
+            if *b < 0.0 {
+                *b += (-*b).min(shiftable) * moved / relief;
+            } else {
+                *b -= b.min(shiftable) * moved / headroom;
+            }
+        }
+        balance
+    }
+
+    // Capacity-credited MW the fleet can count on at the peak
+    pub fn calc_firm_capacity(&self) -> f64 {
+        self.generators.iter().map(|g| g.firm_capacity()).sum()
+    }
+
+    // Firm capacity less peak demand in MW. Demand is flat across the modelled day, so its peak
+    // is the yearly usage. A fleet can balance on energy yet fall short here when it leans on
+    // solar and wind without firm or storage capacity behind them.
+    pub fn calc_firm_capacity_margin(&self, year: u32) -> f64 {
+        self.calc_firm_capacity() - self.calc_total_power_usage(year)
+    }
+
+    // Worst hourly shortfall in MW on a representative day, zero if every hour is covered
+    pub fn calc_peak_deficit(&self, year: u32) -> f64 {
+        self.calc_hourly_power_balance(year).iter().fold(0.0, |worst, b| worst.max(-b))
     }
 
     pub fn calc_total_power_generation(&self, year: u32, hour: Option<u8>) -> f64 {
@@ -850,7 +1194,7 @@ impl Map {
         let mut storage_generation = 0.0;
         
         for generator in &self.generators {
-            let output = generator.get_current_power_output(hour);
+            let output = self.effective_generator_output(generator, year, hour);
             
             if generator.get_generator_type().is_intermittent() {
                 intermittent_generation += output;
@@ -877,6 +1221,7 @@ impl Map {
         }
         
         let mut remaining_deficit = deficit;
+        let year = self.current_year;
         
         // Use storage from cache, starting with most efficient
         for &generator_idx in &self.storage_cache {
@@ -886,6 +1231,7 @@ impl Map {
             
             let generator = &mut self.generators[generator_idx];
             if let Some(storage) = &mut generator.storage {
+                storage.begin_year(year);
                 // Calculate optimal discharge amount based on remaining deficit and storage capacity
                 let max_discharge = storage.current_charge.min(remaining_deficit);
                 if max_discharge > 0.0 {
@@ -895,18 +1241,122 @@ impl Map {
             }
         }
         
+        self.dispatch_hydrogen(remaining_deficit)
+    }
+
+    // Electrolyzers turn surplus into hydrogen before it would otherwise be curtailed.
+    // Returns the surplus absorbed.
+    pub fn charge_hydrogen_storage(&mut self, surplus: f64) -> f64 {
+        let mut remaining_surplus = surplus;
+        let year = self.current_year;
+        for generator in self.generators.iter_mut()
+            .filter(|g| g.is_active() && g.get_generator_type() == &GeneratorType::HydrogenElectrolyzer)
+        {
+            if remaining_surplus <= 0.0 {
+                break;
+            }
+            if let Some(tank) = &mut generator.storage {
+                tank.begin_year(year);
+                remaining_surplus -= tank.charge(remaining_surplus, HYDROGEN_ELECTROLYSIS_EFFICIENCY);
+            }
+        }
+        surplus - remaining_surplus
+    }
+
+    // Burns stored hydrogen in hydrogen turbines, limited by their combined rating.
+    // Returns the deficit left over.
+    pub fn dispatch_hydrogen(&mut self, deficit: f64) -> f64 {
+        let turbine_capacity: f64 = self.generators.iter()
+            .filter(|g| g.is_active() && g.get_generator_type() == &GeneratorType::HydrogenTurbine)
+            .map(|g| g.power_out * g.efficiency * g.operation_percentage)
+            .sum();
+        let mut remaining_deficit = deficit;
+        let mut dispatchable = deficit.min(turbine_capacity);
+        let year = self.current_year;
+
+        for generator in self.generators.iter_mut()
+            .filter(|g| g.is_active() && g.get_generator_type() == &GeneratorType::HydrogenElectrolyzer)
+        {
+            if dispatchable <= 0.0 {
+                break;
+            }
+            if let Some(tank) = &mut generator.storage {
+                tank.begin_year(year);
+                let fuel = tank.current_charge.min(dispatchable / tank.efficiency);
+                if fuel > 0.0 {
+                    let delivered = tank.discharge(fuel);
+                    dispatchable -= delivered;
+                    remaining_deficit -= delivered;
+                }
+            }
+        }
+
         remaining_deficit
     }
 
+    pub fn stored_hydrogen(&self) -> f64 {
+        self.generators.iter()
+            .filter(|g| g.get_generator_type() == &GeneratorType::HydrogenElectrolyzer)
+            .filter_map(|g| g.storage.as_ref())
+            .map(|tank| tank.current_charge)
+            .sum()
+    }
+
+    // Fleet-wide storage for `year`: state of charge as a share of total capacity,
+    // then the energy charged and discharged across batteries, pumped hydro and hydrogen tanks
+    pub fn storage_utilization(&self, year: u32) -> (f64, f64, f64) {
+        let mut stored = 0.0;
+        let mut capacity = 0.0;
+        let mut charged = 0.0;
+        let mut discharged = 0.0;
+        for storage in self.generators.iter()
+            .filter(|g| g.is_active())
+            .filter_map(|g| g.storage.as_ref())
+        {
+            stored += storage.current_charge;
+            capacity += storage.capacity;
+            let (year_charged, year_discharged) = storage.throughput(year);
+            charged += year_charged;
+            discharged += year_discharged;
+        }
+        let state_of_charge = if capacity > 0.0 { stored / capacity } else { 0.0 };
+        (state_of_charge, charged, discharged)
+    }
+
+
+    // Land taken by the operating fleet in km²
+    pub fn calc_total_land_use(&self) -> f64 {
+        self.generators.iter()
+            .filter(|g| g.is_active())
+            .map(|g| g.power_out * g.get_generator_type().land_intensity())
+            .sum()
+    }
 
     pub fn calc_total_co2_emissions(&self) -> f64 {
         let _timing = logging::start_timing("calc_total_co2_emissions", 
             OperationCategory::PowerCalculation { subcategory: PowerCalcType::Other });
         
-        self.generators.iter()
+        let operating: f64 = self.generators.iter()
             .filter(|g| g.is_active())
-            .map(|g| g.get_co2_output())
-            .sum()
+            .map(|g| g.get_co2_output_in_year(self.current_year))
+            .sum();
+        operating + self.calc_embodied_co2_emissions(self.current_year)
+    }
+
+    // Construction emissions of every generator and offset commissioned in `year`
+    pub fn calc_embodied_co2_emissions(&self, year: u32) -> f64 {
+        let factor = self.get_config().embodied_carbon_factor;
+        if factor == 0.0 {
+            return 0.0;
+        }
+
+        let generators = self.generators.iter()
+            .filter(|g| g.commissioning_year == year)
+            .map(|g| g.embodied_co2());
+        let offsets = self.carbon_offsets.iter()
+            .filter(|offset| offset.get_commissioning_year() == year)
+            .map(|offset| offset.embodied_co2());
+        factor * generators.chain(offsets).sum::<f64>()
     }
 
     pub fn calc_total_carbon_offset(&self, year: u32) -> f64 {
@@ -914,12 +1364,28 @@ impl Map {
             OperationCategory::PowerCalculation { subcategory: PowerCalcType::Other });
         
         self.carbon_offsets.iter()
+            .filter(|offset| !offset.is_expired(year))
+            .map(|offset| offset.calc_carbon_offset(year))
+            .sum()
+    }
+
+    // Share of the year's offset that is bought credits rather than sequestration
+    pub fn calc_carbon_credit_offset(&self, year: u32) -> f64 {
+        self.carbon_offsets.iter()
+            .filter(|offset| *offset.get_offset_type() == CarbonOffsetType::CarbonCredit && !offset.is_expired(year))
             .map(|offset| offset.calc_carbon_offset(year))
             .sum()
     }
 
     pub fn calc_net_co2_emissions(&self, year: u32) -> f64 {
-        self.calc_total_co2_emissions() - self.calc_total_carbon_offset(year)
+        self.assets().map(|asset| asset.co2_impact(year)).sum::<f64>() + self.calc_embodied_co2_emissions(year)
+    }
+
+    // Generators (storage included), carbon offsets, then flexible loads
+    pub fn assets(&self) -> impl Iterator<Item = &dyn GridAsset> {
+        self.generators.iter().map(|g| g as &dyn GridAsset)
+            .chain(self.carbon_offsets.iter().map(|o| o as &dyn GridAsset))
+            .chain(self.flexible_loads.iter().map(|l| l as &dyn GridAsset))
     }
 
     pub fn calc_new_generator_opinion(
@@ -940,8 +1406,10 @@ impl Map {
             1.0
         };
 
-        let type_opinion = generator.calc_type_opinion(year);
-        let cost_opinion = generator.calc_cost_opinion(year);
+        // Technology acceptance independent of siting, shifted by any configured per-type modifier
+        let type_modifier = self.get_config().type_opinion_modifier(generator.get_generator_type());
+        let type_opinion = (generator.calc_type_opinion(year) + type_modifier).clamp(0.0, 1.0);
+        let cost_opinion = generator.calc_cost_opinion(year, &self.get_config().inflation);
 
         TRANSMISSION_LOSS_WEIGHT * avg_settlement_opinion +
         PUBLIC_OPINION_WEIGHT * type_opinion +
@@ -952,16 +1420,8 @@ impl Map {
         let _timing = logging::start_timing("calc_total_capital_cost", 
             OperationCategory::PowerCalculation { subcategory: PowerCalcType::Other });
         
-        let generator_costs = self.generators.iter()
-            .filter(|g| !g.get_id().starts_with("Existing_")) // Exclude existing generators
-            .map(|g| g.get_current_cost(year))
-            .sum::<f64>();
-
-        let offset_costs = self.carbon_offsets.iter()
-            .map(|o| o.get_current_cost(year))
-            .sum::<f64>();
-
-        generator_costs + offset_costs
+        let inflation = &self.get_config().inflation;
+        self.assets().map(|asset| asset.annual_cost(year, inflation)).sum()
     }
 
     /// Calculate only the capital cost for generators and offsets added in the current year
@@ -969,29 +1429,106 @@ impl Map {
         let _timing = logging::start_timing("calc_yearly_capital_cost", 
             OperationCategory::PowerCalculation { subcategory: PowerCalcType::Other });
         
+        let inflation = &self.get_config().inflation;
+
         // Only include generators that were added in the current year and are not existing generators
         let generator_costs = self.generators.iter()
             .filter(|g| g.get_build_year() == year && !g.get_id().starts_with("Existing_"))
-            .map(|g| g.get_current_cost(year))
+            .map(|g| g.get_current_cost(year, inflation))
+            .sum::<f64>();
+
+        // Conversions carried out this year are capital spend on the existing plant
+        let retrofit_costs = self.generators.iter()
+            .filter_map(|g| g.retrofit)
+            .filter(|(retrofit_year, _)| *retrofit_year == year)
+            .map(|(_, cost)| cost)
             .sum::<f64>();
 
         // Only include carbon offsets that were added in the current year
         let offset_costs = self.carbon_offsets.iter()
             .filter(|o| o.get_start_year() == year)
-            .map(|o| o.get_current_cost(year))
+            .map(|o| o.get_current_cost(year, inflation))
+            .sum::<f64>();
+
+        let flexible_load_costs = self.flexible_loads.iter()
+            .filter(|l| l.get_build_year() == year)
+            .map(|l| l.get_current_cost(year, inflation))
+            .sum::<f64>();
+
+        generator_costs + retrofit_costs + offset_costs + flexible_load_costs
+    }
+
+    // Running costs for `year` split into (O&M, fuel); carbon offsets have no fuel
+    pub fn calc_yearly_operating_costs(&self, year: u32) -> (f64, f64) {
+        let inflation = &self.get_config().inflation;
+        let mut opex = 0.0;
+        let mut fuel_cost = 0.0;
+        for generator in &self.generators {
+            let operating_cost = generator.get_current_operating_cost(year, inflation);
+            let fuel = operating_cost * generator.get_generator_type().fuel_cost_share();
+            fuel_cost += fuel;
+            opex += operating_cost - fuel;
+        }
+        opex += self.carbon_offsets.iter()
+            .filter(|offset| offset.is_operational() && !offset.is_expired(year))
+            .map(|offset| offset.get_current_operating_cost(year, inflation))
             .sum::<f64>();
+        (opex, fuel_cost)
+    }
+
+    // Decommissioning charged for generators closed early by CloseGenerator this year
+    pub fn calc_yearly_closure_costs(&self, year: u32) -> f64 {
+        self.generators.iter()
+            .filter_map(|g| g.early_closure)
+            .filter(|(closure_year, _)| *closure_year == year)
+            .map(|(_, cost)| cost)
+            .sum()
+    }
 
-        generator_costs + offset_costs
+    pub fn calc_yearly_upgrade_costs(&self, year: u32) -> f64 {
+        self.generators.iter()
+            .flat_map(|g| &g.upgrade_costs)
+            .filter(|(upgrade_year, _)| *upgrade_year == year)
+            .map(|(_, cost)| cost)
+            .sum()
     }
 
     pub fn get_generators(&self) -> &[Generator] {
         &self.generators
     }
 
+    pub fn get_generators_mut(&mut self) -> &mut [Generator] {
+        &mut self.generators
+    }
+
     pub fn get_generator_mut(&mut self, id: &str) -> Option<&mut Generator> {
         self.generators.iter_mut().find(|g| g.get_id() == id)
     }
 
+    // Whether this year's configured build cap for the type is used up, deficit builds included
+    pub fn is_build_cap_reached(&self, gen_type: &GeneratorType, year: u32) -> bool {
+        self.get_config().max_builds_per_year.get(gen_type).is_some_and(|cap| {
+            self.generators.iter()
+                .filter(|g| g.get_generator_type() == gen_type && g.commissioning_year == year)
+                .count() >= *cap
+        })
+    }
+
+    // Nameplate MW of a type that is operating or still being built
+    pub fn installed_capacity(&self, gen_type: &GeneratorType) -> f64 {
+        self.generators.iter()
+            .filter(|g| g.get_generator_type() == gen_type)
+            .filter(|g| g.is_active() || matches!(g.construction_status,
+                ConstructionStatus::Planned | ConstructionStatus::PlanningPermissionGranted | ConstructionStatus::UnderConstruction))
+            .map(|g| g.power_out)
+            .sum()
+    }
+
+    pub fn would_exceed_capacity_cap(&self, gen_type: &GeneratorType, additional_capacity: f64) -> bool {
+        self.get_config().max_installed_capacity.get(gen_type)
+            .is_some_and(|cap| self.installed_capacity(gen_type) + additional_capacity > *cap)
+    }
+
     pub fn get_generator_count(&self) -> usize {
         self.generators.len()
     }
@@ -1000,6 +1537,40 @@ impl Map {
         self.carbon_offsets.len()
     }
 
+    pub fn get_config(&self) -> &SimulationConfig {
+        &self.static_data.config
+    }
+
+    // Rejects maps with generators, settlements or offsets outside the configured extent
+    pub fn validate(&self) -> std::result::Result<(), SimulationError> {
+        let extent = self.get_config().map_extent;
+        let outside: Vec<&str> = self.generators.iter().map(|g| (g.get_id(), g.get_coordinate()))
+            .chain(self.settlements.iter().map(|s| (s.get_id(), s.get_coordinate())))
+            .chain(self.carbon_offsets.iter().map(|o| (o.get_id(), o.get_coordinate())))
+            .filter(|(_, coordinate)| !extent.contains(coordinate))
+            .map(|(id, _)| id)
+            .collect();
+        if outside.is_empty() {
+            return Ok(());
+        }
+        Err(SimulationError::Config(format!(
+            "{} item(s) lie outside the {:.0} x {:.0} m map extent: {}",
+            outside.len(), extent.max_x, extent.max_y, outside.join(", ")
+        )))
+    }
+
+    pub fn get_opinion_trend(&self) -> Option<OpinionTrend> {
+        self.opinion_trend
+    }
+
+    pub fn record_opinion_trend(&mut self, metrics: &YearlyMetrics) {
+        self.opinion_trend = Some(OpinionTrend::from(metrics));
+    }
+
+    pub fn set_availability_factors(&mut self, factors: AvailabilityFactors) {
+        self.availability_factors = factors;
+    }
+
     pub fn get_generator_constraints(&self) -> &GeneratorConstraints {
         &self.static_data.config.generator_constraints
     }
@@ -1012,6 +1583,10 @@ impl Map {
         &self.carbon_offsets
     }
 
+    pub fn get_flexible_loads(&self) -> &[FlexibleLoad] {
+        &self.flexible_loads
+    }
+
     pub fn get_settlements_mut(&mut self) -> &mut Vec<Settlement> {
         &mut self.settlements
     }
@@ -1053,7 +1628,7 @@ impl Map {
             // Update suitability for the generator type in surrounding area
             let (suitability_type, base_score) = match generator.get_generator_type() {
                 GeneratorType::OnshoreWind => (GeneratorSuitabilityType::Onshore, 0.4),
-                GeneratorType::OffshoreWind => (GeneratorSuitabilityType::Offshore, 0.5),
+                GeneratorType::OffshoreWind | GeneratorType::FloatingOffshoreWind => (GeneratorSuitabilityType::Offshore, 0.5),
                 GeneratorType::TidalGenerator | GeneratorType::WaveEnergy => (GeneratorSuitabilityType::Coastal, 0.5),
                 _ => (GeneratorSuitabilityType::Rural, 0.3),
             };
@@ -1081,7 +1656,58 @@ impl Map {
         }
     }
 
+    pub fn is_excluded_location(&self, coordinate: &Coordinate) -> bool {
+        self.get_config().exclusion_zones.iter().any(|zone| zone.contains(coordinate))
+    }
+
+    // Closest grid point outside every exclusion zone, searching outward in square rings
+    pub fn nearest_permitted_location(&self, coordinate: &Coordinate) -> Option<Coordinate> {
+        if !self.is_excluded_location(coordinate) {
+            return Some(coordinate.clone());
+        }
+
+        let extent = self.get_config().map_extent;
+        let max_ring = (extent.max_x.max(extent.max_y) / GRID_CELL_SIZE).ceil() as i32;
+        for ring in 1..=max_ring {
+            let mut best: Option<Coordinate> = None;
+            for i in -ring..=ring {
+                for j in -ring..=ring {
+                    if i.abs() != ring && j.abs() != ring {
+                        continue;
+                    }
+                    let x = coordinate.x + i as f64 * GRID_CELL_SIZE;
+                    let y = coordinate.y + j as f64 * GRID_CELL_SIZE;
+                    let candidate = Coordinate { x, y };
+                    if !extent.contains(&candidate) {
+                        continue;
+                    }
+                    if self.is_excluded_location(&candidate) {
+                        continue;
+                    }
+                    let closer = best.as_ref().is_none_or(|current| {
+                        let (candidate_distance, current_distance) = (candidate.distance_to(coordinate), current.distance_to(coordinate));
+                        candidate_distance < current_distance ||
+                            (candidate_distance == current_distance && candidate.cmp_position(current) == std::cmp::Ordering::Less)
+                    });
+                    if closer {
+                        best = Some(candidate);
+                    }
+                }
+            }
+            if best.is_some() {
+                return best;
+            }
+        }
+        None
+    }
+
+    // Best site for a generator, moved to the nearest permitted point if it falls in an exclusion zone
     pub fn find_best_generator_location(&self, generator_type: &GeneratorType, size: f64) -> Option<Coordinate> {
+        let location = self.find_generator_location_candidate(generator_type, size)?;
+        self.nearest_permitted_location(&location)
+    }
+
+    fn find_generator_location_candidate(&self, generator_type: &GeneratorType, size: f64) -> Option<Coordinate> {
         let _timing = logging::start_timing("find_best_generator_location", 
             OperationCategory::LocationSearch { subcategory: LocationSearchType::GeneratorPlacement });
         
@@ -1113,6 +1739,7 @@ impl Map {
                     // Apply type-specific positioning bias based on generator characteristics
                     let type_bias_x = match generator_type {
                         GeneratorType::OffshoreWind => 0.8, // More towards the west coast
+                        GeneratorType::FloatingOffshoreWind => 0.9, // Deep water lies furthest west
                         GeneratorType::Nuclear => 0.5,     // More central/coastal
                         GeneratorType::OnshoreWind => 0.4, // Widely distributed
                         _ => 0.5, // Default - evenly distributed
@@ -1122,7 +1749,7 @@ impl Map {
                     let x = bounds.min.x + width * (x_offset * 0.7 + type_bias_x * 0.3);
                     let y = bounds.min.y + height * y_offset;
                     
-                    return Some(Coordinate::new(x, y));
+                    return Some(Coordinate { x, y });
                 }
                 return None;
             }
@@ -1145,7 +1772,7 @@ impl Map {
         // Fall back to CPU implementation if Metal search fails or is unavailable
         let initial_min_score = match generator_type {
             GeneratorType::OnshoreWind => 0.2,
-            GeneratorType::OffshoreWind => 0.3,
+            GeneratorType::OffshoreWind | GeneratorType::FloatingOffshoreWind => 0.3,
             GeneratorType::TidalGenerator | GeneratorType::WaveEnergy => 0.35,
             GeneratorType::Nuclear => 0.4,
             GeneratorType::DomesticSolar | GeneratorType::CommercialSolar => 0.2,
@@ -1265,10 +1892,11 @@ impl Map {
         // Return the grid bounds representing Ireland's territory in our grid coordinate system
         // This should match the transformation used in transform_lat_lon_to_grid and transform_grid_to_lat_lon
         // For Ireland: latitude 51.4 to 55.4, longitude -10.6 to -5.9
-        // The grid coordinates should span from (0,0) to (MAP_MAX_X, MAP_MAX_Y)
+        // The grid coordinates span the configured map extent, (0,0) to (MAP_MAX_X, MAP_MAX_Y) by default
+        let extent = self.get_config().map_extent;
         Bounds {
-            min: Coordinate::new(0.0, 0.0),
-            max: Coordinate::new(MAP_MAX_X, MAP_MAX_Y)
+            min: Coordinate { x: 0.0, y: 0.0 },
+            max: Coordinate { x: extent.max_x, y: extent.max_y },
         }
     }
 
@@ -1293,20 +1921,12 @@ impl Map {
             let x = bounds.min.x + (i as f64 * step_size);
             for j in 0..=y_steps {
                 let y = bounds.min.y + (j as f64 * step_size);
-                let coordinate = Coordinate::new(x, y);
-                let base_score = self.calculate_generator_suitability(&coordinate, &generator_type);
-                
-                // Apply size penalty based on generator type
-                let size_factor = match generator_type {
-                    GeneratorType::Nuclear => 0.8,
-                    GeneratorType::CoalPlant | GeneratorType::GasCombinedCycle => 0.6,
-                    GeneratorType::OnshoreWind | GeneratorType::OffshoreWind => 0.4,
-                    _ => 0.3,
+                let coordinate = Coordinate { x, y };
+                let Some(final_score) = self.siting_score(&coordinate, &generator_type, size_penalty) else {
+                    continue;
                 };
-                
-                let final_score = base_score - (size_factor * size_penalty);
 
-                if final_score > best_score {
+                if is_better_location(final_score, &coordinate, best_score, best_location.as_ref()) {
                     best_score = final_score;
                     best_location = Some(coordinate);
                 }
@@ -1316,6 +1936,26 @@ impl Map {
         best_location
     }
 
+    /// Score the CPU location search ranks candidate sites by: suitability less a type-scaled
+    /// size penalty. `None` for excluded locations, which are never sited.
+    pub fn siting_score(&self, coordinate: &Coordinate, generator_type: &GeneratorType, size_penalty: f64) -> Option<f64> {
+        if self.is_excluded_location(coordinate) {
+            return None;
+        }
+        let base_score = self.calculate_generator_suitability(coordinate, generator_type);
+
+        // Apply size penalty based on generator type
+        let size_factor = match generator_type {
+            GeneratorType::Nuclear => 0.8,
+            GeneratorType::CoalPlant | GeneratorType::GasCombinedCycle => 0.6,
+            GeneratorType::OnshoreWind | GeneratorType::OffshoreWind |
+            GeneratorType::FloatingOffshoreWind => 0.4,
+            _ => 0.3,
+        };
+
+        Some(base_score - (size_factor * size_penalty))
+    }
+
     pub fn calculate_generator_suitability(&self, coordinate: &Coordinate, generator_type: &GeneratorType) -> f64 {
         let _timing = logging::start_timing("calculate_generator_suitability", 
             OperationCategory::LocationSearch { subcategory: LocationSearchType::SuitabilityCheck });
@@ -1337,7 +1977,9 @@ impl Map {
 
                 base_score - nearby_penalty
             },
-            GeneratorType::OffshoreWind | GeneratorType::TidalGenerator | GeneratorType::WaveEnergy => {
+            // No bathymetry zones on the map, so floating wind shares the fixed offshore sites
+            GeneratorType::OffshoreWind | GeneratorType::FloatingOffshoreWind |
+            GeneratorType::TidalGenerator | GeneratorType::WaveEnergy => {
                 if !self.is_offshore_region(coordinate) {
                     return 0.0;
                 }
@@ -1408,7 +2050,7 @@ impl Map {
                 
                 if x >= bounds.min.x && x <= bounds.max.x && 
                    y >= bounds.min.y && y <= bounds.max.y {
-                    let test_coord = Coordinate::new(x, y);
+                    let test_coord = Coordinate { x, y };
                     if !self.is_water_tile(&test_coord) {
                         let distance = coordinate.distance_to(&test_coord);
                         min_distance = min_distance.min(distance);
@@ -1489,6 +2131,116 @@ impl Map {
         self.enable_construction_delays = enable;
     }
 
+    // Close every active generator that has reached commissioning year + lifespan. Returns the
+    // retired ids and the average output lost, which the yearly deficit check then has to cover.
+    pub fn retire_end_of_life_generators(&mut self, year: u32) -> (Vec<String>, f64) {
+        let mut retired = Vec::new();
+        let mut lost_output = 0.0;
+        for index in 0..self.generators.len() {
+            let generator = &self.generators[index];
+            if !generator.is_active() || !generator.is_past_end_of_life(year) {
+                continue;
+            }
+            lost_output += self.effective_generator_output(generator, year, None);
+            retired.push(generator.get_id().to_string());
+            self.generators[index].close_generator(year);
+        }
+
+        self.log_retirements(year, &retired, RetirementReason::EndOfLife);
+        (retired, lost_output)
+    }
+
+    fn log_retirements(&mut self, year: u32, ids: &[String], reason: RetirementReason) {
+        if ids.is_empty() {
+            return;
+        }
+        self.retirements.extend(ids.iter().map(|id| Retirement { year, generator_id: id.clone(), reason }));
+        self.after_generator_modification();
+    }
+
+    pub fn get_retirements(&self) -> &[Retirement] {
+        &self.retirements
+    }
+
+    // Stable hash of the grid state for regression checks: a known seed and plan should give a
+    // known fingerprint each year. Insensitive to the order of generators, settlements and offsets.
+    pub fn state_fingerprint(&self, year: u32) -> u64 {
+        let generators = unordered_hash(&self.generators, |hasher, generator| {
+            hasher.write_str(generator.get_id());
+            hasher.write_str(&generator.get_generator_type().to_string());
+            hasher.write_f64(generator.get_size());
+            hasher.write_f64(generator.get_efficiency());
+            hasher.write_f64(generator.operation_percentage);
+            hasher.write_bool(generator.is_active());
+        });
+        let settlements = unordered_hash(&self.settlements, |hasher, settlement| {
+            hasher.write_str(settlement.get_name());
+            hasher.write_u32(settlement.get_population());
+            hasher.write_f64(settlement.get_power_usage());
+        });
+        let offsets = unordered_hash(&self.carbon_offsets, |hasher, offset| {
+            hasher.write_str(offset.get_id());
+            hasher.write_str(&offset.get_offset_type().to_string());
+            hasher.write_f64(offset.get_size());
+            hasher.write_bool(offset.is_operational());
+        });
+
+        let mut hasher = StableHasher::new();
+        hasher.write_u32(year);
+        hasher.write_u64(generators);
+        hasher.write_u64(settlements);
+        hasher.write_u64(offsets);
+        if !self.flexible_loads.is_empty() {
+            hasher.write_u64(unordered_hash(&self.flexible_loads, |hasher, load| {
+                hasher.write_str(load.get_id());
+                hasher.write_f64(load.shiftable_demand());
+            }));
+        }
+        hasher.finish()
+    }
+
+    // Add this year's scheduled builds from the config. They are committed projects, so they
+    // arrive operational whatever the construction delay setting.
+    pub fn add_scheduled_builds(&mut self, year: u32) -> Vec<String> {
+        let builds: Vec<Generator> = self.get_config().scheduled_builds.iter()
+            .filter(|build| build.year == year)
+            .map(|build| build.generator.clone())
+            .collect();
+
+        let enable_construction_delays = self.enable_construction_delays;
+        self.enable_construction_delays = false;
+        let mut added = Vec::new();
+        for mut generator in builds {
+            generator.is_active = true;
+            let id = generator.get_id().to_string();
+            let fleet_size = self.generators.len();
+            self.add_generator(generator);
+            if self.generators.len() > fleet_size {
+                added.push(id);
+            }
+        }
+        self.enable_construction_delays = enable_construction_delays;
+        added
+    }
+
+    // Close every active generator whose type has passed its forced retirement year
+    pub fn retire_phased_out_generators(&mut self, year: u32) -> (Vec<String>, f64) {
+        let mut retired = Vec::new();
+        let mut lost_output = 0.0;
+        for index in 0..self.generators.len() {
+            let generator = &self.generators[index];
+            if !generator.is_active() || !self.get_config().must_retire(generator.get_generator_type(), year) {
+                continue;
+            }
+            lost_output += self.effective_generator_output(generator, year, None);
+            retired.push(generator.get_id().to_string());
+            self.generators[index].close_generator(year);
+        }
+
+        self.log_retirements(year, &retired, RetirementReason::PhaseOut);
+        (retired, lost_output)
+    }
+
     pub fn update_construction_status(&mut self) {
         let current_year = self.current_year;
         
@@ -1535,3 +2287,295 @@ impl LocationAnalysisSource for Map {
         self.calculate_generator_suitability(coordinate, generator_type)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::actions::grid_action::GridAction;
+    use crate::analysis::metrics_calculation::calculate_yearly_metrics;
+    use crate::config::constants::{BASE_YEAR, HYDROGEN_TURBINE_EFFICIENCY};
+    use crate::config::simulation_config::ExclusionZone;
+    use crate::core::actions::apply_action;
+    use crate::test_support::{empty_map, small_map, test_generator, TEST_SITE};
+
+    fn yearly_metrics_json(map: &Map, year: u32) -> String {
+        let metrics = calculate_yearly_metrics(
+            map, year, map.calc_yearly_upgrade_costs(year), map.calc_yearly_closure_costs(year), true, None,
+        );
+        serde_json::to_string(&metrics).unwrap()
+    }
+
+    #[test]
+    fn restore_returns_metrics_to_pre_action_values() {
+        let year = BASE_YEAR;
+        let mut map = small_map();
+
+        let before = yearly_metrics_json(&map, year);
+        let next_id_before = map.next_generator_number;
+        let snapshot = map.snapshot();
+
+        for action in [
+            GridAction::AddGenerator(GeneratorType::OnshoreWind, 100),
+            GridAction::AddCarbonOffset(CarbonOffsetType::Forest, 100),
+            GridAction::AdjustOperation("Existing_Gas".to_string(), 50),
+        ] {
+            apply_action(&mut map, &action, year, None).unwrap();
+        }
+        assert_ne!(yearly_metrics_json(&map, year), before);
+
+        map.restore(snapshot);
+        assert_eq!(yearly_metrics_json(&map, year), before);
+        assert_eq!(map.next_generator_number, next_id_before);
+        assert_eq!(map.generators_near(&Coordinate::new(24_000.0, 26_000.0), 1.0e6, true).len(), 1);
+    }
+
+    #[test]
+    fn identical_maps_pick_the_same_generator_location() {
+        let first = small_map();
+        let second = small_map();
+
+        for generator_type in [GeneratorType::UtilitySolar, GeneratorType::OnshoreWind] {
+            let a = first.find_best_generator_location(&generator_type, 1.0).expect("a site on the first map");
+            let b = second.find_best_generator_location(&generator_type, 1.0).expect("a site on the second map");
+            assert_eq!((a.x.to_bits(), a.y.to_bits()), (b.x.to_bits(), b.y.to_bits()), "{}", generator_type);
+        }
+
+        // A tied score goes to the lower position whichever candidate is scanned first
+        let west = Coordinate { x: 10_000.0, y: 40_000.0 };
+        let east = Coordinate { x: 20_000.0, y: 5_000.0 };
+        assert!(is_better_location(0.5, &west, 0.5, Some(&east)));
+        assert!(!is_better_location(0.5, &east, 0.5, Some(&west)));
+    }
+
+    #[test]
+    fn generators_near_returns_exactly_those_inside_the_radius() {
+        let mut map = empty_map(SimulationConfig::default());
+        for (id, x, y) in [
+            ("Near_East", 12_000.0, 10_000.0),   // 2 km
+            ("Near_North", 10_000.0, 13_000.0),  // 3 km
+            ("Closed", 11_000.0, 11_000.0),      // 1.4 km, but shut down
+            ("Far_East", 15_000.0, 10_000.0),    // 5 km
+            ("Far_South", 10_000.0, 6_000.0),    // 4 km
+        ] {
+            map.add_generator(test_generator(id, GeneratorType::OnshoreWind, Coordinate::new(x, y), 50.0, 0.0));
+        }
+        map.get_generator_mut("Closed").unwrap().close_generator(BASE_YEAR);
+
+        let center = Coordinate::new(10_000.0, 10_000.0);
+        let ids = |include_inactive: bool| -> Vec<String> {
+            map.generators_near(&center, 3_500.0, include_inactive).iter().map(|g| g.get_id().to_string()).collect()
+        };
+
+        assert_eq!(ids(false), vec!["Near_East", "Near_North"]);
+        assert_eq!(ids(true), vec!["Closed", "Near_East", "Near_North"]);
+    }
+
+    #[test]
+    fn identically_built_maps_share_a_fingerprint() {
+        let wind = || test_generator("Wind_1", GeneratorType::OnshoreWind, Coordinate::new(30_000.0, 30_000.0), 100.0, 0.0);
+        let solar = || test_generator("Solar_1", GeneratorType::UtilitySolar, Coordinate::new(20_000.0, 20_000.0), 50.0, 0.0);
+        let mut first = small_map();
+        first.add_generator(wind());
+        first.add_generator(solar());
+        let mut second = small_map();
+        second.add_generator(solar());
+        second.add_generator(wind());
+
+        assert_eq!(first.state_fingerprint(2030), second.state_fingerprint(2030));
+
+        second.get_generator_mut("Existing_Gas").unwrap().operation_percentage = 0.5;
+        assert_ne!(first.state_fingerprint(2030), second.state_fingerprint(2030));
+    }
+
+    #[test]
+    fn candidate_inside_an_exclusion_zone_gives_way_to_one_outside() {
+        let mut map = small_map();
+        let preferred = map.find_best_generator_location(&GeneratorType::OnshoreWind, 1.0).unwrap();
+
+        // Fence off a square park around the preferred site
+        let half_width = 3_000.0;
+        let corner = |dx: f64, dy: f64| Coordinate { x: preferred.x + dx * half_width, y: preferred.y + dy * half_width };
+        let mut config = map.get_config().clone();
+        config.exclusion_zones.push(ExclusionZone::Polygon {
+            name: "Park".to_string(),
+            vertices: vec![corner(-1.0, -1.0), corner(1.0, -1.0), corner(1.0, 1.0), corner(-1.0, 1.0)],
+        });
+        map.set_config(config);
+        assert!(map.is_excluded_location(&preferred));
+
+        let fallback = map.find_best_generator_location(&GeneratorType::OnshoreWind, 1.0).unwrap();
+        assert!(!map.is_excluded_location(&fallback));
+        // The nearest permitted point sits just outside the fence, not somewhere across the map
+        assert!(fallback.distance_to(&preferred) <= 2.0 * half_width, "moved {} m", fallback.distance_to(&preferred));
+    }
+
+    #[test]
+    fn larger_map_extent_accepts_coordinates_the_default_rejects() {
+        // Built as a literal, since Coordinate::new would clamp it into the default extent
+        let beyond_default = Coordinate { x: 80_000.0, y: 30_000.0 };
+        let with_far_plant = |config: SimulationConfig| {
+            let mut map = empty_map(config);
+            map.add_generator(test_generator("Far_Wind", GeneratorType::OnshoreWind, beyond_default.clone(), 100.0, 0.0));
+            map
+        };
+
+        assert!(with_far_plant(SimulationConfig::default()).validate().is_err());
+        let larger = SimulationConfig::builder().map_extent(100_000.0, 100_000.0).build();
+        assert!(with_far_plant(larger).validate().is_ok());
+    }
+
+    #[test]
+    fn surplus_hydrogen_covers_a_later_deficit_after_conversion_losses() {
+        let year = BASE_YEAR;
+        let mut map = empty_map(SimulationConfig::default());
+        for generator_type in [GeneratorType::HydrogenElectrolyzer, GeneratorType::HydrogenTurbine] {
+            apply_action(&mut map, &GridAction::AddGenerator(generator_type, 100), year, None).unwrap();
+        }
+
+        let surplus = 1.0;
+        assert_eq!(map.charge_hydrogen_storage(surplus), surplus);
+        let stored = map.stored_hydrogen();
+        assert!((stored - surplus * HYDROGEN_ELECTROLYSIS_EFFICIENCY).abs() < 1e-9);
+
+        let deficit = 100.0;
+        let remaining = map.handle_power_deficit(deficit, None);
+        let expected_delivery = surplus * HYDROGEN_ELECTROLYSIS_EFFICIENCY * HYDROGEN_TURBINE_EFFICIENCY;
+        assert!((deficit - remaining - expected_delivery).abs() < 1e-9, "delivered {} MW", deficit - remaining);
+        assert!(map.stored_hydrogen() < 1e-9);
+    }
+
+    #[test]
+    fn each_map_prices_its_fleet_with_its_own_inflation_model() {
+        let year = 2040;
+        let capital_cost = |inflation_rate: f64| {
+            let mut map = empty_map(SimulationConfig::builder().inflation_rate(inflation_rate).build());
+            map.add_generator(test_generator("Wind_1", GeneratorType::OnshoreWind, TEST_SITE, 100.0, 0.0));
+            (map.calc_total_capital_cost(year), map.calc_total_capital_cost(BASE_YEAR))
+        };
+
+        let (flat_2040, flat_base) = capital_cost(0.0);
+        let (inflated_2040, inflated_base) = capital_cost(0.05);
+
+        assert_eq!(flat_base, inflated_base);
+        let technology_factor = flat_2040 / flat_base;
+        assert!((inflated_2040 / inflated_base - technology_factor * 1.05_f64.powi(15)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn asset_costs_summed_through_the_trait_match_the_per_type_sums() {
+        let year = 2030;
+        let mut map = small_map();
+        map.add_generator(test_generator("Wind_1", GeneratorType::OnshoreWind, Coordinate::new(30_000.0, 30_000.0), 100.0, 0.0));
+        map.add_carbon_offset(CarbonOffset::new(
+            "Forest_1".to_string(), TEST_SITE, CarbonOffsetType::Forest, 1.0e6, 1.0e4, 100.0, 0.9,
+        ));
+        map.add_flexible_load(FlexibleLoad::new("Load_1".to_string(), "Midtown".to_string(), TEST_SITE, 10.0, 0.3, BASE_YEAR));
+
+        let inflation = &map.get_config().inflation;
+        let through_trait: f64 = map.assets().map(|asset| asset.annual_cost(year, inflation)).sum();
+
+        let generators: f64 = map.get_generators().iter()
+            .filter(|g| !g.get_id().starts_with("Existing_"))
+            .map(|g| g.get_current_cost(year, inflation))
+            .sum();
+        let offsets: f64 = map.get_carbon_offsets().iter().map(|o| o.get_current_cost(year, inflation)).sum();
+        let loads: f64 = map.get_flexible_loads().iter().map(|l| l.get_current_cost(year, inflation)).sum();
+
+        assert!(generators > 0.0 && offsets > 0.0 && loads > 0.0);
+        assert!((through_trait - (generators + offsets + loads)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn coal_opinion_is_below_solar_at_the_same_site() {
+        let config = SimulationConfig::builder()
+            .type_opinion_modifier(GeneratorType::CoalPlant, -0.2)
+            .type_opinion_modifier(GeneratorType::UtilitySolar, 0.1)
+            .build();
+        let mut map = empty_map(config);
+        map.add_settlement(Settlement::new("Midtown".to_string(), TEST_SITE, 50_000, 80.0));
+        let site = Coordinate::new(30_000.0, 30_000.0);
+        // Same cost and size, so only the technology differs
+        let coal = test_generator("Coal", GeneratorType::CoalPlant, site.clone(), 100.0, 1.0e6);
+        let solar = test_generator("Solar", GeneratorType::UtilitySolar, site.clone(), 100.0, 0.0);
+
+        let coal_opinion = map.calc_new_generator_opinion(&site, &coal, BASE_YEAR);
+        let solar_opinion = map.calc_new_generator_opinion(&site, &solar, BASE_YEAR);
+
+        assert!(coal_opinion < solar_opinion, "coal {} vs solar {}", coal_opinion, solar_opinion);
+        let mut unmodified = empty_map(SimulationConfig::default());
+        unmodified.add_settlement(Settlement::new("Midtown".to_string(), TEST_SITE, 50_000, 80.0));
+        assert!(coal_opinion < unmodified.calc_new_generator_opinion(&site, &coal, BASE_YEAR));
+    }
+
+    #[test]
+    fn many_adds_and_closures_across_years_never_duplicate_an_id() {
+        let mut map = small_map();
+        for year in 2025..=2035 {
+            map.current_year = year;
+            for gen_type in [GeneratorType::OnshoreWind, GeneratorType::UtilitySolar, GeneratorType::GasPeaker] {
+                apply_action(&mut map, &GridAction::AddGenerator(gen_type, 100), year, None).unwrap();
+            }
+            // Closing a plant each year would let a count-based name come round again
+            let newest = map.get_generators().last().unwrap().get_id().to_string();
+            apply_action(&mut map, &GridAction::CloseGenerator(newest), year, None).unwrap();
+        }
+
+        let ids: Vec<&str> = map.get_generators().iter().map(|g| g.get_id()).collect();
+        let unique: HashSet<&str> = ids.iter().copied().collect();
+        assert!(ids.len() > 30);
+        assert_eq!(unique.len(), ids.len(), "{:?}", ids);
+
+        // A second generator under an existing ID is turned away
+        let count = map.get_generators().len();
+        map.add_generator(test_generator("Existing_Gas", GeneratorType::GasPeaker, TEST_SITE, 50.0, 1.0e5));
+        assert_eq!(map.get_generators().len(), count);
+    }
+
+    #[test]
+    fn large_battery_adds_its_embodied_emissions_in_its_commissioning_year() {
+        let mut map = small_map();
+        let year = 2030;
+        map.current_year = year;
+        let before = map.calc_total_co2_emissions();
+
+        map.add_generator(test_generator("Big_Battery", GeneratorType::BatteryStorage, TEST_SITE, 500.0, 0.0));
+        let battery = map.get_generators().iter().find(|g| g.get_id() == "Big_Battery").unwrap();
+        assert_eq!(battery.commissioning_year, year);
+        let embodied = map.get_config().embodied_carbon_factor * 500.0 * GeneratorType::BatteryStorage.embodied_co2_intensity();
+        assert!(embodied > 0.0);
+
+        let after = map.calc_total_co2_emissions();
+        assert!((after - before - embodied).abs() < 1e-6 * embodied, "added {} t, expected {} t", after - before, embodied);
+        // Charged once: the next year carries no construction emissions for it
+        assert_eq!(map.calc_embodied_co2_emissions(year + 1), 0.0);
+    }
+
+    #[test]
+    fn flexible_load_shifts_demand_from_the_peak_deficit_into_surplus_hours() {
+        // Solar alone runs a surplus at midday and a deficit overnight
+        let mut map = empty_map(SimulationConfig::default());
+        map.add_settlement(Settlement::new("Midtown".to_string(), TEST_SITE, 50_000, 80.0));
+        let mut solar = test_generator("Solar", GeneratorType::UtilitySolar, Coordinate::new(30_000.0, 25_000.0), 300.0, 0.0);
+        solar.initialize_construction(BASE_YEAR, 0.5, false);
+        map.add_generator(solar);
+        let year = BASE_YEAR;
+        let before = map.calc_hourly_power_balance(year);
+        assert!(before.iter().any(|b| *b < 0.0) && before.iter().any(|b| *b > 0.0));
+
+        apply_action(&mut map, &GridAction::AddFlexibleLoad, year, None).unwrap();
+
+        let after = map.calc_hourly_power_balance(year);
+        assert!(map.calc_shiftable_demand() > 0.0);
+        assert!(map.calc_peak_deficit(year) < before.iter().fold(0.0, |worst: f64, b| worst.max(-b)));
+        for (was, now) in before.iter().zip(&after) {
+            // Deficit hours improve and surplus hours take the moved demand
+            if *was < 0.0 {
+                assert!(now > was && *now <= 0.0);
+            } else {
+                assert!(now <= was && *now >= 0.0);
+            }
+        }
+        // Demand is moved, not removed
+        assert!((before.iter().sum::<f64>() - after.iter().sum::<f64>()).abs() < 1e-6);
+    }
+}
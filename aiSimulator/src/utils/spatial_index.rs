@@ -1,5 +1,6 @@
-use crate::data::poi::Coordinate;
-use crate::config::constants::{MAP_MAX_X, MAP_MAX_Y, GRID_CELL_SIZE};
+use crate::data::poi::{Coordinate, MapExtent};
+use crate::config::constants::GRID_CELL_SIZE;
+use std::collections::BTreeMap;
 use std::fmt;
 
 #[derive(Clone, Debug)]
@@ -17,6 +18,13 @@ pub struct Boundary {
     half_height: f64,
 }
 
+// Kinds of point items tracked for range queries
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum SpatialItemKind {
+    Generator,
+    Settlement,
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum GeneratorSuitabilityType {
     Onshore,
@@ -132,6 +140,15 @@ impl QuadTreeNode {
 #[derive(Clone)]
 pub struct SpatialIndex {
     root: QuadTreeNode,
+    // Point items bucketed by grid cell; BTreeMap keeps query order stable
+    items: BTreeMap<(i32, i32), Vec<SpatialItem>>,
+}
+
+#[derive(Clone, Debug)]
+struct SpatialItem {
+    kind: SpatialItemKind,
+    id: String,
+    coordinate: Coordinate,
 }
 
 // Manual Debug implementation for SpatialIndex
@@ -139,21 +156,83 @@ impl fmt::Debug for SpatialIndex {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("SpatialIndex")
             .field("root", &self.root)
+            .field("item_cells", &self.items.len())
             .finish()
     }
 }
 
 impl SpatialIndex {
-    pub fn new() -> Self {
+    pub fn new(extent: &MapExtent) -> Self {
         Self {
             root: QuadTreeNode::new(
-                Coordinate::new(MAP_MAX_X / 2.0, MAP_MAX_Y / 2.0),
-                MAP_MAX_X / 2.0,
-                MAP_MAX_Y / 2.0,
+                extent.center(),
+                extent.max_x / 2.0,
+                extent.max_y / 2.0,
             ),
+            items: BTreeMap::new(),
+        }
+    }
+
+    fn item_cell(coord: &Coordinate) -> (i32, i32) {
+        (
+            (coord.x / GRID_CELL_SIZE).floor() as i32,
+            (coord.y / GRID_CELL_SIZE).floor() as i32,
+        )
+    }
+
+    pub fn insert_item(&mut self, kind: SpatialItemKind, id: &str, coord: &Coordinate) {
+        self.items
+            .entry(Self::item_cell(coord))
+            .or_default()
+            .push(SpatialItem { kind, id: id.to_string(), coordinate: coord.clone() });
+    }
+
+    pub fn remove_item(&mut self, kind: SpatialItemKind, id: &str, coord: &Coordinate) {
+        let cell = Self::item_cell(coord);
+        if let Some(bucket) = self.items.get_mut(&cell) {
+            bucket.retain(|item| !(item.kind == kind && item.id == id));
+            if bucket.is_empty() {
+                self.items.remove(&cell);
+            }
         }
     }
 
+    pub fn clear_items(&mut self) {
+        self.items.clear();
+    }
+
+    /// Ids of all items within `radius` of `center` (inclusive), sorted by kind then id.
+    pub fn query_radius(&self, center: &Coordinate, radius: f64) -> Vec<(SpatialItemKind, String)> {
+        let min = Coordinate { x: center.x - radius, y: center.y - radius };
+        let max = Coordinate { x: center.x + radius, y: center.y + radius };
+        self.collect_items(&min, &max, |coord| coord.distance_to(center) <= radius)
+    }
+
+    /// Ids of all items inside the box spanned by `min` and `max` (inclusive), sorted by kind then id.
+    pub fn query_bbox(&self, min: &Coordinate, max: &Coordinate) -> Vec<(SpatialItemKind, String)> {
+        self.collect_items(min, max, |coord| {
+            coord.x >= min.x && coord.x <= max.x && coord.y >= min.y && coord.y <= max.y
+        })
+    }
+
+    fn collect_items<F>(&self, min: &Coordinate, max: &Coordinate, inside: F) -> Vec<(SpatialItemKind, String)>
+    where
+        F: Fn(&Coordinate) -> bool,
+    {
+        let (min_x, min_y) = Self::item_cell(min);
+        let (max_x, max_y) = Self::item_cell(max);
+
+        let mut found: Vec<(SpatialItemKind, String)> = self.items
+            .range((min_x, i32::MIN)..=(max_x, i32::MAX))
+            .filter(|((_, y), _)| *y >= min_y && *y <= max_y)
+            .flat_map(|(_, bucket)| bucket.iter())
+            .filter(|item| inside(&item.coordinate))
+            .map(|item| (item.kind, item.id.clone()))
+            .collect();
+        found.sort();
+        found
+    }
+
     pub fn find_best_location(
         &self,
         suitability_type: GeneratorSuitabilityType,
@@ -1,8 +1,20 @@
 // Define a trait to abstract the functionality needed for location analysis
 
+use crate::config::simulation_config::InflationModel;
 use crate::data::poi::Coordinate;
 use crate::models::generator::GeneratorType;
 
 pub trait LocationAnalysisSource {
     fn calculate_generator_suitability(&self, coordinate: &Coordinate, generator_type: &GeneratorType) -> f64;
-} 
\ No newline at end of file
+}
+
+// Anything placed on the map that is paid for and moves net emissions, so cost and emissions
+// totals can be taken over generators (storage included) and carbon offsets in a single pass
+pub trait GridAsset {
+    // Capital cost charged to the plan in `year`, at the price level `inflation` gives that year
+    fn annual_cost(&self, year: u32, inflation: &InflationModel) -> f64;
+    // Net tonnes of CO2 added in `year`; removals are negative
+    fn co2_impact(&self, year: u32) -> f64;
+    fn coordinate(&self) -> &Coordinate;
+    fn is_active(&self) -> bool;
+}